@@ -0,0 +1,62 @@
+"""Load binary position datasets written by the Rust generate_dataset tool."""
+
+from __future__ import annotations
+
+from pathlib import Path
+import struct
+
+import numpy as np
+
+
+MAGIC = b"NTDS"
+VERSION = 1
+HEADER_SIZE = 8
+RECORD_SIZE = 24
+NO_EXACT_SCORE = -128
+
+DATASET_DTYPE = np.dtype(
+    [
+        ("black", "<u8"),
+        ("white", "<u8"),
+        ("search_score", "<f4"),
+        ("ply", "u1"),
+        ("is_black", "u1"),
+        ("final_score", "i1"),
+        ("exact_score", "i1"),
+    ]
+)
+assert DATASET_DTYPE.itemsize == RECORD_SIZE
+
+
+def parse_dataset(payload: bytes) -> np.ndarray:
+    """Return the rows of a dataset payload as a structured array.
+
+    `final_score` and `exact_score` are disc differences for the side to
+    move. `search_score` is the raw, unitless evaluator or search score and
+    is NaN when the generating policy reported none. `exact_score` is
+    NO_EXACT_SCORE for positions that were not solved.
+    """
+    if len(payload) < HEADER_SIZE:
+        raise ValueError(
+            f"dataset too short: expected at least {HEADER_SIZE} bytes, got {len(payload)}"
+        )
+    magic, version, record_size = struct.unpack("<4sHH", payload[:HEADER_SIZE])
+    if magic != MAGIC:
+        raise ValueError(f"invalid magic: expected {MAGIC!r}, got {magic!r}")
+    if version != VERSION:
+        raise ValueError(f"unsupported dataset version: {version}")
+    if record_size != RECORD_SIZE:
+        raise ValueError(f"unsupported record size: {record_size}")
+    if (len(payload) - HEADER_SIZE) % RECORD_SIZE:
+        raise ValueError("dataset ends with a partial record")
+    return np.frombuffer(payload, dtype=DATASET_DTYPE, offset=HEADER_SIZE)
+
+
+def load_dataset(path: str | Path) -> np.ndarray:
+    return parse_dataset(Path(path).read_bytes())
+
+
+def training_labels(rows: np.ndarray) -> np.ndarray:
+    """Exact scores where solved, final disc differences elsewhere."""
+    solved = rows["exact_score"] != NO_EXACT_SCORE
+    return np.where(solved, rows["exact_score"], rows["final_score"]).astype(np.float32)
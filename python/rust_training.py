@@ -2,7 +2,7 @@
 
 from __future__ import annotations
 
-from collections.abc import Callable
+from collections.abc import Callable, Sequence
 from importlib.machinery import ExtensionFileLoader
 from importlib import import_module
 from importlib.util import module_from_spec, spec_from_file_location
@@ -11,6 +11,9 @@ import sys
 from types import ModuleType
 
 ProgressCallback = Callable[[int, int, float], None]
+CheckpointCallback = Callable[[bytes], None]
+MetricsCallback = Callable[[dict[str, object]], None]
+GenerationCallback = Callable[[dict[str, object]], None]
 
 _MODULE_NAME = "_reversi_training"
 _MODULE_DIR = Path(__file__).resolve().parent
@@ -82,6 +85,21 @@ def train_to_bytes(
     progress_callback: ProgressCallback | None = None,
     alpha_decay: str = "none",
     alpha_decay_start_game: int = 0,
+    patterns: Sequence[Sequence[int]] | None = None,
+    scalar_features: Sequence[str] | None = None,
+    checkpoint_interval: int = 0,
+    checkpoint_callback: CheckpointCallback | None = None,
+    metrics_callback: MetricsCallback | None = None,
+    metrics_log: str | Path | None = None,
+    parallel_mode: str = "independent",
+    search_depth: int = 2,
+    exact_td_empties: int = 0,
+    epsilon_schedule: str = "constant",
+    exploration: str = "uniform",
+    league: str | None = None,
+    gate: str | None = None,
+    generation_callback: GenerationCallback | None = None,
+    gate_log: str | Path | None = None,
 ) -> bytes:
     module = _load_extension()
     kwargs = dict(
@@ -102,6 +120,35 @@ def train_to_bytes(
         kwargs["alpha_decay"] = alpha_decay
     if alpha_decay_start_game != 0:
         kwargs["alpha_decay_start_game"] = alpha_decay_start_game
+    if patterns is not None:
+        kwargs["patterns"] = [list(pattern) for pattern in patterns]
+    if scalar_features is not None:
+        kwargs["scalar_features"] = list(scalar_features)
+    if checkpoint_callback is not None:
+        kwargs["checkpoint_interval"] = checkpoint_interval
+        kwargs["checkpoint_callback"] = checkpoint_callback
+    if metrics_callback is not None:
+        kwargs["metrics_callback"] = metrics_callback
+    if metrics_log is not None:
+        kwargs["metrics_log"] = str(metrics_log)
+    if parallel_mode != "independent":
+        kwargs["parallel_mode"] = parallel_mode
+    if search_depth != 2:
+        kwargs["search_depth"] = search_depth
+    if exact_td_empties != 0:
+        kwargs["exact_td_empties"] = exact_td_empties
+    if epsilon_schedule != "constant":
+        kwargs["epsilon_schedule"] = epsilon_schedule
+    if exploration != "uniform":
+        kwargs["exploration"] = exploration
+    if league is not None:
+        kwargs["league"] = league
+    if gate is not None:
+        kwargs["gate"] = gate
+    if generation_callback is not None:
+        kwargs["generation_callback"] = generation_callback
+    if gate_log is not None:
+        kwargs["gate_log"] = str(gate_log)
     try:
         return bytes(module.train_to_bytes(**kwargs))
     except TypeError as exc:
@@ -130,6 +177,21 @@ def train_to_uncompressed_bytes(
     progress_callback: ProgressCallback | None = None,
     alpha_decay: str = "none",
     alpha_decay_start_game: int = 0,
+    patterns: Sequence[Sequence[int]] | None = None,
+    scalar_features: Sequence[str] | None = None,
+    checkpoint_interval: int = 0,
+    checkpoint_callback: CheckpointCallback | None = None,
+    metrics_callback: MetricsCallback | None = None,
+    metrics_log: str | Path | None = None,
+    parallel_mode: str = "independent",
+    search_depth: int = 2,
+    exact_td_empties: int = 0,
+    epsilon_schedule: str = "constant",
+    exploration: str = "uniform",
+    league: str | None = None,
+    gate: str | None = None,
+    generation_callback: GenerationCallback | None = None,
+    gate_log: str | Path | None = None,
 ) -> bytes:
     module = _load_extension()
     kwargs = dict(
@@ -150,6 +212,35 @@ def train_to_uncompressed_bytes(
         kwargs["alpha_decay"] = alpha_decay
     if alpha_decay_start_game != 0:
         kwargs["alpha_decay_start_game"] = alpha_decay_start_game
+    if patterns is not None:
+        kwargs["patterns"] = [list(pattern) for pattern in patterns]
+    if scalar_features is not None:
+        kwargs["scalar_features"] = list(scalar_features)
+    if checkpoint_callback is not None:
+        kwargs["checkpoint_interval"] = checkpoint_interval
+        kwargs["checkpoint_callback"] = checkpoint_callback
+    if metrics_callback is not None:
+        kwargs["metrics_callback"] = metrics_callback
+    if metrics_log is not None:
+        kwargs["metrics_log"] = str(metrics_log)
+    if parallel_mode != "independent":
+        kwargs["parallel_mode"] = parallel_mode
+    if search_depth != 2:
+        kwargs["search_depth"] = search_depth
+    if exact_td_empties != 0:
+        kwargs["exact_td_empties"] = exact_td_empties
+    if epsilon_schedule != "constant":
+        kwargs["epsilon_schedule"] = epsilon_schedule
+    if exploration != "uniform":
+        kwargs["exploration"] = exploration
+    if league is not None:
+        kwargs["league"] = league
+    if gate is not None:
+        kwargs["gate"] = gate
+    if generation_callback is not None:
+        kwargs["generation_callback"] = generation_callback
+    if gate_log is not None:
+        kwargs["gate_log"] = str(gate_log)
     try:
         return bytes(module.train_to_uncompressed_bytes(**kwargs))
     except TypeError as exc:
@@ -165,6 +256,36 @@ def train_to_uncompressed_bytes(
         return bytes(module.train_to_uncompressed_bytes(**kwargs))
 
 
+def resume_training(
+    checkpoint: bytes,
+    progress_interval: int = 0,
+    progress_callback: ProgressCallback | None = None,
+    checkpoint_interval: int = 0,
+    checkpoint_callback: CheckpointCallback | None = None,
+    metrics_callback: MetricsCallback | None = None,
+    metrics_log: str | Path | None = None,
+    compress: bool = True,
+) -> bytes:
+    """Finish a run from bytes handed to a `checkpoint_callback`.
+
+    The returned model is identical to the one the uninterrupted run would
+    have produced.
+    """
+    module = _load_extension()
+    return bytes(
+        module.resume_training(
+            bytes(checkpoint),
+            progress_interval=progress_interval,
+            progress_callback=progress_callback,
+            checkpoint_interval=checkpoint_interval,
+            checkpoint_callback=checkpoint_callback,
+            metrics_callback=metrics_callback,
+            metrics_log=None if metrics_log is None else str(metrics_log),
+            compress=compress,
+        )
+    )
+
+
 def compress_model_bytes(data: bytes) -> bytes:
     module = _load_extension()
     return bytes(module.compress_model_bytes(bytes(data)))
@@ -173,3 +294,27 @@ def compress_model_bytes(data: bytes) -> bytes:
 def decompress_model_bytes(data: bytes) -> bytes:
     module = _load_extension()
     return bytes(module.decompress_model_bytes(bytes(data)))
+
+
+def new_board(black: int | None = None, white: int | None = None) -> object:
+    """Returns an engine board, the start position unless both masks are given."""
+    module = _load_extension()
+    if black is None and white is None:
+        return module.Board()
+    if black is None or white is None:
+        raise ValueError("black and white masks must be given together")
+    return module.Board.from_bitboards(black, white)
+
+
+def load_evaluator(data: bytes) -> object:
+    """Loads model bytes into the engine's n-tuple evaluator."""
+    module = _load_extension()
+    return module.NTupleEvaluator.from_bytes(bytes(data))
+
+
+def new_searcher(
+    evaluator: object, level: int, timeout_ms: int | None = None
+) -> object:
+    """Returns an engine searcher for `level`, optionally with a time budget."""
+    module = _load_extension()
+    return module.Searcher(evaluator, level, timeout_ms=timeout_ms)
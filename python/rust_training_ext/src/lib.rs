@@ -1,45 +1,183 @@
-use pyo3::exceptions::PyRuntimeError;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use reversi::ai::features::ScalarFeature;
+use reversi::ai::level::LevelConfig;
+use reversi::ai::ntuple::NTupleEvaluator;
+use reversi::ai::search::Searcher;
+use reversi::board::Board;
+use reversi::training::gating::{GatingConfig, GatingLog, GenerationReport, run_gating};
+use reversi::training::{
+    AlphaDecayStrategy, CheckpointCallback, EpsilonSchedule, ExplorationPolicy, LeagueConfig,
+    NetworkLayout, ParallelMode, ProgressCallback, TrainingConfig, TrainingLog, TrainingProgress,
+    TrainingRun, TuplePatternSet,
+};
 
-#[pyfunction(signature = (
-    games,
-    alpha = 0.001,
-    alpha_decay = "none",
-    alpha_decay_start_game = 0,
-    lambda_ = 0.7,
-    epsilon = 0.1,
-    seed = 42,
-    threads = 1,
-    initial_model = None,
-    random_opening_plies = 0,
-    progress_interval = 0,
-    progress_callback = None
-))]
-fn train_to_bytes(
-    py: Python<'_>,
-    games: usize,
-    alpha: f32,
-    alpha_decay: &str,
-    alpha_decay_start_game: usize,
-    lambda_: f32,
-    epsilon: f64,
-    seed: u64,
-    threads: usize,
-    initial_model: Option<Vec<u8>>,
-    random_opening_plies: usize,
+fn parse_network_layout(
+    patterns: Option<Vec<Vec<u8>>>,
+    scalar_features: Option<Vec<String>>,
+) -> PyResult<Option<NetworkLayout>> {
+    if patterns.is_none() && scalar_features.is_none() {
+        return Ok(None);
+    }
+    let mut layout = NetworkLayout::default();
+    if let Some(patterns) = patterns {
+        layout.tuple_patterns =
+            Arc::new(TuplePatternSet::new(patterns).map_err(PyValueError::new_err)?);
+    }
+    if let Some(names) = scalar_features {
+        layout.scalar_features = names
+            .iter()
+            .map(|name| ScalarFeature::from_name(name))
+            .collect::<Result<_, _>>()
+            .map_err(PyValueError::new_err)?;
+    }
+    Ok(Some(layout))
+}
+
+/// Python callbacks and outputs attached to a training run.
+#[derive(Default)]
+struct TrainingHooks {
     progress_interval: usize,
     progress_callback: Option<Py<PyAny>>,
+    metrics_callback: Option<Py<PyAny>>,
+    metrics_log: Option<String>,
+    checkpoint_interval: usize,
+    checkpoint_callback: Option<Py<PyAny>>,
+}
+
+impl TrainingHooks {
+    /// Reads the hooks from keyword arguments named like the fields,
+    /// rejecting any other keyword.
+    fn from_kwargs(function: &str, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut hooks = Self::default();
+        for (key, value) in kwargs.into_iter().flatten() {
+            hooks.set(function, &key.extract::<String>()?, &value)?;
+        }
+        Ok(hooks)
+    }
+
+    /// Sets the hook named `key`, rejecting keywords that name no hook.
+    fn set(&mut self, function: &str, key: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        match key {
+            "progress_interval" => self.progress_interval = value.extract()?,
+            "progress_callback" => self.progress_callback = value.extract()?,
+            "metrics_callback" => self.metrics_callback = value.extract()?,
+            "metrics_log" => self.metrics_log = value.extract()?,
+            "checkpoint_interval" => self.checkpoint_interval = value.extract()?,
+            "checkpoint_callback" => self.checkpoint_callback = value.extract()?,
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "{function}() got an unexpected keyword argument '{key}'"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn metrics_dict<'py>(py: Python<'py>, progress: &TrainingProgress) -> PyResult<Bound<'py, PyDict>> {
+    let metrics = &progress.metrics;
+    let dict = PyDict::new(py);
+    dict.set_item("completed", progress.completed)?;
+    dict.set_item("total", progress.total)?;
+    dict.set_item("elapsed_seconds", progress.elapsed_seconds)?;
+    dict.set_item("games", metrics.games)?;
+    dict.set_item("mean_game_length", metrics.mean_game_length)?;
+    dict.set_item("black_win_rate", metrics.black_win_rate)?;
+    dict.set_item("white_win_rate", metrics.white_win_rate)?;
+    dict.set_item("mean_abs_td_error", metrics.mean_abs_td_error)?;
+    dict.set_item(
+        "phase_mean_abs_td_error",
+        metrics.phase_mean_abs_td_error.clone(),
+    )?;
+    dict.set_item("clipped_update_fraction", metrics.clipped_update_fraction)?;
+    dict.set_item("weight_norm", metrics.weight_norm)?;
+    dict.set_item("phase_weight_norms", metrics.phase_weight_norms.clone())?;
+    Ok(dict)
+}
+
+/// Forwards progress to the metrics log and the Python callbacks, keeping
+/// the first Python error in `error`.
+fn progress_reporter<'a>(
+    log: &'a mut Option<TrainingLog>,
+    progress_callback: &'a Option<Py<PyAny>>,
+    metrics_callback: &'a Option<Py<PyAny>>,
+    error: &'a mut Option<PyErr>,
+) -> impl FnMut(&TrainingProgress) -> Result<(), String> + 'a {
+    move |progress: &TrainingProgress| -> Result<(), String> {
+        if let Some(log) = log.as_mut() {
+            log.write(progress)?;
+        }
+        if progress_callback.is_none() && metrics_callback.is_none() {
+            return Ok(());
+        }
+        Python::with_gil(|py| -> PyResult<()> {
+            if let Some(callback) = progress_callback.as_ref() {
+                callback.bind(py).call1((
+                    progress.completed,
+                    progress.total,
+                    progress.elapsed_seconds,
+                ))?;
+            }
+            if let Some(callback) = metrics_callback.as_ref() {
+                callback.bind(py).call1((metrics_dict(py, progress)?,))?;
+            }
+            Ok(())
+        })
+        .map_err(|err| {
+            *error = Some(err);
+            "python progress callback failed".to_string()
+        })
+    }
+}
+
+/// Trains `run` to completion with the GIL released. `checkpoint_callback`
+/// receives the checkpoint bytes accepted by `resume_training`, and
+/// `metrics_callback` a dict of [`TrainingProgress`] fields.
+fn run_training(
+    py: Python<'_>,
+    mut run: TrainingRun,
+    hooks: TrainingHooks,
+    compress: bool,
 ) -> PyResult<Vec<u8>> {
-    let alpha_decay = reversi::training::AlphaDecayStrategy::from_name(alpha_decay)
-        .map_err(PyRuntimeError::new_err)?;
-    let mut callback_error: Option<PyErr> = None;
-    let mut progress = |completed: usize, total: usize, elapsed: f64| -> Result<(), String> {
-        if let Some(callback) = progress_callback.as_ref() {
+    let TrainingHooks {
+        progress_interval,
+        progress_callback,
+        metrics_callback,
+        metrics_log,
+        checkpoint_interval,
+        checkpoint_callback,
+    } = hooks;
+    let mut log = metrics_log
+        .map(|path| TrainingLog::open(Path::new(&path)))
+        .transpose()
+        .map_err(PyValueError::new_err)?;
+    let has_progress_callback =
+        progress_callback.is_some() || metrics_callback.is_some() || log.is_some();
+    let mut progress_error: Option<PyErr> = None;
+    let mut progress = progress_reporter(
+        &mut log,
+        &progress_callback,
+        &metrics_callback,
+        &mut progress_error,
+    );
+    let mut checkpoint_error: Option<PyErr> = None;
+    let mut checkpoint = |run: &TrainingRun| -> Result<(), String> {
+        if let Some(callback) = checkpoint_callback.as_ref() {
+            let bytes = run.to_checkpoint_bytes()?;
             Python::with_gil(|py| {
-                callback.bind(py).call1((completed, total, elapsed)).map_err(|err| {
-                    callback_error = Some(err);
-                    "python progress callback failed".to_string()
-                })?;
+                callback
+                    .bind(py)
+                    .call1((PyBytes::new(py, &bytes),))
+                    .map_err(|err| {
+                        checkpoint_error = Some(err);
+                        "python checkpoint callback failed".to_string()
+                    })?;
                 Ok(())
             })
         } else {
@@ -47,44 +185,32 @@ fn train_to_bytes(
         }
     };
 
+    let has_checkpoint_callback = checkpoint_callback.is_some();
     let result = py.allow_threads(|| {
-        if progress_callback.is_some() {
-            reversi::training::train_to_bytes_with_alpha_decay(
-                games,
-                alpha,
-                alpha_decay,
-                alpha_decay_start_game,
-                lambda_,
-                epsilon,
-                seed,
-                threads,
-                initial_model.as_deref(),
-                random_opening_plies,
-                progress_interval,
-                Some(&mut progress),
-            )
+        let progress: Option<ProgressCallback<'_>> = if has_progress_callback {
+            Some(&mut progress)
         } else {
-            reversi::training::train_to_bytes_with_alpha_decay(
-                games,
-                alpha,
-                alpha_decay,
-                alpha_decay_start_game,
-                lambda_,
-                epsilon,
-                seed,
-                threads,
-                initial_model.as_deref(),
-                random_opening_plies,
-                progress_interval,
-                None,
-            )
+            None
+        };
+        let checkpoint: Option<CheckpointCallback<'_>> = if has_checkpoint_callback {
+            Some(&mut checkpoint)
+        } else {
+            None
+        };
+        run.train(progress_interval, progress, checkpoint_interval, checkpoint)?;
+        let network = run.into_network()?;
+        if compress {
+            network.to_bytes()
+        } else {
+            network.to_uncompressed_bytes()
         }
     });
+    drop(progress);
 
     match result {
         Ok(bytes) => Ok(bytes),
         Err(err) => {
-            if let Some(pyerr) = callback_error {
+            if let Some(pyerr) = progress_error.or(checkpoint_error) {
                 Err(pyerr)
             } else {
                 Err(PyRuntimeError::new_err(err))
@@ -93,90 +219,145 @@ fn train_to_bytes(
     }
 }
 
-#[pyfunction(signature = (
-    games,
-    alpha = 0.001,
-    alpha_decay = "none",
-    alpha_decay_start_game = 0,
-    lambda_ = 0.7,
-    epsilon = 0.1,
-    seed = 42,
-    threads = 1,
-    initial_model = None,
-    random_opening_plies = 0,
-    progress_interval = 0,
-    progress_callback = None
-))]
-fn train_to_uncompressed_bytes(
+/// Python hooks of a gated run.
+struct GatingHooks {
+    gating: GatingConfig,
+    generation_callback: Option<Py<PyAny>>,
+    gate_log: Option<String>,
+}
+
+impl GatingHooks {
+    /// Parses `gate`, returning `None` for a plain run; the generation hooks
+    /// are rejected without a gate.
+    fn parse(
+        gate: Option<&str>,
+        generation_callback: Option<Py<PyAny>>,
+        gate_log: Option<String>,
+    ) -> PyResult<Option<Self>> {
+        match gate {
+            Some(gate) => Ok(Some(Self {
+                gating: GatingConfig::parse(gate).map_err(PyRuntimeError::new_err)?,
+                generation_callback,
+                gate_log,
+            })),
+            None if generation_callback.is_some() || gate_log.is_some() => Err(
+                PyValueError::new_err("generation_callback and gate_log need a gate"),
+            ),
+            None => Ok(None),
+        }
+    }
+}
+
+fn generation_dict<'py>(
+    py: Python<'py>,
+    report: &GenerationReport,
+) -> PyResult<Bound<'py, PyDict>> {
+    let result = &report.result;
+    let dict = PyDict::new(py);
+    dict.set_item("generation", report.generation)?;
+    dict.set_item("games_trained", report.games_trained)?;
+    dict.set_item("match_games", result.games())?;
+    dict.set_item("wins", result.wins)?;
+    dict.set_item("losses", result.losses)?;
+    dict.set_item("draws", result.draws)?;
+    dict.set_item("score", result.score())?;
+    dict.set_item("llr", result.llr)?;
+    dict.set_item("promoted", result.promoted)?;
+    dict.set_item("elapsed_seconds", report.elapsed_seconds)?;
+    Ok(dict)
+}
+
+/// Runs the generations of a gated run with the GIL released and returns the
+/// final incumbent. `generation_callback` receives a dict per generation.
+fn run_gated_training(
     py: Python<'_>,
-    games: usize,
-    alpha: f32,
-    alpha_decay: &str,
-    alpha_decay_start_game: usize,
-    lambda_: f32,
-    epsilon: f64,
-    seed: u64,
-    threads: usize,
+    config: TrainingConfig,
     initial_model: Option<Vec<u8>>,
-    random_opening_plies: usize,
-    progress_interval: usize,
-    progress_callback: Option<Py<PyAny>>,
+    layout: Option<NetworkLayout>,
+    hooks: TrainingHooks,
+    gating: GatingHooks,
+    compress: bool,
 ) -> PyResult<Vec<u8>> {
-    let alpha_decay = reversi::training::AlphaDecayStrategy::from_name(alpha_decay)
-        .map_err(PyRuntimeError::new_err)?;
-    let mut callback_error: Option<PyErr> = None;
-    let mut progress = |completed: usize, total: usize, elapsed: f64| -> Result<(), String> {
-        if let Some(callback) = progress_callback.as_ref() {
-            Python::with_gil(|py| {
-                callback.bind(py).call1((completed, total, elapsed)).map_err(|err| {
-                    callback_error = Some(err);
-                    "python progress callback failed".to_string()
-                })?;
+    let TrainingHooks {
+        progress_interval,
+        progress_callback,
+        metrics_callback,
+        metrics_log,
+        checkpoint_callback,
+        ..
+    } = hooks;
+    if checkpoint_callback.is_some() {
+        return Err(PyValueError::new_err(
+            "gate cannot be combined with checkpoint_callback",
+        ));
+    }
+    let GatingHooks {
+        gating,
+        generation_callback,
+        gate_log,
+    } = gating;
+    let mut log = metrics_log
+        .map(|path| TrainingLog::open(Path::new(&path)))
+        .transpose()
+        .map_err(PyValueError::new_err)?;
+    let mut gate_log = gate_log
+        .map(|path| GatingLog::open(Path::new(&path)))
+        .transpose()
+        .map_err(PyValueError::new_err)?;
+    let has_progress_callback =
+        progress_callback.is_some() || metrics_callback.is_some() || log.is_some();
+    let mut progress_error: Option<PyErr> = None;
+    let mut progress = progress_reporter(
+        &mut log,
+        &progress_callback,
+        &metrics_callback,
+        &mut progress_error,
+    );
+    let mut generation_error: Option<PyErr> = None;
+    let mut generation = |report: &GenerationReport| -> Result<(), String> {
+        if let Some(log) = gate_log.as_mut() {
+            log.write(report)?;
+        }
+        if let Some(callback) = generation_callback.as_ref() {
+            Python::with_gil(|py| -> PyResult<()> {
+                callback.bind(py).call1((generation_dict(py, report)?,))?;
                 Ok(())
             })
-        } else {
-            Ok(())
+            .map_err(|err| {
+                generation_error = Some(err);
+                "python generation callback failed".to_string()
+            })?;
         }
+        Ok(())
     };
 
     let result = py.allow_threads(|| {
-        if progress_callback.is_some() {
-            reversi::training::train_to_uncompressed_bytes_with_alpha_decay(
-                games,
-                alpha,
-                alpha_decay,
-                alpha_decay_start_game,
-                lambda_,
-                epsilon,
-                seed,
-                threads,
-                initial_model.as_deref(),
-                random_opening_plies,
-                progress_interval,
-                Some(&mut progress),
-            )
+        let progress: Option<ProgressCallback<'_>> = if has_progress_callback {
+            Some(&mut progress)
         } else {
-            reversi::training::train_to_uncompressed_bytes_with_alpha_decay(
-                games,
-                alpha,
-                alpha_decay,
-                alpha_decay_start_game,
-                lambda_,
-                epsilon,
-                seed,
-                threads,
-                initial_model.as_deref(),
-                random_opening_plies,
-                progress_interval,
-                None,
-            )
+            None
+        };
+        let network = run_gating(
+            &config,
+            &gating,
+            initial_model.as_deref(),
+            layout.as_ref(),
+            progress_interval,
+            progress,
+            Some(&mut generation),
+        )?;
+        if compress {
+            network.to_bytes()
+        } else {
+            network.to_uncompressed_bytes()
         }
     });
+    drop(progress);
 
     match result {
         Ok(bytes) => Ok(bytes),
         Err(err) => {
-            if let Some(pyerr) = callback_error {
+            if let Some(pyerr) = progress_error.or(generation_error) {
                 Err(pyerr)
             } else {
                 Err(PyRuntimeError::new_err(err))
@@ -185,6 +366,179 @@ fn train_to_uncompressed_bytes(
     }
 }
 
+/// Starts a plain run, or a gated one when `gating` is set.
+fn start_training(
+    py: Python<'_>,
+    config: TrainingConfig,
+    initial_model: Option<Vec<u8>>,
+    layout: Option<NetworkLayout>,
+    hooks: TrainingHooks,
+    gating: Option<GatingHooks>,
+    compress: bool,
+) -> PyResult<Vec<u8>> {
+    if let Some(gating) = gating {
+        return run_gated_training(py, config, initial_model, layout, hooks, gating, compress);
+    }
+    let run = py
+        .allow_threads(|| TrainingRun::new(config, initial_model.as_deref(), layout.as_ref()))
+        .map_err(PyRuntimeError::new_err)?;
+    run_training(py, run, hooks, compress)
+}
+
+/// Keyword arguments of `train_to_bytes` and `train_to_uncompressed_bytes`.
+struct TrainingOptions {
+    config: TrainingConfig,
+    initial_model: Option<Vec<u8>>,
+    patterns: Option<Vec<Vec<u8>>>,
+    scalar_features: Option<Vec<String>>,
+    hooks: TrainingHooks,
+    gate: Option<String>,
+    generation_callback: Option<Py<PyAny>>,
+    gate_log: Option<String>,
+}
+
+impl TrainingOptions {
+    /// Reads the options of a `games`-game run from keyword arguments named
+    /// like the [`TrainingConfig`] fields and the hooks, rejecting any other
+    /// keyword.
+    fn from_kwargs(
+        function: &str,
+        games: usize,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let mut options = Self {
+            config: TrainingConfig {
+                games,
+                alpha: 0.001,
+                alpha_decay: AlphaDecayStrategy::None,
+                alpha_decay_start_game: 0,
+                lambda_: 0.7,
+                epsilon: 0.1,
+                seed: 42,
+                threads: 1,
+                random_opening_plies: 0,
+                parallel_mode: ParallelMode::Independent,
+                search_depth: 2,
+                exact_td_empties: 0,
+                epsilon_schedule: EpsilonSchedule::Constant,
+                exploration: ExplorationPolicy::Uniform,
+                league: None,
+            },
+            initial_model: None,
+            patterns: None,
+            scalar_features: None,
+            hooks: TrainingHooks::default(),
+            gate: None,
+            generation_callback: None,
+            gate_log: None,
+        };
+        let config = &mut options.config;
+        for (key, value) in kwargs.into_iter().flatten() {
+            let key: String = key.extract()?;
+            let name = || value.extract::<String>();
+            match key.as_str() {
+                "alpha" => config.alpha = value.extract()?,
+                "alpha_decay" => {
+                    config.alpha_decay =
+                        AlphaDecayStrategy::from_name(&name()?).map_err(PyRuntimeError::new_err)?
+                }
+                "alpha_decay_start_game" => config.alpha_decay_start_game = value.extract()?,
+                "lambda_" => config.lambda_ = value.extract()?,
+                "epsilon" => config.epsilon = value.extract()?,
+                "seed" => config.seed = value.extract()?,
+                "threads" => config.threads = value.extract()?,
+                "random_opening_plies" => config.random_opening_plies = value.extract()?,
+                "parallel_mode" => {
+                    config.parallel_mode =
+                        ParallelMode::parse(&name()?).map_err(PyRuntimeError::new_err)?
+                }
+                "search_depth" => config.search_depth = value.extract()?,
+                "exact_td_empties" => config.exact_td_empties = value.extract()?,
+                "epsilon_schedule" => {
+                    config.epsilon_schedule =
+                        EpsilonSchedule::parse(&name()?).map_err(PyRuntimeError::new_err)?
+                }
+                "exploration" => {
+                    config.exploration =
+                        ExplorationPolicy::parse(&name()?).map_err(PyRuntimeError::new_err)?
+                }
+                "league" => {
+                    config.league = value
+                        .extract::<Option<String>>()?
+                        .map(|league| LeagueConfig::parse(&league))
+                        .transpose()
+                        .map_err(PyRuntimeError::new_err)?
+                }
+                "initial_model" => options.initial_model = value.extract()?,
+                "patterns" => options.patterns = value.extract()?,
+                "scalar_features" => options.scalar_features = value.extract()?,
+                "gate" => options.gate = value.extract()?,
+                "generation_callback" => options.generation_callback = value.extract()?,
+                "gate_log" => options.gate_log = value.extract()?,
+                _ => options.hooks.set(function, &key, &value)?,
+            }
+        }
+        Ok(options)
+    }
+
+    fn train(self, py: Python<'_>, compress: bool) -> PyResult<Vec<u8>> {
+        let layout = parse_network_layout(self.patterns, self.scalar_features)?;
+        let gating = GatingHooks::parse(
+            self.gate.as_deref(),
+            self.generation_callback,
+            self.gate_log,
+        )?;
+        start_training(
+            py,
+            self.config,
+            self.initial_model,
+            layout,
+            self.hooks,
+            gating,
+            compress,
+        )
+    }
+}
+
+/// Trains a model for `games` self-play games and returns its compressed
+/// bytes. The other settings are keyword arguments named like the
+/// [`TrainingConfig`] fields, the progress hooks and the `gate` hooks.
+#[pyfunction(signature = (games, **options))]
+fn train_to_bytes(
+    py: Python<'_>,
+    games: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<u8>> {
+    TrainingOptions::from_kwargs("train_to_bytes", games, options)?.train(py, true)
+}
+
+/// Like `train_to_bytes`, but returns the uncompressed model bytes.
+#[pyfunction(signature = (games, **options))]
+fn train_to_uncompressed_bytes(
+    py: Python<'_>,
+    games: usize,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<u8>> {
+    TrainingOptions::from_kwargs("train_to_uncompressed_bytes", games, options)?.train(py, false)
+}
+
+/// Continues a run from bytes passed to a `checkpoint_callback`, producing
+/// exactly the model the uninterrupted run would have. The progress, metrics
+/// and checkpoint hooks are keyword arguments named as for `train_to_bytes`.
+#[pyfunction(signature = (checkpoint, compress = true, **hooks))]
+fn resume_training(
+    py: Python<'_>,
+    checkpoint: Vec<u8>,
+    compress: bool,
+    hooks: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<u8>> {
+    let hooks = TrainingHooks::from_kwargs("resume_training", hooks)?;
+    let run = py
+        .allow_threads(|| TrainingRun::from_checkpoint_bytes(&checkpoint))
+        .map_err(PyValueError::new_err)?;
+    run_training(py, run, hooks, compress)
+}
+
 #[pyfunction]
 fn compress_model_bytes(py: Python<'_>, data: Vec<u8>) -> PyResult<Vec<u8>> {
     py.allow_threads(|| reversi::ai::ntuple::compress_model_bytes(&data))
@@ -199,11 +553,174 @@ fn decompress_model_bytes(py: Python<'_>, data: Vec<u8>) -> PyResult<Vec<u8>> {
     .map_err(PyRuntimeError::new_err)
 }
 
+#[pyclass(name = "Board", module = "_reversi_training")]
+#[derive(Clone)]
+struct PyBoard {
+    inner: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    #[new]
+    fn new() -> Self {
+        Self {
+            inner: Board::new(),
+        }
+    }
+
+    #[staticmethod]
+    fn from_bitboards(black: u64, white: u64) -> PyResult<Self> {
+        if black & white != 0 {
+            return Err(PyValueError::new_err("black and white masks overlap"));
+        }
+        Ok(Self {
+            inner: Board::from_bitboards(black, white),
+        })
+    }
+
+    fn bitboards(&self) -> (u64, u64) {
+        self.inner.bitboards()
+    }
+
+    fn legal_moves(&self, is_black: bool) -> u64 {
+        self.inner.legal_moves(is_black)
+    }
+
+    /// Places a disc and returns the flipped mask, rejecting illegal moves.
+    fn place(&mut self, pos: usize, is_black: bool) -> PyResult<u64> {
+        match self.inner.place(pos, is_black) {
+            0 => Err(PyValueError::new_err(format!("illegal move: {pos}"))),
+            flips => Ok(flips),
+        }
+    }
+
+    fn count(&self) -> (u8, u8) {
+        self.inner.count()
+    }
+
+    fn empty_count(&self) -> u8 {
+        self.inner.empty_count()
+    }
+
+    /// Returns 64 cells in row-major order: 0 empty, 1 black, 2 white.
+    fn to_list(&self) -> Vec<u8> {
+        self.inner.to_array().to_vec()
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        let (black, white) = self.inner.bitboards();
+        format!("Board(black={black:#018x}, white={white:#018x})")
+    }
+}
+
+#[pyclass(name = "NTupleEvaluator", module = "_reversi_training", frozen)]
+struct PyNTupleEvaluator {
+    inner: Arc<NTupleEvaluator>,
+}
+
+#[pymethods]
+impl PyNTupleEvaluator {
+    /// Loads a compressed or uncompressed model file's bytes.
+    #[staticmethod]
+    fn from_bytes(py: Python<'_>, data: Vec<u8>) -> PyResult<Self> {
+        let evaluator = py
+            .allow_threads(|| NTupleEvaluator::from_bytes(&data))
+            .map_err(PyValueError::new_err)?;
+        Ok(Self {
+            inner: Arc::new(evaluator),
+        })
+    }
+
+    fn evaluate(&self, board: &PyBoard, is_black: bool) -> f32 {
+        self.inner.evaluate(&board.inner, is_black)
+    }
+}
+
+#[pyclass(name = "Searcher", module = "_reversi_training", frozen)]
+struct PySearcher {
+    evaluator: Arc<NTupleEvaluator>,
+    level: u8,
+    timeout_ms: Option<u64>,
+}
+
+impl PySearcher {
+    /// Builds a fresh engine searcher per call so searches never share state.
+    fn run<T: Send>(
+        &self,
+        py: Python<'_>,
+        board: &PyBoard,
+        is_black: bool,
+        search: impl FnOnce(&mut Searcher<'_>, &Board, bool) -> T + Send,
+    ) -> PyResult<T> {
+        let board = board.inner;
+        if board.legal_moves(is_black) == 0 {
+            return Err(PyValueError::new_err("no legal moves for the side to move"));
+        }
+        let evaluator = Arc::clone(&self.evaluator);
+        let (level, timeout_ms) = (self.level, self.timeout_ms);
+        Ok(py.allow_threads(move || {
+            let mut searcher = match timeout_ms {
+                Some(ms) => Searcher::with_timeout(&evaluator, level, Duration::from_millis(ms)),
+                None => Searcher::new(&evaluator, level),
+            };
+            search(&mut searcher, &board, is_black)
+        }))
+    }
+}
+
+#[pymethods]
+impl PySearcher {
+    #[new]
+    #[pyo3(signature = (evaluator, level, timeout_ms = None))]
+    fn new(evaluator: &PyNTupleEvaluator, level: u8, timeout_ms: Option<u64>) -> PyResult<Self> {
+        LevelConfig::try_for_level(level).map_err(PyValueError::new_err)?;
+        if timeout_ms == Some(0) {
+            return Err(PyValueError::new_err("timeout_ms must be positive"));
+        }
+        Ok(Self {
+            evaluator: Arc::clone(&evaluator.inner),
+            level,
+            timeout_ms,
+        })
+    }
+
+    /// Returns the best move for `is_black`.
+    fn search(&self, py: Python<'_>, board: &PyBoard, is_black: bool) -> PyResult<usize> {
+        self.run(py, board, is_black, |searcher, board, is_black| {
+            searcher.search(board, is_black)
+        })
+    }
+
+    /// Returns `(move, score)` for every root move, scored for `is_black`.
+    fn analyze(
+        &self,
+        py: Python<'_>,
+        board: &PyBoard,
+        is_black: bool,
+    ) -> PyResult<Vec<(usize, f32)>> {
+        self.run(py, board, is_black, |searcher, board, is_black| {
+            searcher.analyze(board, is_black)
+        })
+    }
+}
+
 #[pymodule]
 fn _reversi_training(_py: Python<'_>, module: &Bound<'_, PyModule>) -> PyResult<()> {
     module.add_function(wrap_pyfunction!(train_to_bytes, module)?)?;
     module.add_function(wrap_pyfunction!(train_to_uncompressed_bytes, module)?)?;
+    module.add_function(wrap_pyfunction!(resume_training, module)?)?;
     module.add_function(wrap_pyfunction!(compress_model_bytes, module)?)?;
     module.add_function(wrap_pyfunction!(decompress_model_bytes, module)?)?;
+    module.add_class::<PyBoard>()?;
+    module.add_class::<PyNTupleEvaluator>()?;
+    module.add_class::<PySearcher>()?;
     Ok(())
 }
@@ -0,0 +1,54 @@
+import struct
+
+import numpy as np
+import pytest
+
+from dataset import (
+    DATASET_DTYPE,
+    HEADER_SIZE,
+    MAGIC,
+    NO_EXACT_SCORE,
+    VERSION,
+    parse_dataset,
+    training_labels,
+)
+
+
+def _record(black, white, search_score, ply, is_black, final_score, exact_score):
+    return struct.pack(
+        "<QQfBBbb", black, white, search_score, ply, is_black, final_score, exact_score
+    )
+
+
+def _header(version=VERSION, record_size=DATASET_DTYPE.itemsize):
+    return struct.pack("<4sHH", MAGIC, version, record_size)
+
+
+def test_parse_dataset_maps_records_to_fields():
+    payload = (
+        _header()
+        + _record(0x0000000810000000, 0x0000001008000000, 1.5, 0, 1, 6, NO_EXACT_SCORE)
+        + _record(0x1, 0x2, -3.0, 58, 0, -10, -8)
+    )
+
+    rows = parse_dataset(payload)
+
+    assert len(rows) == 2
+    assert rows["black"][0] == 0x0000000810000000
+    assert rows["search_score"][1] == pytest.approx(-3.0)
+    assert rows["ply"].tolist() == [0, 58]
+    assert rows["is_black"].tolist() == [1, 0]
+    np.testing.assert_array_equal(training_labels(rows), [6.0, -8.0])
+
+
+def test_parse_dataset_rejects_bad_headers_and_partial_records():
+    record = _record(0x1, 0x2, 0.0, 0, 1, 0, NO_EXACT_SCORE)
+
+    with pytest.raises(ValueError, match="magic"):
+        parse_dataset(b"XXXX" + _header()[4:] + record)
+    with pytest.raises(ValueError, match="version"):
+        parse_dataset(_header(version=VERSION + 1) + record)
+    with pytest.raises(ValueError, match="partial"):
+        parse_dataset(_header() + record[:-1])
+    assert len(parse_dataset(_header())) == 0
+    assert HEADER_SIZE == len(_header())
@@ -1,4 +1,6 @@
+from pathlib import Path
 from types import SimpleNamespace
+
 import pytest
 
 import rust_training
@@ -82,6 +84,8 @@ def test_train_to_bytes_delegates_to_extension(monkeypatch) -> None:
         random_opening_plies=4,
         progress_interval=2,
         progress_callback=progress_callback,
+        patterns=[(0, 1, 2), [9, 18]],
+        scalar_features=("mobility", "parity"),
     )
 
     assert payload == b"model-bytes"
@@ -97,6 +101,8 @@ def test_train_to_bytes_delegates_to_extension(monkeypatch) -> None:
     assert captured["random_opening_plies"] == 4
     assert captured["progress_interval"] == 2
     assert captured["progress_callback"] is progress_callback
+    assert captured["patterns"] == [[0, 1, 2], [9, 18]]
+    assert captured["scalar_features"] == ["mobility", "parity"]
     assert callback_calls == [(1, 3, 0.25)]
 
 
@@ -138,6 +144,252 @@ def test_train_to_uncompressed_bytes_delegates_to_extension(monkeypatch) -> None
     assert captured["initial_model"] == b"checkpoint-bytes"
     assert captured["random_opening_plies"] == 4
     assert captured["progress_interval"] == 2
+    assert "patterns" not in captured
+    assert "scalar_features" not in captured
+    assert "checkpoint_callback" not in captured
+
+
+def test_checkpointing_and_resume_delegate_to_extension(monkeypatch) -> None:
+    captured: dict[str, object] = {}
+    saved: list[bytes] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _train_to_bytes(**kwargs):
+        captured["train"] = kwargs
+        kwargs["checkpoint_callback"](b"checkpoint-bytes")
+        return b"model-bytes"
+
+    def _resume_training(checkpoint, **kwargs):
+        captured["resume"] = (checkpoint, kwargs)
+        return b"resumed-model"
+
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(
+            train_to_bytes=_train_to_bytes, resume_training=_resume_training
+        ),
+    )
+
+    payload = rust_training.train_to_bytes(
+        games=10,
+        alpha=0.01,
+        lambda_=0.7,
+        epsilon=0.1,
+        seed=42,
+        threads=1,
+        initial_model=None,
+        random_opening_plies=0,
+        progress_interval=0,
+        checkpoint_interval=5,
+        checkpoint_callback=saved.append,
+    )
+    resumed = rust_training.resume_training(
+        bytearray(saved[0]),
+        checkpoint_interval=5,
+        metrics_log=Path("metrics.csv"),
+        compress=False,
+    )
+
+    assert payload == b"model-bytes"
+    assert captured["train"]["checkpoint_interval"] == 5
+    assert saved == [b"checkpoint-bytes"]
+    assert resumed == b"resumed-model"
+    checkpoint, kwargs = captured["resume"]
+    assert checkpoint == b"checkpoint-bytes"
+    assert kwargs["checkpoint_interval"] == 5
+    assert kwargs["compress"] is False
+    assert kwargs["checkpoint_callback"] is None
+    assert kwargs["metrics_callback"] is None
+    assert kwargs["metrics_log"] == "metrics.csv"
+
+
+def test_train_to_bytes_forwards_metrics_hooks(monkeypatch) -> None:
+    captured: dict[str, object] = {}
+    received: list[dict[str, object]] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _train_to_bytes(**kwargs):
+        captured.update(kwargs)
+        kwargs["metrics_callback"]({"completed": 1, "mean_abs_td_error": 0.5})
+        return b"model-bytes"
+
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(train_to_bytes=_train_to_bytes),
+    )
+
+    rust_training.train_to_bytes(
+        games=1,
+        alpha=0.01,
+        lambda_=0.7,
+        epsilon=0.1,
+        seed=42,
+        threads=1,
+        initial_model=None,
+        random_opening_plies=0,
+        progress_interval=1,
+        metrics_callback=received.append,
+        metrics_log=Path("logs/metrics.jsonl"),
+    )
+
+    assert captured["metrics_log"] == str(Path("logs/metrics.jsonl"))
+    assert received == [{"completed": 1, "mean_abs_td_error": 0.5}]
+
+
+def test_train_to_bytes_forwards_non_default_parallel_mode(monkeypatch) -> None:
+    calls: list[dict[str, object]] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _train_to_bytes(**kwargs):
+        calls.append(kwargs)
+        return b"model-bytes"
+
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(train_to_bytes=_train_to_bytes),
+    )
+
+    for parallel_mode in ("independent", "hogwild"):
+        rust_training.train_to_bytes(
+            games=1,
+            alpha=0.01,
+            lambda_=0.7,
+            epsilon=0.1,
+            seed=42,
+            threads=2,
+            initial_model=None,
+            random_opening_plies=0,
+            progress_interval=0,
+            parallel_mode=parallel_mode,
+        )
+
+    assert "parallel_mode" not in calls[0]
+    assert calls[1]["parallel_mode"] == "hogwild"
+
+
+def test_train_to_bytes_forwards_non_default_search_settings(monkeypatch) -> None:
+    calls: list[dict[str, object]] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _train_to_bytes(**kwargs):
+        calls.append(kwargs)
+        return b"model-bytes"
+
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(train_to_bytes=_train_to_bytes),
+    )
+
+    for search_depth, exact_td_empties in ((2, 0), (3, 12)):
+        rust_training.train_to_bytes(
+            games=1,
+            alpha=0.01,
+            lambda_=0.7,
+            epsilon=0.1,
+            seed=42,
+            threads=1,
+            initial_model=None,
+            random_opening_plies=0,
+            progress_interval=0,
+            search_depth=search_depth,
+            exact_td_empties=exact_td_empties,
+        )
+
+    assert "search_depth" not in calls[0]
+    assert "exact_td_empties" not in calls[0]
+    assert calls[1]["search_depth"] == 3
+    assert calls[1]["exact_td_empties"] == 12
+
+
+def test_train_to_bytes_forwards_non_default_exploration(monkeypatch) -> None:
+    calls: list[dict[str, object]] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _train_to_bytes(**kwargs):
+        calls.append(kwargs)
+        return b"model-bytes"
+
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(train_to_bytes=_train_to_bytes),
+    )
+
+    for epsilon_schedule, exploration, league in (
+        ("constant", "uniform", None),
+        ("phase:0.02", "boltzmann:1.5", "snapshot=100,random"),
+    ):
+        rust_training.train_to_bytes(
+            games=1,
+            alpha=0.01,
+            lambda_=0.7,
+            epsilon=0.2,
+            seed=42,
+            threads=1,
+            initial_model=None,
+            random_opening_plies=0,
+            progress_interval=0,
+            epsilon_schedule=epsilon_schedule,
+            exploration=exploration,
+            league=league,
+        )
+
+    assert "epsilon_schedule" not in calls[0]
+    assert "exploration" not in calls[0]
+    assert "league" not in calls[0]
+    assert calls[1]["epsilon_schedule"] == "phase:0.02"
+    assert calls[1]["exploration"] == "boltzmann:1.5"
+    assert calls[1]["league"] == "snapshot=100,random"
+
+
+def test_train_to_bytes_forwards_gate_only_when_set(monkeypatch) -> None:
+    calls: list[dict[str, object]] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _train_to_uncompressed_bytes(**kwargs):
+        calls.append(kwargs)
+        return b"model-bytes"
+
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(
+            train_to_uncompressed_bytes=_train_to_uncompressed_bytes
+        ),
+    )
+
+    def on_generation(_report: dict[str, object]) -> None:
+        pass
+
+    for gate, generation_callback, gate_log in (
+        (None, None, None),
+        ("generations=3,winrate=0.55", on_generation, Path("gate.jsonl")),
+    ):
+        rust_training.train_to_uncompressed_bytes(
+            games=1,
+            alpha=0.01,
+            lambda_=0.7,
+            epsilon=0.2,
+            seed=42,
+            threads=1,
+            initial_model=None,
+            random_opening_plies=0,
+            progress_interval=0,
+            gate=gate,
+            generation_callback=generation_callback,
+            gate_log=gate_log,
+        )
+
+    assert "gate" not in calls[0]
+    assert "generation_callback" not in calls[0]
+    assert "gate_log" not in calls[0]
+    assert calls[1]["gate"] == "generations=3,winrate=0.55"
+    assert calls[1]["generation_callback"] is on_generation
+    assert calls[1]["gate_log"] == "gate.jsonl"
 
 
 def test_train_to_bytes_prefers_local_release_extension(monkeypatch) -> None:
@@ -275,3 +527,45 @@ def test_train_to_bytes_rejects_parallel_threads_with_legacy_extension(
             random_opening_plies=0,
             progress_interval=0,
         )
+
+
+def test_engine_helpers_delegate_to_extension_classes(monkeypatch) -> None:
+    calls: list[tuple[str, tuple[object, ...], dict[str, object]]] = []
+    monkeypatch.setattr(rust_training, "_candidate_extension_paths", lambda: ())
+
+    def _recorder(name: str):
+        def _record(*args, **kwargs):
+            calls.append((name, args, kwargs))
+            return name
+
+        return _record
+
+    board = _recorder("Board")
+    board.from_bitboards = _recorder("Board.from_bitboards")
+    evaluator = SimpleNamespace(from_bytes=_recorder("NTupleEvaluator.from_bytes"))
+    monkeypatch.setattr(
+        rust_training,
+        "import_module",
+        lambda _name: SimpleNamespace(
+            Board=board,
+            NTupleEvaluator=evaluator,
+            Searcher=_recorder("Searcher"),
+        ),
+    )
+
+    assert rust_training.new_board() == "Board"
+    assert rust_training.new_board(1, 2) == "Board.from_bitboards"
+    with pytest.raises(ValueError, match="together"):
+        rust_training.new_board(black=1)
+    loaded = rust_training.load_evaluator(bytearray(b"model"))
+    assert loaded == "NTupleEvaluator.from_bytes"
+    assert rust_training.new_searcher("evaluator", 4) == "Searcher"
+    assert rust_training.new_searcher("evaluator", 8, timeout_ms=250) == "Searcher"
+
+    assert calls == [
+        ("Board", (), {}),
+        ("Board.from_bitboards", (1, 2), {}),
+        ("NTupleEvaluator.from_bytes", (b"model",), {}),
+        ("Searcher", ("evaluator", 4), {"timeout_ms": None}),
+        ("Searcher", ("evaluator", 8), {"timeout_ms": 250}),
+    ]
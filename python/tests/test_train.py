@@ -114,6 +114,37 @@ def test_verify_exported_model_detects_crc_mismatch() -> None:
         output.unlink(missing_ok=True)
 
 
+def test_verify_exported_model_accepts_tc_accumulators() -> None:
+    output = _output_path("_generated_tc_weights.bin")
+    pattern = [0, 1]
+    entries = 3 ** len(pattern) * NTupleNetwork.PHASE_COUNT
+    data = bytes([len(pattern), *pattern])
+    data += struct.pack(f"<{entries}f", *([0.5] * entries))
+    data += struct.pack(f"<{entries}I", *([1] * entries))
+    data += struct.pack(f"<{2 * entries}f", *([0.25] * (2 * entries)))
+
+    def model_bytes(data: bytes) -> bytes:
+        header = struct.pack(
+            "<4sIIII",
+            MAGIC,
+            6,
+            1,
+            zlib.crc32(data) & 0xFFFFFFFF,
+            NTupleNetwork.PHASE_COUNT,
+        )
+        return compress_model_bytes(header + data)
+
+    try:
+        output.write_bytes(model_bytes(data))
+        verify_exported_model(output, [pattern])
+
+        output.write_bytes(model_bytes(data[:-8]))
+        with pytest.raises(ValueError, match="TC accumulators truncated"):
+            verify_exported_model(output, [pattern])
+    finally:
+        output.unlink(missing_ok=True)
+
+
 def test_main_emits_progress_logs(capsys: pytest.CaptureFixture[str]) -> None:
     output = _output_path("_generated_progress_weights.bin")
     status = _output_path("_generated_progress_status.json")
@@ -219,10 +250,25 @@ def test_train_and_export_writes_checkpoints_and_resumes(monkeypatch) -> None:
             resume_from=resume,
             status_file=None,
             verify=True,
+            metrics_log=checkpoint_dir / "metrics.jsonl",
+            parallel_mode="sync:100",
+            exact_td_empties=10,
+            epsilon_schedule="linear:0.01:1000",
+            exploration="boltzmann:2",
+            league="positional=2,self=0.5",
         )
 
         assert result == output
         assert [call["games"] for call in calls] == [2, 2, 1]
+        assert all(
+            call["metrics_log"] == checkpoint_dir / "metrics.jsonl" for call in calls
+        )
+        assert all(call["parallel_mode"] == "sync:100" for call in calls)
+        assert all(call["search_depth"] == 2 for call in calls)
+        assert all(call["exact_td_empties"] == 10 for call in calls)
+        assert all(call["epsilon_schedule"] == "linear:0.01:1000" for call in calls)
+        assert all(call["exploration"] == "boltzmann:2" for call in calls)
+        assert all(call["league"] == "positional=2,self=0.5" for call in calls)
         assert calls[0]["initial_model"] == resume_bytes
         assert all(call["random_opening_plies"] == 4 for call in calls)
         assert all(call["alpha_decay"] == "inverse_game" for call in calls)
@@ -310,6 +356,65 @@ def test_train_and_export_supports_inverse_visit_with_checkpoints(monkeypatch) -
         checkpoint_dir.rmdir()
 
 
+def test_train_and_export_forwards_gate(
+    monkeypatch, capsys: pytest.CaptureFixture[str]
+) -> None:
+    output = _output_path("_generated_gated_weights.bin")
+    calls: list[dict[str, object]] = []
+
+    def _train_to_bytes(**kwargs):
+        calls.append(kwargs)
+        kwargs["generation_callback"](
+            {
+                "generation": 1,
+                "wins": 3,
+                "losses": 1,
+                "draws": 0,
+                "score": 0.75,
+                "promoted": True,
+            }
+        )
+        return b"gated-model"
+
+    monkeypatch.setattr("train.train_to_bytes", _train_to_bytes)
+
+    def _train(checkpoint_interval: int) -> Path:
+        return train_and_export(
+            games=4,
+            alpha=0.01,
+            lambda_=0.7,
+            epsilon=0.1,
+            output=output,
+            seed=42,
+            threads=1,
+            random_opening_plies=0,
+            alpha_decay="none",
+            alpha_decay_start_game=0,
+            progress_interval=0,
+            checkpoint_interval=checkpoint_interval,
+            checkpoint_dir=None,
+            resume_from=None,
+            status_file=None,
+            verify=False,
+            gate="generations=2,sprt=0:10",
+            gate_log=Path("gate.csv"),
+        )
+
+    try:
+        assert _train(0) == output
+        assert output.read_bytes() == b"gated-model"
+        assert calls[0]["gate"] == "generations=2,sprt=0:10"
+        assert calls[0]["gate_log"] == Path("gate.csv")
+        printed = capsys.readouterr().out
+        assert "[gate] generation 1: 3W-1L-0D score=0.750 promoted" in printed
+
+        with pytest.raises(ValueError, match="checkpoint_interval"):
+            _train(2)
+        assert len(calls) == 1
+    finally:
+        output.unlink(missing_ok=True)
+
+
 def test_main_writes_failed_status_file(monkeypatch) -> None:
     status = _output_path("_generated_failed_status.json")
 
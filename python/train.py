@@ -16,7 +16,9 @@ from export_model import HEADER_SIZE, MAGIC, VERSION
 from ntuple import NTupleNetwork
 from rust_training import decompress_model_bytes, train_to_bytes
 
-SUPPORTED_MODEL_VERSIONS = (3, VERSION)
+# Written instead of VERSION when training with alpha_decay="tc".
+TC_MODEL_VERSION = 6
+SUPPORTED_MODEL_VERSIONS = (3, VERSION, TC_MODEL_VERSION)
 
 
 def build_parser() -> argparse.ArgumentParser:
@@ -29,7 +31,7 @@ def build_parser() -> argparse.ArgumentParser:
     parser.add_argument(
         "--alpha-decay",
         type=str,
-        choices=("none", "inverse_game", "inverse_visit"),
+        choices=("none", "inverse_game", "inverse_visit", "tc"),
         default="none",
         help="Learning-rate decay schedule.",
     )
@@ -67,6 +69,58 @@ def build_parser() -> argparse.ArgumentParser:
         default=0,
         help="Training worker threads (0 uses the maximum available CPU count).",
     )
+    parser.add_argument(
+        "--parallel-mode",
+        default="independent",
+        help="How worker threads share weights: independent (merge once at the "
+        "end), sync:<games> (merge and redistribute every N games) or hogwild "
+        "(one lock-free shared table, not reproducible).",
+    )
+    parser.add_argument(
+        "--search-depth",
+        type=int,
+        default=2,
+        help="Plies searched when choosing each self-play move.",
+    )
+    parser.add_argument(
+        "--exact-td-empties",
+        type=int,
+        default=0,
+        help="Solve positions with at most this many empties exactly and use "
+        "the solved score as the TD target (0 disables).",
+    )
+    parser.add_argument(
+        "--epsilon-schedule",
+        default="constant",
+        help="How epsilon changes: constant, linear:<end>:<games>, "
+        "exponential:<decay>[:<floor>] or phase:<endgame> (opening uses --epsilon).",
+    )
+    parser.add_argument(
+        "--exploration",
+        default="uniform",
+        help="How exploratory moves are picked: uniform or boltzmann:<temperature> "
+        "(softmax over the searched move scores).",
+    )
+    parser.add_argument(
+        "--league",
+        default=None,
+        help="Train against an opponent pool instead of pure self-play, e.g. "
+        "snapshot=5000,keep=8,positional=2,random,self=0.2.",
+    )
+    parser.add_argument(
+        "--gate",
+        default=None,
+        help="Train generations of --games games from the incumbent and keep a "
+        "candidate only if it wins a fixed-seed match, e.g. "
+        "generations=10,games=400,sprt=0:10 or generations=5,winrate=0.55. "
+        "Cannot be combined with checkpoints.",
+    )
+    parser.add_argument(
+        "--gate-log",
+        type=Path,
+        default=None,
+        help="Optional .csv or .jsonl file each gating generation is appended to.",
+    )
     parser.add_argument(
         "--progress-interval",
         type=int,
@@ -103,6 +157,13 @@ def build_parser() -> argparse.ArgumentParser:
         default=None,
         help="Optional JSON file updated with the latest training status.",
     )
+    parser.add_argument(
+        "--metrics-log",
+        type=Path,
+        default=None,
+        help="Optional .csv or .jsonl file that training metrics are appended to "
+        "at every progress interval.",
+    )
     parser.add_argument(
         "--verify",
         action=argparse.BooleanOptionalAction,
@@ -270,6 +331,17 @@ def verify_exported_model(path: Path, tuple_patterns: Sequence[Sequence[int]]) -
                     )
                 offset = end
 
+    if version >= TC_MODEL_VERSION:
+        for phase_idx in range(phase_count):
+            for tuple_idx, pattern in enumerate(tuple_patterns):
+                end = offset + 3 ** len(pattern) * 8
+                if end > len(data):
+                    raise ValueError(
+                        "TC accumulators truncated at phase "
+                        f"{phase_idx}, tuple index {tuple_idx}"
+                    )
+                offset = end
+
     if offset != len(data):
         raise ValueError(
             f"unexpected trailing bytes in model data: {len(data) - offset}"
@@ -303,6 +375,15 @@ def train_and_export(
     resume_from: Path | None,
     status_file: Path | None,
     verify: bool,
+    metrics_log: Path | None = None,
+    parallel_mode: str = "independent",
+    search_depth: int = 2,
+    exact_td_empties: int = 0,
+    epsilon_schedule: str = "constant",
+    exploration: str = "uniform",
+    league: str | None = None,
+    gate: str | None = None,
+    gate_log: Path | None = None,
 ) -> Path:
     """Run training, export the model, and validate the resulting binary."""
     if games < 0:
@@ -321,6 +402,8 @@ def train_and_export(
         raise ValueError(f"progress_interval must be >= 0, got {progress_interval}")
     if checkpoint_interval < 0:
         raise ValueError(f"checkpoint_interval must be >= 0, got {checkpoint_interval}")
+    if gate is not None and checkpoint_interval > 0:
+        raise ValueError("gate cannot be combined with checkpoint_interval")
 
     output_path = output
     output_path.parent.mkdir(parents=True, exist_ok=True)
@@ -364,6 +447,14 @@ def train_and_export(
         log_training_progress(completed, total, elapsed_seconds)
         emit_status(completed, state="running")
 
+    def on_generation(report: dict[str, object]) -> None:
+        print(
+            f"[gate] generation {report['generation']}: "
+            f"{report['wins']}W-{report['losses']}L-{report['draws']}D "
+            f"score={report['score']:.3f} "
+            f"{'promoted' if report['promoted'] else 'rejected'}"
+        )
+
     if checkpoint_interval == 0 or games == 0:
         model_bytes = train_to_bytes(
             games=games,
@@ -378,6 +469,16 @@ def train_and_export(
             progress_callback=on_progress,
             alpha_decay=alpha_decay,
             alpha_decay_start_game=alpha_decay_start_game,
+            metrics_log=metrics_log,
+            parallel_mode=parallel_mode,
+            search_depth=search_depth,
+            exact_td_empties=exact_td_empties,
+            epsilon_schedule=epsilon_schedule,
+            exploration=exploration,
+            league=league,
+            gate=gate,
+            generation_callback=on_generation if gate is not None else None,
+            gate_log=gate_log,
         )
         output_path.write_bytes(model_bytes)
         if verify:
@@ -417,6 +518,13 @@ def train_and_export(
             progress_callback=on_progress,
             alpha_decay=alpha_decay,
             alpha_decay_start_game=alpha_decay_start_game + completed_games,
+            metrics_log=metrics_log,
+            parallel_mode=parallel_mode,
+            search_depth=search_depth,
+            exact_td_empties=exact_td_empties,
+            epsilon_schedule=epsilon_schedule,
+            exploration=exploration,
+            league=league,
         )
         completed_games += chunk_games
 
@@ -472,6 +580,15 @@ def main(argv: list[str] | None = None) -> int:
             resume_from=args.resume_from,
             status_file=args.status_file,
             verify=args.verify,
+            metrics_log=args.metrics_log,
+            parallel_mode=args.parallel_mode,
+            search_depth=args.search_depth,
+            exact_td_empties=args.exact_td_empties,
+            epsilon_schedule=args.epsilon_schedule,
+            exploration=args.exploration,
+            league=args.league,
+            gate=args.gate,
+            gate_log=args.gate_log,
         )
         print(
             f"Model exported{(' and verified' if args.verify else '')}: {output_path} "
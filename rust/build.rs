@@ -1,10 +1,19 @@
 use std::env;
 use std::fs;
+use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
+#[allow(dead_code)]
+#[path = "src/ai/metadata.rs"]
+mod metadata;
+
+use metadata::ModelMetadata;
+
 const MODEL_ENV_VAR: &str = "REVERSI_MODEL_PATH";
+const PRINT_METADATA_ENV_VAR: &str = "REVERSI_PRINT_MODEL_METADATA";
 const DEFAULT_MODEL_PATH: &str = "src/ai/weights.bin";
 const OUT_FILE_NAME: &str = "embedded_weights.bin";
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xB5, 0x2F, 0xFD];
 
 fn main() {
     let manifest_dir =
@@ -15,6 +24,7 @@ fn main() {
         PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR must be set")).join(OUT_FILE_NAME);
 
     println!("cargo::rerun-if-env-changed={MODEL_ENV_VAR}");
+    println!("cargo::rerun-if-env-changed={PRINT_METADATA_ENV_VAR}");
     println!(
         "cargo::rerun-if-changed={}",
         selected_model_path.as_os_str().to_string_lossy()
@@ -27,6 +37,35 @@ fn main() {
             out_path.display()
         );
     }
+
+    if env::var_os(PRINT_METADATA_ENV_VAR).is_some() {
+        print_model_metadata(&selected_model_path);
+    }
+}
+
+fn print_model_metadata(model_path: &Path) {
+    let Ok(bytes) = fs::read(model_path) else {
+        return;
+    };
+    let bytes = if bytes.starts_with(ZSTD_MAGIC) {
+        match zstd::stream::decode_all(Cursor::new(&bytes)) {
+            Ok(decoded) => decoded,
+            Err(_) => return,
+        }
+    } else {
+        bytes
+    };
+
+    if let Some(metadata) = ModelMetadata::find_trailing(&bytes) {
+        let entries: Vec<String> = metadata
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        println!(
+            "cargo::warning=embedded model metadata: {}",
+            entries.join(", ")
+        );
+    }
 }
 
 fn resolve_model_path(manifest_dir: &Path, default_model_path: &Path) -> PathBuf {
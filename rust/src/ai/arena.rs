@@ -0,0 +1,234 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::ai::level::LevelConfig;
+use crate::ai::ntuple::NTupleEvaluator;
+use crate::ai::search::Searcher;
+use crate::board::Board;
+
+/// Loop iterations, passes included, after which a game counts as stuck.
+const MAX_GAME_STEPS: usize = 200;
+
+/// Settings of an evaluator-vs-evaluator match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MatchConfig {
+    pub games: usize,
+    /// Random plies before the engines take over. Games `2k` and `2k + 1`
+    /// share opening `k` with colors swapped.
+    pub opening_plies: usize,
+    pub seed: u64,
+    /// Search settings of both sides.
+    pub level: LevelConfig,
+}
+
+/// Games of a match from the candidate's side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchRecord {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    /// Sum of the candidate's final disc differences.
+    pub disc_diff: i64,
+}
+
+impl MatchRecord {
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Mean points per game with a draw worth half a win.
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 + 0.5 * self.draws as f64) / self.games() as f64
+    }
+
+    pub fn mean_diff(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        self.disc_diff as f64 / self.games() as f64
+    }
+
+    fn record(&mut self, diff: i32) {
+        match diff.cmp(&0) {
+            std::cmp::Ordering::Greater => self.wins += 1,
+            std::cmp::Ordering::Less => self.losses += 1,
+            std::cmp::Ordering::Equal => self.draws += 1,
+        }
+        self.disc_diff += i64::from(diff);
+    }
+}
+
+/// Plays `candidate` against `baseline` for `config.games` games. After each
+/// game `after_game` sees the running record and can end the match early by
+/// returning `false`.
+pub fn play_match(
+    candidate: &NTupleEvaluator,
+    baseline: &NTupleEvaluator,
+    config: &MatchConfig,
+    mut after_game: impl FnMut(&MatchRecord) -> bool,
+) -> Result<MatchRecord, String> {
+    let mut record = MatchRecord::default();
+    let mut opening = (Board::new(), true);
+    for game_idx in 0..config.games {
+        if game_idx % 2 == 0 {
+            let seed = config.seed.wrapping_add((game_idx / 2) as u64);
+            opening = random_opening(config.opening_plies, &mut ChaCha8Rng::seed_from_u64(seed));
+        }
+        let candidate_is_black = game_idx % 2 == 0;
+        record.record(play_game(
+            candidate,
+            baseline,
+            &config.level,
+            opening,
+            candidate_is_black,
+        )?);
+        if !after_game(&record) {
+            break;
+        }
+    }
+    Ok(record)
+}
+
+/// Plays one game from `opening` with both sides searching at `level` and
+/// returns the candidate's final disc difference.
+pub fn play_game(
+    candidate: &NTupleEvaluator,
+    baseline: &NTupleEvaluator,
+    level: &LevelConfig,
+    (board, is_black): (Board, bool),
+    candidate_is_black: bool,
+) -> Result<i32, String> {
+    let board = play_out(board, is_black, |board, is_black| {
+        let evaluator = if is_black == candidate_is_black {
+            candidate
+        } else {
+            baseline
+        };
+        Ok(Searcher::with_level_config(evaluator, level).search(board, is_black))
+    })?;
+    let (black, white) = board.count();
+    let diff = i32::from(black) - i32::from(white);
+    Ok(if candidate_is_black { diff } else { -diff })
+}
+
+/// Plays the game on from `board`, asking `choose_move` for every move, and
+/// returns the final board. Passes are made here, so `choose_move` only sees
+/// positions where the side to move has a legal move.
+pub fn play_out(
+    mut board: Board,
+    mut is_black: bool,
+    mut choose_move: impl FnMut(&Board, bool) -> Result<usize, String>,
+) -> Result<Board, String> {
+    for _ in 0..MAX_GAME_STEPS {
+        let legal = board.legal_moves(is_black);
+        if legal == 0 {
+            if board.legal_moves(!is_black) == 0 {
+                return Ok(board);
+            }
+            is_black = !is_black;
+            continue;
+        }
+        let mv = choose_move(&board, is_black)?;
+        if board.place(mv, is_black) == 0 {
+            return Err(format!("selected illegal move {mv}"));
+        }
+        is_black = !is_black;
+    }
+    Err(format!(
+        "game exceeded {MAX_GAME_STEPS} steps without terminating"
+    ))
+}
+
+/// Plays up to `plies` uniformly random moves from the start position,
+/// passing when needed, and returns the position with the side to move.
+pub fn random_opening(plies: usize, rng: &mut impl Rng) -> (Board, bool) {
+    let mut board = Board::new();
+    let mut is_black = true;
+    let mut played = 0usize;
+    while played < plies {
+        let mut legal = board.legal_moves(is_black);
+        if legal == 0 {
+            if board.legal_moves(!is_black) == 0 {
+                break;
+            }
+            is_black = !is_black;
+            continue;
+        }
+        for _ in 0..rng.gen_range(0..legal.count_ones()) {
+            legal &= legal - 1;
+        }
+        board.place(legal.trailing_zeros() as usize, is_black);
+        is_black = !is_black;
+        played += 1;
+    }
+    (board, is_black)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::test_support::corner_evaluator;
+
+    fn match_config(games: usize) -> MatchConfig {
+        MatchConfig {
+            games,
+            opening_plies: 4,
+            seed: 5,
+            level: LevelConfig {
+                depth: 1,
+                exact_solve_empties: 0,
+                wld_empties: 0,
+                time_budget_ms: u32::MAX,
+                selectivity: 0,
+                randomness: 0.0,
+            },
+        }
+    }
+
+    #[test]
+    fn play_out_passes_and_rejects_illegal_moves() {
+        // Black cannot flank a1, so it passes; white d1 then takes every disc.
+        let board = Board::from_bitboards(0b0110, 0b0001);
+        let finished = play_out(board, true, |board, is_black| {
+            assert_ne!(board.legal_moves(is_black), 0);
+            Ok(board.legal_moves(is_black).trailing_zeros() as usize)
+        })
+        .unwrap();
+        assert_eq!(finished.count(), (0, 4));
+
+        let error = play_out(Board::new(), true, |_, _| Ok(0)).unwrap_err();
+        assert!(error.contains("illegal move 0"), "{error}");
+    }
+
+    #[test]
+    fn random_opening_is_seeded_and_stops_at_the_ply_count() {
+        let opening = |seed| random_opening(6, &mut ChaCha8Rng::seed_from_u64(seed));
+        let (board, is_black) = opening(3);
+        assert_eq!(board.empty_count(), 60 - 6);
+        assert!(is_black);
+        assert_eq!(opening(3), (board, is_black));
+        assert_eq!(
+            random_opening(0, &mut ChaCha8Rng::seed_from_u64(3)).0,
+            Board::new()
+        );
+    }
+
+    #[test]
+    fn paired_openings_cancel_out_between_equal_models() {
+        let evaluator = corner_evaluator(20.0);
+        let record = play_match(&evaluator, &evaluator, &match_config(6), |_| true).unwrap();
+        assert_eq!(record.games(), 6);
+        assert_eq!(record.wins, record.losses);
+        assert_eq!(record.disc_diff, 0);
+        assert_eq!(record.score(), 0.5);
+
+        let stopped = play_match(&evaluator, &evaluator, &match_config(6), |record| {
+            record.games() < 3
+        })
+        .unwrap();
+        assert_eq!(stopped.games(), 3);
+    }
+}
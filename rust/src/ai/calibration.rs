@@ -0,0 +1,483 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::ai::level::LevelConfig;
+use crate::ai::ntuple::NTupleEvaluator;
+use crate::ai::search::Searcher;
+use crate::board::Board;
+
+pub const CALIBRATION_MAGIC: &[u8; 4] = b"NTCL";
+pub const CALIBRATION_VERSION: u16 = 1;
+const PHASE_FIELDS: usize = 4;
+const MAX_DISC_DIFFERENCE: f32 = 64.0;
+/// Phases with fewer samples borrow the fit of the nearest fitted phase.
+const MIN_PHASE_SAMPLES: usize = 32;
+const LOGISTIC_ITERATIONS: usize = 50;
+/// Ridge penalty on the standardized logistic fit so perfectly separated
+/// phases still get a finite slope.
+const LOGISTIC_RIDGE: f64 = 1e-2;
+const SOLVE_TIME_BUDGET_MS: u32 = 60_000;
+
+/// Maps raw scores of one phase to outcomes: `disc = disc_slope * score +
+/// disc_intercept` and `win = sigmoid(win_slope * score + win_intercept)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseCalibration {
+    pub disc_slope: f32,
+    pub disc_intercept: f32,
+    pub win_slope: f32,
+    pub win_intercept: f32,
+}
+
+/// A raw score translated to the side to move's expected final disc
+/// difference and win probability (draws count as half a win).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibratedScore {
+    pub disc_difference: f32,
+    pub win_probability: f32,
+}
+
+impl CalibratedScore {
+    /// Outcome of an exactly solved position.
+    pub fn exact(disc_difference: f32) -> Self {
+        let win_probability = if disc_difference > 0.0 {
+            1.0
+        } else if disc_difference < 0.0 {
+            0.0
+        } else {
+            0.5
+        };
+        Self {
+            disc_difference,
+            win_probability,
+        }
+    }
+}
+
+/// One self-play position: its phase, the evaluator's raw score and the final
+/// disc difference (solved or played out), both from the side to move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationSample {
+    pub phase_idx: usize,
+    pub score: f32,
+    pub outcome: f32,
+}
+
+/// Self-play settings for [`collect_samples`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleConfig {
+    pub games: usize,
+    /// Probability of a random move instead of the greedy one.
+    pub epsilon: f64,
+    pub random_opening_plies: usize,
+    /// Positions with at most this many empties use their solved outcome.
+    pub solve_empties: u8,
+    pub seed: u64,
+}
+
+/// Per-phase score calibration stored with a model. Stored in the NTRV
+/// payload after the scalar feature block, before the metadata block:
+/// `"NTCL", version: u16`, then per phase `disc_slope, disc_intercept,
+/// win_slope, win_intercept` as f32.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    phases: Vec<PhaseCalibration>,
+}
+
+impl Calibration {
+    pub fn new(phases: Vec<PhaseCalibration>) -> Result<Self, String> {
+        if phases.is_empty() {
+            return Err("calibration needs at least one phase".to_string());
+        }
+        if phases.iter().any(|phase| {
+            [
+                phase.disc_slope,
+                phase.disc_intercept,
+                phase.win_slope,
+                phase.win_intercept,
+            ]
+            .iter()
+            .any(|value| !value.is_finite())
+        }) {
+            return Err("calibration contains non-finite value".to_string());
+        }
+        Ok(Self { phases })
+    }
+
+    /// Fits every phase by least squares (disc difference) and ridge-regularized
+    /// logistic regression (win probability).
+    pub fn fit(samples: &[CalibrationSample], phase_count: usize) -> Result<Self, String> {
+        let mut by_phase = vec![Vec::new(); phase_count];
+        for sample in samples {
+            let phase = by_phase.get_mut(sample.phase_idx).ok_or_else(|| {
+                format!(
+                    "sample phase {} is out of range for {phase_count} phases",
+                    sample.phase_idx
+                )
+            })?;
+            phase.push(*sample);
+        }
+
+        let fitted: Vec<Option<PhaseCalibration>> = by_phase
+            .iter()
+            .map(|samples| (samples.len() >= MIN_PHASE_SAMPLES).then(|| fit_phase(samples)))
+            .collect();
+        if fitted.iter().all(Option::is_none) {
+            return Err(format!(
+                "not enough calibration samples: every phase has fewer than {MIN_PHASE_SAMPLES}"
+            ));
+        }
+        let phases = (0..phase_count)
+            .map(|phase_idx| {
+                (0..phase_count)
+                    .filter_map(|idx| fitted[idx].map(|fit| (phase_idx.abs_diff(idx), fit)))
+                    .min_by_key(|(distance, _)| *distance)
+                    .map(|(_, fit)| fit)
+                    .expect("at least one phase is fitted")
+            })
+            .collect();
+        Self::new(phases)
+    }
+
+    pub fn phases(&self) -> &[PhaseCalibration] {
+        &self.phases
+    }
+
+    pub fn calibrate(&self, phase_idx: usize, score: f32) -> CalibratedScore {
+        let phase = &self.phases[phase_idx.min(self.phases.len() - 1)];
+        let disc_difference = (phase.disc_slope * score + phase.disc_intercept)
+            .clamp(-MAX_DISC_DIFFERENCE, MAX_DISC_DIFFERENCE);
+        let win_probability =
+            sigmoid(f64::from(phase.win_slope * score + phase.win_intercept)) as f32;
+        CalibratedScore {
+            disc_difference,
+            win_probability,
+        }
+    }
+
+    pub fn to_block(&self) -> Vec<u8> {
+        let mut block = Vec::with_capacity(4 + 2 + self.phases.len() * PHASE_FIELDS * 4);
+        block.extend_from_slice(CALIBRATION_MAGIC);
+        block.extend_from_slice(&CALIBRATION_VERSION.to_le_bytes());
+        for phase in &self.phases {
+            for value in [
+                phase.disc_slope,
+                phase.disc_intercept,
+                phase.win_slope,
+                phase.win_intercept,
+            ] {
+                block.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        block
+    }
+
+    /// Reads a block at `offset` if one starts there, advancing `offset`.
+    pub fn read_block(
+        payload: &[u8],
+        offset: &mut usize,
+        phase_count: usize,
+    ) -> Result<Option<Self>, String> {
+        if !payload[*offset..].starts_with(CALIBRATION_MAGIC) {
+            return Ok(None);
+        }
+        let start = *offset + CALIBRATION_MAGIC.len();
+        let end = phase_count
+            .checked_mul(PHASE_FIELDS * 4)
+            .and_then(|len| len.checked_add(start + 2))
+            .filter(|&end| end <= payload.len())
+            .ok_or_else(|| "unexpected EOF while reading calibration block".to_string())?;
+        let version = u16::from_le_bytes([payload[start], payload[start + 1]]);
+        if version != CALIBRATION_VERSION {
+            return Err(format!("unsupported calibration block version {version}"));
+        }
+        let values: Vec<f32> = payload[start + 2..end]
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        let phases = values
+            .chunks_exact(PHASE_FIELDS)
+            .map(|fields| PhaseCalibration {
+                disc_slope: fields[0],
+                disc_intercept: fields[1],
+                win_slope: fields[2],
+                win_intercept: fields[3],
+            })
+            .collect();
+        let calibration = Self::new(phases)?;
+        *offset = end;
+        Ok(Some(calibration))
+    }
+}
+
+/// Plays `config.games` epsilon-greedy self-play games with `evaluator` and
+/// returns one sample per position where the side to move has a legal move.
+pub fn collect_samples(
+    evaluator: &NTupleEvaluator,
+    config: &SampleConfig,
+) -> Vec<CalibrationSample> {
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let solver_config = LevelConfig {
+        depth: 1,
+        exact_solve_empties: config.solve_empties,
+        wld_empties: 0,
+        time_budget_ms: SOLVE_TIME_BUDGET_MS,
+        selectivity: 0,
+        randomness: 0.0,
+    };
+    let mut samples = Vec::new();
+
+    for _ in 0..config.games {
+        let mut board = Board::new();
+        let mut is_black = true;
+        let mut ply = 0usize;
+        // (phase, raw score, side to move, solved outcome)
+        let mut positions: Vec<(usize, f32, bool, Option<f32>)> = Vec::new();
+        loop {
+            let legal = board.legal_moves(is_black);
+            if legal == 0 {
+                if board.legal_moves(!is_black) == 0 {
+                    break;
+                }
+                is_black = !is_black;
+                continue;
+            }
+
+            let solved = (board.empty_count() <= config.solve_empties).then(|| {
+                Searcher::with_level_config(evaluator, &solver_config)
+                    .analyze(&board, is_black)
+                    .into_iter()
+                    .map(|(_, score)| score)
+                    .fold(f32::NEG_INFINITY, f32::max)
+            });
+            if ply >= config.random_opening_plies {
+                positions.push((
+                    evaluator.phase_index(&board),
+                    evaluator.evaluate(&board, is_black),
+                    is_black,
+                    solved,
+                ));
+            }
+
+            let mv = if ply < config.random_opening_plies || rng.gen_bool(config.epsilon) {
+                nth_bit(legal, rng.gen_range(0..legal.count_ones()))
+            } else {
+                greedy_move(evaluator, &board, is_black, legal)
+            };
+            board.place(mv, is_black);
+            is_black = !is_black;
+            ply += 1;
+        }
+
+        let (black, white) = board.count();
+        let black_diff = f32::from(black) - f32::from(white);
+        samples.extend(
+            positions
+                .into_iter()
+                .map(|(phase_idx, score, is_black, solved)| CalibrationSample {
+                    phase_idx,
+                    score,
+                    outcome: solved.unwrap_or(if is_black { black_diff } else { -black_diff }),
+                }),
+        );
+    }
+    samples
+}
+
+fn greedy_move(evaluator: &NTupleEvaluator, board: &Board, is_black: bool, legal: u64) -> usize {
+    let mut best = (f32::NEG_INFINITY, legal.trailing_zeros() as usize);
+    let mut remaining = legal;
+    while remaining != 0 {
+        let mv = remaining.trailing_zeros() as usize;
+        remaining &= remaining - 1;
+        let mut next = *board;
+        next.place(mv, is_black);
+        let score = -evaluator.evaluate(&next, !is_black);
+        if score > best.0 {
+            best = (score, mv);
+        }
+    }
+    best.1
+}
+
+fn nth_bit(mut mask: u64, n: u32) -> usize {
+    for _ in 0..n {
+        mask &= mask - 1;
+    }
+    mask.trailing_zeros() as usize
+}
+
+fn fit_phase(samples: &[CalibrationSample]) -> PhaseCalibration {
+    let n = samples.len() as f64;
+    let mean_score = samples.iter().map(|s| f64::from(s.score)).sum::<f64>() / n;
+    let mean_outcome = samples.iter().map(|s| f64::from(s.outcome)).sum::<f64>() / n;
+    let (covariance, variance) = samples.iter().fold((0.0, 0.0), |(cov, var), s| {
+        let ds = f64::from(s.score) - mean_score;
+        (
+            cov + ds * (f64::from(s.outcome) - mean_outcome),
+            var + ds * ds,
+        )
+    });
+    let disc_slope = if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    };
+    let disc_intercept = mean_outcome - disc_slope * mean_score;
+
+    // Logistic regression on standardized scores via Newton's method.
+    let std_dev = (variance / n).sqrt();
+    let std_dev = if std_dev > 0.0 { std_dev } else { 1.0 };
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|s| {
+            let target = if s.outcome > 0.0 {
+                1.0
+            } else if s.outcome < 0.0 {
+                0.0
+            } else {
+                0.5
+            };
+            ((f64::from(s.score) - mean_score) / std_dev, target)
+        })
+        .collect();
+    let (mut slope, mut intercept) = (0.0f64, 0.0f64);
+    for _ in 0..LOGISTIC_ITERATIONS {
+        let mut gradient = [LOGISTIC_RIDGE * slope, LOGISTIC_RIDGE * intercept];
+        let mut hessian = [LOGISTIC_RIDGE, 0.0, LOGISTIC_RIDGE];
+        for &(z, target) in &points {
+            let p = sigmoid(slope * z + intercept);
+            let error = p - target;
+            let weight = p * (1.0 - p);
+            gradient[0] += error * z;
+            gradient[1] += error;
+            hessian[0] += weight * z * z;
+            hessian[1] += weight * z;
+            hessian[2] += weight;
+        }
+        let determinant = hessian[0] * hessian[2] - hessian[1] * hessian[1];
+        if determinant <= f64::EPSILON {
+            break;
+        }
+        let step_slope = (hessian[2] * gradient[0] - hessian[1] * gradient[1]) / determinant;
+        let step_intercept = (hessian[0] * gradient[1] - hessian[1] * gradient[0]) / determinant;
+        slope -= step_slope;
+        intercept -= step_intercept;
+        if step_slope.abs().max(step_intercept.abs()) < 1e-9 {
+            break;
+        }
+    }
+
+    PhaseCalibration {
+        disc_slope: disc_slope as f32,
+        disc_intercept: disc_intercept as f32,
+        win_slope: (slope / std_dev) as f32,
+        win_intercept: (intercept - slope * mean_score / std_dev) as f32,
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(phase_idx: usize, score: f32, outcome: f32) -> CalibrationSample {
+        CalibrationSample {
+            phase_idx,
+            score,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn fit_recovers_linear_disc_mapping_and_monotone_win_rate() {
+        let mut samples = Vec::new();
+        for i in 0..200 {
+            let score = (i as f32 - 100.0) / 50.0;
+            // Noisy outcomes: the sign flips for a band around zero.
+            let noise = if i % 7 == 0 { -3.0 } else { 1.0 };
+            samples.push(sample(0, score, 10.0 * score + noise));
+        }
+
+        let calibration = Calibration::fit(&samples, 3).unwrap();
+        let phase = calibration.phases()[0];
+        assert!((phase.disc_slope - 10.0).abs() < 0.5, "{phase:?}");
+        assert!(phase.win_slope > 0.0);
+        // Phases without samples borrow the nearest fitted phase.
+        assert_eq!(calibration.phases()[2], phase);
+
+        let low = calibration.calibrate(0, -1.0);
+        let high = calibration.calibrate(0, 1.0);
+        assert!(high.disc_difference > 5.0 && low.disc_difference < -5.0);
+        assert!(high.win_probability > 0.8 && low.win_probability < 0.2);
+        assert!(calibration.calibrate(0, 1e6).disc_difference <= MAX_DISC_DIFFERENCE);
+    }
+
+    #[test]
+    fn fit_rejects_out_of_range_or_missing_samples() {
+        assert!(Calibration::fit(&[sample(3, 0.0, 0.0)], 2).is_err());
+        assert!(Calibration::fit(&[sample(0, 0.0, 0.0)], 2).is_err());
+    }
+
+    #[test]
+    fn self_play_samples_use_solved_and_final_outcomes() {
+        let model = crate::ai::ntuple::ModelFile {
+            version: 3,
+            tuples: vec![vec![0]],
+            phase_count: 30,
+            weights: crate::ai::ntuple::ModelWeights::Float(vec![vec![vec![0.0; 3]]; 30]),
+            visit_counts: None,
+            tc_accumulators: None,
+            features: None,
+            calibration: None,
+            metadata: None,
+        };
+        let evaluator = NTupleEvaluator::from_bytes(&model.to_uncompressed_bytes().unwrap())
+            .expect("model must parse");
+        let config = SampleConfig {
+            games: 3,
+            epsilon: 0.5,
+            random_opening_plies: 2,
+            solve_empties: 6,
+            seed: 7,
+        };
+
+        let samples = collect_samples(&evaluator, &config);
+        assert_eq!(samples, collect_samples(&evaluator, &config));
+        assert!(samples.len() > 3 * 40);
+        assert!(samples.iter().all(|sample| sample.phase_idx < 30
+            && sample.score == 0.0
+            && sample.outcome.abs() <= MAX_DISC_DIFFERENCE));
+    }
+
+    #[test]
+    fn block_round_trips_and_rejects_bad_input() {
+        let calibration = Calibration::new(vec![
+            PhaseCalibration {
+                disc_slope: 2.0,
+                disc_intercept: -1.0,
+                win_slope: 0.5,
+                win_intercept: 0.25,
+            };
+            2
+        ])
+        .unwrap();
+        let mut payload = vec![9u8];
+        payload.extend_from_slice(&calibration.to_block());
+        payload.push(7);
+
+        let mut offset = 1;
+        let decoded = Calibration::read_block(&payload, &mut offset, 2).unwrap();
+        assert_eq!(decoded, Some(calibration));
+        assert_eq!(offset, payload.len() - 1);
+
+        let mut offset = 0;
+        assert_eq!(Calibration::read_block(&payload, &mut offset, 2), Ok(None));
+        let mut offset = 1;
+        assert!(Calibration::read_block(&payload, &mut offset, 3).is_err());
+        assert_eq!(CalibratedScore::exact(-2.0).win_probability, 0.0);
+        assert_eq!(CalibratedScore::exact(0.0).win_probability, 0.5);
+    }
+}
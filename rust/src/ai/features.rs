@@ -0,0 +1,379 @@
+use crate::board::Board;
+
+pub const FEATURES_MAGIC: &[u8; 4] = b"NTSF";
+/// Version 2 computes `Stability` with [`Board::stable_discs`]; version 1
+/// blocks are still read when they do not use it.
+pub const FEATURES_VERSION: u16 = 2;
+const FEATURES_VERSION_EDGE_STABILITY: u16 = 1;
+const NOT_A_FILE: u64 = 0xfefefefefefefefe;
+const NOT_H_FILE: u64 = 0x7f7f7f7f7f7f7f7f;
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+
+/// Hand-crafted board features evaluated from the side-to-move perspective
+/// as "mine minus theirs", scaled to roughly `[-1, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScalarFeature {
+    /// Legal move count difference.
+    Mobility,
+    /// Empty squares next to opponent discs minus those next to own discs.
+    PotentialMobility,
+    /// Discs next to an empty square.
+    Frontier,
+    /// Discs that can no longer be flipped, see [`Board::stable_discs`].
+    Stability,
+    /// +1 when the side to move gets the last move with an odd number of empties.
+    Parity,
+    /// Corners playable right now.
+    CornerAccess,
+}
+
+impl ScalarFeature {
+    pub const ALL: [Self; 6] = [
+        Self::Mobility,
+        Self::PotentialMobility,
+        Self::Frontier,
+        Self::Stability,
+        Self::Parity,
+        Self::CornerAccess,
+    ];
+
+    /// Identifier stored in the model file.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Mobility => 0,
+            Self::PotentialMobility => 1,
+            Self::Frontier => 2,
+            Self::Stability => 3,
+            Self::Parity => 4,
+            Self::CornerAccess => 5,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|feature| feature.id() == id)
+            .ok_or_else(|| format!("unsupported scalar feature id {id}"))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mobility => "mobility",
+            Self::PotentialMobility => "potential_mobility",
+            Self::Frontier => "frontier",
+            Self::Stability => "stability",
+            Self::Parity => "parity",
+            Self::CornerAccess => "corner_access",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|feature| feature.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|feature| feature.name()).collect();
+                format!(
+                    "unsupported scalar feature '{name}' (expected one of: {})",
+                    names.join(", ")
+                )
+            })
+    }
+
+    pub fn value(self, board: &Board, is_black: bool) -> f32 {
+        let (black, white) = board.bitboards();
+        let (me, opp) = if is_black {
+            (black, white)
+        } else {
+            (white, black)
+        };
+        let empty = !(me | opp);
+
+        match self {
+            Self::Mobility => {
+                count_diff(board.legal_moves(is_black), board.legal_moves(!is_black)) / 8.0
+            }
+            Self::PotentialMobility => {
+                count_diff(neighbours(opp) & empty, neighbours(me) & empty) / 16.0
+            }
+            Self::Frontier => {
+                let next_to_empty = neighbours(empty);
+                count_diff(me & next_to_empty, opp & next_to_empty) / 16.0
+            }
+            Self::Stability => {
+                count_diff(board.stable_discs(is_black), board.stable_discs(!is_black)) / 16.0
+            }
+            Self::Parity => {
+                if empty.count_ones() % 2 == 1 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Self::CornerAccess => {
+                count_diff(
+                    board.legal_moves(is_black) & CORNERS,
+                    board.legal_moves(!is_black) & CORNERS,
+                ) / 2.0
+            }
+        }
+    }
+}
+
+/// Per-phase weights of the scalar features enabled in a model. Stored in the
+/// NTRV payload after the tuple weights (and v4 visit counts), before the
+/// metadata block: `"NTSF", version: u16, feature_count: u8, feature ids`,
+/// then `[phase][feature]` f32 weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarFeatureWeights {
+    features: Vec<ScalarFeature>,
+    weights: Vec<Vec<f32>>,
+}
+
+impl ScalarFeatureWeights {
+    /// Zero weights for `features` in each of `phase_count` phases.
+    pub fn new(features: Vec<ScalarFeature>, phase_count: usize) -> Result<Self, String> {
+        if features.is_empty() {
+            return Err("scalar feature list must not be empty".to_string());
+        }
+        for (idx, feature) in features.iter().enumerate() {
+            if features[..idx].contains(feature) {
+                return Err(format!(
+                    "scalar feature '{}' is listed twice",
+                    feature.name()
+                ));
+            }
+        }
+        let weights = vec![vec![0.0; features.len()]; phase_count];
+        Ok(Self { features, weights })
+    }
+
+    pub fn features(&self) -> &[ScalarFeature] {
+        &self.features
+    }
+
+    /// `[phase][feature]` weights.
+    pub fn weights(&self) -> &[Vec<f32>] {
+        &self.weights
+    }
+
+    pub fn weights_mut(&mut self) -> &mut [Vec<f32>] {
+        &mut self.weights
+    }
+
+    /// Feature values in the order of [`Self::features`].
+    pub fn values(&self, board: &Board, is_black: bool) -> Vec<f32> {
+        self.features
+            .iter()
+            .map(|feature| feature.value(board, is_black))
+            .collect()
+    }
+
+    pub fn evaluate(&self, board: &Board, is_black: bool, phase_idx: usize) -> f32 {
+        self.features
+            .iter()
+            .zip(&self.weights[phase_idx])
+            .map(|(feature, weight)| weight * feature.value(board, is_black))
+            .sum()
+    }
+
+    /// Copy without the listed features, or `None` when nothing is left.
+    pub fn without(&self, removed: &[ScalarFeature]) -> Option<Self> {
+        let keep: Vec<usize> = (0..self.features.len())
+            .filter(|&idx| !removed.contains(&self.features[idx]))
+            .collect();
+        if keep.is_empty() {
+            return None;
+        }
+        Some(Self {
+            features: keep.iter().map(|&idx| self.features[idx]).collect(),
+            weights: self
+                .weights
+                .iter()
+                .map(|phase| keep.iter().map(|&idx| phase[idx]).collect())
+                .collect(),
+        })
+    }
+
+    pub fn to_block(&self) -> Result<Vec<u8>, String> {
+        if self
+            .weights
+            .iter()
+            .flatten()
+            .any(|value| !value.is_finite())
+        {
+            return Err("scalar feature weights contain non-finite value".to_string());
+        }
+        let mut block =
+            Vec::with_capacity(4 + 2 + 1 + self.features.len() * (1 + 4 * self.weights.len()));
+        block.extend_from_slice(FEATURES_MAGIC);
+        block.extend_from_slice(&FEATURES_VERSION.to_le_bytes());
+        block.push(self.features.len() as u8);
+        block.extend(self.features.iter().map(|feature| feature.id()));
+        for value in self.weights.iter().flatten() {
+            block.extend_from_slice(&value.to_le_bytes());
+        }
+        Ok(block)
+    }
+
+    /// Reads a block at `offset` if one starts there, advancing `offset`.
+    pub fn read_block(
+        payload: &[u8],
+        offset: &mut usize,
+        phase_count: usize,
+    ) -> Result<Option<Self>, String> {
+        if !payload[*offset..].starts_with(FEATURES_MAGIC) {
+            return Ok(None);
+        }
+        let mut cursor = *offset + FEATURES_MAGIC.len();
+        let header = read_bytes(payload, &mut cursor, 3)?;
+        let version = u16::from_le_bytes([header[0], header[1]]);
+        if version != FEATURES_VERSION && version != FEATURES_VERSION_EDGE_STABILITY {
+            return Err(format!(
+                "unsupported scalar feature block version {version}"
+            ));
+        }
+        let ids = read_bytes(payload, &mut cursor, header[2] as usize)?;
+        let features = ids
+            .iter()
+            .map(|&id| ScalarFeature::from_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if version == FEATURES_VERSION_EDGE_STABILITY
+            && features.contains(&ScalarFeature::Stability)
+        {
+            return Err(
+                "scalar feature block version 1 has edge-only stability weights; retrain the model"
+                    .to_string(),
+            );
+        }
+        let mut weights = Self::new(features, phase_count)?;
+        for (phase_idx, phase) in weights.weights.iter_mut().enumerate() {
+            for value in phase.iter_mut() {
+                let bytes = read_bytes(payload, &mut cursor, 4)?;
+                *value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                if !value.is_finite() {
+                    return Err(format!(
+                        "non-finite scalar feature weight at phase #{phase_idx}"
+                    ));
+                }
+            }
+        }
+        *offset = cursor;
+        Ok(Some(weights))
+    }
+}
+
+fn read_bytes<'a>(data: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let end = offset
+        .checked_add(len)
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| "unexpected EOF while reading scalar feature block".to_string())?;
+    let bytes = &data[*offset..end];
+    *offset = end;
+    Ok(bytes)
+}
+
+fn count_diff(mine: u64, theirs: u64) -> f32 {
+    mine.count_ones() as f32 - theirs.count_ones() as f32
+}
+
+fn neighbours(mask: u64) -> u64 {
+    let horizontal = ((mask << 1) & NOT_A_FILE) | ((mask >> 1) & NOT_H_FILE);
+    let row = mask | horizontal;
+    horizontal | (row << 8) | (row >> 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opening_position_features_are_symmetric() {
+        let board = Board::new();
+        for feature in ScalarFeature::ALL {
+            let black = feature.value(&board, true);
+            let white = feature.value(&board, false);
+            if feature == ScalarFeature::Parity {
+                assert_eq!(black, -1.0);
+            } else {
+                assert_eq!(black, 0.0, "{}", feature.name());
+                assert_eq!(white, 0.0, "{}", feature.name());
+            }
+            assert_eq!(ScalarFeature::from_id(feature.id()), Ok(feature));
+            assert_eq!(ScalarFeature::from_name(feature.name()), Ok(feature));
+        }
+    }
+
+    #[test]
+    fn stability_counts_stable_disc_difference() {
+        // Black owns a1-c1 and the rest of the a-file is white, so the file is full.
+        let black = 0b111 | (1u64 << 8) | (1u64 << 16);
+        let white = 0x0101_0101_0101_0101 & !black;
+        let board = Board::from_bitboards(black, white);
+        assert_eq!(
+            ScalarFeature::Stability.value(&board, true),
+            (5.0 - 5.0) / 16.0
+        );
+
+        // Without a corner nothing on a partial edge is stable.
+        let board = Board::from_bitboards(0b0110, 1u64 << 20);
+        assert_eq!(ScalarFeature::Stability.value(&board, true), 0.0);
+        let board = Board::from_bitboards(0b0111, 1u64 << 20);
+        assert_eq!(ScalarFeature::Stability.value(&board, true), 3.0 / 16.0);
+    }
+
+    #[test]
+    fn block_round_trips_and_rejects_bad_input() {
+        let mut weights =
+            ScalarFeatureWeights::new(vec![ScalarFeature::Mobility, ScalarFeature::Stability], 2)
+                .expect("features must be valid");
+        weights.weights_mut()[1][0] = 0.5;
+
+        let mut payload = b"xx".to_vec();
+        payload.extend_from_slice(&weights.to_block().expect("must encode"));
+        let mut offset = 2;
+        let parsed = ScalarFeatureWeights::read_block(&payload, &mut offset, 2)
+            .expect("must parse")
+            .expect("block must be present");
+        assert_eq!(parsed, weights);
+        assert_eq!(offset, payload.len());
+
+        let mut offset = 0;
+        assert_eq!(
+            ScalarFeatureWeights::read_block(&payload, &mut offset, 2),
+            Ok(None)
+        );
+        let mut offset = 2;
+        assert!(
+            ScalarFeatureWeights::read_block(&payload[..payload.len() - 1], &mut offset, 2)
+                .is_err()
+        );
+        assert!(
+            ScalarFeatureWeights::new(vec![ScalarFeature::Parity, ScalarFeature::Parity], 1)
+                .is_err()
+        );
+
+        // Version 1 blocks load unless they carry edge-only stability weights.
+        let mut legacy = payload.clone();
+        legacy[6..8].copy_from_slice(&FEATURES_VERSION_EDGE_STABILITY.to_le_bytes());
+        let mut offset = 2;
+        let error = ScalarFeatureWeights::read_block(&legacy, &mut offset, 2).unwrap_err();
+        assert!(error.contains("stability"), "{error}");
+        let mobility = ScalarFeatureWeights::new(vec![ScalarFeature::Mobility], 2)
+            .expect("features must be valid");
+        let mut legacy = mobility.to_block().expect("must encode");
+        legacy[4..6].copy_from_slice(&FEATURES_VERSION_EDGE_STABILITY.to_le_bytes());
+        let mut offset = 0;
+        assert_eq!(
+            ScalarFeatureWeights::read_block(&legacy, &mut offset, 2),
+            Ok(Some(mobility))
+        );
+
+        let ablated = weights
+            .without(&[ScalarFeature::Mobility])
+            .expect("one left");
+        assert_eq!(ablated.features(), &[ScalarFeature::Stability]);
+        assert_eq!(ablated.weights(), &[vec![0.0], vec![0.0]]);
+        assert!(ablated.without(&[ScalarFeature::Stability]).is_none());
+    }
+}
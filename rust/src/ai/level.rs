@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+pub const MIN_LEVEL: u8 = 1;
+pub const MAX_LEVEL: u8 = 10;
+const MAX_DEPTH: u8 = 60;
+const MAX_EMPTIES: u8 = 64;
+
+/// Search settings that define one AI difficulty level.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelConfig {
+    /// Maximum iterative-deepening depth of the midgame search.
+    pub depth: u8,
+    /// Solve the exact final disc difference at or below this many empties (0 disables).
+    pub exact_solve_empties: u8,
+    /// Solve win/loss/draw at or below this many empties when the exact solve
+    /// does not apply (0 disables).
+    pub wld_empties: u8,
+    /// Wall-clock budget for one move in milliseconds.
+    pub time_budget_ms: u32,
+    /// Maximum number of moves searched at interior midgame nodes with at least
+    /// two plies left (0 searches every move).
+    pub selectivity: u8,
+    /// Temperature in discs of final disc difference for stochastic root move
+    /// selection (0.0 always plays the best move).
+    pub randomness: f32,
+}
+
+/// Built-in levels. Levels 1-6 keep the search settings of REQUIREMENTS.md 2.3;
+/// levels 1-2 additionally sample moves so beginners see varied, fallible play.
+pub const LEVEL_TABLE: [LevelConfig; MAX_LEVEL as usize] = [
+    LevelConfig {
+        randomness: 4.0,
+        ..LevelConfig::fixed_depth(1, 0, 5_000)
+    },
+    LevelConfig {
+        randomness: 1.5,
+        ..LevelConfig::fixed_depth(2, 0, 5_000)
+    },
+    LevelConfig::fixed_depth(3, 10, 5_000),
+    LevelConfig::fixed_depth(4, 12, 5_000),
+    LevelConfig::fixed_depth(5, 14, 5_000),
+    LevelConfig::fixed_depth(6, 16, 5_000),
+    LevelConfig {
+        depth: 7,
+        exact_solve_empties: 18,
+        wld_empties: 20,
+        time_budget_ms: 8_000,
+        selectivity: 0,
+        randomness: 0.0,
+    },
+    LevelConfig {
+        depth: 8,
+        exact_solve_empties: 18,
+        wld_empties: 22,
+        time_budget_ms: 10_000,
+        selectivity: 0,
+        randomness: 0.0,
+    },
+    LevelConfig {
+        depth: 10,
+        exact_solve_empties: 20,
+        wld_empties: 22,
+        time_budget_ms: 15_000,
+        selectivity: 8,
+        randomness: 0.0,
+    },
+    LevelConfig {
+        depth: 12,
+        exact_solve_empties: 20,
+        wld_empties: 24,
+        time_budget_ms: 20_000,
+        selectivity: 6,
+        randomness: 0.0,
+    },
+];
+
+impl LevelConfig {
+    const fn fixed_depth(depth: u8, exact_solve_empties: u8, time_budget_ms: u32) -> Self {
+        Self {
+            depth,
+            exact_solve_empties,
+            wld_empties: 0,
+            time_budget_ms,
+            selectivity: 0,
+            randomness: 0.0,
+        }
+    }
+
+    /// Looks up a built-in level.
+    pub fn for_level(level: u8) -> Option<Self> {
+        if (MIN_LEVEL..=MAX_LEVEL).contains(&level) {
+            Some(LEVEL_TABLE[(level - MIN_LEVEL) as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Looks up a built-in level, reporting the valid range on failure.
+    pub fn try_for_level(level: u8) -> Result<Self, String> {
+        Self::for_level(level).ok_or_else(|| format!("level must be in {MIN_LEVEL}..={MAX_LEVEL}"))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_DEPTH).contains(&self.depth) {
+            return Err(format!(
+                "depth must be in 1..={MAX_DEPTH}, got {}",
+                self.depth
+            ));
+        }
+        if self.exact_solve_empties > MAX_EMPTIES {
+            return Err(format!(
+                "exact_solve_empties must be at most {MAX_EMPTIES}, got {}",
+                self.exact_solve_empties
+            ));
+        }
+        if self.wld_empties > MAX_EMPTIES {
+            return Err(format!(
+                "wld_empties must be at most {MAX_EMPTIES}, got {}",
+                self.wld_empties
+            ));
+        }
+        if self.time_budget_ms == 0 {
+            return Err("time_budget_ms must be greater than 0".to_string());
+        }
+        if !self.randomness.is_finite() || self.randomness < 0.0 {
+            return Err(format!(
+                "randomness must be a finite value >= 0.0, got {}",
+                self.randomness
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_table_reproduces_original_levels() {
+        let exact_thresholds = [0, 0, 10, 12, 14, 16];
+
+        for (level, exact) in (1..=6u8).zip(exact_thresholds) {
+            let config = LevelConfig::for_level(level).expect("level must exist");
+            assert_eq!(config.depth, level);
+            assert_eq!(config.exact_solve_empties, exact);
+            assert_eq!(config.wld_empties, 0);
+            assert_eq!(config.time_budget_ms, 5_000);
+            assert_eq!(config.selectivity, 0);
+        }
+    }
+
+    #[test]
+    fn only_beginner_levels_are_stochastic() {
+        for level in MIN_LEVEL..=MAX_LEVEL {
+            let config = LevelConfig::for_level(level).expect("level must exist");
+            assert_eq!(config.randomness > 0.0, level <= 2, "level {level}");
+        }
+        assert!(LEVEL_TABLE[0].randomness > LEVEL_TABLE[1].randomness);
+    }
+
+    #[test]
+    fn default_table_entries_are_valid_and_get_stronger() {
+        for level in MIN_LEVEL..=MAX_LEVEL {
+            LevelConfig::for_level(level)
+                .expect("level must exist")
+                .validate()
+                .expect("built-in level must be valid");
+        }
+        for pair in LEVEL_TABLE.windows(2) {
+            assert!(pair[1].depth >= pair[0].depth);
+            assert!(pair[1].exact_solve_empties >= pair[0].exact_solve_empties);
+        }
+    }
+
+    #[test]
+    fn for_level_rejects_out_of_range_levels() {
+        assert!(LevelConfig::for_level(0).is_none());
+        assert!(LevelConfig::for_level(MAX_LEVEL + 1).is_none());
+        assert_eq!(
+            LevelConfig::try_for_level(0).unwrap_err(),
+            format!("level must be in 1..={MAX_LEVEL}")
+        );
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_configs() {
+        let base = LevelConfig::for_level(4).unwrap();
+
+        let zero_depth = LevelConfig { depth: 0, ..base };
+        assert!(zero_depth.validate().unwrap_err().contains("depth"));
+
+        let no_budget = LevelConfig {
+            time_budget_ms: 0,
+            ..base
+        };
+        assert!(no_budget.validate().unwrap_err().contains("time_budget_ms"));
+
+        let too_many_empties = LevelConfig {
+            wld_empties: 65,
+            ..base
+        };
+        assert!(
+            too_many_empties
+                .validate()
+                .unwrap_err()
+                .contains("wld_empties")
+        );
+
+        let negative_randomness = LevelConfig {
+            randomness: -0.5,
+            ..base
+        };
+        assert!(
+            negative_randomness
+                .validate()
+                .unwrap_err()
+                .contains("randomness")
+        );
+    }
+}
@@ -0,0 +1,525 @@
+use std::sync::Mutex;
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use web_time::{Duration, Instant};
+
+use crate::ai::level::LevelConfig;
+use crate::ai::ntuple::NTupleEvaluator;
+use crate::board::Board;
+use crate::game::MoveSelector;
+
+const DEFAULT_MAX_NODES: usize = 200_000;
+/// About 24 MB of nodes, enough for the default simulation budget.
+const DEFAULT_MAX_TREE_NODES: usize = 500_000;
+const DEFAULT_TIME_BUDGET_MS: u64 = 5_000;
+const DEFAULT_EXPLORATION: f32 = 1.5;
+/// Raw evaluator score (unitless, not calibrated to discs) that maps to
+/// `tanh(1)` when turning evaluations into values.
+const VALUE_SCALE: f32 = 16.0;
+/// Softmax temperature, in raw evaluator score units, used to turn child
+/// evaluations into priors.
+const PRIOR_TEMPERATURE: f32 = 8.0;
+const ROOT_MOVE: u8 = u8::MAX;
+
+/// Budgets and tuning for one MCTS move decision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MctsConfig {
+    /// Maximum number of simulations, each of which expands or revisits one
+    /// leaf node (0 leaves only the time budget).
+    pub max_nodes: usize,
+    /// Nodes the tree may hold. Once it is full, simulations keep revisiting
+    /// the existing leaves instead of expanding them.
+    pub max_tree_nodes: usize,
+    /// Wall-clock budget for one move.
+    pub time_budget: Duration,
+    /// PUCT exploration constant.
+    pub exploration: f32,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            max_nodes: DEFAULT_MAX_NODES,
+            max_tree_nodes: DEFAULT_MAX_TREE_NODES,
+            time_budget: Duration::from_millis(DEFAULT_TIME_BUDGET_MS),
+            exploration: DEFAULT_EXPLORATION,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    board: Board,
+    /// Side to move. Passes are folded in, so this side has a legal move
+    /// unless the node is terminal.
+    is_black: bool,
+    mv: u8,
+    first_child: u32,
+    child_count: u8,
+    expanded: bool,
+    prior: f32,
+    /// Evaluator value from Black's perspective, in (-1, 1).
+    static_value: f32,
+    visits: u32,
+    /// Sum of backed-up values from Black's perspective.
+    black_value_sum: f32,
+}
+
+impl Node {
+    fn new(board: Board, is_black: bool, mv: u8) -> Self {
+        Self {
+            board,
+            is_black,
+            mv,
+            first_child: 0,
+            child_count: 0,
+            expanded: false,
+            prior: 0.0,
+            static_value: 0.0,
+            visits: 0,
+            black_value_sum: 0.0,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.board.legal_moves(self.is_black) == 0
+    }
+
+    fn children(&self) -> std::ops::Range<usize> {
+        let first = self.first_child as usize;
+        first..first + self.child_count as usize
+    }
+}
+
+/// PUCT tree search. With an evaluator, leaves are valued and children are
+/// given priors by the N-tuple network; without one, leaves are valued by
+/// uniformly random playouts and priors are uniform.
+pub struct Mcts<'a> {
+    evaluator: Option<&'a NTupleEvaluator>,
+    config: MctsConfig,
+    rng: ChaCha8Rng,
+    start_time: Instant,
+    nodes: Vec<Node>,
+    path: Vec<usize>,
+}
+
+impl<'a> Mcts<'a> {
+    pub fn new(evaluator: Option<&'a NTupleEvaluator>, config: MctsConfig, seed: u64) -> Self {
+        Self {
+            evaluator,
+            config,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            start_time: Instant::now(),
+            nodes: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+
+    /// Returns the most visited root move, or `None` when `is_black` has no legal move.
+    pub fn search(&mut self, board: &Board, is_black: bool) -> Option<usize> {
+        let legal = board.legal_moves(is_black);
+        if legal == 0 {
+            return None;
+        }
+        if legal.count_ones() == 1 {
+            return Some(legal.trailing_zeros() as usize);
+        }
+
+        self.start_time = Instant::now();
+        self.nodes.clear();
+        self.nodes.push(Node::new(*board, is_black, ROOT_MOVE));
+        loop {
+            self.run_iteration();
+            if self.budget_exhausted() {
+                break;
+            }
+        }
+
+        self.best_root_move()
+    }
+
+    /// Number of simulations run by the last search.
+    pub fn simulations(&self) -> usize {
+        self.nodes.first().map_or(0, |root| root.visits as usize)
+    }
+
+    fn budget_exhausted(&self) -> bool {
+        (self.config.max_nodes > 0 && self.nodes[0].visits as usize >= self.config.max_nodes)
+            || self.start_time.elapsed() >= self.config.time_budget
+    }
+
+    fn run_iteration(&mut self) {
+        let mut path = std::mem::take(&mut self.path);
+        path.clear();
+
+        let mut idx = 0usize;
+        path.push(idx);
+        while self.nodes[idx].expanded && self.nodes[idx].child_count > 0 {
+            idx = self.select_child(idx);
+            path.push(idx);
+        }
+
+        let value = if self.nodes[idx].is_terminal() {
+            self.nodes[idx].expanded = true;
+            terminal_value(&self.nodes[idx].board)
+        } else {
+            if self.has_room_for_children(idx) {
+                self.expand(idx);
+            }
+            self.leaf_value(idx)
+        };
+
+        for &node_idx in &path {
+            let node = &mut self.nodes[node_idx];
+            node.visits += 1;
+            node.black_value_sum += value;
+        }
+        self.path = path;
+    }
+
+    fn has_room_for_children(&self, idx: usize) -> bool {
+        let node = &self.nodes[idx];
+        let child_count = node.board.legal_moves(node.is_black).count_ones() as usize;
+        self.nodes.len() + child_count <= self.config.max_tree_nodes
+    }
+
+    fn select_child(&self, parent_idx: usize) -> usize {
+        let parent = &self.nodes[parent_idx];
+        let sqrt_visits = (parent.visits.max(1) as f32).sqrt();
+        let mut best_idx = parent.first_child as usize;
+        let mut best_score = f32::NEG_INFINITY;
+
+        for child_idx in parent.children() {
+            let child = &self.nodes[child_idx];
+            let exploit = mean_value(child, parent.is_black);
+            let explore =
+                self.config.exploration * child.prior * sqrt_visits / (1.0 + child.visits as f32);
+            let score = exploit + explore;
+            if score > best_score {
+                best_score = score;
+                best_idx = child_idx;
+            }
+        }
+
+        best_idx
+    }
+
+    fn expand(&mut self, idx: usize) {
+        let Node {
+            board, is_black, ..
+        } = self.nodes[idx];
+        let first_child = self.nodes.len();
+        let mut moves = board.legal_moves(is_black);
+        while moves != 0 {
+            let mv = moves.trailing_zeros() as usize;
+            moves &= moves - 1;
+
+            let mut next = board;
+            let _ = next.place(mv, is_black);
+            let next_is_black = if next.legal_moves(!is_black) != 0 {
+                !is_black
+            } else if next.legal_moves(is_black) != 0 {
+                is_black
+            } else {
+                !is_black
+            };
+            self.nodes.push(Node::new(next, next_is_black, mv as u8));
+        }
+
+        let children = first_child..self.nodes.len();
+        let child_count = children.len();
+        if let Some(evaluator) = self.evaluator {
+            let mut max_score = f32::NEG_INFINITY;
+            for child in &mut self.nodes[children.clone()] {
+                let score = evaluator.evaluate(&child.board, child.is_black);
+                let black_score = if child.is_black { score } else { -score };
+                child.static_value = (black_score / VALUE_SCALE).tanh();
+                // Store the mover's score in `prior` until it is normalized below.
+                child.prior = if is_black { black_score } else { -black_score };
+                max_score = max_score.max(child.prior);
+            }
+            let mut total = 0.0f32;
+            for child in &mut self.nodes[children.clone()] {
+                child.prior = ((child.prior - max_score) / PRIOR_TEMPERATURE).exp();
+                total += child.prior;
+            }
+            for child in &mut self.nodes[children] {
+                child.prior /= total;
+            }
+        } else {
+            let uniform = 1.0 / child_count as f32;
+            for child in &mut self.nodes[children] {
+                child.prior = uniform;
+            }
+        }
+
+        let node = &mut self.nodes[idx];
+        node.first_child = first_child as u32;
+        node.child_count = child_count as u8;
+        node.expanded = true;
+    }
+
+    fn leaf_value(&mut self, idx: usize) -> f32 {
+        let node = self.nodes[idx];
+        match self.evaluator {
+            Some(evaluator) if idx == 0 => {
+                let score = evaluator.evaluate(&node.board, node.is_black);
+                let black_score = if node.is_black { score } else { -score };
+                (black_score / VALUE_SCALE).tanh()
+            }
+            Some(_) => node.static_value,
+            None => self.random_playout(node.board, node.is_black),
+        }
+    }
+
+    fn random_playout(&mut self, mut board: Board, mut is_black: bool) -> f32 {
+        loop {
+            let mut legal = board.legal_moves(is_black);
+            if legal == 0 {
+                is_black = !is_black;
+                legal = board.legal_moves(is_black);
+                if legal == 0 {
+                    return terminal_value(&board);
+                }
+            }
+
+            let mut pick = self.rng.gen_range(0..legal.count_ones());
+            while pick > 0 {
+                legal &= legal - 1;
+                pick -= 1;
+            }
+            let _ = board.place(legal.trailing_zeros() as usize, is_black);
+            is_black = !is_black;
+        }
+    }
+
+    fn best_root_move(&self) -> Option<usize> {
+        let root = &self.nodes[0];
+        root.children()
+            .map(|idx| &self.nodes[idx])
+            .max_by(|left, right| {
+                left.visits
+                    .cmp(&right.visits)
+                    .then_with(|| {
+                        mean_value(left, root.is_black).total_cmp(&mean_value(right, root.is_black))
+                    })
+                    .then_with(|| right.mv.cmp(&left.mv))
+            })
+            .map(|child| child.mv as usize)
+    }
+}
+
+/// `MoveSelector` that runs MCTS within the level's time budget.
+pub struct MctsMoveSelector {
+    evaluator: Option<NTupleEvaluator>,
+    max_nodes: usize,
+    exploration: f32,
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl MctsMoveSelector {
+    /// `evaluator: None` falls back to random playouts.
+    pub fn new(
+        evaluator: Option<NTupleEvaluator>,
+        max_nodes: usize,
+        exploration: f32,
+        seed: u64,
+    ) -> Self {
+        Self {
+            evaluator,
+            max_nodes,
+            exploration,
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl MoveSelector for MctsMoveSelector {
+    fn select_move(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Option<usize> {
+        let seed = self
+            .rng
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .next_u64();
+        let config = MctsConfig {
+            max_nodes: self.max_nodes,
+            time_budget: Duration::from_millis(u64::from(level.time_budget_ms)),
+            exploration: self.exploration,
+            ..MctsConfig::default()
+        };
+        Mcts::new(self.evaluator.as_ref(), config, seed).search(board, is_black)
+    }
+}
+
+fn mean_value(node: &Node, for_black: bool) -> f32 {
+    if node.visits == 0 {
+        return 0.0;
+    }
+    let black_mean = node.black_value_sum / node.visits as f32;
+    if for_black { black_mean } else { -black_mean }
+}
+
+fn terminal_value(board: &Board) -> f32 {
+    let (black, white) = board.count();
+    match black.cmp(&white) {
+        std::cmp::Ordering::Greater => 1.0,
+        std::cmp::Ordering::Less => -1.0,
+        std::cmp::Ordering::Equal => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::search::Searcher;
+    use crate::ai::test_support::{corner_evaluator, endgame_position};
+
+    const NODE_BUDGET: usize = 2_000;
+
+    fn bit(pos: usize) -> u64 {
+        1u64 << pos
+    }
+
+    fn node_budget_config() -> MctsConfig {
+        MctsConfig {
+            max_nodes: NODE_BUDGET,
+            time_budget: Duration::from_secs(60),
+            ..MctsConfig::default()
+        }
+    }
+
+    #[test]
+    fn search_returns_none_without_legal_moves_and_single_move_immediately() {
+        let mut mcts = Mcts::new(None, node_budget_config(), 1);
+        let full = Board::from_bitboards(u64::MAX, 0);
+        assert_eq!(mcts.search(&full, true), None);
+
+        let only_corner = Board::from_bitboards(bit(18), bit(9));
+        assert_eq!(mcts.search(&only_corner, true), Some(0));
+        assert_eq!(mcts.simulations(), 0, "forced move must not build a tree");
+    }
+
+    #[test]
+    fn random_playouts_are_reproducible_for_fixed_seed() {
+        let board = Board::new();
+        let run = |seed| {
+            let mut mcts = Mcts::new(None, node_budget_config(), seed);
+            let mv = mcts.search(&board, true).expect("opening has legal moves");
+            (mv, mcts.simulations())
+        };
+
+        let (mv, nodes) = run(9);
+        assert_eq!(run(9), (mv, nodes));
+        assert_ne!(board.legal_moves(true) & bit(mv), 0);
+    }
+
+    #[test]
+    fn node_budget_bounds_simulations() {
+        let (board, is_black) = endgame_position(40);
+        let mut mcts = Mcts::new(None, node_budget_config(), 3);
+
+        let mv = mcts
+            .search(&board, is_black)
+            .expect("midgame has legal moves");
+
+        assert_ne!(board.legal_moves(is_black) & bit(mv), 0);
+        assert_eq!(mcts.simulations(), NODE_BUDGET);
+    }
+
+    #[test]
+    fn time_budget_stops_search_without_node_limit() {
+        let config = MctsConfig {
+            max_nodes: 0,
+            time_budget: Duration::from_millis(20),
+            ..MctsConfig::default()
+        };
+        let mut mcts = Mcts::new(None, config, 5);
+        let started = Instant::now();
+
+        let mv = mcts
+            .search(&Board::new(), true)
+            .expect("opening has legal moves");
+
+        assert_ne!(Board::new().legal_moves(true) & bit(mv), 0);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn full_tree_stops_expanding_but_keeps_simulating() {
+        let config = MctsConfig {
+            max_nodes: 0,
+            max_tree_nodes: 100,
+            time_budget: Duration::from_millis(20),
+            ..MctsConfig::default()
+        };
+        let mut mcts = Mcts::new(None, config, 7);
+
+        let mv = mcts
+            .search(&Board::new(), true)
+            .expect("opening has legal moves");
+
+        assert_ne!(Board::new().legal_moves(true) & bit(mv), 0);
+        assert!(mcts.nodes.len() <= 100);
+        assert!(mcts.simulations() > 100);
+    }
+
+    #[test]
+    fn evaluator_priors_steer_search_to_corner() {
+        let evaluator = corner_evaluator(20.0);
+        // Black can take a1 through b2 or play e5 through d4.
+        let board = Board::from_bitboards(bit(18), bit(9) | bit(27));
+        assert_eq!(board.legal_moves(true), bit(0) | bit(36));
+
+        let mut mcts = Mcts::new(Some(&evaluator), node_budget_config(), 11);
+
+        assert_eq!(mcts.search(&board, true), Some(0));
+    }
+
+    #[test]
+    fn random_playouts_find_a_winning_endgame_move() {
+        let (board, is_black) = endgame_position(8);
+        let evaluator = corner_evaluator(20.0);
+        let exact = LevelConfig {
+            exact_solve_empties: 64,
+            ..LevelConfig::for_level(1).expect("level 1 must exist")
+        };
+        let scores = Searcher::with_level_config(&evaluator, &exact).analyze(&board, is_black);
+        let best = scores
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let mut mcts = Mcts::new(None, node_budget_config(), 13);
+
+        let mv = mcts
+            .search(&board, is_black)
+            .expect("endgame has legal moves");
+        let chosen = scores
+            .iter()
+            .find(|(candidate, _)| *candidate == mv)
+            .map(|(_, score)| *score)
+            .expect("chosen move must be legal");
+
+        assert_eq!(chosen.partial_cmp(&0.0), best.partial_cmp(&0.0));
+    }
+
+    #[test]
+    fn move_selector_is_reproducible_for_fixed_seed() {
+        let level = LevelConfig::for_level(4).expect("level 4 must exist");
+        let (board, is_black) = endgame_position(30);
+        let play = |seed| {
+            let selector = MctsMoveSelector::new(None, 500, DEFAULT_EXPLORATION, seed);
+            (0..3)
+                .map(|_| selector.select_move(&board, is_black, &level))
+                .collect::<Vec<_>>()
+        };
+
+        let moves = play(21);
+        assert_eq!(play(21), moves);
+        assert!(
+            moves
+                .iter()
+                .all(|mv| mv.is_some_and(|mv| board.legal_moves(is_black) & bit(mv) != 0))
+        );
+    }
+}
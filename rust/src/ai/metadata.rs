@@ -0,0 +1,252 @@
+// Only depends on `std`: build.rs includes this file by path.
+use std::collections::BTreeMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const METADATA_MAGIC: &[u8; 4] = b"NTMD";
+pub const METADATA_VERSION: u16 = 1;
+const TRAILER_SIZE: usize = 4 + 2 + 4;
+
+pub const KEY_ALPHA: &str = "alpha";
+pub const KEY_LAMBDA: &str = "lambda";
+pub const KEY_EPSILON: &str = "epsilon";
+pub const KEY_ALPHA_DECAY: &str = "alpha_decay";
+pub const KEY_GAMES: &str = "games";
+pub const KEY_SEED: &str = "seed";
+/// Creation time in seconds since the Unix epoch. Training leaves it unset so
+/// a fixed seed gives byte-identical models; tools stamp it when writing.
+pub const KEY_CREATED_AT: &str = "created_at";
+/// CRC32 of the uncompressed model training started from.
+pub const KEY_PARENT_HASH: &str = "parent_hash";
+/// How parallel workers shared weights, see `ParallelMode::name`.
+pub const KEY_PARALLEL_MODE: &str = "parallel_mode";
+/// Self-play search depth, only stored when it differs from the default.
+pub const KEY_SEARCH_DEPTH: &str = "search_depth";
+/// Empties at or below which TD targets came from an exact solve.
+pub const KEY_EXACT_TD_EMPTIES: &str = "exact_td_empties";
+/// See `EpsilonSchedule::name`; absent for a constant epsilon.
+pub const KEY_EPSILON_SCHEDULE: &str = "epsilon_schedule";
+/// See `ExplorationPolicy::name`; absent for uniform exploration.
+pub const KEY_EXPLORATION: &str = "exploration";
+/// See `LeagueConfig::name`; absent for plain self-play.
+pub const KEY_LEAGUE: &str = "league";
+/// See `GatingConfig::name`; set on candidates a gating run promoted.
+pub const KEY_GATE: &str = "gate";
+/// Number of positions a supervised fit trained on.
+pub const KEY_SUPERVISED_POSITIONS: &str = "supervised_positions";
+pub const KEY_SUPERVISED_EPOCHS: &str = "supervised_epochs";
+/// Phase range a supervised fit was limited to, e.g. `24-29`.
+pub const KEY_SUPERVISED_PHASES: &str = "supervised_phases";
+/// L2 penalty of a supervised fit.
+pub const KEY_L2: &str = "l2";
+/// Prefix of benchmark result keys, e.g. `benchmark.vs_random.win_rate`.
+pub const BENCHMARK_PREFIX: &str = "benchmark.";
+
+/// Optional key/value block at the end of an NTRV payload describing how the
+/// model was produced.
+///
+/// Layout (little endian): `entry_count: u32`, then per entry
+/// `key_len: u16, key, value_len: u32, value` (UTF-8), followed by the trailer
+/// `body_len: u32, version: u16, "NTMD"` so the block can be found from the
+/// end of the model without parsing the weights.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelMetadata {
+    entries: BTreeMap<String, String>,
+}
+
+impl ModelMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl ToString) {
+        self.entries.insert(key.into(), value.to_string());
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.entries.remove(key)
+    }
+
+    /// Records the current time under [`KEY_CREATED_AT`]. Native only: the
+    /// `std` clock panics on wasm32.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stamp_created_at(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        self.insert(KEY_CREATED_AT, now);
+    }
+
+    /// Records a benchmark result under [`BENCHMARK_PREFIX`].
+    pub fn set_benchmark(&mut self, name: &str, value: impl ToString) {
+        self.insert(format!("{BENCHMARK_PREFIX}{name}"), value);
+    }
+
+    /// Benchmark results with the prefix stripped.
+    pub fn benchmarks(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix(BENCHMARK_PREFIX)?, value)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Serializes the block including its trailer.
+    pub fn to_block(&self) -> Result<Vec<u8>, String> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (key, value) in &self.entries {
+            let key_len =
+                u16::try_from(key.len()).map_err(|_| format!("metadata key is too long: {key}"))?;
+            let value_len = u32::try_from(value.len())
+                .map_err(|_| format!("metadata value is too long for key {key}"))?;
+            body.extend_from_slice(&key_len.to_le_bytes());
+            body.extend_from_slice(key.as_bytes());
+            body.extend_from_slice(&value_len.to_le_bytes());
+            body.extend_from_slice(value.as_bytes());
+        }
+        let body_len =
+            u32::try_from(body.len()).map_err(|_| "metadata block is too large".to_string())?;
+
+        body.extend_from_slice(&body_len.to_le_bytes());
+        body.extend_from_slice(&METADATA_VERSION.to_le_bytes());
+        body.extend_from_slice(METADATA_MAGIC);
+        Ok(body)
+    }
+
+    /// Parses a buffer holding exactly one block. Blocks written by a newer
+    /// metadata version are skipped (`Ok(None)`) so their models still load.
+    pub fn from_block(block: &[u8]) -> Result<Option<Self>, String> {
+        if block.len() < TRAILER_SIZE || !block.ends_with(METADATA_MAGIC) {
+            return Err("payload has trailing bytes that are not a metadata block".to_string());
+        }
+        let trailer = &block[block.len() - TRAILER_SIZE..];
+        let body_len = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let version = u16::from_le_bytes([trailer[4], trailer[5]]);
+        if body_len as usize != block.len() - TRAILER_SIZE {
+            return Err("metadata block length does not match its trailer".to_string());
+        }
+        if version > METADATA_VERSION {
+            return Ok(None);
+        }
+        if version == 0 {
+            return Err("unsupported metadata version 0".to_string());
+        }
+
+        let body = &block[..block.len() - TRAILER_SIZE];
+        let mut offset = 0usize;
+        let count = read_u32(body, &mut offset)?;
+        let mut metadata = Self::new();
+        for _ in 0..count {
+            let key_len = read_bytes(body, &mut offset, 2)?;
+            let key_len = u16::from_le_bytes([key_len[0], key_len[1]]) as usize;
+            let key = read_string(body, &mut offset, key_len)?;
+            let value_len = read_u32(body, &mut offset)? as usize;
+            let value = read_string(body, &mut offset, value_len)?;
+            metadata.entries.insert(key, value);
+        }
+        if offset != body.len() {
+            return Err("metadata block has trailing bytes".to_string());
+        }
+        Ok(Some(metadata))
+    }
+
+    /// Locates a block at the end of an uncompressed model without parsing
+    /// the weights. Returns `None` when no well-formed block is present.
+    pub fn find_trailing(data: &[u8]) -> Option<Self> {
+        if data.len() < TRAILER_SIZE || !data.ends_with(METADATA_MAGIC) {
+            return None;
+        }
+        let trailer = &data[data.len() - TRAILER_SIZE..];
+        let body_len = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let start = data
+            .len()
+            .checked_sub(TRAILER_SIZE)?
+            .checked_sub(body_len as usize)?;
+        Self::from_block(&data[start..]).ok().flatten()
+    }
+}
+
+fn read_bytes<'a>(data: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let end = offset
+        .checked_add(len)
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| "unexpected EOF while reading metadata".to_string())?;
+    let bytes = &data[*offset..end];
+    *offset = end;
+    Ok(bytes)
+}
+
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32, String> {
+    let bytes = read_bytes(data, offset, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_string(data: &[u8], offset: &mut usize, len: usize) -> Result<String, String> {
+    let bytes = read_bytes(data, offset, len)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| "metadata is not valid UTF-8".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ModelMetadata {
+        let mut metadata = ModelMetadata::new();
+        metadata.insert(KEY_ALPHA, 0.01f32);
+        metadata.insert(KEY_SEED, 42u64);
+        metadata.insert(KEY_PARENT_HASH, "0badf00d");
+        metadata.set_benchmark("vs_random.win_rate", 0.95);
+        metadata
+    }
+
+    #[test]
+    fn block_round_trips_and_is_found_from_the_end() {
+        let metadata = sample();
+        let block = metadata.to_block().expect("must encode");
+        assert_eq!(
+            ModelMetadata::from_block(&block),
+            Ok(Some(metadata.clone()))
+        );
+
+        let mut model = b"weights payload".to_vec();
+        model.extend_from_slice(&block);
+        assert_eq!(ModelMetadata::find_trailing(&model), Some(metadata.clone()));
+        assert_eq!(
+            metadata.benchmarks().collect::<Vec<_>>(),
+            vec![("vs_random.win_rate", "0.95")]
+        );
+    }
+
+    #[test]
+    fn newer_versions_are_skipped_and_corruption_is_rejected() {
+        let mut block = sample().to_block().expect("must encode");
+        let version_offset = block.len() - 6;
+
+        let mut newer = block.clone();
+        newer[version_offset..version_offset + 2]
+            .copy_from_slice(&(METADATA_VERSION + 1).to_le_bytes());
+        assert_eq!(ModelMetadata::from_block(&newer), Ok(None));
+
+        block[0] = 0xFF;
+        assert!(ModelMetadata::from_block(&block).is_err());
+        assert!(ModelMetadata::from_block(b"garbage").is_err());
+        assert_eq!(ModelMetadata::find_trailing(b"no metadata here"), None);
+    }
+}
@@ -1,2 +1,13 @@
+pub mod arena;
+pub mod calibration;
+pub mod features;
+pub mod level;
+pub mod mcts;
+pub mod metadata;
 pub mod ntuple;
+pub mod ponder;
+pub mod positional;
+pub mod sampling;
 pub mod search;
+#[cfg(test)]
+mod test_support;
@@ -1,6 +1,9 @@
 use std::borrow::Cow;
 use std::io::Cursor;
 
+use crate::ai::calibration::{CalibratedScore, Calibration};
+use crate::ai::features::{ScalarFeature, ScalarFeatureWeights};
+use crate::ai::metadata::ModelMetadata;
 use crate::board::Board;
 
 const MAGIC: &[u8; 4] = b"NTRV";
@@ -10,11 +13,14 @@ const VERSION_V1: u32 = 1;
 const VERSION_V2: u32 = 2;
 const VERSION_V3: u32 = 3;
 const VERSION_V4: u32 = 4;
+const VERSION_V5: u32 = 5;
+const VERSION_V6: u32 = 6;
 const HEADER_SIZE: usize = 20;
 const BOARD_SIZE: usize = 8;
 const BOARD_CELLS: usize = BOARD_SIZE * BOARD_SIZE;
 const SYMMETRY_NORMALIZATION_ROTATIONS4: f32 = 0.25;
 const SYMMETRY_NORMALIZATION_DIHEDRAL8: f32 = 0.125;
+const LEGACY_WEIGHT_SCALE: f32 = 0.5;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum SymmetryMode {
@@ -31,13 +37,68 @@ impl SymmetryMode {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelWeights {
+    /// `[phase][tuple][index]` weights of NTRV v1-v4.
+    Float(Vec<Vec<Vec<f32>>>),
+    /// NTRV v5: `weight = values[phase][tuple][index] * scales[phase][tuple]`.
+    Quantized {
+        values: Vec<Vec<Vec<i16>>>,
+        scales: Vec<Vec<f32>>,
+    },
+}
+
+impl ModelWeights {
+    /// `[phase][tuple][index]` weights as f32, dequantizing v5 values.
+    pub fn to_f32(&self) -> Vec<Vec<Vec<f32>>> {
+        match self {
+            Self::Float(weights) => weights.clone(),
+            Self::Quantized { values, scales } => values
+                .iter()
+                .zip(scales)
+                .map(|(phase_values, phase_scales)| {
+                    phase_values
+                        .iter()
+                        .zip(phase_scales)
+                        .map(|(tuple_values, &scale)| {
+                            tuple_values
+                                .iter()
+                                .map(|&value| f32::from(value) * scale)
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PositionOccurrence {
+    feature_idx: usize,
+    radix: u32,
+}
+
 /// Inference-time N-Tuple evaluator loaded from `weights.bin`.
 #[derive(Debug, Clone)]
 pub struct NTupleEvaluator {
     tuples: Vec<Vec<u8>>,
     phase_count: usize,
-    weights: Vec<Vec<Vec<f32>>>,
+    weights: ModelWeights,
     symmetry_mode: SymmetryMode,
+    /// Features (symmetry-major, then tuple) touched by each board square.
+    position_occurrences: Vec<Vec<PositionOccurrence>>,
+    features: Option<ScalarFeatureWeights>,
+    calibration: Option<Calibration>,
+    metadata: Option<ModelMetadata>,
+}
+
+/// Tuple indices of one position for both player views, updated from move
+/// flip masks instead of being re-derived from the board at every leaf.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EvalState {
+    black_view: Vec<u32>,
+    white_view: Vec<u32>,
 }
 
 pub fn compress_model_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
@@ -55,14 +116,36 @@ pub fn decompress_model_bytes(data: &[u8]) -> Result<Cow<'_, [u8]>, String> {
     }
 }
 
-impl NTupleEvaluator {
-    /// Deserialize evaluator data from `weights.bin` format.
+/// `[phase][tuple][index]` TC-learning `[signed, absolute]` update sums.
+pub type TcAccumulators = Vec<Vec<Vec<[f32; 2]>>>;
+
+/// Decoded contents of a `weights.bin` file for tools that inspect or rewrite
+/// models. Unlike [`NTupleEvaluator`] it keeps the version, the visit counts
+/// and the TC-learning accumulators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelFile {
+    pub version: u32,
+    pub tuples: Vec<Vec<u8>>,
+    pub phase_count: usize,
+    pub weights: ModelWeights,
+    /// `[phase][tuple][index]` visit counts, only present in v4 and v6 files.
+    pub visit_counts: Option<Vec<Vec<Vec<u32>>>>,
+    /// TC-learning accumulators, only present in v6 files.
+    pub tc_accumulators: Option<TcAccumulators>,
+    /// Optional per-phase scalar feature weights, added to the tuple score.
+    pub features: Option<ScalarFeatureWeights>,
+    /// Optional per-phase mapping from raw score to disc difference and win probability.
+    pub calibration: Option<Calibration>,
+    pub metadata: Option<ModelMetadata>,
+}
+
+impl ModelFile {
     pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
         let bytes = decompress_model_bytes(data)?;
-        Self::from_uncompressed_bytes(bytes.as_ref())
+        Self::parse(bytes.as_ref(), true)
     }
 
-    fn from_uncompressed_bytes(data: &[u8]) -> Result<Self, String> {
+    fn parse(data: &[u8], keep_visit_counts: bool) -> Result<Self, String> {
         if data.len() < HEADER_SIZE {
             return Err(format!(
                 "weights data too short: expected at least {HEADER_SIZE} bytes, got {}",
@@ -77,25 +160,18 @@ impl NTupleEvaluator {
         let version = read_u32_le(data, 4)?;
         let num_tuples = read_u32_le(data, 8)? as usize;
         let expected_crc = read_u32_le(data, 12)?;
-        let (phase_count, symmetry_mode) = match version {
-            VERSION_V1 => (1, SymmetryMode::Rotations4),
-            VERSION_V2 => {
+        let phase_count = match version {
+            VERSION_V1 => 1,
+            VERSION_V2 | VERSION_V3 | VERSION_V4 | VERSION_V5 | VERSION_V6 => {
                 let count = read_u32_le(data, 16)? as usize;
                 if count == 0 {
                     return Err("phase_count must be greater than 0".to_string());
                 }
-                (count, SymmetryMode::Rotations4)
-            }
-            VERSION_V3 | VERSION_V4 => {
-                let count = read_u32_le(data, 16)? as usize;
-                if count == 0 {
-                    return Err("phase_count must be greater than 0".to_string());
-                }
-                (count, SymmetryMode::Dihedral8)
+                count
             }
             _ => {
                 return Err(format!(
-                    "unsupported weights version: expected {VERSION_V1}, {VERSION_V2}, {VERSION_V3}, or {VERSION_V4}, got {version}"
+                    "unsupported weights version: expected {VERSION_V1}, {VERSION_V2}, {VERSION_V3}, {VERSION_V4}, {VERSION_V5}, or {VERSION_V6}, got {version}"
                 ));
             }
         };
@@ -136,94 +212,772 @@ impl NTupleEvaluator {
             tuples.push(tuple);
         }
 
-        let mut weights = Vec::with_capacity(phase_count);
-        for phase_idx in 0..phase_count {
-            let mut phase_weights = Vec::with_capacity(num_tuples);
-            for (tuple_idx, tuple) in tuples.iter().enumerate() {
-                let entries = pow3(tuple.len())?;
-                let bytes_len = entries
-                    .checked_mul(4)
-                    .ok_or_else(|| "weights byte length overflow".to_string())?;
+        let weights = if version == VERSION_V5 {
+            read_quantized_weights(payload, &mut offset, &tuples, phase_count)?
+        } else {
+            ModelWeights::Float(read_float_weights(
+                payload,
+                &mut offset,
+                &tuples,
+                phase_count,
+            )?)
+        };
 
-                if offset + bytes_len > payload.len() {
-                    return Err(format!(
-                        "unexpected EOF while reading weights for phase #{phase_idx}, tuple #{tuple_idx}"
-                    ));
-                }
+        let visit_counts = if matches!(version, VERSION_V4 | VERSION_V6) {
+            read_visit_counts(
+                payload,
+                &mut offset,
+                &tuples,
+                phase_count,
+                keep_visit_counts,
+            )?
+        } else {
+            None
+        };
+        let tc_accumulators = if version == VERSION_V6 {
+            read_tc_accumulators(
+                payload,
+                &mut offset,
+                &tuples,
+                phase_count,
+                keep_visit_counts,
+            )?
+        } else {
+            None
+        };
+
+        let features = ScalarFeatureWeights::read_block(payload, &mut offset, phase_count)?;
+        let calibration = Calibration::read_block(payload, &mut offset, phase_count)?;
+
+        let metadata = if offset == payload.len() {
+            None
+        } else {
+            ModelMetadata::from_block(&payload[offset..])?
+        };
+
+        Ok(Self {
+            version,
+            tuples,
+            phase_count,
+            weights,
+            visit_counts,
+            tc_accumulators,
+            features,
+            calibration,
+            metadata,
+        })
+    }
 
-                let mut tuple_weights = Vec::with_capacity(entries);
-                for i in 0..entries {
-                    let start = offset + i * 4;
-                    let mut chunk = [0u8; 4];
-                    chunk.copy_from_slice(&payload[start..start + 4]);
-                    let value = f32::from_le_bytes(chunk);
+    /// Serializes in the format of `self.version` without compression.
+    pub fn to_uncompressed_bytes(&self) -> Result<Vec<u8>, String> {
+        if self.phase_count == 0 {
+            return Err("phase_count must be greater than 0".to_string());
+        }
+        if self.version == VERSION_V1 && self.phase_count != 1 {
+            return Err("v1 models have exactly one phase".to_string());
+        }
+
+        let mut payload = Vec::new();
+        for tuple in &self.tuples {
+            payload.push(tuple.len() as u8);
+            payload.extend_from_slice(tuple);
+        }
+
+        match (&self.weights, self.version) {
+            (ModelWeights::Float(weights), VERSION_V1..=VERSION_V4 | VERSION_V6) => {
+                self.check_shape(weights, "weights")?;
+                for value in weights.iter().flatten().flatten() {
                     if !value.is_finite() {
-                        return Err(format!(
-                            "non-finite weight at phase #{phase_idx}, tuple #{tuple_idx}, entry #{i}"
-                        ));
+                        return Err("weights contain non-finite value".to_string());
+                    }
+                    payload.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            (ModelWeights::Quantized { values, scales }, VERSION_V5) => {
+                self.check_shape(values, "weights")?;
+                for (phase_values, phase_scales) in values.iter().zip(scales) {
+                    if phase_scales.len() != self.tuples.len() {
+                        return Err("scales must have one entry per tuple".to_string());
+                    }
+                    for (tuple_values, scale) in phase_values.iter().zip(phase_scales) {
+                        payload.extend_from_slice(&scale.to_le_bytes());
+                        for value in tuple_values {
+                            payload.extend_from_slice(&value.to_le_bytes());
+                        }
                     }
-                    tuple_weights.push(value);
                 }
-
-                offset += bytes_len;
-                phase_weights.push(tuple_weights);
             }
-            weights.push(phase_weights);
+            (_, version) => {
+                return Err(format!(
+                    "weights representation does not match version {version}"
+                ));
+            }
         }
 
-        if version == VERSION_V4 {
-            for phase_idx in 0..phase_count {
-                for (tuple_idx, tuple) in tuples.iter().enumerate() {
-                    let entries = pow3(tuple.len())?;
-                    let bytes_len = entries
-                        .checked_mul(4)
-                        .ok_or_else(|| "visit count byte length overflow".to_string())?;
-                    if offset + bytes_len > payload.len() {
-                        return Err(format!(
-                            "unexpected EOF while reading visit counts for phase #{phase_idx}, tuple #{tuple_idx}"
-                        ));
+        let entries: usize = self
+            .tuples
+            .iter()
+            .map(|tuple| pow3(tuple.len()))
+            .sum::<Result<usize, String>>()?;
+        if matches!(self.version, VERSION_V4 | VERSION_V6) {
+            match &self.visit_counts {
+                Some(visit_counts) => {
+                    self.check_shape(visit_counts, "visit_counts")?;
+                    for count in visit_counts.iter().flatten().flatten() {
+                        payload.extend_from_slice(&count.to_le_bytes());
                     }
-                    offset += bytes_len;
                 }
+                None => payload.resize(payload.len() + entries * self.phase_count * 4, 0),
+            }
+        }
+        if self.version == VERSION_V6 {
+            match &self.tc_accumulators {
+                Some(accumulators) => {
+                    self.check_shape(accumulators, "tc_accumulators")?;
+                    for value in accumulators.iter().flatten().flatten().flatten() {
+                        payload.extend_from_slice(&value.to_le_bytes());
+                    }
+                }
+                None => payload.resize(payload.len() + entries * self.phase_count * 8, 0),
+            }
+        }
+
+        if let Some(features) = &self.features {
+            if features.weights().len() != self.phase_count {
+                return Err(
+                    "scalar feature weights phase length must match phase_count".to_string()
+                );
+            }
+            payload.extend_from_slice(&features.to_block()?);
+        }
+
+        if let Some(calibration) = &self.calibration {
+            if calibration.phases().len() != self.phase_count {
+                return Err("calibration phase length must match phase_count".to_string());
             }
+            payload.extend_from_slice(&calibration.to_block());
         }
 
-        if offset != payload.len() {
-            return Err("weights payload has trailing bytes".to_string());
+        if let Some(metadata) = &self.metadata {
+            payload.extend_from_slice(&metadata.to_block()?);
+        }
+
+        let phase_field = if self.version == VERSION_V1 {
+            0
+        } else {
+            self.phase_count as u32
+        };
+        let mut output = Vec::with_capacity(HEADER_SIZE + payload.len());
+        output.extend_from_slice(MAGIC);
+        output.extend_from_slice(&self.version.to_le_bytes());
+        output.extend_from_slice(&(self.tuples.len() as u32).to_le_bytes());
+        output.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        output.extend_from_slice(&phase_field.to_le_bytes());
+        output.extend_from_slice(&payload);
+        Ok(output)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        compress_model_bytes(&self.to_uncompressed_bytes()?)
+    }
+
+    /// Converts to the v4 training format. v1/v2 weights were trained with
+    /// 4 rotations and are halved like `convert_model.py` does; v5 weights are
+    /// dequantized and v6 TC accumulators dropped.
+    pub fn into_v4(self) -> Self {
+        let scale = if self.version <= VERSION_V2 {
+            LEGACY_WEIGHT_SCALE
+        } else {
+            1.0
+        };
+        let mut weights = self.weights.to_f32();
+        if scale != 1.0 {
+            for value in weights.iter_mut().flatten().flatten() {
+                *value *= scale;
+            }
+        }
+        Self {
+            version: VERSION_V4,
+            weights: ModelWeights::Float(weights),
+            tc_accumulators: None,
+            ..self
         }
+    }
 
+    /// Converts a v3+ model to the int16 v5 format.
+    pub fn into_quantized(self) -> Result<Self, String> {
+        if self.version <= VERSION_V2 {
+            return Err("only v3+ (8-symmetry) models can be quantized".to_string());
+        }
+        let weights = match self.weights {
+            ModelWeights::Float(weights) => quantize_weights(&self.tuples, &weights)?,
+            quantized @ ModelWeights::Quantized { .. } => quantized,
+        };
         Ok(Self {
-            tuples,
-            phase_count,
+            version: VERSION_V5,
             weights,
+            visit_counts: None,
+            tc_accumulators: None,
+            ..self
+        })
+    }
+
+    fn check_shape<W>(&self, values: &[Vec<Vec<W>>], label: &str) -> Result<(), String> {
+        if values.len() != self.phase_count {
+            return Err(format!("{label} phase length must match phase_count"));
+        }
+        for (phase_idx, phase_values) in values.iter().enumerate() {
+            if phase_values.len() != self.tuples.len() {
+                return Err(format!(
+                    "{label}[{phase_idx}] tuple length must match tuple count"
+                ));
+            }
+            for (tuple_idx, (tuple, tuple_values)) in
+                self.tuples.iter().zip(phase_values).enumerate()
+            {
+                let expected_len = pow3(tuple.len())?;
+                if tuple_values.len() != expected_len {
+                    return Err(format!(
+                        "{label}[{phase_idx}][{tuple_idx}] length must be {expected_len}, got {}",
+                        tuple_values.len()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl NTupleEvaluator {
+    /// Deserialize evaluator data from `weights.bin` format.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let bytes = decompress_model_bytes(data)?;
+        Self::from_uncompressed_bytes(bytes.as_ref())
+    }
+
+    fn from_uncompressed_bytes(data: &[u8]) -> Result<Self, String> {
+        let model = ModelFile::parse(data, false)?;
+        let symmetry_mode = if model.version <= VERSION_V2 {
+            SymmetryMode::Rotations4
+        } else {
+            SymmetryMode::Dihedral8
+        };
+        let position_occurrences = build_position_occurrences(&model.tuples, symmetry_mode);
+        Ok(Self {
+            tuples: model.tuples,
+            phase_count: model.phase_count,
+            weights: model.weights,
             symmetry_mode,
+            position_occurrences,
+            features: model.features,
+            calibration: model.calibration,
+            metadata: model.metadata,
         })
     }
 
+    /// Scalar feature weights stored with the model, if any.
+    pub fn scalar_features(&self) -> Option<&ScalarFeatureWeights> {
+        self.features.as_ref()
+    }
+
+    /// Copy that ignores the listed scalar features, for ablation runs.
+    pub fn without_scalar_features(&self, removed: &[ScalarFeature]) -> Self {
+        Self {
+            features: self
+                .features
+                .as_ref()
+                .and_then(|features| features.without(removed)),
+            ..self.clone()
+        }
+    }
+
+    /// Score calibration stored with the model, if any.
+    pub fn calibration(&self) -> Option<&Calibration> {
+        self.calibration.as_ref()
+    }
+
+    /// Maps a raw side-to-move score of `board` (from [`Self::evaluate`] or a
+    /// search rooted at `board`) to disc difference and win probability.
+    pub fn calibrate(&self, board: &Board, score: f32) -> Option<CalibratedScore> {
+        let calibration = self.calibration.as_ref()?;
+        Some(calibration.calibrate(self.phase_index(board), score))
+    }
+
+    /// Weight phase used for `board`.
+    pub fn phase_index(&self, board: &Board) -> usize {
+        phase_index_for_board(board, self.phase_count)
+    }
+
+    /// Training metadata stored with the model, if any.
+    pub fn metadata(&self) -> Option<&ModelMetadata> {
+        self.metadata.as_ref()
+    }
+
     /// Evaluate from the side-to-move perspective.
     pub fn evaluate(&self, board: &Board, is_black: bool) -> f32 {
         let cells = board.to_array();
         let phase_idx = phase_index_for_board(board, self.phase_count);
-        let phase_weights = &self.weights[phase_idx];
-        let mut score = 0.0f32;
+        let tuple_index = |tuple: &[u8], symmetry: u8| {
+            tuple.iter().fold(0usize, |acc, &pos| {
+                let transformed = transform_pos(pos, symmetry);
+                let value = map_to_player_view(cells[transformed], is_black) as usize;
+                acc * 3 + value
+            })
+        };
+
+        let score = match &self.weights {
+            ModelWeights::Float(weights) => {
+                let phase_weights = &weights[phase_idx];
+                let mut score = 0.0f32;
+                for symmetry in 0..self.symmetry_mode.count() {
+                    for (tuple, weights) in self.tuples.iter().zip(phase_weights.iter()) {
+                        score += weights[tuple_index(tuple, symmetry)];
+                    }
+                }
+                score
+            }
+            ModelWeights::Quantized { values, scales } => {
+                let mut score = 0.0f32;
+                for ((tuple, values), &scale) in self
+                    .tuples
+                    .iter()
+                    .zip(&values[phase_idx])
+                    .zip(&scales[phase_idx])
+                {
+                    let sum: i32 = (0..self.symmetry_mode.count())
+                        .map(|symmetry| i32::from(values[tuple_index(tuple, symmetry)]))
+                        .sum();
+                    score += sum as f32 * scale;
+                }
+                score
+            }
+        };
+
+        score * self.symmetry_normalization() + self.scalar_score(board, is_black, phase_idx)
+    }
+
+    /// Whether the model was loaded from the quantized NTRV v5 format.
+    pub fn is_quantized(&self) -> bool {
+        matches!(self.weights, ModelWeights::Quantized { .. })
+    }
+
+    /// Re-encodes a float Dihedral8 model as zstd-compressed NTRV v5.
+    pub fn to_quantized_bytes(&self) -> Result<Vec<u8>, String> {
+        let ModelWeights::Float(weights) = &self.weights else {
+            return Err("model is already quantized".to_string());
+        };
+        if self.symmetry_mode != SymmetryMode::Dihedral8 {
+            return Err("only v3+ (8-symmetry) models can be quantized".to_string());
+        }
+        let model = ModelFile {
+            version: VERSION_V5,
+            tuples: self.tuples.clone(),
+            phase_count: self.phase_count,
+            weights: quantize_weights(&self.tuples, weights)?,
+            visit_counts: None,
+            tc_accumulators: None,
+            features: self.features.clone(),
+            calibration: self.calibration.clone(),
+            metadata: self.metadata.clone(),
+        };
+        compress_model_bytes(&model.to_uncompressed_bytes()?)
+    }
+
+    /// Derives the incremental state of `board` from scratch.
+    pub fn eval_state(&self, board: &Board) -> EvalState {
+        let feature_count = self.tuples.len() * self.symmetry_mode.count() as usize;
+        let mut state = EvalState {
+            black_view: vec![0; feature_count],
+            white_view: vec![0; feature_count],
+        };
+        let (black, white) = board.bitboards();
+        for (pos, occurrences) in self.position_occurrences.iter().enumerate() {
+            let (black_digit, white_digit) = if black & (1u64 << pos) != 0 {
+                (1, 2)
+            } else if white & (1u64 << pos) != 0 {
+                (2, 1)
+            } else {
+                continue;
+            };
+            for occurrence in occurrences {
+                state.black_view[occurrence.feature_idx] += black_digit * occurrence.radix;
+                state.white_view[occurrence.feature_idx] += white_digit * occurrence.radix;
+            }
+        }
+        state
+    }
+
+    /// Updates `state` for `is_black` playing `pos` with the flip mask returned by `Board::place`.
+    pub fn apply_move(&self, state: &mut EvalState, pos: usize, flips: u64, is_black: bool) {
+        self.update_state(state, pos, flips, is_black, 1);
+    }
+
+    /// Reverts a previous `apply_move` with the same arguments.
+    pub fn undo_move(&self, state: &mut EvalState, pos: usize, flips: u64, is_black: bool) {
+        self.update_state(state, pos, flips, is_black, -1);
+    }
+
+    /// Same result as `evaluate`, reading tuple indices from `state`, which must
+    /// describe `board`.
+    pub fn evaluate_state(&self, state: &EvalState, board: &Board, is_black: bool) -> f32 {
+        let phase_idx = phase_index_for_board(board, self.phase_count);
+        let indices = if is_black {
+            &state.black_view
+        } else {
+            &state.white_view
+        };
+        let tuple_count = self.tuples.len();
+
+        let score = match &self.weights {
+            ModelWeights::Float(weights) => {
+                let mut score = 0.0f32;
+                for symmetry_indices in indices.chunks(tuple_count) {
+                    for (weights, &idx) in weights[phase_idx].iter().zip(symmetry_indices) {
+                        score += weights[idx as usize];
+                    }
+                }
+                score
+            }
+            ModelWeights::Quantized { values, scales } => {
+                let mut score = 0.0f32;
+                for (tuple_idx, (values, &scale)) in
+                    values[phase_idx].iter().zip(&scales[phase_idx]).enumerate()
+                {
+                    let sum: i32 = indices[tuple_idx..]
+                        .iter()
+                        .step_by(tuple_count)
+                        .map(|&idx| i32::from(values[idx as usize]))
+                        .sum();
+                    score += sum as f32 * scale;
+                }
+                score
+            }
+        };
+
+        score * self.symmetry_normalization() + self.scalar_score(board, is_black, phase_idx)
+    }
+
+    fn scalar_score(&self, board: &Board, is_black: bool, phase_idx: usize) -> f32 {
+        self.features.as_ref().map_or(0.0, |features| {
+            features.evaluate(board, is_black, phase_idx)
+        })
+    }
+
+    // Mover's view: placed 0 -> 1, flipped 2 -> 1. Other view: placed 0 -> 2, flipped 1 -> 2.
+    fn update_state(
+        &self,
+        state: &mut EvalState,
+        pos: usize,
+        flips: u64,
+        is_black: bool,
+        sign: i32,
+    ) {
+        let (mover_view, other_view) = if is_black {
+            (&mut state.black_view, &mut state.white_view)
+        } else {
+            (&mut state.white_view, &mut state.black_view)
+        };
+
+        for occurrence in &self.position_occurrences[pos] {
+            let radix = occurrence.radix as i32;
+            let idx = occurrence.feature_idx;
+            mover_view[idx] = mover_view[idx].wrapping_add_signed(sign * radix);
+            other_view[idx] = other_view[idx].wrapping_add_signed(sign * 2 * radix);
+        }
+
+        let mut flipped = flips;
+        while flipped != 0 {
+            let flipped_pos = flipped.trailing_zeros() as usize;
+            flipped &= flipped - 1;
+            for occurrence in &self.position_occurrences[flipped_pos] {
+                let radix = occurrence.radix as i32;
+                let idx = occurrence.feature_idx;
+                mover_view[idx] = mover_view[idx].wrapping_add_signed(-sign * radix);
+                other_view[idx] = other_view[idx].wrapping_add_signed(sign * radix);
+            }
+        }
+    }
+
+    fn symmetry_normalization(&self) -> f32 {
+        match self.symmetry_mode {
+            SymmetryMode::Rotations4 => SYMMETRY_NORMALIZATION_ROTATIONS4,
+            SymmetryMode::Dihedral8 => SYMMETRY_NORMALIZATION_DIHEDRAL8,
+        }
+    }
+}
+
+/// Serializes 8-symmetry tuple weights as uncompressed NTRV v5. Each phase and
+/// tuple block is an f32 scale followed by int16 values, `weight = value * scale`;
+/// the scale maps the block's largest magnitude to `i16::MAX`. Scalar feature
+/// weights stay f32.
+pub fn encode_quantized_model<T: AsRef<[u8]>>(
+    tuples: &[T],
+    phase_weights: &[Vec<Vec<f32>>],
+    features: Option<&ScalarFeatureWeights>,
+    metadata: Option<&ModelMetadata>,
+) -> Result<Vec<u8>, String> {
+    let tuples: Vec<Vec<u8>> = tuples.iter().map(|tuple| tuple.as_ref().to_vec()).collect();
+    let weights = quantize_weights(&tuples, phase_weights)?;
+    ModelFile {
+        version: VERSION_V5,
+        tuples,
+        phase_count: phase_weights.len(),
+        weights,
+        visit_counts: None,
+        tc_accumulators: None,
+        features: features.cloned(),
+        calibration: None,
+        metadata: metadata.cloned(),
+    }
+    .to_uncompressed_bytes()
+}
+
+fn quantize_weights(
+    tuples: &[Vec<u8>],
+    phase_weights: &[Vec<Vec<f32>>],
+) -> Result<ModelWeights, String> {
+    if phase_weights.is_empty() {
+        return Err("phase_count must be greater than 0".to_string());
+    }
+
+    let mut values = Vec::with_capacity(phase_weights.len());
+    let mut scales = Vec::with_capacity(phase_weights.len());
+    for (phase_idx, weights) in phase_weights.iter().enumerate() {
+        if weights.len() != tuples.len() {
+            return Err(format!(
+                "weights[{phase_idx}] tuple length must match tuple count"
+            ));
+        }
+        let mut phase_values = Vec::with_capacity(tuples.len());
+        let mut phase_scales = Vec::with_capacity(tuples.len());
+        for (tuple_idx, (tuple, tuple_weights)) in tuples.iter().zip(weights).enumerate() {
+            let expected_len = pow3(tuple.len())?;
+            if tuple_weights.len() != expected_len {
+                return Err(format!(
+                    "weights[{phase_idx}][{tuple_idx}] length must be {expected_len}, got {}",
+                    tuple_weights.len()
+                ));
+            }
+            if tuple_weights.iter().any(|value| !value.is_finite()) {
+                return Err(format!(
+                    "weights[{phase_idx}][{tuple_idx}] contains non-finite value"
+                ));
+            }
+
+            let max_abs = tuple_weights
+                .iter()
+                .fold(0.0f32, |acc, value| acc.max(value.abs()));
+            let scale = max_abs / f32::from(i16::MAX);
+            phase_values.push(
+                tuple_weights
+                    .iter()
+                    .map(|value| {
+                        if scale > 0.0 {
+                            (value / scale)
+                                .round()
+                                .clamp(f32::from(-i16::MAX), f32::from(i16::MAX))
+                                as i16
+                        } else {
+                            0
+                        }
+                    })
+                    .collect(),
+            );
+            phase_scales.push(scale);
+        }
+        values.push(phase_values);
+        scales.push(phase_scales);
+    }
+    Ok(ModelWeights::Quantized { values, scales })
+}
+
+fn build_position_occurrences(
+    tuples: &[Vec<u8>],
+    symmetry_mode: SymmetryMode,
+) -> Vec<Vec<PositionOccurrence>> {
+    let mut occurrences = vec![Vec::new(); BOARD_CELLS];
+    for symmetry in 0..symmetry_mode.count() {
+        for (tuple_idx, tuple) in tuples.iter().enumerate() {
+            let feature_idx = symmetry as usize * tuples.len() + tuple_idx;
+            let mut radix = 1u32;
+            // `evaluate` folds cells most-significant first, so the last cell has radix 1.
+            for &pos in tuple.iter().rev() {
+                occurrences[transform_pos(pos, symmetry)]
+                    .push(PositionOccurrence { feature_idx, radix });
+                radix *= 3;
+            }
+        }
+    }
+    occurrences
+}
+
+fn read_float_weights(
+    payload: &[u8],
+    offset: &mut usize,
+    tuples: &[Vec<u8>],
+    phase_count: usize,
+) -> Result<Vec<Vec<Vec<f32>>>, String> {
+    let mut weights = Vec::with_capacity(phase_count);
+    for phase_idx in 0..phase_count {
+        let mut phase_weights = Vec::with_capacity(tuples.len());
+        for (tuple_idx, tuple) in tuples.iter().enumerate() {
+            let entries = pow3(tuple.len())?;
+            let bytes_len = entries
+                .checked_mul(4)
+                .ok_or_else(|| "weights byte length overflow".to_string())?;
+
+            if *offset + bytes_len > payload.len() {
+                return Err(format!(
+                    "unexpected EOF while reading weights for phase #{phase_idx}, tuple #{tuple_idx}"
+                ));
+            }
+
+            let mut tuple_weights = Vec::with_capacity(entries);
+            for i in 0..entries {
+                let start = *offset + i * 4;
+                let mut chunk = [0u8; 4];
+                chunk.copy_from_slice(&payload[start..start + 4]);
+                let value = f32::from_le_bytes(chunk);
+                if !value.is_finite() {
+                    return Err(format!(
+                        "non-finite weight at phase #{phase_idx}, tuple #{tuple_idx}, entry #{i}"
+                    ));
+                }
+                tuple_weights.push(value);
+            }
+
+            *offset += bytes_len;
+            phase_weights.push(tuple_weights);
+        }
+        weights.push(phase_weights);
+    }
+    Ok(weights)
+}
+
+fn read_quantized_weights(
+    payload: &[u8],
+    offset: &mut usize,
+    tuples: &[Vec<u8>],
+    phase_count: usize,
+) -> Result<ModelWeights, String> {
+    let mut values = Vec::with_capacity(phase_count);
+    let mut scales = Vec::with_capacity(phase_count);
+    for phase_idx in 0..phase_count {
+        let mut phase_values = Vec::with_capacity(tuples.len());
+        let mut phase_scales = Vec::with_capacity(tuples.len());
+        for (tuple_idx, tuple) in tuples.iter().enumerate() {
+            let entries = pow3(tuple.len())?;
+            let bytes_len = entries
+                .checked_mul(2)
+                .and_then(|len| len.checked_add(4))
+                .ok_or_else(|| "weights byte length overflow".to_string())?;
+
+            if *offset + bytes_len > payload.len() {
+                return Err(format!(
+                    "unexpected EOF while reading weights for phase #{phase_idx}, tuple #{tuple_idx}"
+                ));
+            }
 
-        for symmetry in 0..self.symmetry_mode.count() {
-            for (tuple, weights) in self.tuples.iter().zip(phase_weights.iter()) {
-                let idx = tuple.iter().fold(0usize, |acc, &pos| {
-                    let transformed = transform_pos(pos, symmetry);
-                    let value = map_to_player_view(cells[transformed], is_black) as usize;
-                    acc * 3 + value
-                });
-                score += weights[idx];
+            let mut chunk = [0u8; 4];
+            chunk.copy_from_slice(&payload[*offset..*offset + 4]);
+            let scale = f32::from_le_bytes(chunk);
+            if !scale.is_finite() || scale < 0.0 {
+                return Err(format!(
+                    "invalid scale at phase #{phase_idx}, tuple #{tuple_idx}: {scale}"
+                ));
             }
+            let tuple_values = payload[*offset + 4..*offset + bytes_len]
+                .chunks_exact(2)
+                .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+                .collect();
+
+            *offset += bytes_len;
+            phase_values.push(tuple_values);
+            phase_scales.push(scale);
         }
+        values.push(phase_values);
+        scales.push(phase_scales);
+    }
+    Ok(ModelWeights::Quantized { values, scales })
+}
 
-        score
-            * match self.symmetry_mode {
-                SymmetryMode::Rotations4 => SYMMETRY_NORMALIZATION_ROTATIONS4,
-                SymmetryMode::Dihedral8 => SYMMETRY_NORMALIZATION_DIHEDRAL8,
+fn read_visit_counts(
+    payload: &[u8],
+    offset: &mut usize,
+    tuples: &[Vec<u8>],
+    phase_count: usize,
+    keep: bool,
+) -> Result<Option<Vec<Vec<Vec<u32>>>>, String> {
+    let mut counts = Vec::with_capacity(if keep { phase_count } else { 0 });
+    for phase_idx in 0..phase_count {
+        let mut phase_counts = Vec::with_capacity(if keep { tuples.len() } else { 0 });
+        for (tuple_idx, tuple) in tuples.iter().enumerate() {
+            let entries = pow3(tuple.len())?;
+            let bytes_len = entries
+                .checked_mul(4)
+                .ok_or_else(|| "visit count byte length overflow".to_string())?;
+            if *offset + bytes_len > payload.len() {
+                return Err(format!(
+                    "unexpected EOF while reading visit counts for phase #{phase_idx}, tuple #{tuple_idx}"
+                ));
             }
+            if keep {
+                phase_counts.push(
+                    payload[*offset..*offset + bytes_len]
+                        .chunks_exact(4)
+                        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                        .collect(),
+                );
+            }
+            *offset += bytes_len;
+        }
+        if keep {
+            counts.push(phase_counts);
+        }
     }
+    Ok(keep.then_some(counts))
+}
+
+fn read_tc_accumulators(
+    payload: &[u8],
+    offset: &mut usize,
+    tuples: &[Vec<u8>],
+    phase_count: usize,
+    keep: bool,
+) -> Result<Option<TcAccumulators>, String> {
+    let mut accumulators = Vec::with_capacity(if keep { phase_count } else { 0 });
+    for phase_idx in 0..phase_count {
+        let mut phase_accumulators = Vec::with_capacity(if keep { tuples.len() } else { 0 });
+        for (tuple_idx, tuple) in tuples.iter().enumerate() {
+            let entries = pow3(tuple.len())?;
+            let bytes_len = entries
+                .checked_mul(8)
+                .ok_or_else(|| "TC accumulator byte length overflow".to_string())?;
+            if *offset + bytes_len > payload.len() {
+                return Err(format!(
+                    "unexpected EOF while reading TC accumulators for phase #{phase_idx}, tuple #{tuple_idx}"
+                ));
+            }
+            if keep {
+                phase_accumulators.push(
+                    payload[*offset..*offset + bytes_len]
+                        .chunks_exact(8)
+                        .map(|chunk| {
+                            [
+                                f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+                                f32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+                            ]
+                        })
+                        .collect(),
+                );
+            }
+            *offset += bytes_len;
+        }
+        if keep {
+            accumulators.push(phase_accumulators);
+        }
+    }
+    Ok(keep.then_some(accumulators))
 }
 
 fn phase_index_for_board(board: &Board, phase_count: usize) -> usize {
@@ -281,6 +1035,7 @@ fn pow3(exp: usize) -> Result<usize, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ai::calibration::PhaseCalibration;
 
     fn build_weights_blob_v1(tuples: &[Vec<u8>], weights: &[Vec<f32>]) -> Vec<u8> {
         let phase_weights = vec![weights.to_vec()];
@@ -404,7 +1159,7 @@ mod tests {
 
         assert_eq!(evaluator.tuples, tuples);
         assert_eq!(evaluator.phase_count, 2);
-        assert_eq!(evaluator.weights, phase_weights);
+        assert_eq!(evaluator.weights, ModelWeights::Float(phase_weights));
         assert_eq!(evaluator.symmetry_mode, SymmetryMode::Rotations4);
     }
 
@@ -421,7 +1176,7 @@ mod tests {
 
         assert_eq!(evaluator.tuples, tuples);
         assert_eq!(evaluator.phase_count, 2);
-        assert_eq!(evaluator.weights, phase_weights);
+        assert_eq!(evaluator.weights, ModelWeights::Float(phase_weights));
         assert_eq!(evaluator.symmetry_mode, SymmetryMode::Dihedral8);
     }
 
@@ -438,7 +1193,7 @@ mod tests {
 
         assert_eq!(evaluator.tuples, tuples);
         assert_eq!(evaluator.phase_count, 2);
-        assert_eq!(evaluator.weights, phase_weights);
+        assert_eq!(evaluator.weights, ModelWeights::Float(phase_weights));
         assert_eq!(evaluator.symmetry_mode, SymmetryMode::Dihedral8);
     }
 
@@ -452,7 +1207,7 @@ mod tests {
 
         assert_eq!(evaluator.tuples, tuples);
         assert_eq!(evaluator.phase_count, 1);
-        assert_eq!(evaluator.weights, vec![weights]);
+        assert_eq!(evaluator.weights, ModelWeights::Float(vec![weights]));
         assert_eq!(evaluator.symmetry_mode, SymmetryMode::Rotations4);
     }
 
@@ -535,7 +1290,7 @@ mod tests {
 
         assert_eq!(evaluator.tuples, tuples);
         assert_eq!(evaluator.phase_count, 2);
-        assert_eq!(evaluator.weights, weights);
+        assert_eq!(evaluator.weights, ModelWeights::Float(weights));
     }
 
     #[test]
@@ -614,4 +1369,303 @@ mod tests {
 
         assert_eq!(evaluator.evaluate(&late_board, true), 1.0);
     }
+
+    fn varied_weights(tuples: &[Vec<u8>], phase: usize) -> Vec<Vec<f32>> {
+        tuples
+            .iter()
+            .map(|tuple| {
+                (0..3usize.pow(tuple.len() as u32))
+                    .map(|idx| ((idx * 7 + phase * 13) % 23) as f32 * 0.25 - 2.5)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn incremental_state_tracks_full_evaluation_through_a_game() {
+        let tuples = vec![vec![0, 1, 2, 9], vec![18, 27, 36], vec![3, 11, 19, 20, 28]];
+        for (version, phase_count, quantize) in [
+            (VERSION_V2, 3u32, false),
+            (VERSION_V3, 30, false),
+            (VERSION_V3, 30, true),
+        ] {
+            let phase_weights: Vec<_> = (0..phase_count as usize)
+                .map(|phase| varied_weights(&tuples, phase))
+                .collect();
+            let bytes = build_weights_blob(version, &tuples, &phase_weights, phase_count);
+            let mut evaluator = NTupleEvaluator::from_bytes(&bytes).expect("must parse");
+            if quantize {
+                let quantized = evaluator.to_quantized_bytes().expect("must quantize");
+                evaluator = NTupleEvaluator::from_bytes(&quantized).expect("v5 must parse");
+            }
+
+            let mut board = Board::new();
+            let mut state = evaluator.eval_state(&board);
+            let mut is_black = true;
+            let mut ply = 0usize;
+            loop {
+                let mut legal = board.legal_moves(is_black);
+                if legal == 0 {
+                    is_black = !is_black;
+                    legal = board.legal_moves(is_black);
+                    if legal == 0 {
+                        break;
+                    }
+                }
+                // Deterministic but varied move choice.
+                for _ in 0..(ply % legal.count_ones() as usize) {
+                    legal &= legal - 1;
+                }
+                let mv = legal.trailing_zeros() as usize;
+
+                let before_board = board;
+                let before_state = state.clone();
+                let flips = board.place(mv, is_black);
+                evaluator.apply_move(&mut state, mv, flips, is_black);
+                assert_eq!(state, evaluator.eval_state(&board), "ply {ply}");
+                for view in [true, false] {
+                    assert_eq!(
+                        evaluator.evaluate_state(&state, &board, view),
+                        evaluator.evaluate(&board, view)
+                    );
+                }
+
+                let mut undone = state.clone();
+                evaluator.undo_move(&mut undone, mv, flips, is_black);
+                assert_eq!(undone, before_state);
+                assert_eq!(undone, evaluator.eval_state(&before_board));
+
+                is_black = !is_black;
+                ply += 1;
+            }
+            assert!(ply > 50, "game must be long enough to cover every phase");
+        }
+    }
+
+    #[test]
+    fn quantized_model_stays_close_to_float_model() {
+        let tuples = vec![vec![0, 1, 2, 9], vec![18, 27, 36], vec![3, 11, 19, 20, 28]];
+        let phase_weights: Vec<_> = (0..30)
+            .map(|phase| varied_weights(&tuples, phase))
+            .collect();
+        let float =
+            NTupleEvaluator::from_bytes(&build_weights_blob_v3(&tuples, &phase_weights, 30))
+                .expect("v3 must parse");
+        let encoded =
+            encode_quantized_model(&tuples, &phase_weights, None, None).expect("must encode");
+        assert_eq!(&encoded[4..8], &VERSION_V5.to_le_bytes());
+
+        let quantized = NTupleEvaluator::from_bytes(&encoded).expect("v5 must parse");
+        assert!(quantized.is_quantized());
+        assert!(!float.is_quantized());
+        assert_eq!(quantized.tuples, float.tuples);
+        assert_eq!(quantized.phase_count, 30);
+        assert_eq!(quantized.symmetry_mode, SymmetryMode::Dihedral8);
+        assert!(
+            quantized
+                .to_quantized_bytes()
+                .unwrap_err()
+                .contains("already quantized")
+        );
+
+        let mut board = Board::new();
+        let mut is_black = true;
+        while board.legal_moves(is_black) != 0 {
+            for view in [true, false] {
+                let diff = (quantized.evaluate(&board, view) - float.evaluate(&board, view)).abs();
+                assert!(diff < 1e-3, "diff {diff}");
+            }
+            let mv = board.legal_moves(is_black).trailing_zeros() as usize;
+            board.place(mv, is_black);
+            is_black = !is_black;
+        }
+    }
+
+    #[test]
+    fn encode_quantized_model_handles_all_zero_tuples() {
+        let tuples = vec![vec![0u8, 1]];
+        let encoded = encode_quantized_model(&tuples, &[vec![vec![0.0; 9]]], None, None)
+            .expect("must encode");
+        let evaluator = NTupleEvaluator::from_bytes(&encoded).expect("must parse");
+        assert_eq!(evaluator.evaluate(&Board::new(), true), 0.0);
+
+        let err =
+            encode_quantized_model(&tuples, &[vec![vec![f32::NAN; 9]]], None, None).unwrap_err();
+        assert!(err.contains("non-finite"));
+        let err = encode_quantized_model(&tuples, &[vec![vec![0.0; 8]]], None, None).unwrap_err();
+        assert!(err.contains("length must be 9"));
+    }
+
+    #[test]
+    fn from_bytes_rejects_invalid_quantization_scale() {
+        let tuples = vec![vec![0u8, 1]];
+        let encoded = encode_quantized_model(&tuples, &[vec![vec![1.0; 9]]], None, None)
+            .expect("must encode");
+        // The scale follows the header and the 3-byte tuple definition.
+        let scale_offset = HEADER_SIZE + 3;
+        for bad_scale in [-1.0f32, f32::INFINITY] {
+            let mut corrupted = encoded.clone();
+            corrupted[scale_offset..scale_offset + 4].copy_from_slice(&bad_scale.to_le_bytes());
+            let crc = crc32fast::hash(&corrupted[HEADER_SIZE..]);
+            corrupted[12..16].copy_from_slice(&crc.to_le_bytes());
+            assert!(NTupleEvaluator::from_bytes(&corrupted).is_err());
+        }
+    }
+
+    // Appends `tail` to the payload of `blob` and refreshes the CRC.
+    fn append_to_payload(mut blob: Vec<u8>, tail: &[u8]) -> Vec<u8> {
+        blob.extend_from_slice(tail);
+        let crc = crc32fast::hash(&blob[HEADER_SIZE..]);
+        blob[12..16].copy_from_slice(&crc.to_le_bytes());
+        blob
+    }
+
+    #[test]
+    fn from_bytes_reads_optional_metadata_block() {
+        let tuples = vec![vec![0u8, 1]];
+        let weights = vec![vec![vec![0.5f32; 9]]];
+        let plain = build_weights_blob_v4(&tuples, &weights, 1);
+        assert!(
+            NTupleEvaluator::from_bytes(&plain)
+                .expect("must parse")
+                .metadata()
+                .is_none()
+        );
+
+        let mut metadata = ModelMetadata::new();
+        metadata.insert("games", 1000);
+        let block = metadata.to_block().expect("must encode");
+        let evaluator = NTupleEvaluator::from_bytes(&append_to_payload(plain.clone(), &block))
+            .expect("metadata must parse");
+        assert_eq!(evaluator.metadata(), Some(&metadata));
+
+        let quantized = NTupleEvaluator::from_bytes(&evaluator.to_quantized_bytes().unwrap())
+            .expect("v5 must parse");
+        assert_eq!(quantized.metadata(), Some(&metadata));
+
+        let err = NTupleEvaluator::from_bytes(&append_to_payload(plain, &[1, 2, 3])).unwrap_err();
+        assert!(err.contains("not a metadata block"));
+    }
+
+    #[test]
+    fn calibration_block_round_trips_and_maps_scores() {
+        let tuples = vec![vec![0u8, 1]];
+        let weights = vec![vec![vec![0.5f32; 9]]; 2];
+        let mut model =
+            ModelFile::from_bytes(&build_weights_blob_v4(&tuples, &weights, 2)).expect("v4");
+        let phase = PhaseCalibration {
+            disc_slope: 4.0,
+            disc_intercept: 1.0,
+            win_slope: 2.0,
+            win_intercept: 0.0,
+        };
+        model.calibration = Some(Calibration::new(vec![phase; 2]).unwrap());
+        let mut metadata = ModelMetadata::new();
+        metadata.insert("games", 10);
+        model.metadata = Some(metadata.clone());
+
+        let bytes = model.to_uncompressed_bytes().unwrap();
+        assert_eq!(ModelFile::from_bytes(&bytes).unwrap(), model);
+        let evaluator = NTupleEvaluator::from_bytes(&bytes).unwrap();
+        assert_eq!(evaluator.metadata(), Some(&metadata));
+        let calibrated = evaluator.calibrate(&Board::new(), 2.0).unwrap();
+        assert_eq!(calibrated.disc_difference, 9.0);
+        assert!((calibrated.win_probability - 0.982).abs() < 1e-3);
+
+        let quantized = NTupleEvaluator::from_bytes(&evaluator.to_quantized_bytes().unwrap())
+            .expect("v5 must parse");
+        assert_eq!(quantized.calibration(), evaluator.calibration());
+
+        model.calibration = Some(Calibration::new(vec![phase]).unwrap());
+        assert!(model.to_uncompressed_bytes().is_err());
+    }
+
+    #[test]
+    fn model_file_round_trips_and_converts_legacy_versions_to_v4() {
+        let tuples = vec![vec![0u8, 1], vec![9u8]];
+        let phase_weights: Vec<_> = (0..2).map(|phase| varied_weights(&tuples, phase)).collect();
+
+        let v4 = build_weights_blob_v4(&tuples, &phase_weights, 2);
+        let model = ModelFile::from_bytes(&v4).expect("v4 must parse");
+        assert_eq!(model.version, VERSION_V4);
+        assert_eq!(model.visit_counts.as_ref().map(Vec::len), Some(2));
+        assert_eq!(model.to_uncompressed_bytes().unwrap(), v4);
+
+        let v2 = ModelFile::from_bytes(&build_weights_blob_v2(&tuples, &phase_weights, 2))
+            .expect("v2 must parse");
+        assert!(v2.visit_counts.is_none());
+        let converted = v2.into_v4();
+        let halved: Vec<Vec<Vec<f32>>> = phase_weights
+            .iter()
+            .map(|phase| {
+                phase
+                    .iter()
+                    .map(|tuple| tuple.iter().map(|value| value * 0.5).collect())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(converted.weights, ModelWeights::Float(halved));
+        let reparsed = ModelFile::from_bytes(&converted.to_bytes().unwrap()).expect("must parse");
+        assert_eq!(reparsed.version, VERSION_V4);
+        assert!(
+            reparsed
+                .visit_counts
+                .unwrap()
+                .iter()
+                .flatten()
+                .flatten()
+                .all(|&count| count == 0)
+        );
+
+        let quantized = model.into_quantized().expect("v4 must quantize");
+        let dequantized = ModelFile::from_bytes(&quantized.to_uncompressed_bytes().unwrap())
+            .expect("v5 must parse")
+            .into_v4();
+        for (actual, expected) in dequantized
+            .weights
+            .to_f32()
+            .iter()
+            .flatten()
+            .flatten()
+            .zip(phase_weights.iter().flatten().flatten())
+        {
+            assert!((actual - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn model_file_round_trips_v6_tc_accumulators() {
+        let tuples = vec![vec![0u8, 1], vec![9u8]];
+        let phase_weights: Vec<_> = (0..2).map(|phase| varied_weights(&tuples, phase)).collect();
+        let mut model = ModelFile::from_bytes(&build_weights_blob_v4(&tuples, &phase_weights, 2))
+            .expect("v4 must parse");
+        let accumulators: TcAccumulators = phase_weights
+            .iter()
+            .map(|phase| {
+                phase
+                    .iter()
+                    .map(|tuple| {
+                        (0..tuple.len())
+                            .map(|index| [index as f32 - 1.0, index as f32 + 0.5])
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        model.version = VERSION_V6;
+        model.tc_accumulators = Some(accumulators.clone());
+
+        let bytes = model.to_uncompressed_bytes().unwrap();
+        let reparsed = ModelFile::from_bytes(&bytes).expect("v6 must parse");
+        assert_eq!(reparsed.version, VERSION_V6);
+        assert_eq!(reparsed.tc_accumulators.as_ref(), Some(&accumulators));
+        assert_eq!(reparsed.weights, ModelWeights::Float(phase_weights.clone()));
+        let evaluator = NTupleEvaluator::from_bytes(&bytes).expect("v6 must load");
+        assert_eq!(evaluator.weights, ModelWeights::Float(phase_weights));
+
+        let converted = reparsed.into_v4();
+        assert_eq!(converted.version, VERSION_V4);
+        assert!(converted.tc_accumulators.is_none());
+        assert!(ModelFile::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+    }
 }
@@ -0,0 +1,201 @@
+use std::mem;
+
+use crate::ai::level::LevelConfig;
+use crate::ai::ntuple::NTupleEvaluator;
+use crate::ai::search::{Searcher, TranspositionTable};
+use crate::board::Board;
+
+/// Thinks on the opponent's time.
+///
+/// After every AI move the transposition table is kept and the opponent's
+/// most likely reply is read from it. `ponder` then searches the position
+/// after that reply in small time slices, so that the next `search` either
+/// returns the pondered move immediately or starts from a warm table.
+#[derive(Debug, Default)]
+pub struct Ponderer {
+    table: TranspositionTable,
+    /// Position the opponent is thinking about.
+    root: Option<(Board, bool)>,
+    predicted_reply: Option<usize>,
+    pondered: bool,
+    result: Option<usize>,
+}
+
+impl Ponderer {
+    /// Searches the best move, reusing pondering work when the opponent played
+    /// the predicted reply, and prepares the next ponder target.
+    /// Caller contract: `board` must have at least one legal move for `is_black`.
+    pub fn search(
+        &mut self,
+        evaluator: &NTupleEvaluator,
+        level: &LevelConfig,
+        board: &Board,
+        is_black: bool,
+    ) -> usize {
+        let previous = mem::take(self);
+        let hit = previous.pondered && previous.target() == Some((*board, is_black));
+
+        let (best_move, table) = match previous.result {
+            Some(best_move) if hit => (best_move, previous.table),
+            _ => {
+                let table = if hit {
+                    previous.table
+                } else {
+                    TranspositionTable::default()
+                };
+                let mut searcher =
+                    Searcher::with_level_config(evaluator, level).with_transposition_table(table);
+                let best_move = searcher.search(board, is_black);
+                (best_move, searcher.into_transposition_table())
+            }
+        };
+
+        let mut after = *board;
+        let _ = after.place(best_move, is_black);
+        let opponent_is_black = !is_black;
+        self.predicted_reply = table
+            .best_move(&after, opponent_is_black)
+            .filter(|&reply| after.legal_moves(opponent_is_black) & (1u64 << reply) != 0);
+        self.root = Some((after, opponent_is_black));
+        self.table = table;
+        best_move
+    }
+
+    /// Spends up to `budget_ms` searching the position after the predicted
+    /// reply. `board` is the position the opponent is to move in.
+    /// Returns `true` while another call would make further progress.
+    pub fn ponder(
+        &mut self,
+        evaluator: &NTupleEvaluator,
+        level: &LevelConfig,
+        board: &Board,
+        is_black: bool,
+        budget_ms: u32,
+    ) -> bool {
+        if self.root != Some((*board, is_black)) || self.result.is_some() {
+            return false;
+        }
+        let Some((target, ai_is_black)) = self.target() else {
+            return false;
+        };
+
+        let slice = LevelConfig {
+            time_budget_ms: budget_ms.max(1),
+            ..*level
+        };
+        let mut searcher = Searcher::with_level_config(evaluator, &slice)
+            .with_transposition_table(mem::take(&mut self.table));
+        let best_move = searcher.search(&target, ai_is_black);
+        let completed = !searcher.timed_out();
+        self.table = searcher.into_transposition_table();
+        self.pondered = true;
+        if completed {
+            self.result = Some(best_move);
+        }
+        !completed
+    }
+
+    /// Drops all pondering work, including the kept transposition table.
+    pub fn cancel(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Opponent move the current ponder target assumes.
+    pub fn predicted_reply(&self) -> Option<usize> {
+        self.predicted_reply
+    }
+
+    /// Finished ponder result for the predicted position, if any.
+    pub fn result(&self) -> Option<usize> {
+        self.result
+    }
+
+    fn target(&self) -> Option<(Board, bool)> {
+        let (root, opponent_is_black) = self.root?;
+        let reply = self.predicted_reply?;
+        let mut next = root;
+        let _ = next.place(reply, opponent_is_black);
+        let ai_is_black = !opponent_is_black;
+        (next.legal_moves(ai_is_black) != 0).then_some((next, ai_is_black))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::test_support::corner_evaluator;
+
+    const PONDER_SLICE_MS: u32 = 60_000;
+
+    fn level() -> LevelConfig {
+        LevelConfig::for_level(4).expect("level 4 must exist")
+    }
+
+    // Plays the AI's opening move as Black and returns the position White faces.
+    fn after_ai_opening(ponderer: &mut Ponderer, evaluator: &NTupleEvaluator) -> Board {
+        let board = Board::new();
+        let mv = ponderer.search(evaluator, &level(), &board, true);
+        let mut after = board;
+        assert_ne!(after.place(mv, true), 0);
+        after
+    }
+
+    #[test]
+    fn ponder_hit_returns_the_move_a_fresh_search_would_play() {
+        let evaluator = corner_evaluator(20.0);
+        let mut ponderer = Ponderer::default();
+        let after = after_ai_opening(&mut ponderer, &evaluator);
+        let reply = ponderer.predicted_reply().expect("reply must be predicted");
+
+        assert!(!ponderer.ponder(&evaluator, &level(), &after, false, PONDER_SLICE_MS));
+        let pondered = ponderer.result().expect("generous slice must finish");
+
+        let mut target = after;
+        assert_ne!(target.place(reply, false), 0);
+        let fresh = Searcher::with_level_config(&evaluator, &level()).search(&target, true);
+        assert_eq!(pondered, fresh);
+        assert_eq!(ponderer.search(&evaluator, &level(), &target, true), fresh);
+    }
+
+    #[test]
+    fn ponder_miss_falls_back_to_a_fresh_search() {
+        let evaluator = corner_evaluator(20.0);
+        let mut ponderer = Ponderer::default();
+        let after = after_ai_opening(&mut ponderer, &evaluator);
+        let reply = ponderer.predicted_reply().expect("reply must be predicted");
+        assert!(!ponderer.ponder(&evaluator, &level(), &after, false, PONDER_SLICE_MS));
+
+        let legal = after.legal_moves(false);
+        let other = (legal & !(1u64 << reply)).trailing_zeros() as usize;
+        let mut target = after;
+        assert_ne!(target.place(other, false), 0);
+        let fresh = Searcher::with_level_config(&evaluator, &level()).search(&target, true);
+
+        assert_eq!(ponderer.search(&evaluator, &level(), &target, true), fresh);
+    }
+
+    #[test]
+    fn ponder_ignores_positions_it_did_not_predict_from() {
+        let evaluator = corner_evaluator(20.0);
+        let mut ponderer = Ponderer::default();
+        assert!(!ponderer.ponder(&evaluator, &level(), &Board::new(), true, PONDER_SLICE_MS));
+
+        let after = after_ai_opening(&mut ponderer, &evaluator);
+        assert!(!ponderer.ponder(&evaluator, &level(), &after, true, PONDER_SLICE_MS));
+        assert!(ponderer.result().is_none());
+    }
+
+    #[test]
+    fn cancel_drops_prediction_and_result() {
+        let evaluator = corner_evaluator(20.0);
+        let mut ponderer = Ponderer::default();
+        let after = after_ai_opening(&mut ponderer, &evaluator);
+        assert!(!ponderer.ponder(&evaluator, &level(), &after, false, PONDER_SLICE_MS));
+
+        ponderer.cancel();
+
+        assert!(ponderer.predicted_reply().is_none());
+        assert!(ponderer.result().is_none());
+        assert!(!ponderer.ponder(&evaluator, &level(), &after, false, PONDER_SLICE_MS));
+    }
+}
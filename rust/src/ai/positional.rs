@@ -0,0 +1,260 @@
+use web_time::{Duration, Instant};
+
+use crate::board::Board;
+
+/// Classic square weights of the hand-written positional player.
+const POSITION_WEIGHTS: [i32; 64] = [
+    120, -20, 20, 5, 5, 20, -20, 120, -20, -40, -5, -5, -5, -5, -40, -20, 20, -5, 15, 3, 3, 15, -5,
+    20, 5, -5, 3, 3, 3, 3, -5, 5, 5, -5, 3, 3, 3, 3, -5, 5, 20, -5, 15, 3, 3, 15, -5, 20, -20, -40,
+    -5, -5, -5, -5, -40, -20, 120, -20, 20, 5, 5, 20, -20, 120,
+];
+
+/// Move of a fixed-depth alpha-beta player over square weights, mobility,
+/// corners and disc count; the baseline opponent of the benchmarks. Ties
+/// break by square in the board's canonical orientation, so the player is
+/// deterministic. `timeout_ms: 0` searches without a time limit.
+pub fn choose_positional_move(
+    board: &Board,
+    is_black: bool,
+    depth: u8,
+    timeout_ms: u64,
+) -> Option<usize> {
+    let legal = board.legal_moves(is_black);
+    if legal == 0 {
+        return None;
+    }
+
+    let deadline = if timeout_ms == 0 {
+        None
+    } else {
+        Some(Instant::now() + Duration::from_millis(timeout_ms))
+    };
+    let moves = sort_moves_for_positional_search(legal, board, is_black);
+    let mut best_move = moves[0];
+    let mut best_score = f32::NEG_INFINITY;
+    let mut alpha = f32::NEG_INFINITY;
+    let beta = f32::INFINITY;
+
+    for mv in moves {
+        if deadline.is_some_and(|limit| Instant::now() >= limit) {
+            break;
+        }
+        let mut next = *board;
+        let _ = next.place(mv, is_black);
+        let score = -positional_negamax(
+            &next,
+            !is_black,
+            depth.saturating_sub(1),
+            -beta,
+            -alpha,
+            deadline,
+        );
+        if is_better_move(board, score, mv, best_score, best_move) {
+            best_score = score;
+            best_move = mv;
+        }
+        if score > alpha {
+            alpha = score;
+        }
+    }
+
+    Some(best_move)
+}
+
+fn positional_negamax(
+    board: &Board,
+    is_black: bool,
+    depth: u8,
+    alpha: f32,
+    beta: f32,
+    deadline: Option<Instant>,
+) -> f32 {
+    if deadline.is_some_and(|limit| Instant::now() >= limit) {
+        return positional_eval(board, is_black);
+    }
+    if depth == 0 {
+        return positional_eval(board, is_black);
+    }
+
+    let legal = board.legal_moves(is_black);
+    if legal == 0 {
+        let opp_legal = board.legal_moves(!is_black);
+        if opp_legal == 0 {
+            return terminal_score(board, is_black);
+        }
+        return -positional_negamax(board, !is_black, depth, -beta, -alpha, deadline);
+    }
+
+    let moves = sort_moves_for_positional_search(legal, board, is_black);
+    let mut alpha = alpha;
+    let mut best = f32::NEG_INFINITY;
+    let mut best_move = moves[0];
+
+    for mv in moves {
+        if deadline.is_some_and(|limit| Instant::now() >= limit) {
+            break;
+        }
+        let mut next = *board;
+        let _ = next.place(mv, is_black);
+        let score = -positional_negamax(&next, !is_black, depth - 1, -beta, -alpha, deadline);
+        if is_better_move(board, score, mv, best, best_move) {
+            best = score;
+            best_move = mv;
+        }
+        if score > alpha {
+            alpha = score;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    best
+}
+
+fn sort_moves_for_positional_search(legal: u64, board: &Board, is_black: bool) -> Vec<usize> {
+    let tie_break_symmetry = canonical_symmetry(board);
+    let mut scored_moves: Vec<(usize, f32)> = bitboard_to_positions(legal)
+        .into_iter()
+        .map(|mv| {
+            let mut next = *board;
+            let _ = next.place(mv, is_black);
+            (mv, -positional_eval(&next, !is_black))
+        })
+        .collect();
+
+    scored_moves.sort_by(|(left_mv, left_score), (right_mv, right_score)| {
+        right_score.total_cmp(left_score).then_with(|| {
+            transform_pos(*left_mv as u8, tie_break_symmetry)
+                .cmp(&transform_pos(*right_mv as u8, tie_break_symmetry))
+        })
+    });
+
+    scored_moves.into_iter().map(|(mv, _)| mv).collect()
+}
+
+fn positional_eval(board: &Board, is_black: bool) -> f32 {
+    let cells = board.to_array();
+    let mut black_positional = 0i32;
+    let mut white_positional = 0i32;
+
+    for (idx, cell) in cells.iter().enumerate() {
+        match *cell {
+            1 => black_positional += POSITION_WEIGHTS[idx],
+            2 => white_positional += POSITION_WEIGHTS[idx],
+            _ => {}
+        }
+    }
+
+    let black_mobility = board.legal_moves(true).count_ones() as i32;
+    let white_mobility = board.legal_moves(false).count_ones() as i32;
+    let black_corners = corners_taken(&cells, 1) as i32;
+    let white_corners = corners_taken(&cells, 2) as i32;
+    let (black_count, white_count) = board.count();
+    let black_score = (black_positional - white_positional)
+        + 5 * (black_mobility - white_mobility)
+        + 25 * (black_corners - white_corners)
+        + (black_count as i32 - white_count as i32);
+
+    let score = if is_black { black_score } else { -black_score };
+    score as f32
+}
+
+fn corners_taken(cells: &[u8; 64], stone: u8) -> usize {
+    [0usize, 7, 56, 63]
+        .into_iter()
+        .filter(|&idx| cells[idx] == stone)
+        .count()
+}
+
+fn terminal_score(board: &Board, is_black: bool) -> f32 {
+    let (black, white) = board.count();
+    if is_black {
+        black as f32 - white as f32
+    } else {
+        white as f32 - black as f32
+    }
+}
+
+fn bitboard_to_positions(mut mask: u64) -> Vec<usize> {
+    let mut out = Vec::new();
+    while mask != 0 {
+        let mv = mask.trailing_zeros() as usize;
+        out.push(mv);
+        mask &= mask - 1;
+    }
+    out
+}
+
+fn is_better_move(board: &Board, score: f32, mv: usize, best_score: f32, best_move: usize) -> bool {
+    let tie_break_symmetry = canonical_symmetry(board);
+    let move_key = transform_pos(mv as u8, tie_break_symmetry);
+    let best_key = transform_pos(best_move as u8, tie_break_symmetry);
+
+    score > best_score || (score == best_score && move_key < best_key)
+}
+
+fn canonical_symmetry(board: &Board) -> u8 {
+    let cells = board.to_array();
+    let mut best = None;
+
+    for symmetry in 0..8u8 {
+        let transformed = transform_cells(&cells, symmetry);
+        if best
+            .as_ref()
+            .is_none_or(|(current, current_sym): &(Vec<u8>, u8)| {
+                transformed < *current || (transformed == *current && symmetry < *current_sym)
+            })
+        {
+            best = Some((transformed, symmetry));
+        }
+    }
+
+    best.expect("at least one symmetry must exist").1
+}
+
+fn transform_cells(cells: &[u8; 64], symmetry: u8) -> Vec<u8> {
+    let mut transformed = vec![0u8; 64];
+    for (pos, value) in cells.iter().copied().enumerate() {
+        transformed[transform_pos(pos as u8, symmetry)] = value;
+    }
+    transformed
+}
+
+fn transform_pos(pos: u8, symmetry: u8) -> usize {
+    let row = (pos as usize) / 8;
+    let col = (pos as usize) % 8;
+
+    let (nr, nc) = match symmetry {
+        0 => (row, col),
+        1 => (col, 7 - row),
+        2 => (7 - row, 7 - col),
+        3 => (7 - col, row),
+        4 => (row, 7 - col),
+        5 => (7 - col, 7 - row),
+        6 => (7 - row, col),
+        _ => (col, row),
+    };
+
+    nr * 8 + nc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positional_player_prefers_corners_and_passes_without_moves() {
+        // Black on c3 can take a1 over b2 or e5 over d4.
+        let board = Board::from_bitboards(1 << 18, (1 << 9) | (1 << 27));
+        assert_eq!(board.legal_moves(true), (1 << 0) | (1 << 36));
+        for depth in 1..=3 {
+            assert_eq!(choose_positional_move(&board, true, depth, 0), Some(0));
+        }
+        assert_eq!(
+            choose_positional_move(&Board::new(), true, 2, 0),
+            choose_positional_move(&Board::new(), true, 2, 60_000)
+        );
+        assert_eq!(choose_positional_move(&board, false, 2, 0), None);
+    }
+}
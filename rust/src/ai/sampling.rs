@@ -0,0 +1,102 @@
+use rand::Rng;
+
+/// Samples a move with probability proportional to `exp(score / temperature)`.
+/// A non-positive temperature returns the first highest-scoring move.
+pub fn softmax_sample<R: Rng + ?Sized>(
+    scored_moves: &[(usize, f32)],
+    temperature: f32,
+    rng: &mut R,
+) -> Option<usize> {
+    let best_score = scored_moves
+        .iter()
+        .map(|(_, score)| *score)
+        .fold(f32::NEG_INFINITY, f32::max);
+    if temperature <= 0.0 || !best_score.is_finite() {
+        return scored_moves
+            .iter()
+            .find(|(_, score)| *score == best_score)
+            .map(|(mv, _)| *mv);
+    }
+
+    // Shift by the best score so the largest weight is exactly 1.0.
+    let weights: Vec<f64> = scored_moves
+        .iter()
+        .map(|(_, score)| (f64::from(*score - best_score) / f64::from(temperature)).exp())
+        .collect();
+    let total: f64 = weights.iter().sum();
+    let mut target = rng.gen_range(0.0..total);
+
+    for ((mv, _), weight) in scored_moves.iter().zip(weights.iter()) {
+        if target < *weight {
+            return Some(*mv);
+        }
+        target -= weight;
+    }
+
+    scored_moves.last().map(|(mv, _)| *mv)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    const SCORED: [(usize, f32); 3] = [(19, 2.0), (26, 6.0), (37, -4.0)];
+
+    #[test]
+    fn zero_temperature_plays_best_move() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+        for _ in 0..32 {
+            assert_eq!(softmax_sample(&SCORED, 0.0, &mut rng), Some(26));
+        }
+    }
+
+    #[test]
+    fn empty_input_returns_none() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+        assert_eq!(softmax_sample(&[], 1.0, &mut rng), None);
+    }
+
+    #[test]
+    fn sampling_is_reproducible_for_fixed_seed() {
+        let draw = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            (0..64)
+                .map(|_| softmax_sample(&SCORED, 4.0, &mut rng).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(draw(7), draw(7));
+    }
+
+    #[test]
+    fn sampling_prefers_better_moves_but_still_varies() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2026);
+        let mut counts = [0usize; 3];
+
+        for _ in 0..2_000 {
+            let mv = softmax_sample(&SCORED, 4.0, &mut rng).unwrap();
+            let idx = SCORED
+                .iter()
+                .position(|(candidate, _)| *candidate == mv)
+                .unwrap();
+            counts[idx] += 1;
+        }
+
+        assert!(counts[1] > counts[0]);
+        assert!(counts[0] > counts[2]);
+        assert!(counts[2] > 0, "worst move must remain possible");
+    }
+
+    #[test]
+    fn large_score_gaps_do_not_overflow() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let scored = [(0usize, 1.0e6f32), (1, -1.0e6)];
+
+        assert_eq!(softmax_sample(&scored, 0.5, &mut rng), Some(0));
+    }
+}
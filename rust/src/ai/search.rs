@@ -2,12 +2,14 @@ use std::collections::HashMap;
 
 use web_time::{Duration, Instant};
 
-use crate::ai::ntuple::NTupleEvaluator;
+use crate::ai::calibration::CalibratedScore;
+use crate::ai::level::LevelConfig;
+use crate::ai::ntuple::{EvalState, NTupleEvaluator};
 use crate::board::Board;
 #[cfg(test)]
 use std::sync::atomic::{AtomicBool, Ordering};
 
-const DEFAULT_TIMEOUT_SECS: u64 = 5;
+const DEFAULT_TIMEOUT_MS: u32 = 5_000;
 const MIN_SCORE: f32 = f32::NEG_INFINITY;
 const MAX_SCORE: f32 = f32::INFINITY;
 #[cfg(test)]
@@ -44,6 +46,31 @@ struct TranspositionEntry {
     bound: Bound,
 }
 
+/// Transposition table that can be handed from one `Searcher` to the next,
+/// e.g. to carry pondering work over to the real search.
+#[derive(Debug, Default)]
+pub struct TranspositionTable {
+    entries: HashMap<SearchKey, TranspositionEntry>,
+}
+
+impl TranspositionTable {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stored best move for `board`, preferring endgame-solver entries.
+    pub fn best_move(&self, board: &Board, is_black: bool) -> Option<usize> {
+        self.entries
+            .get(&search_key(board, is_black, true))
+            .or_else(|| self.entries.get(&search_key(board, is_black, false)))
+            .map(|entry| entry.best_move)
+    }
+}
+
 impl SearchResult {
     fn negate(self) -> Self {
         match self {
@@ -58,27 +85,74 @@ pub struct Searcher<'a> {
     start_time: Instant,
     timeout: Duration,
     max_depth: u8,
+    exact_solve_empties: u8,
+    wld_empties: u8,
+    selectivity: u8,
     timed_out: bool,
+    reuse_transposition_table: bool,
     transposition_table: HashMap<SearchKey, TranspositionEntry>,
+    /// Incremental features of the node currently being searched.
+    eval_state: EvalState,
 }
 
 impl<'a> Searcher<'a> {
-    pub fn new(evaluator: &'a NTupleEvaluator, max_depth: u8) -> Self {
-        Self::with_timeout(
-            evaluator,
-            max_depth,
-            Duration::from_secs(DEFAULT_TIMEOUT_SECS),
-        )
+    /// Creates a searcher for a built-in level.
+    /// Levels outside the table search to `level` plies without endgame solving.
+    pub fn new(evaluator: &'a NTupleEvaluator, level: u8) -> Self {
+        Self::with_level_config(evaluator, &level_config_or_depth_only(level))
     }
 
-    pub fn with_timeout(evaluator: &'a NTupleEvaluator, max_depth: u8, timeout: Duration) -> Self {
+    /// Creates a searcher for a built-in level with an explicit time budget.
+    pub fn with_timeout(evaluator: &'a NTupleEvaluator, level: u8, timeout: Duration) -> Self {
+        let mut searcher = Self::new(evaluator, level);
+        searcher.timeout = timeout;
+        searcher
+    }
+
+    pub fn with_level_config(evaluator: &'a NTupleEvaluator, config: &LevelConfig) -> Self {
         Self {
             evaluator,
             start_time: Instant::now(),
-            timeout,
-            max_depth,
+            timeout: Duration::from_millis(u64::from(config.time_budget_ms)),
+            max_depth: config.depth,
+            exact_solve_empties: config.exact_solve_empties,
+            wld_empties: config.wld_empties,
+            selectivity: config.selectivity,
             timed_out: false,
+            reuse_transposition_table: false,
             transposition_table: HashMap::new(),
+            eval_state: EvalState::default(),
+        }
+    }
+
+    /// Creates a searcher for [`Self::exact_score`] without a time budget.
+    pub fn exact_solver(evaluator: &'a NTupleEvaluator) -> Self {
+        let mut searcher = Self::with_level_config(
+            evaluator,
+            &LevelConfig {
+                depth: 1,
+                exact_solve_empties: 64,
+                wld_empties: 0,
+                time_budget_ms: 0,
+                selectivity: 0,
+                randomness: 0.0,
+            },
+        );
+        searcher.timeout = Duration::MAX;
+        searcher
+    }
+
+    /// Starts from `table` and keeps its entries across searches instead of
+    /// clearing them.
+    pub fn with_transposition_table(mut self, table: TranspositionTable) -> Self {
+        self.transposition_table = table.entries;
+        self.reuse_transposition_table = true;
+        self
+    }
+
+    pub fn into_transposition_table(self) -> TranspositionTable {
+        TranspositionTable {
+            entries: self.transposition_table,
         }
     }
 
@@ -87,7 +161,10 @@ impl<'a> Searcher<'a> {
     pub fn search(&mut self, board: &Board, is_black: bool) -> usize {
         self.start_time = Instant::now();
         self.timed_out = false;
-        self.transposition_table.clear();
+        if !self.reuse_transposition_table {
+            self.transposition_table.clear();
+        }
+        self.eval_state = self.evaluator.eval_state(board);
 
         let legal = board.legal_moves(is_black);
         let moves = bitboard_to_positions(legal);
@@ -114,16 +191,147 @@ impl<'a> Searcher<'a> {
             }
         }
 
-        if self.should_exact_solve(board)
-            && !self.timed_out
-            && let SearchResult::Complete(mv, _score) = self.exact_solve(board, is_black)
+        if self.timed_out {
+            return best_move;
+        }
+
+        if self.should_exact_solve(board) {
+            if let SearchResult::Complete(mv, _score) = self.exact_solve(board, is_black) {
+                best_move = mv;
+            }
+        } else if self.should_wld_solve(board)
+            && let SearchResult::Complete(mv, score) = self.wld_solve(board, is_black)
+            && score >= 0.0
         {
+            // A proven loss keeps the midgame choice, which is the better practical try.
             best_move = mv;
         }
 
         best_move
     }
 
+    /// Scores every root move from the side-to-move perspective.
+    /// Scores come from the deepest fully completed iteration, replaced by exact
+    /// disc differences when the position is within the exact-solve threshold.
+    /// Caller contract: `board` must have at least one legal move for `is_black`.
+    pub fn analyze(&mut self, board: &Board, is_black: bool) -> Vec<(usize, f32)> {
+        self.start_time = Instant::now();
+        self.timed_out = false;
+        if !self.reuse_transposition_table {
+            self.transposition_table.clear();
+        }
+        self.eval_state = self.evaluator.eval_state(board);
+
+        let legal = board.legal_moves(is_black);
+        debug_assert!(legal != 0, "analyze() requires at least one legal move");
+        let moves = bitboard_to_sorted_moves(
+            legal,
+            board,
+            is_black,
+            self.evaluator,
+            &mut self.eval_state,
+            None,
+        );
+        let mut scored_moves = Vec::new();
+
+        // Depth 1 never times out (see `negaalpha`), so every legal move gets a
+        // score even when the budget is already spent.
+        'depths: for depth in 1..=self.max_depth.max(1) {
+            let mut current = Vec::with_capacity(moves.len());
+            for &mv in &moves {
+                let mut next = *board;
+                let flips = next.place(mv, is_black);
+                self.evaluator
+                    .apply_move(&mut self.eval_state, mv, flips, is_black);
+                let result =
+                    self.negaalpha(&next, !is_black, depth - 1, depth, MIN_SCORE, MAX_SCORE);
+                self.evaluator
+                    .undo_move(&mut self.eval_state, mv, flips, is_black);
+                match result {
+                    SearchResult::Complete(_, score) => current.push((mv, -score)),
+                    SearchResult::TimedOut => break 'depths,
+                }
+            }
+            scored_moves = current;
+        }
+
+        if self.timed_out || !self.should_exact_solve(board) {
+            return scored_moves;
+        }
+
+        let mut exact_moves = Vec::with_capacity(moves.len());
+        for &mv in &moves {
+            let mut next = *board;
+            let flips = next.place(mv, is_black);
+            self.evaluator
+                .apply_move(&mut self.eval_state, mv, flips, is_black);
+            let result =
+                self.negaalpha_exact(&next, !is_black, next.empty_count(), MIN_SCORE, MAX_SCORE);
+            self.evaluator
+                .undo_move(&mut self.eval_state, mv, flips, is_black);
+            match result {
+                SearchResult::Complete(_, score) => exact_moves.push((mv, -score)),
+                SearchResult::TimedOut => return scored_moves,
+            }
+        }
+        exact_moves
+    }
+
+    /// Like [`Self::analyze`], with each score mapped to the final disc
+    /// difference and win probability. Exactly solved scores are used as is;
+    /// otherwise the model calibration applies, `None` without one.
+    pub fn analyze_calibrated(
+        &mut self,
+        board: &Board,
+        is_black: bool,
+    ) -> Vec<(usize, f32, Option<CalibratedScore>)> {
+        let scored_moves = self.analyze(board, is_black);
+        let solved = !self.timed_out && self.should_exact_solve(board);
+        scored_moves
+            .into_iter()
+            .map(|(mv, score)| {
+                let calibrated = if solved {
+                    Some(CalibratedScore::exact(score))
+                } else {
+                    self.evaluator.calibrate(board, score)
+                };
+                (mv, score, calibrated)
+            })
+            .collect()
+    }
+
+    /// Like [`Self::analyze`], with scores on the final disc difference scale
+    /// so a sampling temperature means the same in the midgame and when
+    /// solved: exact scores as is, otherwise calibrated with the model's
+    /// calibration and raw without one (models are trained on disc
+    /// differences).
+    pub fn analyze_discs(&mut self, board: &Board, is_black: bool) -> Vec<(usize, f32)> {
+        self.analyze_calibrated(board, is_black)
+            .into_iter()
+            .map(|(mv, score, calibrated)| {
+                (
+                    mv,
+                    calibrated.map_or(score, |calibrated| calibrated.disc_difference),
+                )
+            })
+            .collect()
+    }
+
+    /// Final disc difference for the side to move under perfect play, or
+    /// `None` when the time budget runs out first. Unlike [`Self::search`],
+    /// `board` may require a pass or be finished.
+    pub fn exact_score(&mut self, board: &Board, is_black: bool) -> Option<f32> {
+        self.start_time = Instant::now();
+        self.timed_out = false;
+        if !self.reuse_transposition_table {
+            self.transposition_table.clear();
+        }
+        match self.exact_solve(board, is_black) {
+            SearchResult::Complete(_, score) => Some(score),
+            SearchResult::TimedOut => None,
+        }
+    }
+
     pub fn timed_out(&self) -> bool {
         self.timed_out
     }
@@ -144,7 +352,11 @@ impl<'a> Searcher<'a> {
         }
 
         if depth == 0 {
-            return SearchResult::Complete(0, self.evaluator.evaluate(board, is_black));
+            return SearchResult::Complete(
+                0,
+                self.evaluator
+                    .evaluate_state(&self.eval_state, board, is_black),
+            );
         }
 
         let key = search_key(board, is_black, false);
@@ -182,15 +394,28 @@ impl<'a> Searcher<'a> {
                 .negate();
         }
 
-        let moves =
-            bitboard_to_sorted_moves(legal, board, is_black, self.evaluator, preferred_move);
+        let mut moves = bitboard_to_sorted_moves(
+            legal,
+            board,
+            is_black,
+            self.evaluator,
+            &mut self.eval_state,
+            preferred_move,
+        );
+        if self.selectivity > 0 && depth >= 2 && depth < root_depth {
+            moves.truncate(self.selectivity as usize);
+        }
         let mut best_move = moves[0];
         let mut best_score = MIN_SCORE;
 
         for mv in moves {
             let mut next = *board;
-            let _ = next.place(mv, is_black);
+            let flips = next.place(mv, is_black);
+            self.evaluator
+                .apply_move(&mut self.eval_state, mv, flips, is_black);
             let result = self.negaalpha(&next, !is_black, depth - 1, root_depth, -beta, -alpha);
+            self.evaluator
+                .undo_move(&mut self.eval_state, mv, flips, is_black);
 
             match result {
                 SearchResult::TimedOut => return SearchResult::TimedOut,
@@ -226,22 +451,25 @@ impl<'a> Searcher<'a> {
     }
 
     fn should_exact_solve(&self, board: &Board) -> bool {
-        let empty = board.empty_count();
-        match self.max_depth {
-            // REQUIREMENTS.md 2.3: Level 1-2 do not use exact solving.
-            1 | 2 => false,
-            3 => empty <= 10,
-            4 => empty <= 12,
-            5 => empty <= 14,
-            6 => empty <= 16,
-            _ => false,
-        }
+        board.empty_count() <= self.exact_solve_empties
+    }
+
+    fn should_wld_solve(&self, board: &Board) -> bool {
+        board.empty_count() <= self.wld_empties
     }
 
     fn exact_solve(&mut self, board: &Board, is_black: bool) -> SearchResult {
+        self.eval_state = self.evaluator.eval_state(board);
         self.negaalpha_exact(board, is_black, board.empty_count(), MIN_SCORE, MAX_SCORE)
     }
 
+    /// Solves win/loss/draw with a null window around zero; only the sign of
+    /// the returned score is exact.
+    fn wld_solve(&mut self, board: &Board, is_black: bool) -> SearchResult {
+        self.eval_state = self.evaluator.eval_state(board);
+        self.negaalpha_exact(board, is_black, board.empty_count(), -1.0, 1.0)
+    }
+
     fn negaalpha_exact(
         &mut self,
         board: &Board,
@@ -289,6 +517,10 @@ impl<'a> Searcher<'a> {
             }
         }
 
+        if let Some(score) = stability_upper_bound(board, is_black, alpha) {
+            return SearchResult::Complete(preferred_move.unwrap_or(0), score);
+        }
+
         let legal = board.legal_moves(is_black);
         if legal == 0 {
             let opp_legal = board.legal_moves(!is_black);
@@ -300,15 +532,25 @@ impl<'a> Searcher<'a> {
                 .negate();
         }
 
-        let moves =
-            bitboard_to_sorted_moves(legal, board, is_black, self.evaluator, preferred_move);
+        let moves = bitboard_to_sorted_moves(
+            legal,
+            board,
+            is_black,
+            self.evaluator,
+            &mut self.eval_state,
+            preferred_move,
+        );
         let mut best_move = moves[0];
         let mut best_score = MIN_SCORE;
 
         for mv in moves {
             let mut next = *board;
-            let _ = next.place(mv, is_black);
+            let flips = next.place(mv, is_black);
+            self.evaluator
+                .apply_move(&mut self.eval_state, mv, flips, is_black);
             let result = self.negaalpha_exact(&next, !is_black, empties - 1, -beta, -alpha);
+            self.evaluator
+                .undo_move(&mut self.eval_state, mv, flips, is_black);
 
             match result {
                 SearchResult::TimedOut => return SearchResult::TimedOut,
@@ -344,6 +586,17 @@ impl<'a> Searcher<'a> {
     }
 }
 
+fn level_config_or_depth_only(level: u8) -> LevelConfig {
+    LevelConfig::for_level(level).unwrap_or(LevelConfig {
+        depth: level,
+        exact_solve_empties: 0,
+        wld_empties: 0,
+        time_budget_ms: DEFAULT_TIMEOUT_MS,
+        selectivity: 0,
+        randomness: 0.0,
+    })
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
 pub(crate) fn set_force_exact_solve_timeout_for_test(force: bool) {
@@ -367,6 +620,19 @@ fn exact_score(board: &Board, is_black: bool) -> f32 {
     }
 }
 
+/// Upper bound on the final disc difference when the opponent's stable discs
+/// already keep it at or below `alpha`. Counting the opponent's discs first
+/// skips the stability pass whenever no cutoff is possible.
+fn stability_upper_bound(board: &Board, is_black: bool, alpha: f32) -> Option<f32> {
+    let (black, white) = board.bitboards();
+    let opp = if is_black { white } else { black };
+    if 64.0 - 2.0 * opp.count_ones() as f32 > alpha {
+        return None;
+    }
+    let upper = 64.0 - 2.0 * board.stable_discs(!is_black).count_ones() as f32;
+    (upper <= alpha).then_some(upper)
+}
+
 fn search_key(board: &Board, is_black: bool, exact: bool) -> SearchKey {
     let (black, white) = board.bitboards();
     SearchKey {
@@ -402,6 +668,7 @@ fn bitboard_to_sorted_moves(
     board: &Board,
     is_black: bool,
     evaluator: &NTupleEvaluator,
+    eval_state: &mut EvalState,
     preferred_move: Option<usize>,
 ) -> Vec<usize> {
     let tie_break_symmetry = canonical_symmetry(board);
@@ -409,9 +676,11 @@ fn bitboard_to_sorted_moves(
         .into_iter()
         .map(|mv| {
             let mut next = *board;
-            let _ = next.place(mv, is_black);
+            let flips = next.place(mv, is_black);
+            evaluator.apply_move(eval_state, mv, flips, is_black);
             // Move ordering heuristic from the current player's perspective.
-            let score = -evaluator.evaluate(&next, !is_black);
+            let score = -evaluator.evaluate_state(eval_state, &next, !is_black);
+            evaluator.undo_move(eval_state, mv, flips, is_black);
             (mv, score)
         })
         .collect();
@@ -481,6 +750,7 @@ fn transform_pos(pos: u8, symmetry: u8) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ai::test_support::endgame_position;
 
     const MAGIC: &[u8; 4] = b"NTRV";
     const VERSION: u32 = 1;
@@ -628,6 +898,221 @@ mod tests {
         assert!(!Searcher::new(&evaluator, 6).should_exact_solve(&board_17));
     }
 
+    #[test]
+    fn custom_level_config_controls_endgame_thresholds() {
+        let evaluator = build_constant_evaluator();
+        let config = LevelConfig {
+            depth: 2,
+            exact_solve_empties: 6,
+            wld_empties: 9,
+            time_budget_ms: 1_000,
+            selectivity: 0,
+            randomness: 0.0,
+        };
+        let searcher = Searcher::with_level_config(&evaluator, &config);
+
+        assert!(searcher.should_exact_solve(&board_with_empty_count(6)));
+        assert!(!searcher.should_exact_solve(&board_with_empty_count(7)));
+        assert!(searcher.should_wld_solve(&board_with_empty_count(9)));
+        assert!(!searcher.should_wld_solve(&board_with_empty_count(10)));
+        assert_eq!(searcher.timeout, Duration::from_millis(1_000));
+    }
+
+    #[test]
+    fn wld_solve_agrees_with_exact_solve_on_outcome() {
+        let evaluator = build_constant_evaluator();
+        let (board, is_black) = endgame_position(10);
+
+        let mut exact_searcher = Searcher::new(&evaluator, 6);
+        let SearchResult::Complete(_, exact) = exact_searcher.exact_solve(&board, is_black) else {
+            panic!("exact solve must complete");
+        };
+        let mut wld_searcher = Searcher::new(&evaluator, 6);
+        let SearchResult::Complete(mv, wld) = wld_searcher.wld_solve(&board, is_black) else {
+            panic!("wld solve must complete");
+        };
+
+        assert_eq!(wld.partial_cmp(&0.0), exact.partial_cmp(&0.0));
+        assert_ne!(board.legal_moves(is_black) & (1u64 << mv), 0);
+    }
+
+    #[test]
+    fn selective_search_still_returns_legal_moves() {
+        let evaluator = build_constant_evaluator();
+        let config = LevelConfig {
+            depth: 4,
+            exact_solve_empties: 0,
+            wld_empties: 0,
+            time_budget_ms: 5_000,
+            selectivity: 1,
+            randomness: 0.0,
+        };
+        let (board, is_black) = endgame_position(30);
+
+        let mv = Searcher::with_level_config(&evaluator, &config).search(&board, is_black);
+
+        assert_ne!(board.legal_moves(is_black) & (1u64 << mv), 0);
+    }
+
+    #[test]
+    fn analyze_scores_every_legal_move_and_agrees_with_search() {
+        let evaluator = build_constant_evaluator();
+        let board = Board::new();
+
+        let scored = Searcher::new(&evaluator, 3).analyze(&board, true);
+        let mut moves: Vec<usize> = scored.iter().map(|(mv, _)| *mv).collect();
+        moves.sort_unstable();
+
+        assert_eq!(moves, bitboard_to_positions(board.legal_moves(true)));
+        assert!(scored.iter().all(|(_, score)| *score == 0.0));
+    }
+
+    #[test]
+    fn analyze_uses_exact_scores_inside_solve_threshold() {
+        let evaluator = build_constant_evaluator();
+        let (board, is_black) = endgame_position(8);
+
+        let scored = Searcher::new(&evaluator, 3).analyze(&board, is_black);
+        let mut solver = Searcher::new(&evaluator, 3);
+        let SearchResult::Complete(_, best) = solver.exact_solve(&board, is_black) else {
+            panic!("exact solve must complete");
+        };
+
+        let analyzed_best = scored
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(MIN_SCORE, f32::max);
+        assert_eq!(analyzed_best, best);
+    }
+
+    fn reference_exact_score(board: &Board, is_black: bool) -> f32 {
+        let legal = board.legal_moves(is_black);
+        if legal == 0 {
+            if board.legal_moves(!is_black) == 0 {
+                return exact_score(board, is_black);
+            }
+            return -reference_exact_score(board, !is_black);
+        }
+        bitboard_to_positions(legal)
+            .into_iter()
+            .map(|mv| {
+                let mut next = *board;
+                let _ = next.place(mv, is_black);
+                -reference_exact_score(&next, !is_black)
+            })
+            .fold(MIN_SCORE, f32::max)
+    }
+
+    #[test]
+    fn stability_cutoff_keeps_exact_and_wld_results() {
+        let evaluator = build_constant_evaluator();
+        for empties in [6, 7, 8] {
+            let (board, is_black) = endgame_position(empties);
+            let expected = reference_exact_score(&board, is_black);
+
+            let SearchResult::Complete(_, exact) =
+                Searcher::new(&evaluator, 3).exact_solve(&board, is_black)
+            else {
+                panic!("exact solve must complete");
+            };
+            assert_eq!(exact, expected);
+
+            let SearchResult::Complete(_, wld) =
+                Searcher::new(&evaluator, 3).wld_solve(&board, is_black)
+            else {
+                panic!("wld solve must complete");
+            };
+            assert_eq!(wld.signum(), expected.signum());
+        }
+    }
+
+    #[test]
+    fn exact_score_solves_passes_and_finished_boards() {
+        let evaluator = build_constant_evaluator();
+        let mut solver = Searcher::exact_solver(&evaluator);
+        for empties in [6, 8] {
+            let (board, is_black) = endgame_position(empties);
+            for side in [is_black, !is_black] {
+                assert_eq!(
+                    solver.exact_score(&board, side),
+                    Some(reference_exact_score(&board, side))
+                );
+            }
+        }
+
+        let finished = board_with_empty_count(3);
+        assert_eq!(solver.exact_score(&finished, true), Some(61.0));
+        assert_eq!(solver.exact_score(&finished, false), Some(-61.0));
+
+        let mut searcher = Searcher::with_timeout(&evaluator, 6, Duration::ZERO);
+        assert_eq!(searcher.exact_score(&Board::new(), true), None);
+    }
+
+    #[test]
+    fn stability_upper_bound_only_cuts_below_alpha() {
+        // Black owns the whole first rank: 8 stable discs cap white at 64 - 16.
+        let board = Board::from_bitboards(0xff, bit(20));
+        assert_eq!(stability_upper_bound(&board, false, 48.0), Some(48.0));
+        assert_eq!(stability_upper_bound(&board, false, 47.0), None);
+        assert_eq!(stability_upper_bound(&board, true, 63.0), None);
+    }
+
+    #[test]
+    fn analyze_calibrated_reports_exact_outcomes_when_solved() {
+        let evaluator = build_constant_evaluator();
+        let (board, is_black) = endgame_position(6);
+
+        let analysis = Searcher::new(&evaluator, 3).analyze_calibrated(&board, is_black);
+        assert!(!analysis.is_empty());
+        for (_, score, calibrated) in analysis {
+            assert_eq!(calibrated, Some(CalibratedScore::exact(score)));
+        }
+
+        // Outside the solve threshold an uncalibrated model has nothing to report.
+        let analysis = Searcher::new(&evaluator, 3).analyze_calibrated(&Board::new(), true);
+        assert!(
+            analysis
+                .iter()
+                .all(|(_, _, calibrated)| calibrated.is_none())
+        );
+    }
+
+    #[test]
+    fn analyze_scores_every_move_without_time_budget() {
+        let evaluator = build_constant_evaluator();
+        let level = |exact_solve_empties| LevelConfig {
+            depth: 6,
+            exact_solve_empties,
+            wld_empties: 0,
+            time_budget_ms: 0,
+            selectivity: 0,
+            randomness: 1.0,
+        };
+
+        let mut searcher = Searcher::with_level_config(&evaluator, &level(0));
+        let analysis = searcher.analyze(&Board::new(), true);
+        assert_eq!(analysis.len(), 4);
+        assert!(searcher.timed_out());
+
+        let (board, is_black) = endgame_position(12);
+        let legal = board.legal_moves(is_black).count_ones() as usize;
+        let analysis =
+            Searcher::with_level_config(&evaluator, &level(12)).analyze_discs(&board, is_black);
+        assert_eq!(analysis.len(), legal);
+    }
+
+    #[test]
+    fn analyze_discs_keeps_exact_scores_when_solved() {
+        let evaluator = build_constant_evaluator();
+        let (board, is_black) = endgame_position(6);
+
+        let exact = Searcher::new(&evaluator, 3).analyze(&board, is_black);
+        assert_eq!(
+            Searcher::new(&evaluator, 3).analyze_discs(&board, is_black),
+            exact
+        );
+    }
+
     #[test]
     fn exact_solve_stops_when_deadline_is_already_exceeded() {
         let evaluator = build_constant_evaluator();
@@ -646,7 +1131,10 @@ mod tests {
         let board = Board::new();
         let legal = board.legal_moves(true);
 
-        let moves = bitboard_to_sorted_moves(legal, &board, true, &evaluator, Some(44));
+        let mut eval_state = evaluator.eval_state(&board);
+
+        let moves =
+            bitboard_to_sorted_moves(legal, &board, true, &evaluator, &mut eval_state, Some(44));
 
         assert_eq!(moves[0], 44);
         assert_eq!(moves, vec![44, 19, 26, 37]);
@@ -0,0 +1,41 @@
+use crate::ai::ntuple::{ModelFile, ModelWeights, NTupleEvaluator};
+use crate::board::Board;
+
+/// Evaluator worth `weight` per corner held: a single-cell tuple on a1 whose
+/// rotations cover every corner.
+pub(crate) fn corner_evaluator(weight: f32) -> NTupleEvaluator {
+    let model = ModelFile {
+        version: 3,
+        tuples: vec![vec![0]],
+        phase_count: 30,
+        weights: ModelWeights::Float(vec![vec![vec![0.0, weight, -weight]]; 30]),
+        visit_counts: None,
+        tc_accumulators: None,
+        features: None,
+        calibration: None,
+        metadata: None,
+    };
+    NTupleEvaluator::from_bytes(&model.to_uncompressed_bytes().unwrap())
+        .expect("corner evaluator must parse")
+}
+
+/// Plays first legal moves from the opening until `empties` squares remain and
+/// returns the position with a side to move that has a legal move.
+pub(crate) fn endgame_position(empties: u8) -> (Board, bool) {
+    let mut board = Board::new();
+    let mut is_black = true;
+    while board.empty_count() > empties {
+        let mut legal = board.legal_moves(is_black);
+        if legal == 0 {
+            is_black = !is_black;
+            legal = board.legal_moves(is_black);
+            assert_ne!(legal, 0, "game ended before reaching {empties} empties");
+        }
+        let _ = board.place(legal.trailing_zeros() as usize, is_black);
+        is_black = !is_black;
+    }
+    if board.legal_moves(is_black) == 0 {
+        is_black = !is_black;
+    }
+    (board, is_black)
+}
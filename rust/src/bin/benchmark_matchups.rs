@@ -1,29 +1,28 @@
 use std::env;
 use std::fs;
 use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use rand::SeedableRng;
 use rand::prelude::SliceRandom;
+use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use reversi::ai::arena;
+use reversi::ai::features::ScalarFeature;
+use reversi::ai::level::LevelConfig;
+use reversi::ai::mcts::{Mcts, MctsConfig};
 use reversi::ai::ntuple::NTupleEvaluator;
+use reversi::ai::positional::choose_positional_move;
 use reversi::ai::search::Searcher;
 use reversi::board::Board;
+use reversi::cli::parse_value;
 use web_time::Duration as WebDuration;
 
 const EMBEDDED_MODEL_BYTES: &[u8] =
     include_bytes!(concat!(env!("OUT_DIR"), "/embedded_weights.bin"));
-const MAX_GAME_STEPS: usize = 200;
-const MIN_LEVEL: u8 = 1;
-const MAX_LEVEL: u8 = 6;
 const DEFAULT_WEIGHTS_TIMEOUT_MS: u64 = 250;
 const DEFAULT_OPPONENT_TIMEOUT_MS: u64 = 250;
 const DISABLED_TIMEOUT_SECS: u64 = 60 * 60 * 24 * 365;
-const POSITION_WEIGHTS: [i32; 64] = [
-    120, -20, 20, 5, 5, 20, -20, 120, -20, -40, -5, -5, -5, -5, -40, -20, 20, -5, 15, 3, 3, 15, -5,
-    20, 5, -5, 3, 3, 3, 3, -5, 5, 5, -5, 3, 3, 3, 3, -5, 5, 20, -5, 15, 3, 3, 15, -5, 20, -20, -40,
-    -5, -5, -5, -5, -40, -20, 120, -20, 20, 5, 5, 20, -20, 120,
-];
+const DEFAULT_MCTS_EXPLORATION: f32 = 1.5;
 const GOLDEN_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
 
 #[derive(Clone, Debug)]
@@ -36,13 +35,25 @@ struct Config {
     opponent_timeout_ms: u64,
     weights_path: Option<PathBuf>,
     opponent_weights_path: Option<PathBuf>,
+    engine: Engine,
+    opponent_engine: Option<Engine>,
+    mcts_nodes: usize,
+    mcts_exploration: f32,
+    mcts_random_playouts: bool,
+    ablate_features: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Engine {
+    AlphaBeta,
+    Mcts,
 }
 
 #[derive(Clone, Copy, Debug)]
 enum Opponent<'a> {
     Random,
     PositionalSearch,
-    WeightsModel(&'a NTupleEvaluator),
+    WeightsModel(&'a NTupleEvaluator, Engine),
 }
 
 #[derive(Default)]
@@ -62,7 +73,10 @@ fn main() -> Result<(), String> {
     let config = parse_args(env::args().skip(1).collect())?;
     let evaluator = load_evaluator(config.weights_path.as_ref())?;
     let opponent_evaluator = load_optional_evaluator(config.opponent_weights_path.as_ref())?;
-    let primary_label = model_source_label(config.weights_path.as_ref());
+    let primary_label = engine_label(
+        &model_source_label(config.weights_path.as_ref()),
+        config.engine,
+    );
 
     println!(
         "Benchmarking weights.bin AI: games_per_matchup={}, level={}, seed={}, random_opening_plies={}, weights_timeout_ms={}, opponent_timeout_ms={}",
@@ -73,6 +87,18 @@ fn main() -> Result<(), String> {
         config.weights_timeout_ms,
         config.opponent_timeout_ms
     );
+    if config.engine == Engine::Mcts || config.opponent_engine == Some(Engine::Mcts) {
+        println!(
+            "MCTS: nodes={}, exploration={}, leaf={}",
+            config.mcts_nodes,
+            config.mcts_exploration,
+            if config.mcts_random_playouts {
+                "random-playouts"
+            } else {
+                "evaluator"
+            }
+        );
+    }
     if let Some(path) = &config.weights_path {
         println!("Model source: {}", path.display());
     } else {
@@ -83,11 +109,26 @@ fn main() -> Result<(), String> {
     }
     println!();
 
-    if let Some(opponent_evaluator) = opponent_evaluator.as_ref() {
-        let opponent_label = model_source_label(config.opponent_weights_path.as_ref());
+    if config.ablate_features {
+        return run_feature_ablation(&evaluator, &primary_label, &config);
+    }
+
+    // Without an opponent model, --opponent-engine pits the primary model against itself.
+    let opponent_model = match (opponent_evaluator.as_ref(), config.opponent_engine) {
+        (Some(opponent_evaluator), _) => Some((
+            opponent_evaluator,
+            model_source_label(config.opponent_weights_path.as_ref()),
+        )),
+        (None, Some(_)) => Some((&evaluator, model_source_label(config.weights_path.as_ref()))),
+        (None, None) => None,
+    };
+
+    if let Some((opponent_evaluator, opponent_source)) = opponent_model {
+        let opponent_engine = config.opponent_engine.unwrap_or(Engine::AlphaBeta);
+        let opponent_label = engine_label(&opponent_source, opponent_engine);
         let stats = benchmark_matchup(
             &evaluator,
-            Opponent::WeightsModel(opponent_evaluator),
+            Opponent::WeightsModel(opponent_evaluator, opponent_engine),
             &config,
             config.seed.wrapping_add(GOLDEN_GAMMA),
         )?;
@@ -125,6 +166,49 @@ fn main() -> Result<(), String> {
     Ok(())
 }
 
+/// Plays the model against copies of itself with one scalar feature (and then
+/// all of them) removed, so each feature's contribution shows up as win rate.
+fn run_feature_ablation(
+    evaluator: &NTupleEvaluator,
+    primary_label: &str,
+    config: &Config,
+) -> Result<(), String> {
+    let features = evaluator
+        .scalar_features()
+        .map(|features| features.features().to_vec())
+        .ok_or_else(|| "--ablate-features needs a model with scalar features".to_string())?;
+    let opponent_engine = config.opponent_engine.unwrap_or(config.engine);
+    let mut ablations: Vec<(String, Vec<ScalarFeature>)> = features
+        .iter()
+        .map(|&feature| (format!("without {}", feature.name()), vec![feature]))
+        .collect();
+    if features.len() > 1 {
+        ablations.push(("without scalar features".to_string(), features));
+    }
+
+    for (offset, (label, removed)) in ablations.into_iter().enumerate() {
+        let ablated = evaluator.without_scalar_features(&removed);
+        let stats = benchmark_matchup(
+            evaluator,
+            Opponent::WeightsModel(&ablated, opponent_engine),
+            config,
+            config
+                .seed
+                .wrapping_add(GOLDEN_GAMMA.wrapping_mul((offset as u64) + 1)),
+        )?;
+        print_stats(
+            &format!(
+                "{primary_label} vs {}",
+                engine_label(&format!("model {label}"), opponent_engine)
+            ),
+            "primary_model_move_ms",
+            "ablated_model_move_ms",
+            &stats,
+        );
+    }
+    Ok(())
+}
+
 fn parse_args(args: Vec<String>) -> Result<Config, String> {
     let mut config = Config {
         games_per_matchup: 20,
@@ -135,6 +219,12 @@ fn parse_args(args: Vec<String>) -> Result<Config, String> {
         opponent_timeout_ms: DEFAULT_OPPONENT_TIMEOUT_MS,
         weights_path: None,
         opponent_weights_path: None,
+        engine: Engine::AlphaBeta,
+        opponent_engine: None,
+        mcts_nodes: 0,
+        mcts_exploration: DEFAULT_MCTS_EXPLORATION,
+        mcts_random_playouts: false,
+        ablate_features: false,
     };
 
     let mut idx = 0usize;
@@ -178,6 +268,28 @@ fn parse_args(args: Vec<String>) -> Result<Config, String> {
                     .ok_or_else(|| "missing value for --opponent-weights-path".to_string())?;
                 config.opponent_weights_path = Some(PathBuf::from(raw));
             }
+            "--engine" => {
+                idx += 1;
+                config.engine = parse_engine(&args, idx, "--engine")?;
+            }
+            "--opponent-engine" => {
+                idx += 1;
+                config.opponent_engine = Some(parse_engine(&args, idx, "--opponent-engine")?);
+            }
+            "--mcts-nodes" => {
+                idx += 1;
+                config.mcts_nodes = parse_value(&args, idx, "--mcts-nodes")?;
+            }
+            "--mcts-exploration" => {
+                idx += 1;
+                config.mcts_exploration = parse_value(&args, idx, "--mcts-exploration")?;
+            }
+            "--mcts-random-playouts" => {
+                config.mcts_random_playouts = true;
+            }
+            "--ablate-features" => {
+                config.ablate_features = true;
+            }
             "--help" | "-h" => {
                 print_usage();
                 std::process::exit(0);
@@ -190,21 +302,42 @@ fn parse_args(args: Vec<String>) -> Result<Config, String> {
     if config.games_per_matchup == 0 {
         return Err("games must be greater than 0".to_string());
     }
-    if !(MIN_LEVEL..=MAX_LEVEL).contains(&config.level) {
-        return Err(format!("level must be in {MIN_LEVEL}..={MAX_LEVEL}"));
-    }
+    LevelConfig::try_for_level(config.level)?;
     if config.weights_timeout_ms == 0 {
         return Err("weights-timeout-ms must be greater than 0".to_string());
     }
+    if !config.mcts_exploration.is_finite() || config.mcts_exploration < 0.0 {
+        return Err("mcts-exploration must be a finite value >= 0".to_string());
+    }
+    if config.ablate_features && config.opponent_weights_path.is_some() {
+        return Err(
+            "--ablate-features cannot be combined with --opponent-weights-path".to_string(),
+        );
+    }
+    if config.opponent_engine == Some(Engine::Mcts)
+        && config.opponent_timeout_ms == 0
+        && config.mcts_nodes == 0
+    {
+        return Err(
+            "MCTS opponent needs --mcts-nodes or a non-zero --opponent-timeout-ms".to_string(),
+        );
+    }
 
     Ok(config)
 }
 
-fn parse_value<T: std::str::FromStr>(args: &[String], idx: usize, flag: &str) -> Result<T, String> {
-    args.get(idx)
+fn parse_engine(args: &[String], idx: usize, flag: &str) -> Result<Engine, String> {
+    match args
+        .get(idx)
         .ok_or_else(|| format!("missing value for {flag}"))?
-        .parse::<T>()
-        .map_err(|_| format!("invalid value for {flag}"))
+        .as_str()
+    {
+        "alphabeta" => Ok(Engine::AlphaBeta),
+        "mcts" => Ok(Engine::Mcts),
+        other => Err(format!(
+            "invalid value for {flag}: {other} (expected alphabeta or mcts)"
+        )),
+    }
 }
 
 fn print_usage() {
@@ -213,7 +346,7 @@ fn print_usage() {
          \n\
          Options:\n\
            --games <N>                 Number of games per matchup (default: 20)\n\
-           --level <1-6>               Search depth level for weights AI and positional player (default: 4)\n\
+           --level <1-10>              Level for weights AI; its depth also drives the positional player (default: 4)\n\
            --seed <N>                  Base seed for random opponent/openings (default: 42)\n\
            --random-opening-plies <N>  Random plies applied before benchmark players take over (default: 0)\n\
            --weights-timeout-ms <N>    Per-move timeout for weights.bin AI in milliseconds (default: 250)\n\
@@ -221,6 +354,13 @@ fn print_usage() {
            --weights-path <PATH>       Optional external weights.bin to benchmark instead of embedded model\n\
            --opponent-weights-path <PATH>\n\
                                       Optional external weights.bin for direct model-vs-model benchmark\n\
+           --engine <alphabeta|mcts>   Engine for the weights AI (default: alphabeta)\n\
+           --opponent-engine <alphabeta|mcts>\n\
+                                      Engine for the model opponent; without --opponent-weights-path it plays the primary model (default: alphabeta)\n\
+           --mcts-nodes <N>            MCTS simulations per move; 0 relies on the timeout alone (default: 0)\n\
+           --mcts-exploration <C>      MCTS PUCT exploration constant (default: 1.5)\n\
+           --mcts-random-playouts      Value MCTS leaves by random playouts instead of the model\n\
+           --ablate-features           Play the model against itself with each scalar feature removed\n\
            --help                      Show this message"
     );
 }
@@ -249,6 +389,13 @@ fn model_source_label(weights_path: Option<&PathBuf>) -> String {
         .unwrap_or_else(|| "embedded model".to_string())
 }
 
+fn engine_label(source: &str, engine: Engine) -> String {
+    match engine {
+        Engine::AlphaBeta => source.to_string(),
+        Engine::Mcts => format!("{source} [mcts]"),
+    }
+}
+
 fn benchmark_matchup(
     evaluator: &NTupleEvaluator,
     opponent: Opponent<'_>,
@@ -266,16 +413,7 @@ fn benchmark_matchup(
             stats.weights_white_games += 1;
         }
 
-        let outcome = play_game(
-            evaluator,
-            opponent,
-            config.level,
-            config.random_opening_plies,
-            config.weights_timeout_ms,
-            config.opponent_timeout_ms,
-            weights_is_black,
-            &mut rng,
-        )?;
+        let outcome = play_game(evaluator, opponent, config, weights_is_black, &mut rng)?;
 
         stats.games += 1;
         if outcome.final_diff > 0.0 {
@@ -302,84 +440,58 @@ struct GameOutcome {
 fn play_game(
     evaluator: &NTupleEvaluator,
     opponent: Opponent<'_>,
-    level: u8,
-    random_opening_plies: usize,
-    weights_timeout_ms: u64,
-    opponent_timeout_ms: u64,
+    config: &Config,
     weights_is_black: bool,
     rng: &mut ChaCha8Rng,
 ) -> Result<GameOutcome, String> {
-    let mut board = Board::new();
-    let mut current_is_black = true;
-    let mut placed_plies = 0usize;
-    let mut steps = 0usize;
+    let positional_depth = LevelConfig::try_for_level(config.level)?.depth;
     let mut weights_move_ms = Vec::new();
     let mut opponent_move_ms = Vec::new();
 
-    while placed_plies < random_opening_plies {
-        if !play_random_ply(&mut board, &mut current_is_black, rng)? {
-            break;
-        }
-        placed_plies += 1;
-    }
-
-    loop {
-        if steps > MAX_GAME_STEPS {
-            return Err(format!(
-                "game exceeded {MAX_GAME_STEPS} steps without terminating"
-            ));
-        }
-
-        let legal = board.legal_moves(current_is_black);
-        if legal == 0 {
-            let opp_legal = board.legal_moves(!current_is_black);
-            if opp_legal == 0 {
-                break;
-            }
-            current_is_black = !current_is_black;
-            continue;
-        }
-
+    let (opening, opening_is_black) = arena::random_opening(config.random_opening_plies, rng);
+    let board = arena::play_out(opening, opening_is_black, |board, current_is_black| {
         let weights_turn = current_is_black == weights_is_black;
         let started = Instant::now();
         let mv = if weights_turn {
-            let mut searcher =
-                Searcher::with_timeout(evaluator, level, model_timeout(weights_timeout_ms));
-            searcher.search(&board, current_is_black)
+            engine_move(
+                evaluator,
+                config.engine,
+                config,
+                config.weights_timeout_ms,
+                board,
+                current_is_black,
+                rng,
+            )?
         } else {
             match opponent {
-                Opponent::Random => random_move(legal, rng)
+                Opponent::Random => random_move(board.legal_moves(current_is_black), rng)
                     .ok_or_else(|| "random opponent failed to choose move".to_string())?,
-                Opponent::PositionalSearch => {
-                    choose_positional_move(&board, current_is_black, level, opponent_timeout_ms)
-                        .ok_or_else(|| "positional opponent failed to choose move".to_string())?
-                }
-                Opponent::WeightsModel(opponent_evaluator) => {
-                    let mut searcher = Searcher::with_timeout(
-                        opponent_evaluator,
-                        level,
-                        model_timeout(opponent_timeout_ms),
-                    );
-                    searcher.search(&board, current_is_black)
-                }
+                Opponent::PositionalSearch => choose_positional_move(
+                    board,
+                    current_is_black,
+                    positional_depth,
+                    config.opponent_timeout_ms,
+                )
+                .ok_or_else(|| "positional opponent failed to choose move".to_string())?,
+                Opponent::WeightsModel(opponent_evaluator, engine) => engine_move(
+                    opponent_evaluator,
+                    engine,
+                    config,
+                    config.opponent_timeout_ms,
+                    board,
+                    current_is_black,
+                    rng,
+                )?,
             }
         };
         let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
-
-        let mut next = board;
-        if next.place(mv, current_is_black) == 0 {
-            return Err(format!("selected illegal move {mv}"));
-        }
-        board = next;
-        current_is_black = !current_is_black;
-        steps += 1;
-
         if weights_turn {
             weights_move_ms.push(elapsed_ms);
         } else {
             opponent_move_ms.push(elapsed_ms);
         }
-    }
+        Ok(mv)
+    })?;
 
     let (black, white) = board.count();
     let final_diff = if weights_is_black {
@@ -395,29 +507,33 @@ fn play_game(
     })
 }
 
-fn play_random_ply(
-    board: &mut Board,
-    current_is_black: &mut bool,
+fn engine_move(
+    evaluator: &NTupleEvaluator,
+    engine: Engine,
+    config: &Config,
+    timeout_ms: u64,
+    board: &Board,
+    is_black: bool,
     rng: &mut ChaCha8Rng,
-) -> Result<bool, String> {
-    loop {
-        let legal = board.legal_moves(*current_is_black);
-        if legal == 0 {
-            let opp_legal = board.legal_moves(!*current_is_black);
-            if opp_legal == 0 {
-                return Ok(false);
-            }
-            *current_is_black = !*current_is_black;
-            continue;
+) -> Result<usize, String> {
+    match engine {
+        Engine::AlphaBeta => {
+            let mut searcher =
+                Searcher::with_timeout(evaluator, config.level, model_timeout(timeout_ms));
+            Ok(searcher.search(board, is_black))
         }
-
-        let mv = random_move(legal, rng)
-            .ok_or_else(|| "failed to sample random opening move".to_string())?;
-        if board.place(mv, *current_is_black) == 0 {
-            return Err(format!("random opening selected illegal move {mv}"));
+        Engine::Mcts => {
+            let mcts_config = MctsConfig {
+                max_nodes: config.mcts_nodes,
+                time_budget: model_timeout(timeout_ms),
+                exploration: config.mcts_exploration,
+                ..MctsConfig::default()
+            };
+            let leaf_evaluator = (!config.mcts_random_playouts).then_some(evaluator);
+            Mcts::new(leaf_evaluator, mcts_config, rng.next_u64())
+                .search(board, is_black)
+                .ok_or_else(|| "MCTS engine failed to choose move".to_string())
         }
-        *current_is_black = !*current_is_black;
-        return Ok(true);
     }
 }
 
@@ -427,169 +543,6 @@ fn random_move(legal: u64, rng: &mut ChaCha8Rng) -> Option<usize> {
     moves.into_iter().next()
 }
 
-fn choose_positional_move(
-    board: &Board,
-    is_black: bool,
-    depth: u8,
-    timeout_ms: u64,
-) -> Option<usize> {
-    let legal = board.legal_moves(is_black);
-    if legal == 0 {
-        return None;
-    }
-
-    let deadline = if timeout_ms == 0 {
-        None
-    } else {
-        Some(Instant::now() + Duration::from_millis(timeout_ms))
-    };
-    let moves = sort_moves_for_positional_search(legal, board, is_black);
-    let mut best_move = moves[0];
-    let mut best_score = f32::NEG_INFINITY;
-    let mut alpha = f32::NEG_INFINITY;
-    let beta = f32::INFINITY;
-
-    for mv in moves {
-        if deadline.is_some_and(|limit| Instant::now() >= limit) {
-            break;
-        }
-        let mut next = *board;
-        let _ = next.place(mv, is_black);
-        let score = -positional_negamax(
-            &next,
-            !is_black,
-            depth.saturating_sub(1),
-            -beta,
-            -alpha,
-            deadline,
-        );
-        if is_better_move(board, score, mv, best_score, best_move) {
-            best_score = score;
-            best_move = mv;
-        }
-        if score > alpha {
-            alpha = score;
-        }
-    }
-
-    Some(best_move)
-}
-
-fn positional_negamax(
-    board: &Board,
-    is_black: bool,
-    depth: u8,
-    alpha: f32,
-    beta: f32,
-    deadline: Option<Instant>,
-) -> f32 {
-    if deadline.is_some_and(|limit| Instant::now() >= limit) {
-        return positional_eval(board, is_black);
-    }
-    if depth == 0 {
-        return positional_eval(board, is_black);
-    }
-
-    let legal = board.legal_moves(is_black);
-    if legal == 0 {
-        let opp_legal = board.legal_moves(!is_black);
-        if opp_legal == 0 {
-            return terminal_score(board, is_black);
-        }
-        return -positional_negamax(board, !is_black, depth, -beta, -alpha, deadline);
-    }
-
-    let moves = sort_moves_for_positional_search(legal, board, is_black);
-    let mut alpha = alpha;
-    let mut best = f32::NEG_INFINITY;
-    let mut best_move = moves[0];
-
-    for mv in moves {
-        if deadline.is_some_and(|limit| Instant::now() >= limit) {
-            break;
-        }
-        let mut next = *board;
-        let _ = next.place(mv, is_black);
-        let score = -positional_negamax(&next, !is_black, depth - 1, -beta, -alpha, deadline);
-        if is_better_move(board, score, mv, best, best_move) {
-            best = score;
-            best_move = mv;
-        }
-        if score > alpha {
-            alpha = score;
-        }
-        if alpha >= beta {
-            break;
-        }
-    }
-
-    best
-}
-
-fn sort_moves_for_positional_search(legal: u64, board: &Board, is_black: bool) -> Vec<usize> {
-    let tie_break_symmetry = canonical_symmetry(board);
-    let mut scored_moves: Vec<(usize, f32)> = bitboard_to_positions(legal)
-        .into_iter()
-        .map(|mv| {
-            let mut next = *board;
-            let _ = next.place(mv, is_black);
-            (mv, -positional_eval(&next, !is_black))
-        })
-        .collect();
-
-    scored_moves.sort_by(|(left_mv, left_score), (right_mv, right_score)| {
-        right_score.total_cmp(left_score).then_with(|| {
-            transform_pos(*left_mv as u8, tie_break_symmetry)
-                .cmp(&transform_pos(*right_mv as u8, tie_break_symmetry))
-        })
-    });
-
-    scored_moves.into_iter().map(|(mv, _)| mv).collect()
-}
-
-fn positional_eval(board: &Board, is_black: bool) -> f32 {
-    let cells = board.to_array();
-    let mut black_positional = 0i32;
-    let mut white_positional = 0i32;
-
-    for (idx, cell) in cells.iter().enumerate() {
-        match *cell {
-            1 => black_positional += POSITION_WEIGHTS[idx],
-            2 => white_positional += POSITION_WEIGHTS[idx],
-            _ => {}
-        }
-    }
-
-    let black_mobility = board.legal_moves(true).count_ones() as i32;
-    let white_mobility = board.legal_moves(false).count_ones() as i32;
-    let black_corners = corners_taken(&cells, 1) as i32;
-    let white_corners = corners_taken(&cells, 2) as i32;
-    let (black_count, white_count) = board.count();
-    let black_score = (black_positional - white_positional)
-        + 5 * (black_mobility - white_mobility)
-        + 25 * (black_corners - white_corners)
-        + (black_count as i32 - white_count as i32);
-
-    let score = if is_black { black_score } else { -black_score };
-    score as f32
-}
-
-fn corners_taken(cells: &[u8; 64], stone: u8) -> usize {
-    [0usize, 7, 56, 63]
-        .into_iter()
-        .filter(|&idx| cells[idx] == stone)
-        .count()
-}
-
-fn terminal_score(board: &Board, is_black: bool) -> f32 {
-    let (black, white) = board.count();
-    if is_black {
-        black as f32 - white as f32
-    } else {
-        white as f32 - black as f32
-    }
-}
-
 fn bitboard_to_positions(mut mask: u64) -> Vec<usize> {
     let mut out = Vec::new();
     while mask != 0 {
@@ -600,59 +553,6 @@ fn bitboard_to_positions(mut mask: u64) -> Vec<usize> {
     out
 }
 
-fn is_better_move(board: &Board, score: f32, mv: usize, best_score: f32, best_move: usize) -> bool {
-    let tie_break_symmetry = canonical_symmetry(board);
-    let move_key = transform_pos(mv as u8, tie_break_symmetry);
-    let best_key = transform_pos(best_move as u8, tie_break_symmetry);
-
-    score > best_score || (score == best_score && move_key < best_key)
-}
-
-fn canonical_symmetry(board: &Board) -> u8 {
-    let cells = board.to_array();
-    let mut best = None;
-
-    for symmetry in 0..8u8 {
-        let transformed = transform_cells(&cells, symmetry);
-        if best
-            .as_ref()
-            .is_none_or(|(current, current_sym): &(Vec<u8>, u8)| {
-                transformed < *current || (transformed == *current && symmetry < *current_sym)
-            })
-        {
-            best = Some((transformed, symmetry));
-        }
-    }
-
-    best.expect("at least one symmetry must exist").1
-}
-
-fn transform_cells(cells: &[u8; 64], symmetry: u8) -> Vec<u8> {
-    let mut transformed = vec![0u8; 64];
-    for (pos, value) in cells.iter().copied().enumerate() {
-        transformed[transform_pos(pos as u8, symmetry)] = value;
-    }
-    transformed
-}
-
-fn transform_pos(pos: u8, symmetry: u8) -> usize {
-    let row = (pos as usize) / 8;
-    let col = (pos as usize) % 8;
-
-    let (nr, nc) = match symmetry {
-        0 => (row, col),
-        1 => (col, 7 - row),
-        2 => (7 - row, 7 - col),
-        3 => (7 - col, row),
-        4 => (row, 7 - col),
-        5 => (7 - col, 7 - row),
-        6 => (7 - row, col),
-        _ => (col, row),
-    };
-
-    nr * 8 + nc
-}
-
 fn print_stats(
     label: &str,
     primary_move_label: &str,
@@ -752,7 +652,7 @@ fn max_value(samples: &[f64]) -> f64 {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_args;
+    use super::{Engine, parse_args};
 
     #[test]
     fn parse_args_supports_direct_model_matchup() {
@@ -783,6 +683,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_args_accepts_extended_levels_and_rejects_unknown_ones() {
+        let config =
+            parse_args(vec!["--level".to_string(), "10".to_string()]).expect("level 10 exists");
+        assert_eq!(config.level, 10);
+
+        let err = parse_args(vec!["--level".to_string(), "11".to_string()])
+            .expect_err("level 11 is not in the table");
+        assert!(err.contains("level must be in 1..=10"));
+    }
+
+    #[test]
+    fn parse_args_selects_engines_for_both_sides() {
+        let config = parse_args(vec![
+            "--engine".to_string(),
+            "mcts".to_string(),
+            "--opponent-engine".to_string(),
+            "alphabeta".to_string(),
+            "--mcts-nodes".to_string(),
+            "5000".to_string(),
+        ])
+        .expect("args should parse");
+
+        assert_eq!(config.engine, Engine::Mcts);
+        assert_eq!(config.opponent_engine, Some(Engine::AlphaBeta));
+        assert_eq!(config.mcts_nodes, 5000);
+
+        let err = parse_args(vec!["--engine".to_string(), "minimax".to_string()])
+            .expect_err("unknown engine should fail");
+        assert!(err.contains("expected alphabeta or mcts"));
+
+        let err = parse_args(vec![
+            "--opponent-engine".to_string(),
+            "mcts".to_string(),
+            "--opponent-timeout-ms".to_string(),
+            "0".to_string(),
+        ])
+        .expect_err("unbounded MCTS opponent should fail");
+        assert!(err.contains("--mcts-nodes"));
+    }
+
     #[test]
     fn parse_args_rejects_missing_opponent_weights_path_value() {
         let err = parse_args(vec!["--opponent-weights-path".to_string()])
@@ -790,4 +731,18 @@ mod tests {
 
         assert!(err.contains("missing value for --opponent-weights-path"));
     }
+
+    #[test]
+    fn parse_args_accepts_feature_ablation_without_opponent_model() {
+        let config = parse_args(vec!["--ablate-features".to_string()]).expect("args should parse");
+        assert!(config.ablate_features);
+
+        let err = parse_args(vec![
+            "--ablate-features".to_string(),
+            "--opponent-weights-path".to_string(),
+            "models/challenger.bin".to_string(),
+        ])
+        .expect_err("ablation plays the primary model against itself");
+        assert!(err.contains("--ablate-features"));
+    }
 }
@@ -0,0 +1,405 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use reversi::ai::level::LevelConfig;
+use reversi::ai::mcts::MctsMoveSelector;
+use reversi::ai::ntuple::NTupleEvaluator;
+use reversi::ai::sampling::softmax_sample;
+use reversi::ai::search::Searcher;
+use reversi::board::Board;
+use reversi::cli::parse_value;
+use reversi::game::MoveSelector;
+use reversi::training::dataset::{
+    self, DatasetConfig, DatasetFormat, DatasetPolicy, DatasetRow, DatasetWriter,
+};
+use reversi::training::{ExplorationPolicy, MAX_EXACT_TD_EMPTIES, TrainableNTuple};
+use reversi::types::MoveAnalysis;
+
+const EMBEDDED_MODEL_BYTES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/embedded_weights.bin"));
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PolicyKind {
+    Trainer,
+    Search { level: u8 },
+    Mcts { level: u8 },
+}
+
+impl PolicyKind {
+    /// Parses `trainer`, `search:<level>` or `mcts:<level>`.
+    fn parse(name: &str) -> Result<Self, String> {
+        if name == "trainer" {
+            return Ok(Self::Trainer);
+        }
+        let parse_level = |level: &str| {
+            level
+                .parse::<u8>()
+                .ok()
+                .filter(|level| LevelConfig::try_for_level(*level).is_ok())
+        };
+        let policy = match name.split_once(':') {
+            Some(("search", level)) => parse_level(level).map(|level| Self::Search { level }),
+            Some(("mcts", level)) => parse_level(level).map(|level| Self::Mcts { level }),
+            _ => None,
+        };
+        policy.ok_or_else(|| {
+            format!(
+                "unsupported policy '{name}' (expected trainer, search:<level> or mcts:<level>)"
+            )
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Config {
+    policy: PolicyKind,
+    model: Option<PathBuf>,
+    search_depth: u8,
+    epsilon: f64,
+    exploration: ExplorationPolicy,
+    mcts_nodes: usize,
+    generation: DatasetConfig,
+    /// `None` picks CSV for a `.csv` output and binary otherwise.
+    format: Option<DatasetFormat>,
+    output: PathBuf,
+}
+
+/// Plays each move by the regular alpha-beta search at a level, like the
+/// browser build does.
+struct SearchMoveSelector {
+    evaluator: NTupleEvaluator,
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl MoveSelector for SearchMoveSelector {
+    fn select_move(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Option<usize> {
+        if board.legal_moves(is_black) == 0 {
+            return None;
+        }
+        let mut searcher = Searcher::with_level_config(&self.evaluator, level);
+        if level.randomness > 0.0 {
+            let scored_moves = searcher.analyze_discs(board, is_black);
+            let mut rng = self
+                .rng
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            return softmax_sample(&scored_moves, level.randomness, &mut *rng);
+        }
+        Some(searcher.search(board, is_black))
+    }
+
+    fn analyze(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Vec<MoveAnalysis> {
+        Searcher::with_level_config(&self.evaluator, level)
+            .analyze(board, is_black)
+            .into_iter()
+            .map(|(mv, score)| MoveAnalysis {
+                row: (mv / 8) as u8,
+                col: (mv % 8) as u8,
+                score,
+                disc_difference: None,
+                win_probability: None,
+            })
+            .collect()
+    }
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_args(env::args().skip(1).collect())?;
+    generate(&config)
+}
+
+fn generate(config: &Config) -> Result<(), String> {
+    let started = Instant::now();
+    let model_bytes = match &config.model {
+        Some(path) => {
+            fs::read(path).map_err(|err| format!("failed to read {}: {err}", path.display()))?
+        }
+        None => EMBEDDED_MODEL_BYTES.to_vec(),
+    };
+
+    let format = config.format.unwrap_or_else(|| format_for(&config.output));
+    let mut writer = DatasetWriter::create(&config.output, format)?;
+    let games = config.generation.games;
+    let mut finished = 0;
+    let mut sink = |rows: &[DatasetRow]| -> Result<(), String> {
+        writer.write_rows(rows)?;
+        finished += 1;
+        if finished % 100 == 0 || finished == games {
+            println!("[{finished}/{games}] games written");
+        }
+        Ok(())
+    };
+
+    let rows = match config.policy {
+        PolicyKind::Trainer => {
+            let network = TrainableNTuple::from_bytes(&model_bytes)?;
+            let policy = DatasetPolicy::Trainer {
+                network: &network,
+                search_depth: config.search_depth,
+                epsilon: config.epsilon,
+                exploration: config.exploration,
+            };
+            dataset::generate(&policy, &config.generation, &mut sink)?
+        }
+        PolicyKind::Search { level } => {
+            let selector = SearchMoveSelector {
+                evaluator: NTupleEvaluator::from_bytes(&model_bytes)?,
+                rng: Mutex::new(ChaCha8Rng::seed_from_u64(config.generation.seed)),
+            };
+            let policy = DatasetPolicy::Selector {
+                selector: &selector,
+                level: LevelConfig::try_for_level(level)?,
+            };
+            dataset::generate(&policy, &config.generation, &mut sink)?
+        }
+        PolicyKind::Mcts { level } => {
+            let selector = MctsMoveSelector::new(
+                Some(NTupleEvaluator::from_bytes(&model_bytes)?),
+                config.mcts_nodes,
+                1.5,
+                config.generation.seed,
+            );
+            let policy = DatasetPolicy::Selector {
+                selector: &selector,
+                level: LevelConfig::try_for_level(level)?,
+            };
+            dataset::generate(&policy, &config.generation, &mut sink)?
+        }
+    };
+    writer.finish()?;
+
+    println!(
+        "Wrote {rows} positions from {games} games to {} ({}) in {:.1}s",
+        config.output.display(),
+        format.name(),
+        started.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+fn format_for(path: &Path) -> DatasetFormat {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("csv") => DatasetFormat::Csv,
+        _ => DatasetFormat::Binary,
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    let mut config = Config {
+        policy: PolicyKind::Trainer,
+        model: None,
+        search_depth: 1,
+        epsilon: 0.05,
+        exploration: ExplorationPolicy::Uniform,
+        mcts_nodes: 2000,
+        generation: DatasetConfig {
+            games: 1000,
+            seed: 42,
+            threads: 0,
+            random_opening_plies: 8,
+            exact_empties: 14,
+        },
+        format: None,
+        output: PathBuf::from("dataset.bin"),
+    };
+
+    let mut idx = 0;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--policy" => {
+                idx += 1;
+                config.policy = PolicyKind::parse(&parse_value::<String>(&args, idx, "--policy")?)?;
+            }
+            "--model" => {
+                idx += 1;
+                config.model = Some(PathBuf::from(parse_value::<String>(&args, idx, "--model")?));
+            }
+            "--search-depth" => {
+                idx += 1;
+                config.search_depth = parse_value(&args, idx, "--search-depth")?;
+            }
+            "--epsilon" => {
+                idx += 1;
+                config.epsilon = parse_value(&args, idx, "--epsilon")?;
+            }
+            "--exploration" => {
+                idx += 1;
+                config.exploration =
+                    ExplorationPolicy::parse(&parse_value::<String>(&args, idx, "--exploration")?)?;
+            }
+            "--mcts-nodes" => {
+                idx += 1;
+                config.mcts_nodes = parse_value(&args, idx, "--mcts-nodes")?;
+            }
+            "--games" => {
+                idx += 1;
+                config.generation.games = parse_value(&args, idx, "--games")?;
+            }
+            "--seed" => {
+                idx += 1;
+                config.generation.seed = parse_value(&args, idx, "--seed")?;
+            }
+            "--threads" => {
+                idx += 1;
+                config.generation.threads = parse_value(&args, idx, "--threads")?;
+            }
+            "--random-opening-plies" => {
+                idx += 1;
+                config.generation.random_opening_plies =
+                    parse_value(&args, idx, "--random-opening-plies")?;
+            }
+            "--exact-empties" => {
+                idx += 1;
+                config.generation.exact_empties = parse_value(&args, idx, "--exact-empties")?;
+            }
+            "--format" => {
+                idx += 1;
+                config.format = Some(DatasetFormat::from_name(&parse_value::<String>(
+                    &args, idx, "--format",
+                )?)?);
+            }
+            "--output" => {
+                idx += 1;
+                config.output = PathBuf::from(parse_value::<String>(&args, idx, "--output")?);
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+        idx += 1;
+    }
+
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate(config: &Config) -> Result<(), String> {
+    if config.generation.games == 0 {
+        return Err("games must be greater than 0".to_string());
+    }
+    if config.search_depth == 0 {
+        return Err("search_depth must be greater than 0".to_string());
+    }
+    if !(0.0..=1.0).contains(&config.epsilon) {
+        return Err("epsilon must be between 0 and 1".to_string());
+    }
+    if config.generation.exact_empties > MAX_EXACT_TD_EMPTIES {
+        return Err(format!(
+            "exact_empties must be at most {MAX_EXACT_TD_EMPTIES}"
+        ));
+    }
+    Ok(())
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --release --manifest-path rust/Cargo.toml --bin generate_dataset -- [options]\n\
+         \n\
+         Plays games in parallel and writes every position before a move as a\n\
+         dataset row: bitboards, side to move, ply, search score, final disc\n\
+         difference and, near the end, the exact score.\n\
+         \n\
+         Options:\n\
+           --policy <NAME>             trainer, search:<level> or mcts:<level> (default: trainer)\n\
+           --model <PATH>              Model that plays and scores (default: embedded model)\n\
+           --search-depth <N>          Trainer search depth in plies (default: 1)\n\
+           --epsilon <F>               Trainer exploration rate (default: 0.05)\n\
+           --exploration <NAME>        uniform or boltzmann:<temperature> (default: uniform)\n\
+           --mcts-nodes <N>            MCTS simulations per move (default: 2000)\n\
+           --games <N>                 Games to play (default: 1000)\n\
+           --seed <N>                  Seed for openings and exploration (default: 42)\n\
+           --threads <N>               Worker threads, 0 for every CPU (default: 0)\n\
+           --random-opening-plies <N>  Random moves at the start of each game (default: 8)\n\
+           --exact-empties <N>         Solve positions with at most N empties, 0 to\n\
+                                       disable (default: 14, max: {MAX_EXACT_TD_EMPTIES})\n\
+           --format <NAME>             binary or csv (default: from the output extension)\n\
+           --output <PATH>             Dataset to write (default: dataset.bin)\n\
+           --help                      Show this message\n\
+         \n\
+         Scores are disc differences for the side to move. Binary datasets are an\n\
+         8-byte header followed by fixed 24-byte little-endian records; see\n\
+         python/dataset.py for a numpy loader. Only the trainer policy reproduces\n\
+         its games exactly for a seed."
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_fill_the_generation_settings() {
+        let config = parse_args(args(&[
+            "--policy",
+            "search:3",
+            "--games",
+            "20",
+            "--threads",
+            "2",
+            "--exact-empties",
+            "10",
+            "--output",
+            "positions.csv",
+        ]))
+        .unwrap();
+        assert_eq!(config.policy, PolicyKind::Search { level: 3 });
+        assert_eq!(config.generation.games, 20);
+        assert_eq!(config.generation.threads, 2);
+        assert_eq!(config.generation.exact_empties, 10);
+        assert_eq!(format_for(&config.output), DatasetFormat::Csv);
+        assert_eq!(
+            format_for(Path::new("positions.bin")),
+            DatasetFormat::Binary
+        );
+
+        assert_eq!(
+            PolicyKind::parse("mcts:5"),
+            Ok(PolicyKind::Mcts { level: 5 })
+        );
+        assert!(PolicyKind::parse("search:99").is_err());
+        assert!(PolicyKind::parse("search").is_err());
+        assert!(parse_args(args(&["--games", "0"])).is_err());
+        assert!(parse_args(args(&["--exact-empties", "30"])).is_err());
+        assert!(parse_args(args(&["--format", "parquet"])).is_err());
+    }
+
+    #[test]
+    fn writes_a_readable_dataset() {
+        let dir = env::temp_dir().join(format!("reversi-generate-dataset-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let model = dir.join("fresh.bin");
+        fs::write(&model, TrainableNTuple::new().to_bytes().unwrap()).unwrap();
+        let output = dir.join("positions.bin");
+        let config = parse_args(args(&[
+            "--model",
+            model.to_str().unwrap(),
+            "--games",
+            "3",
+            "--threads",
+            "2",
+            "--exact-empties",
+            "4",
+            "--output",
+            output.to_str().unwrap(),
+        ]))
+        .unwrap();
+
+        generate(&config).unwrap();
+        let rows = dataset::read_dataset(&fs::read(&output).unwrap()).unwrap();
+        assert_eq!(rows.iter().filter(|row| row.ply == 0).count(), 3);
+        assert!(rows.iter().any(|row| row.exact_score.is_some()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,828 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use reversi::ai::calibration::{self, Calibration, SampleConfig};
+use reversi::ai::metadata::{KEY_PARENT_HASH, ModelMetadata};
+use reversi::ai::ntuple::{ModelFile, ModelWeights, NTupleEvaluator, decompress_model_bytes};
+use reversi::cli::parse_value;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Command {
+    Info {
+        path: PathBuf,
+    },
+    Diff {
+        left: PathBuf,
+        right: PathBuf,
+    },
+    Convert {
+        input: PathBuf,
+        output: PathBuf,
+        target: ConvertTarget,
+        compress: bool,
+    },
+    Average {
+        output: PathBuf,
+        inputs: Vec<PathBuf>,
+        weights: Vec<f64>,
+        compress: bool,
+    },
+    Calibrate {
+        input: PathBuf,
+        output: PathBuf,
+        samples: SampleConfig,
+        compress: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConvertTarget {
+    /// Keep the input version and only change compression.
+    Keep,
+    V4,
+    V5,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct WeightStats {
+    min: f32,
+    max: f32,
+    mean: f64,
+    mean_abs: f64,
+    nonzero: usize,
+    count: usize,
+}
+
+#[derive(Debug, PartialEq)]
+struct TupleDelta {
+    max_abs: f32,
+    max_abs_phase: usize,
+    mean_abs: f64,
+    rms: f64,
+}
+
+fn main() -> Result<(), String> {
+    match parse_args(env::args().skip(1).collect())? {
+        Command::Info { path } => print_info(&path),
+        Command::Diff { left, right } => print_diff(&left, &right),
+        Command::Convert {
+            input,
+            output,
+            target,
+            compress,
+        } => {
+            let model = read_model(&input)?;
+            let source_version = model.version;
+            let converted = match target {
+                ConvertTarget::Keep => model,
+                ConvertTarget::V4 => model.into_v4(),
+                ConvertTarget::V5 => model.into_quantized()?,
+            };
+            write_model(&output, &converted, compress)?;
+            println!(
+                "Converted {} (v{source_version}) -> {} (v{}, {})",
+                input.display(),
+                output.display(),
+                converted.version,
+                compression_label(compress)
+            );
+            Ok(())
+        }
+        Command::Average {
+            output,
+            inputs,
+            weights,
+            compress,
+        } => {
+            let mut models = Vec::with_capacity(inputs.len());
+            let mut parent_hashes = Vec::with_capacity(inputs.len());
+            for (path, weight) in inputs.iter().zip(&weights) {
+                let bytes = read_bytes(path)?;
+                parent_hashes.push(format!(
+                    "{:08x}",
+                    crc32fast::hash(decompress_model_bytes(&bytes)?.as_ref())
+                ));
+                models.push((ModelFile::from_bytes(&bytes)?.into_v4(), *weight));
+            }
+
+            let mut averaged = average_models(&models)?;
+            let mut metadata = ModelMetadata::new();
+            metadata.insert(KEY_PARENT_HASH, parent_hashes.join(","));
+            metadata.stamp_created_at();
+            averaged.metadata = Some(metadata);
+            write_model(&output, &averaged, compress)?;
+            println!(
+                "Averaged {} models into {} ({})",
+                models.len(),
+                output.display(),
+                compression_label(compress)
+            );
+            Ok(())
+        }
+        Command::Calibrate {
+            input,
+            output,
+            samples,
+            compress,
+        } => {
+            let bytes = read_bytes(&input)?;
+            let mut model = ModelFile::from_bytes(&bytes)?;
+            let evaluator = NTupleEvaluator::from_bytes(&bytes)?;
+            let collected = calibration::collect_samples(&evaluator, &samples);
+            let fitted = Calibration::fit(&collected, model.phase_count)?;
+
+            let mut counts = vec![0usize; model.phase_count];
+            for sample in &collected {
+                counts[sample.phase_idx] += 1;
+            }
+            println!(
+                "{:>5} {:>8} {:>12} {:>12} {:>12} {:>12}",
+                "phase", "samples", "disc_slope", "disc_bias", "win_slope", "win_bias"
+            );
+            for (phase_idx, (phase, count)) in fitted.phases().iter().zip(&counts).enumerate() {
+                println!(
+                    "{phase_idx:>5} {count:>8} {:>12.4} {:>12.4} {:>12.4} {:>12.4}",
+                    phase.disc_slope, phase.disc_intercept, phase.win_slope, phase.win_intercept
+                );
+            }
+
+            model.calibration = Some(fitted);
+            write_model(&output, &model, compress)?;
+            println!(
+                "Calibrated {} from {} positions of {} games into {} ({})",
+                input.display(),
+                collected.len(),
+                samples.games,
+                output.display(),
+                compression_label(compress)
+            );
+            Ok(())
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Command, String> {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(
+            "missing subcommand (expected info, diff, convert, average or calibrate)".to_string(),
+        );
+    };
+
+    let mut positional = Vec::new();
+    let mut target = ConvertTarget::V4;
+    let mut compress = true;
+    let mut weights = None;
+    let mut samples = SampleConfig {
+        games: 200,
+        epsilon: 0.1,
+        random_opening_plies: 4,
+        solve_empties: 12,
+        seed: 42,
+    };
+    let mut idx = 0usize;
+    while idx < rest.len() {
+        match rest[idx].as_str() {
+            "--to" => {
+                idx += 1;
+                target = match rest.get(idx).map(String::as_str) {
+                    Some("keep") => ConvertTarget::Keep,
+                    Some("v4") => ConvertTarget::V4,
+                    Some("v5") => ConvertTarget::V5,
+                    Some(other) => {
+                        return Err(format!(
+                            "invalid value for --to: {other} (expected keep, v4 or v5)"
+                        ));
+                    }
+                    None => return Err("missing value for --to".to_string()),
+                };
+            }
+            "--uncompressed" => compress = false,
+            "--weights" => {
+                idx += 1;
+                let raw = rest
+                    .get(idx)
+                    .ok_or_else(|| "missing value for --weights".to_string())?;
+                weights = Some(
+                    raw.split(',')
+                        .map(|value| {
+                            value
+                                .trim()
+                                .parse::<f64>()
+                                .map_err(|_| "invalid value for --weights".to_string())
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                );
+            }
+            "--games" => {
+                idx += 1;
+                samples.games = parse_value(rest, idx, "--games")?;
+            }
+            "--epsilon" => {
+                idx += 1;
+                samples.epsilon = parse_value(rest, idx, "--epsilon")?;
+            }
+            "--random-opening-plies" => {
+                idx += 1;
+                samples.random_opening_plies = parse_value(rest, idx, "--random-opening-plies")?;
+            }
+            "--solve-empties" => {
+                idx += 1;
+                samples.solve_empties = parse_value(rest, idx, "--solve-empties")?;
+            }
+            "--seed" => {
+                idx += 1;
+                samples.seed = parse_value(rest, idx, "--seed")?;
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown argument: {flag}")),
+            path => positional.push(PathBuf::from(path)),
+        }
+        idx += 1;
+    }
+
+    let expect_paths = |count: usize| {
+        if positional.len() == count {
+            Ok(())
+        } else {
+            Err(format!(
+                "{subcommand} expects {count} path(s), got {}",
+                positional.len()
+            ))
+        }
+    };
+    match subcommand.as_str() {
+        "info" => {
+            expect_paths(1)?;
+            Ok(Command::Info {
+                path: positional.remove(0),
+            })
+        }
+        "diff" => {
+            expect_paths(2)?;
+            let right = positional.pop().expect("two paths");
+            let left = positional.pop().expect("two paths");
+            Ok(Command::Diff { left, right })
+        }
+        "convert" => {
+            expect_paths(2)?;
+            let output = positional.pop().expect("two paths");
+            let input = positional.pop().expect("two paths");
+            Ok(Command::Convert {
+                input,
+                output,
+                target,
+                compress,
+            })
+        }
+        "average" => {
+            if positional.len() < 3 {
+                return Err("average expects an output path and at least two models".to_string());
+            }
+            let output = positional.remove(0);
+            let weights = weights.unwrap_or_else(|| vec![1.0; positional.len()]);
+            if weights.len() != positional.len() {
+                return Err(format!(
+                    "--weights has {} values for {} models",
+                    weights.len(),
+                    positional.len()
+                ));
+            }
+            if weights
+                .iter()
+                .any(|weight| !weight.is_finite() || *weight < 0.0)
+                || weights.iter().sum::<f64>() <= 0.0
+            {
+                return Err("--weights must be finite, >= 0 and not all zero".to_string());
+            }
+            Ok(Command::Average {
+                output,
+                inputs: positional,
+                weights,
+                compress,
+            })
+        }
+        "calibrate" => {
+            expect_paths(2)?;
+            if samples.games == 0 {
+                return Err("--games must be greater than 0".to_string());
+            }
+            if !(0.0..=1.0).contains(&samples.epsilon) {
+                return Err("--epsilon must be in 0..=1".to_string());
+            }
+            let output = positional.pop().expect("two paths");
+            let input = positional.pop().expect("two paths");
+            Ok(Command::Calibrate {
+                input,
+                output,
+                samples,
+                compress,
+            })
+        }
+        other => Err(format!(
+            "unknown subcommand: {other} (expected info, diff, convert, average or calibrate)"
+        )),
+    }
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --manifest-path rust/Cargo.toml --bin model_tool -- <command> [options]\n\
+         \n\
+         Commands:\n\
+           info <MODEL>                       Header, tuples, per-phase weight stats and v4 visit coverage\n\
+           diff <MODEL_A> <MODEL_B>           Per-tuple weight deltas between two models\n\
+           convert <INPUT> <OUTPUT>           Convert a model (default: to v4, zstd-compressed)\n\
+           average <OUTPUT> <MODEL>...        Weighted average of models with identical tuples\n\
+           calibrate <INPUT> <OUTPUT>         Fit score -> disc difference / win probability from self-play\n\
+         \n\
+         Options:\n\
+           --to <keep|v4|v5>                  convert: target version; keep only changes compression (default: v4)\n\
+           --uncompressed                     convert/average/calibrate: write without zstd compression\n\
+           --weights <W1,W2,...>              average: relative model weights (default: equal)\n\
+           --games <N>                        calibrate: self-play games (default: 200)\n\
+           --epsilon <E>                      calibrate: random move probability (default: 0.1)\n\
+           --random-opening-plies <N>         calibrate: unsampled random opening plies (default: 4)\n\
+           --solve-empties <N>                calibrate: use solved outcomes at or below N empties (default: 12)\n\
+           --seed <N>                         calibrate: self-play seed (default: 42)\n\
+           --help                             Show this message"
+    );
+}
+
+fn read_bytes(path: &PathBuf) -> Result<Vec<u8>, String> {
+    fs::read(path)
+        .map_err(|err| format!("failed to read model bytes from {}: {err}", path.display()))
+}
+
+fn read_model(path: &PathBuf) -> Result<ModelFile, String> {
+    ModelFile::from_bytes(&read_bytes(path)?)
+}
+
+fn write_model(path: &PathBuf, model: &ModelFile, compress: bool) -> Result<(), String> {
+    let bytes = if compress {
+        model.to_bytes()?
+    } else {
+        model.to_uncompressed_bytes()?
+    };
+    fs::write(path, bytes).map_err(|err| format!("failed to write {}: {err}", path.display()))
+}
+
+fn compression_label(compress: bool) -> &'static str {
+    if compress { "zstd" } else { "uncompressed" }
+}
+
+fn print_info(path: &PathBuf) -> Result<(), String> {
+    let bytes = read_bytes(path)?;
+    let raw = decompress_model_bytes(&bytes)?;
+    let model = ModelFile::from_bytes(&bytes)?;
+    let crc32 = u32::from_le_bytes([raw[12], raw[13], raw[14], raw[15]]);
+
+    println!(
+        "File: {} ({} bytes, {}, {} bytes uncompressed)",
+        path.display(),
+        bytes.len(),
+        if raw.len() == bytes.len() {
+            "uncompressed"
+        } else {
+            "zstd"
+        },
+        raw.len()
+    );
+    println!(
+        "Header: version={}, tuples={}, phases={}, crc32={crc32:#010x}, symmetries={}",
+        model.version,
+        model.tuples.len(),
+        model.phase_count,
+        if model.version <= 2 { 4 } else { 8 }
+    );
+    if let ModelWeights::Quantized { .. } = model.weights {
+        println!("Weights: int16 with per-tuple scales");
+    }
+
+    println!();
+    println!("Tuples:");
+    for (tuple_idx, tuple) in model.tuples.iter().enumerate() {
+        println!("  #{tuple_idx:<3} len={:<2} {:?}", tuple.len(), tuple);
+    }
+
+    println!();
+    println!(
+        "{:>5} {:>12} {:>12} {:>12} {:>12} {:>9}",
+        "phase", "min", "max", "mean", "mean_abs", "nonzero%"
+    );
+    for (phase_idx, phase_weights) in model.weights.to_f32().iter().enumerate() {
+        let stats = weight_stats(phase_weights.iter().flatten().copied());
+        println!(
+            "{phase_idx:>5} {:>12.6} {:>12.6} {:>12.3e} {:>12.3e} {:>9.2}",
+            stats.min,
+            stats.max,
+            stats.mean,
+            stats.mean_abs,
+            percentage(stats.nonzero, stats.count)
+        );
+    }
+
+    if let Some(visit_counts) = &model.visit_counts {
+        println!();
+        println!("{:>5} {:>9} {:>14}", "phase", "visited%", "total_visits");
+        for (phase_idx, phase_counts) in visit_counts.iter().enumerate() {
+            let counts: Vec<u32> = phase_counts.iter().flatten().copied().collect();
+            let visited = counts.iter().filter(|&&count| count > 0).count();
+            let total: u64 = counts.iter().map(|&count| u64::from(count)).sum();
+            println!(
+                "{phase_idx:>5} {:>9.2} {total:>14}",
+                percentage(visited, counts.len())
+            );
+        }
+    }
+
+    if let Some(features) = &model.features {
+        println!();
+        print!("{:>5}", "phase");
+        for feature in features.features() {
+            print!(" {:>18}", feature.name());
+        }
+        println!();
+        for (phase_idx, phase_weights) in features.weights().iter().enumerate() {
+            print!("{phase_idx:>5}");
+            for weight in phase_weights {
+                print!(" {weight:>18.6}");
+            }
+            println!();
+        }
+    }
+
+    if let Some(calibration) = &model.calibration {
+        println!();
+        println!(
+            "{:>5} {:>12} {:>12} {:>12} {:>12}",
+            "phase", "disc_slope", "disc_bias", "win_slope", "win_bias"
+        );
+        for (phase_idx, phase) in calibration.phases().iter().enumerate() {
+            println!(
+                "{phase_idx:>5} {:>12.4} {:>12.4} {:>12.4} {:>12.4}",
+                phase.disc_slope, phase.disc_intercept, phase.win_slope, phase.win_intercept
+            );
+        }
+    }
+
+    if let Some(metadata) = &model.metadata {
+        println!();
+        println!("Metadata:");
+        for (key, value) in metadata.iter() {
+            println!("  {key}={value}");
+        }
+    }
+    Ok(())
+}
+
+fn print_diff(left: &PathBuf, right: &PathBuf) -> Result<(), String> {
+    let left_model = read_model(left)?;
+    let right_model = read_model(right)?;
+    if left_model.version != right_model.version {
+        println!(
+            "Note: comparing raw weights of v{} and v{} models",
+            left_model.version, right_model.version
+        );
+    }
+    let deltas = tuple_deltas(&left_model, &right_model)?;
+
+    println!("{} -> {}", left.display(), right.display());
+    println!(
+        "{:>5} {:>12} {:>9} {:>12} {:>12}",
+        "tuple", "max_abs", "at_phase", "mean_abs", "rms"
+    );
+    for (tuple_idx, delta) in deltas.iter().enumerate() {
+        println!(
+            "{tuple_idx:>5} {:>12.6} {:>9} {:>12.6} {:>12.6}",
+            delta.max_abs, delta.max_abs_phase, delta.mean_abs, delta.rms
+        );
+    }
+    Ok(())
+}
+
+fn weight_stats(values: impl Iterator<Item = f32>) -> WeightStats {
+    let mut stats = WeightStats {
+        min: f32::INFINITY,
+        max: f32::NEG_INFINITY,
+        ..WeightStats::default()
+    };
+    let mut sum = 0.0f64;
+    let mut abs_sum = 0.0f64;
+    for value in values {
+        stats.min = stats.min.min(value);
+        stats.max = stats.max.max(value);
+        sum += f64::from(value);
+        abs_sum += f64::from(value.abs());
+        stats.nonzero += usize::from(value != 0.0);
+        stats.count += 1;
+    }
+    if stats.count == 0 {
+        return WeightStats::default();
+    }
+    stats.mean = sum / stats.count as f64;
+    stats.mean_abs = abs_sum / stats.count as f64;
+    stats
+}
+
+fn ensure_same_shape(left: &ModelFile, right: &ModelFile) -> Result<(), String> {
+    if left.tuples != right.tuples {
+        return Err("models use different tuple patterns".to_string());
+    }
+    if left.phase_count != right.phase_count {
+        return Err(format!(
+            "models have different phase counts: {} vs {}",
+            left.phase_count, right.phase_count
+        ));
+    }
+    let feature_list = |model: &ModelFile| {
+        model
+            .features
+            .as_ref()
+            .map(|features| features.features().to_vec())
+    };
+    if feature_list(left) != feature_list(right) {
+        return Err("models use different scalar features".to_string());
+    }
+    Ok(())
+}
+
+fn tuple_deltas(left: &ModelFile, right: &ModelFile) -> Result<Vec<TupleDelta>, String> {
+    ensure_same_shape(left, right)?;
+    let left_weights = left.weights.to_f32();
+    let right_weights = right.weights.to_f32();
+
+    Ok((0..left.tuples.len())
+        .map(|tuple_idx| {
+            let mut delta = TupleDelta {
+                max_abs: 0.0,
+                max_abs_phase: 0,
+                mean_abs: 0.0,
+                rms: 0.0,
+            };
+            let mut count = 0usize;
+            for (phase_idx, (left_phase, right_phase)) in
+                left_weights.iter().zip(&right_weights).enumerate()
+            {
+                for (a, b) in left_phase[tuple_idx].iter().zip(&right_phase[tuple_idx]) {
+                    let diff = (b - a).abs();
+                    if diff > delta.max_abs {
+                        delta.max_abs = diff;
+                        delta.max_abs_phase = phase_idx;
+                    }
+                    delta.mean_abs += f64::from(diff);
+                    delta.rms += f64::from(diff) * f64::from(diff);
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                delta.mean_abs /= count as f64;
+                delta.rms = (delta.rms / count as f64).sqrt();
+            }
+            delta
+        })
+        .collect())
+}
+
+/// Averages v4 models with the given relative weights, including scalar
+/// feature weights. Visit counts are summed and TC accumulators dropped.
+fn average_models(models: &[(ModelFile, f64)]) -> Result<ModelFile, String> {
+    let (first, _) = models
+        .first()
+        .ok_or_else(|| "average needs at least one model".to_string())?;
+    let total_weight: f64 = models.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return Err("model weights must not all be zero".to_string());
+    }
+
+    let mut weights = first.weights.to_f32();
+    weights
+        .iter_mut()
+        .flatten()
+        .flatten()
+        .for_each(|value| *value = 0.0);
+    let mut features = first.features.clone();
+    if let Some(features) = features.as_mut() {
+        features
+            .weights_mut()
+            .iter_mut()
+            .flatten()
+            .for_each(|value| *value = 0.0);
+    }
+    let mut visit_counts = first.visit_counts.clone();
+    if let Some(counts) = visit_counts.as_mut() {
+        counts
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .for_each(|count| *count = 0);
+    }
+
+    for (model, weight) in models {
+        ensure_same_shape(first, model)?;
+        let share = (weight / total_weight) as f32;
+        for (acc, value) in weights
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .zip(model.weights.to_f32().iter().flatten().flatten())
+        {
+            *acc += value * share;
+        }
+        if let (Some(acc), Some(model_features)) = (features.as_mut(), &model.features) {
+            for (acc, value) in acc
+                .weights_mut()
+                .iter_mut()
+                .flatten()
+                .zip(model_features.weights().iter().flatten())
+            {
+                *acc += value * share;
+            }
+        }
+        match (visit_counts.as_mut(), &model.visit_counts) {
+            (Some(acc), Some(counts)) => {
+                for (acc, count) in acc
+                    .iter_mut()
+                    .flatten()
+                    .flatten()
+                    .zip(counts.iter().flatten().flatten())
+                {
+                    *acc = acc.saturating_add(*count);
+                }
+            }
+            _ => visit_counts = None,
+        }
+    }
+
+    Ok(ModelFile {
+        version: 4,
+        tuples: first.tuples.clone(),
+        phase_count: first.phase_count,
+        weights: ModelWeights::Float(weights),
+        visit_counts,
+        tc_accumulators: None,
+        features,
+        calibration: None,
+        metadata: None,
+    })
+}
+
+fn percentage(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 * 100.0 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(weights: Vec<f32>, visits: u32) -> ModelFile {
+        ModelFile {
+            version: 4,
+            tuples: vec![vec![0, 1]],
+            phase_count: 1,
+            visit_counts: Some(vec![vec![vec![visits; weights.len()]]]),
+            tc_accumulators: None,
+            weights: ModelWeights::Float(vec![vec![weights]]),
+            features: None,
+            calibration: None,
+            metadata: None,
+        }
+    }
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_args_reads_each_subcommand() {
+        assert_eq!(
+            parse_args(args(&["info", "a.bin"])),
+            Ok(Command::Info {
+                path: PathBuf::from("a.bin")
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "convert",
+                "old.bin",
+                "new.bin",
+                "--to",
+                "keep",
+                "--uncompressed"
+            ])),
+            Ok(Command::Convert {
+                input: PathBuf::from("old.bin"),
+                output: PathBuf::from("new.bin"),
+                target: ConvertTarget::Keep,
+                compress: false,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "average",
+                "out.bin",
+                "a.bin",
+                "b.bin",
+                "--weights",
+                "3,1"
+            ])),
+            Ok(Command::Average {
+                output: PathBuf::from("out.bin"),
+                inputs: vec![PathBuf::from("a.bin"), PathBuf::from("b.bin")],
+                weights: vec![3.0, 1.0],
+                compress: true,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "calibrate",
+                "in.bin",
+                "out.bin",
+                "--games",
+                "50",
+                "--solve-empties",
+                "10",
+                "--uncompressed"
+            ])),
+            Ok(Command::Calibrate {
+                input: PathBuf::from("in.bin"),
+                output: PathBuf::from("out.bin"),
+                samples: SampleConfig {
+                    games: 50,
+                    epsilon: 0.1,
+                    random_opening_plies: 4,
+                    solve_empties: 10,
+                    seed: 42,
+                },
+                compress: false,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_args_rejects_bad_invocations() {
+        assert!(
+            parse_args(Vec::new())
+                .unwrap_err()
+                .contains("missing subcommand")
+        );
+        assert!(
+            parse_args(args(&["diff", "a.bin"]))
+                .unwrap_err()
+                .contains("expects 2 path(s)")
+        );
+        assert!(
+            parse_args(args(&[
+                "average",
+                "out.bin",
+                "a.bin",
+                "b.bin",
+                "--weights",
+                "1"
+            ]))
+            .unwrap_err()
+            .contains("1 values for 2 models")
+        );
+        assert!(
+            parse_args(args(&["convert", "a", "b", "--to", "v9"]))
+                .unwrap_err()
+                .contains("expected keep, v4 or v5")
+        );
+    }
+
+    #[test]
+    fn average_models_weights_values_and_sums_visits() {
+        let averaged =
+            average_models(&[(model(vec![4.0; 9], 2), 3.0), (model(vec![0.0; 9], 5), 1.0)])
+                .expect("same shapes must average");
+
+        assert_eq!(
+            averaged.weights,
+            ModelWeights::Float(vec![vec![vec![3.0; 9]]])
+        );
+        assert_eq!(averaged.visit_counts, Some(vec![vec![vec![7; 9]]]));
+
+        let mut other_shape = model(vec![0.0; 9], 0);
+        other_shape.tuples = vec![vec![0, 2]];
+        assert!(average_models(&[(model(vec![0.0; 9], 0), 1.0), (other_shape, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn tuple_deltas_report_largest_change_and_rms() {
+        let mut changed = vec![1.0; 9];
+        changed[4] = 4.0;
+        let deltas = tuple_deltas(&model(vec![1.0; 9], 0), &model(changed, 0)).unwrap();
+
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].max_abs, 3.0);
+        assert!((deltas[0].mean_abs - 3.0 / 9.0).abs() < 1e-9);
+        assert!((deltas[0].rms - 1.0).abs() < 1e-9);
+    }
+}
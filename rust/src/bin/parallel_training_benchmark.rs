@@ -0,0 +1,379 @@
+use std::env;
+use std::time::{Duration, Instant};
+
+use reversi::ai::arena::{self, MatchConfig};
+use reversi::ai::level::LevelConfig;
+use reversi::ai::ntuple::NTupleEvaluator;
+use reversi::cli::parse_value;
+use reversi::training::{
+    AlphaDecayStrategy, DEFAULT_TRAINING_SEARCH_DEPTH, EpsilonSchedule, ExplorationPolicy,
+    ParallelMode, TrainingConfig, TrainingRun,
+};
+
+const BUDGET_EXHAUSTED: &str = "training budget exhausted";
+
+#[derive(Clone, Debug)]
+struct Config {
+    modes: Vec<ParallelMode>,
+    threads: usize,
+    budget_seconds: f64,
+    check_interval: usize,
+    max_train_games: usize,
+    alpha: f32,
+    alpha_decay: AlphaDecayStrategy,
+    lambda_: f32,
+    epsilon: f64,
+    random_opening_plies: usize,
+    games: usize,
+    level: u8,
+    timeout_ms: u64,
+    seed: u64,
+}
+
+#[derive(Clone, Debug)]
+struct ModeResult {
+    label: String,
+    train_games: usize,
+    train_seconds: f64,
+    wins: usize,
+    losses: usize,
+    draws: usize,
+    mean_diff: f64,
+}
+
+impl ModeResult {
+    fn games_per_hour(&self) -> f64 {
+        if self.train_seconds > 0.0 {
+            self.train_games as f64 * 3600.0 / self.train_seconds
+        } else {
+            0.0
+        }
+    }
+
+    fn score(&self) -> f64 {
+        let games = self.wins + self.losses + self.draws;
+        if games == 0 {
+            0.0
+        } else {
+            (self.wins as f64 + self.draws as f64 * 0.5) / games as f64
+        }
+    }
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_args(env::args().skip(1).collect())?;
+    println!(
+        "Training each mode for {:.0}s (threads={}, modes={}), then playing {} games at level {} against a sequential run with the same budget",
+        config.budget_seconds,
+        config.threads,
+        config
+            .modes
+            .iter()
+            .map(|mode| mode.name())
+            .collect::<Vec<_>>()
+            .join(", "),
+        config.games,
+        config.level
+    );
+
+    let started = Instant::now();
+    let (baseline, baseline_games, baseline_seconds) = train_for_budget(&config, 1, None)?;
+    println!(
+        "sequential: {baseline_games} games in {baseline_seconds:.1}s ({:.0} games/hour)",
+        baseline_games as f64 * 3600.0 / baseline_seconds.max(f64::EPSILON)
+    );
+
+    let mut results = Vec::with_capacity(config.modes.len());
+    for mode in &config.modes {
+        let (evaluator, train_games, train_seconds) =
+            train_for_budget(&config, config.threads, Some(*mode))?;
+        let mut result = ModeResult {
+            label: mode.name(),
+            train_games,
+            train_seconds,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            mean_diff: 0.0,
+        };
+        play_match(&mut result, &evaluator, &baseline, &config)?;
+        println!(
+            "{}: {} games in {:.1}s, {:.2}% vs sequential",
+            result.label,
+            result.train_games,
+            result.train_seconds,
+            result.score() * 100.0
+        );
+        results.push(result);
+    }
+
+    println!();
+    println!("mode            games/hour  games      score    W/L/D        mean_diff");
+    for result in &results {
+        println!(
+            "{:<14}  {:>10.0}  {:>9}  {:>6.2}%  {:>3}/{:>3}/{:>3}  {:>+9.2}",
+            result.label,
+            result.games_per_hour(),
+            result.train_games,
+            result.score() * 100.0,
+            result.wins,
+            result.losses,
+            result.draws,
+            result.mean_diff
+        );
+    }
+    println!("Total time: {:.1}s", started.elapsed().as_secs_f64());
+    Ok(())
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    let mut config = Config {
+        modes: vec![
+            ParallelMode::Independent,
+            ParallelMode::SyncRounds { round_games: 1_000 },
+            ParallelMode::Hogwild,
+        ],
+        threads: 0,
+        budget_seconds: 600.0,
+        check_interval: 200,
+        max_train_games: 100_000_000,
+        alpha: 0.01,
+        alpha_decay: AlphaDecayStrategy::None,
+        lambda_: 0.7,
+        epsilon: 0.1,
+        random_opening_plies: 4,
+        games: 40,
+        level: 2,
+        timeout_ms: 1_000,
+        seed: 42,
+    };
+
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--modes" => {
+                idx += 1;
+                config.modes = parse_value::<String>(&args, idx, "--modes")?
+                    .split(',')
+                    .map(|mode| ParallelMode::parse(mode.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+            "--threads" => {
+                idx += 1;
+                config.threads = parse_value(&args, idx, "--threads")?;
+            }
+            "--budget-seconds" => {
+                idx += 1;
+                config.budget_seconds = parse_value(&args, idx, "--budget-seconds")?;
+            }
+            "--check-interval" => {
+                idx += 1;
+                config.check_interval = parse_value(&args, idx, "--check-interval")?;
+            }
+            "--max-train-games" => {
+                idx += 1;
+                config.max_train_games = parse_value(&args, idx, "--max-train-games")?;
+            }
+            "--alpha" => {
+                idx += 1;
+                config.alpha = parse_value(&args, idx, "--alpha")?;
+            }
+            "--alpha-decay" => {
+                idx += 1;
+                config.alpha_decay = AlphaDecayStrategy::from_name(&parse_value::<String>(
+                    &args,
+                    idx,
+                    "--alpha-decay",
+                )?)?;
+            }
+            "--lambda" => {
+                idx += 1;
+                config.lambda_ = parse_value(&args, idx, "--lambda")?;
+            }
+            "--epsilon" => {
+                idx += 1;
+                config.epsilon = parse_value(&args, idx, "--epsilon")?;
+            }
+            "--random-opening-plies" => {
+                idx += 1;
+                config.random_opening_plies = parse_value(&args, idx, "--random-opening-plies")?;
+            }
+            "--games" => {
+                idx += 1;
+                config.games = parse_value(&args, idx, "--games")?;
+            }
+            "--level" => {
+                idx += 1;
+                config.level = parse_value(&args, idx, "--level")?;
+            }
+            "--timeout-ms" => {
+                idx += 1;
+                config.timeout_ms = parse_value(&args, idx, "--timeout-ms")?;
+            }
+            "--seed" => {
+                idx += 1;
+                config.seed = parse_value(&args, idx, "--seed")?;
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+        idx += 1;
+    }
+
+    LevelConfig::try_for_level(config.level)?;
+    if config.modes.is_empty() {
+        return Err("modes must not be empty".to_string());
+    }
+    if !config.budget_seconds.is_finite() || config.budget_seconds <= 0.0 {
+        return Err("budget-seconds must be greater than 0".to_string());
+    }
+    if config.check_interval == 0 {
+        return Err("check-interval must be greater than 0".to_string());
+    }
+    if config.max_train_games == 0 {
+        return Err("max-train-games must be greater than 0".to_string());
+    }
+    if config.timeout_ms == 0 {
+        return Err("timeout-ms must be greater than 0".to_string());
+    }
+    if config.games == 0 {
+        return Err("games must be greater than 0".to_string());
+    }
+
+    Ok(config)
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --release --manifest-path rust/Cargo.toml --bin parallel_training_benchmark -- [options]\n\
+         \n\
+         Trains a network with each parallel mode for the same wall-clock budget and\n\
+         plays it against a single-threaded run given that budget, to compare how much\n\
+         strength each mode buys per hour.\n\
+         \n\
+         Options:\n\
+           --modes <M,M,...>         Parallel modes: independent, hogwild, sync:<games>\n\
+                                     (default: independent,sync:1000,hogwild)\n\
+           --threads <N>             Training threads per mode, 0 = all CPUs (default: 0)\n\
+           --budget-seconds <F>      Wall-clock training budget per run (default: 600)\n\
+           --check-interval <N>      Games between budget checks (default: 200)\n\
+           --max-train-games <N>     Stop earlier after N games (default: 100000000)\n\
+           --alpha <F>               Learning rate (default: 0.01)\n\
+           --alpha-decay <NAME>      none, inverse_game, inverse_visit or tc (default: none)\n\
+           --lambda <F>              Eligibility trace decay (default: 0.7)\n\
+           --epsilon <F>             Exploration rate (default: 0.1)\n\
+           --random-opening-plies <N> Random plies before each game (default: 4)\n\
+           --games <N>               Benchmark games per mode, colors alternate (default: 40)\n\
+           --level <1-10>            Search level for benchmark games (default: 2)\n\
+           --timeout-ms <N>          Per-move search timeout in milliseconds (default: 1000)\n\
+           --seed <N>                Seed for training and openings (default: 42)\n\
+           --help                    Show this message"
+    );
+}
+
+/// Trains until the budget runs out and returns the network with the games
+/// played and the seconds spent; `parallel_mode` is `None` for the baseline.
+fn train_for_budget(
+    config: &Config,
+    threads: usize,
+    parallel_mode: Option<ParallelMode>,
+) -> Result<(NTupleEvaluator, usize, f64), String> {
+    let training = TrainingConfig {
+        games: config.max_train_games,
+        alpha: config.alpha,
+        alpha_decay: config.alpha_decay,
+        alpha_decay_start_game: 0,
+        lambda_: config.lambda_,
+        epsilon: config.epsilon,
+        seed: config.seed,
+        threads,
+        random_opening_plies: config.random_opening_plies,
+        parallel_mode: parallel_mode.unwrap_or(ParallelMode::Independent),
+        search_depth: DEFAULT_TRAINING_SEARCH_DEPTH,
+        exact_td_empties: 0,
+        epsilon_schedule: EpsilonSchedule::Constant,
+        exploration: ExplorationPolicy::Uniform,
+        league: None,
+    };
+    let mut run = TrainingRun::new(training, None, None)?;
+    let budget = Duration::from_secs_f64(config.budget_seconds);
+    let started = Instant::now();
+    let mut stop_at_budget = |_: &TrainingRun| -> Result<(), String> {
+        if started.elapsed() >= budget {
+            Err(BUDGET_EXHAUSTED.to_string())
+        } else {
+            Ok(())
+        }
+    };
+    match run.train(0, None, config.check_interval, Some(&mut stop_at_budget)) {
+        Ok(()) => {}
+        Err(err) if err == BUDGET_EXHAUSTED => {}
+        Err(err) => return Err(err),
+    }
+    let train_seconds = started.elapsed().as_secs_f64();
+    let network = run.current_network()?;
+    let evaluator = NTupleEvaluator::from_bytes(&network.to_uncompressed_bytes()?)?;
+    Ok((evaluator, run.completed_games(), train_seconds))
+}
+
+/// Game `i` uses the same opening for every mode so results are comparable.
+fn play_match(
+    result: &mut ModeResult,
+    candidate: &NTupleEvaluator,
+    baseline: &NTupleEvaluator,
+    config: &Config,
+) -> Result<(), String> {
+    let level = LevelConfig {
+        time_budget_ms: u32::try_from(config.timeout_ms).unwrap_or(u32::MAX),
+        ..LevelConfig::try_for_level(config.level)?
+    };
+    let record = arena::play_match(
+        candidate,
+        baseline,
+        &MatchConfig {
+            games: config.games,
+            opening_plies: config.random_opening_plies,
+            seed: config.seed,
+            level,
+        },
+        |_| true,
+    )?;
+    result.wins = record.wins;
+    result.losses = record.losses;
+    result.draws = record.draws;
+    result.mean_diff = record.mean_diff();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args_reads_modes_and_rejects_bad_budgets() {
+        let config = parse_args(vec![
+            "--modes".to_string(),
+            "hogwild, sync:250".to_string(),
+            "--budget-seconds".to_string(),
+            "30".to_string(),
+        ])
+        .expect("args should parse");
+        assert_eq!(
+            config.modes,
+            vec![
+                ParallelMode::Hogwild,
+                ParallelMode::SyncRounds { round_games: 250 }
+            ]
+        );
+        assert_eq!(config.budget_seconds, 30.0);
+
+        let err = parse_args(vec!["--modes".to_string(), "sync:0".to_string()]).unwrap_err();
+        assert!(err.contains("unsupported parallel mode"));
+
+        let err = parse_args(vec!["--budget-seconds".to_string(), "0".to_string()]).unwrap_err();
+        assert!(err.contains("budget-seconds"));
+    }
+}
@@ -0,0 +1,297 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use reversi::ai::arena::{self, MatchConfig};
+use reversi::ai::level::LevelConfig;
+use reversi::ai::ntuple::{NTupleEvaluator, decompress_model_bytes};
+use reversi::ai::search::Searcher;
+use reversi::board::Board;
+use reversi::cli::parse_value;
+use web_time::Duration as WebDuration;
+
+const EMBEDDED_MODEL_BYTES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/embedded_weights.bin"));
+const RANDOM_OPENING_PLIES: usize = 4;
+
+#[derive(Clone, Debug)]
+struct Config {
+    weights_path: Option<PathBuf>,
+    output_path: Option<PathBuf>,
+    eval_positions: usize,
+    search_positions: usize,
+    games: usize,
+    level: u8,
+    timeout_ms: u64,
+    seed: u64,
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_args(env::args().skip(1).collect())?;
+    let source_bytes = match &config.weights_path {
+        Some(path) => fs::read(path)
+            .map_err(|err| format!("failed to read model bytes from {}: {err}", path.display()))?,
+        None => EMBEDDED_MODEL_BYTES.to_vec(),
+    };
+    let float = NTupleEvaluator::from_bytes(&source_bytes)?;
+    let quantized_bytes = float.to_quantized_bytes()?;
+    let quantized = NTupleEvaluator::from_bytes(&quantized_bytes)?;
+
+    match &config.weights_path {
+        Some(path) => println!("Model source: {}", path.display()),
+        None => println!("Model source: embedded model in current binary"),
+    }
+    println!(
+        "Size: f32 raw={} bytes, file={} bytes; int16 raw={} bytes, zstd={} bytes",
+        decompress_model_bytes(&source_bytes)?.len(),
+        source_bytes.len(),
+        decompress_model_bytes(&quantized_bytes)?.len(),
+        quantized_bytes.len()
+    );
+
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let positions: Vec<(Board, bool)> = (0..config.eval_positions)
+        .map(|_| random_position(&mut rng))
+        .collect();
+    let mut diffs: Vec<f64> = positions
+        .iter()
+        .map(|(board, is_black)| {
+            (float.evaluate(board, *is_black) - quantized.evaluate(board, *is_black)).abs() as f64
+        })
+        .collect();
+    diffs.sort_by(f64::total_cmp);
+    println!(
+        "Eval abs diff over {} positions: mean={:.6}, p95={:.6}, max={:.6}",
+        diffs.len(),
+        mean(&diffs),
+        percentile(&diffs, 95),
+        diffs.last().copied().unwrap_or(0.0)
+    );
+
+    let timeout = WebDuration::from_millis(config.timeout_ms);
+    let searched: Vec<_> = positions
+        .iter()
+        .filter(|(board, is_black)| board.legal_moves(*is_black) != 0)
+        .take(config.search_positions)
+        .collect();
+    let agreements = searched
+        .iter()
+        .filter(|(board, is_black)| {
+            let float_move =
+                Searcher::with_timeout(&float, config.level, timeout).search(board, *is_black);
+            let quantized_move =
+                Searcher::with_timeout(&quantized, config.level, timeout).search(board, *is_black);
+            float_move == quantized_move
+        })
+        .count();
+    println!(
+        "Move agreement at level {}: {}/{} ({:.2}%)",
+        config.level,
+        agreements,
+        searched.len(),
+        percentage(agreements, searched.len())
+    );
+
+    let level = LevelConfig {
+        time_budget_ms: u32::try_from(config.timeout_ms).unwrap_or(u32::MAX),
+        ..LevelConfig::try_for_level(config.level)?
+    };
+    let tally = arena::play_match(
+        &quantized,
+        &float,
+        &MatchConfig {
+            games: config.games,
+            opening_plies: RANDOM_OPENING_PLIES,
+            seed: config.seed,
+            level,
+        },
+        |_| true,
+    )?;
+    if config.games > 0 {
+        println!(
+            "int16 vs f32 over {} games: W/L/D={}/{}/{} (score {:.2}%)",
+            config.games,
+            tally.wins,
+            tally.losses,
+            tally.draws,
+            tally.score() * 100.0
+        );
+    }
+
+    if let Some(path) = &config.output_path {
+        fs::write(path, &quantized_bytes)
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+        println!("Wrote quantized model to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    let mut config = Config {
+        weights_path: None,
+        output_path: None,
+        eval_positions: 10_000,
+        search_positions: 100,
+        games: 20,
+        level: 4,
+        timeout_ms: 250,
+        seed: 42,
+    };
+
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--weights-path" => {
+                idx += 1;
+                config.weights_path = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--weights-path",
+                )?));
+            }
+            "--output" => {
+                idx += 1;
+                config.output_path = Some(PathBuf::from(parse_value::<String>(
+                    &args, idx, "--output",
+                )?));
+            }
+            "--eval-positions" => {
+                idx += 1;
+                config.eval_positions = parse_value(&args, idx, "--eval-positions")?;
+            }
+            "--search-positions" => {
+                idx += 1;
+                config.search_positions = parse_value(&args, idx, "--search-positions")?;
+            }
+            "--games" => {
+                idx += 1;
+                config.games = parse_value(&args, idx, "--games")?;
+            }
+            "--level" => {
+                idx += 1;
+                config.level = parse_value(&args, idx, "--level")?;
+            }
+            "--timeout-ms" => {
+                idx += 1;
+                config.timeout_ms = parse_value(&args, idx, "--timeout-ms")?;
+            }
+            "--seed" => {
+                idx += 1;
+                config.seed = parse_value(&args, idx, "--seed")?;
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+        idx += 1;
+    }
+
+    LevelConfig::try_for_level(config.level)?;
+    if config.timeout_ms == 0 {
+        return Err("timeout-ms must be greater than 0".to_string());
+    }
+    if config.search_positions > config.eval_positions {
+        return Err("search-positions must not exceed eval-positions".to_string());
+    }
+
+    Ok(config)
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --manifest-path rust/Cargo.toml --bin quantization_report -- [options]\n\
+         \n\
+         Quantizes an f32 model to int16 (NTRV v5) and compares both.\n\
+         \n\
+         Options:\n\
+           --weights-path <PATH>     f32 model to quantize instead of the embedded model\n\
+           --output <PATH>           Write the quantized model to PATH\n\
+           --eval-positions <N>      Random positions for evaluation diffs (default: 10000)\n\
+           --search-positions <N>    Positions searched for move agreement (default: 100)\n\
+           --games <N>               Head-to-head games, colors alternate (default: 20)\n\
+           --level <1-10>            Search level for agreement and games (default: 4)\n\
+           --timeout-ms <N>          Per-move search timeout in milliseconds (default: 250)\n\
+           --seed <N>                Seed for positions and openings (default: 42)\n\
+           --help                    Show this message"
+    );
+}
+
+/// Plays a random number of random plies from the initial position.
+fn random_position(rng: &mut ChaCha8Rng) -> (Board, bool) {
+    let plies = rng.gen_range(0..60);
+    arena::random_opening(plies, rng)
+}
+
+fn mean(samples: &[f64]) -> f64 {
+    if samples.is_empty() {
+        0.0
+    } else {
+        samples.iter().sum::<f64>() / samples.len() as f64
+    }
+}
+
+fn percentile(sorted: &[f64], pct: usize) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((pct as f64 / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+    sorted[rank.min(sorted.len() - 1)]
+}
+
+fn percentage(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 * 100.0 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_args;
+
+    #[test]
+    fn parse_args_reads_paths_and_counts() {
+        let config = parse_args(vec![
+            "--weights-path".to_string(),
+            "models/current.bin".to_string(),
+            "--output".to_string(),
+            "models/current-int16.bin".to_string(),
+            "--games".to_string(),
+            "0".to_string(),
+            "--search-positions".to_string(),
+            "10".to_string(),
+        ])
+        .expect("args should parse");
+
+        assert_eq!(
+            config.output_path.as_deref().and_then(|path| path.to_str()),
+            Some("models/current-int16.bin")
+        );
+        assert_eq!(config.games, 0);
+        assert_eq!(config.search_positions, 10);
+    }
+
+    #[test]
+    fn parse_args_rejects_invalid_settings() {
+        let err = parse_args(vec!["--timeout-ms".to_string(), "0".to_string()]).unwrap_err();
+        assert!(err.contains("timeout-ms"));
+
+        let err = parse_args(vec![
+            "--eval-positions".to_string(),
+            "5".to_string(),
+            "--search-positions".to_string(),
+            "6".to_string(),
+        ])
+        .unwrap_err();
+        assert!(err.contains("search-positions"));
+
+        let err = parse_args(vec!["--output".to_string()]).unwrap_err();
+        assert!(err.contains("missing value for --output"));
+    }
+}
@@ -0,0 +1,468 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use reversi::ai::features::ScalarFeature;
+use reversi::ai::metadata::KEY_PARENT_HASH;
+use reversi::ai::ntuple::decompress_model_bytes;
+use reversi::cli::parse_value;
+use reversi::training::dataset::{self, DatasetRow};
+use reversi::training::supervised::{
+    self, EpochReport, LabelledPosition, RecordFormat, SupervisedConfig,
+};
+use reversi::training::{
+    MAX_EXACT_TD_EMPTIES, NetworkLayout, PHASE_COUNT, TrainableNTuple, TuplePatternSet,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Config {
+    data: Vec<PathBuf>,
+    /// `None` detects the format of each file from its contents.
+    format: Option<RecordFormat>,
+    solve_empties: u8,
+    fit: SupervisedConfig,
+    initial_model: Option<PathBuf>,
+    patterns: Option<PathBuf>,
+    scalar_features: Vec<ScalarFeature>,
+    output: PathBuf,
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_args(env::args().skip(1).collect())?;
+    train(&config)
+}
+
+fn train(config: &Config) -> Result<(), String> {
+    let started = Instant::now();
+    let positions = load_positions(config)?;
+    println!(
+        "Loaded {} positions from {} file(s)",
+        positions.len(),
+        config.data.len()
+    );
+
+    let initial_model = match &config.initial_model {
+        Some(path) => Some(read_file(path)?),
+        None => None,
+    };
+    let mut network = match &initial_model {
+        Some(bytes) => TrainableNTuple::from_bytes(bytes)?,
+        None => load_layout(config)?.build()?,
+    };
+
+    let epochs = config.fit.epochs;
+    let mut on_epoch = |report: &EpochReport| -> Result<(), String> {
+        print_epoch(report, epochs);
+        Ok(())
+    };
+    supervised::fit(&mut network, &positions, &config.fit, Some(&mut on_epoch))?;
+    if let Some(bytes) = &initial_model {
+        network.metadata_mut().insert(
+            KEY_PARENT_HASH,
+            format!(
+                "{:08x}",
+                crc32fast::hash(decompress_model_bytes(bytes)?.as_ref())
+            ),
+        );
+    }
+    network.metadata_mut().stamp_created_at();
+
+    write_atomic(&config.output, &network.to_bytes()?)?;
+    println!(
+        "Wrote {} ({epochs} epochs in {:.1}s)",
+        config.output.display(),
+        started.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+fn load_positions(config: &Config) -> Result<Vec<LabelledPosition>, String> {
+    let mut positions = Vec::new();
+    for path in &config.data {
+        let bytes = read_file(path)?;
+        if dataset::is_dataset(&bytes) {
+            let rows = dataset::read_dataset(&bytes)
+                .map_err(|err| format!("{} (dataset): {err}", path.display()))?;
+            positions.extend(rows.iter().map(DatasetRow::labelled));
+            continue;
+        }
+        let text = String::from_utf8(bytes)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let format = config.format.unwrap_or_else(|| RecordFormat::detect(&text));
+        positions.extend(
+            supervised::parse_positions(&text, format)
+                .map_err(|err| format!("{} ({}): {err}", path.display(), format.name()))?,
+        );
+    }
+    if config.solve_empties > 0 {
+        supervised::solve_labels(&mut positions, config.solve_empties);
+    }
+    Ok(positions)
+}
+
+fn load_layout(config: &Config) -> Result<NetworkLayout, String> {
+    let mut layout = NetworkLayout::default();
+    if let Some(path) = &config.patterns {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        layout.tuple_patterns = Arc::new(TuplePatternSet::parse(&text)?);
+    }
+    layout.scalar_features = config.scalar_features.clone();
+    Ok(layout)
+}
+
+fn print_epoch(report: &EpochReport, epochs: usize) {
+    match report.validation_mse {
+        Some(validation_mse) => println!(
+            "[{}/{epochs}] train_mse={:.3} validation_mse={validation_mse:.3}",
+            report.epoch, report.train_mse
+        ),
+        None => println!(
+            "[{}/{epochs}] train_mse={:.3}",
+            report.epoch, report.train_mse
+        ),
+    }
+}
+
+/// Writes through a temporary file so an interrupted write never leaves a
+/// truncated model behind.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = PathBuf::from(temp_name);
+    fs::write(&temp_path, bytes)
+        .map_err(|err| format!("failed to write {}: {err}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .map_err(|err| format!("failed to replace {}: {err}", path.display()))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|err| format!("failed to read {}: {err}", path.display()))
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    let mut config = Config {
+        data: Vec::new(),
+        format: None,
+        solve_empties: 0,
+        fit: SupervisedConfig::default(),
+        initial_model: None,
+        patterns: None,
+        scalar_features: Vec::new(),
+        output: PathBuf::from("weights.bin"),
+    };
+
+    let mut idx = 0;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--data" => {
+                idx += 1;
+                config
+                    .data
+                    .push(PathBuf::from(parse_value::<String>(&args, idx, "--data")?));
+            }
+            "--format" => {
+                idx += 1;
+                config.format = match parse_value::<String>(&args, idx, "--format")?.as_str() {
+                    "auto" => None,
+                    name => Some(RecordFormat::from_name(name)?),
+                };
+            }
+            "--solve-empties" => {
+                idx += 1;
+                config.solve_empties = parse_value(&args, idx, "--solve-empties")?;
+            }
+            "--epochs" => {
+                idx += 1;
+                config.fit.epochs = parse_value(&args, idx, "--epochs")?;
+            }
+            "--batch-size" => {
+                idx += 1;
+                config.fit.batch_size = parse_value(&args, idx, "--batch-size")?;
+            }
+            "--learning-rate" => {
+                idx += 1;
+                config.fit.learning_rate = parse_value(&args, idx, "--learning-rate")?;
+            }
+            "--l2" => {
+                idx += 1;
+                config.fit.l2 = parse_value(&args, idx, "--l2")?;
+            }
+            "--phases" => {
+                idx += 1;
+                let spec = parse_value::<String>(&args, idx, "--phases")?;
+                let (first, last) = spec.split_once('-').unwrap_or((&spec, &spec));
+                let parse_phase = |phase: &str| {
+                    phase
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid value for --phases: '{spec}'"))
+                };
+                config.fit.phases = parse_phase(first)?..=parse_phase(last)?;
+            }
+            "--validation" => {
+                idx += 1;
+                config.fit.validation_fraction = parse_value(&args, idx, "--validation")?;
+            }
+            "--seed" => {
+                idx += 1;
+                config.fit.seed = parse_value(&args, idx, "--seed")?;
+            }
+            "--initial-model" => {
+                idx += 1;
+                config.initial_model = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--initial-model",
+                )?));
+            }
+            "--patterns" => {
+                idx += 1;
+                config.patterns = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--patterns",
+                )?));
+            }
+            "--scalar-features" => {
+                idx += 1;
+                config.scalar_features = parse_value::<String>(&args, idx, "--scalar-features")?
+                    .split(',')
+                    .map(|name| ScalarFeature::from_name(name.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+            "--output" => {
+                idx += 1;
+                config.output = PathBuf::from(parse_value::<String>(&args, idx, "--output")?);
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+        idx += 1;
+    }
+
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate(config: &Config) -> Result<(), String> {
+    if config.data.is_empty() {
+        return Err("at least one --data file is required".to_string());
+    }
+    if config.solve_empties > MAX_EXACT_TD_EMPTIES {
+        return Err(format!(
+            "solve_empties must be at most {MAX_EXACT_TD_EMPTIES}"
+        ));
+    }
+    if config.initial_model.is_some()
+        && (config.patterns.is_some() || !config.scalar_features.is_empty())
+    {
+        return Err(
+            "--patterns and --scalar-features only apply without --initial-model".to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --release --manifest-path rust/Cargo.toml --bin supervised_train -- --data <PATH> [options]\n\
+         \n\
+         Fits an N-tuple network to labelled positions by mini-batch regression,\n\
+         either to bootstrap a fresh network or to fine-tune phases of a model.\n\
+         \n\
+         Options:\n\
+           --data <PATH>             Game records, positions or a binary dataset; repeat\n\
+                                     for more files\n\
+           --format <NAME>           auto, transcript, ggf or positions (default: auto)\n\
+           --solve-empties <N>       Relabel positions with at most N empties by an exact\n\
+                                     solve (default: 0)\n\
+           --epochs <N>              Passes over the positions (default: 10)\n\
+           --batch-size <N>          Positions per gradient step (default: 256)\n\
+           --learning-rate <F>       Gradient step size (default: 0.5)\n\
+           --l2 <F>                  L2 penalty on the weights each batch touches (default: 0.0001)\n\
+           --phases <A-B>            Only fit phases A to B, e.g. 24-29 (default: 0-{last_phase})\n\
+           --validation <F>          Share of positions held out for validation (default: 0)\n\
+           --seed <N>                Shuffle seed (default: 42)\n\
+           --initial-model <PATH>    Fine-tune a model instead of a fresh network\n\
+           --patterns <PATH>         Tuple pattern file for a fresh network\n\
+           --scalar-features <A,B>   Scalar features for a fresh network\n\
+           --output <PATH>           Model to write (default: weights.bin)\n\
+           --help                    Show this message\n\
+         \n\
+         Transcripts hold one played-out game per line (e.g. f5d6c3...). GGF games\n\
+         that stop early are labelled from their RE result. Position files hold one\n\
+         `<64 squares> <side> <score>` line per position, squares in a1..h8 order\n\
+         as X/O/-, scored as the final disc difference for the side to move.\n\
+         Binary datasets from generate_dataset are recognised by their header and\n\
+         labelled with the exact score where solved, else the final disc difference.",
+        last_phase = PHASE_COUNT - 1
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use reversi::board::Board;
+
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_fill_the_fit_settings() {
+        let config = parse_args(args(&[
+            "--data",
+            "games.ggf",
+            "--data",
+            "solved.txt",
+            "--format",
+            "positions",
+            "--phases",
+            "24-29",
+            "--l2",
+            "0",
+            "--epochs",
+            "3",
+        ]))
+        .unwrap();
+        assert_eq!(
+            config.data,
+            vec![PathBuf::from("games.ggf"), PathBuf::from("solved.txt")]
+        );
+        assert_eq!(config.format, Some(RecordFormat::Positions));
+        assert_eq!(config.fit.phases, 24..=29);
+        assert_eq!(config.fit.l2, 0.0);
+        assert_eq!(config.fit.epochs, 3);
+        assert_eq!(
+            config.fit.batch_size,
+            SupervisedConfig::default().batch_size
+        );
+
+        let single = parse_args(args(&["--data", "a.txt", "--phases", "29"])).unwrap();
+        assert_eq!(single.fit.phases, 29..=29);
+        assert_eq!(single.format, None);
+
+        assert!(parse_args(Vec::new()).is_err());
+        assert!(parse_args(args(&["--data", "a.txt", "--phases", "x-3"])).is_err());
+        assert!(parse_args(args(&["--data", "a.txt", "--format", "pgn"])).is_err());
+        assert!(parse_args(args(&["--data", "a.txt", "--solve-empties", "30"])).is_err());
+        assert!(
+            parse_args(args(&[
+                "--data",
+                "a.txt",
+                "--initial-model",
+                "w.bin",
+                "--patterns",
+                "p.txt",
+            ]))
+            .is_err()
+        );
+    }
+
+    /// A played-out game in which each side always takes its lowest legal square.
+    fn first_move_transcript() -> String {
+        let mut board = Board::new();
+        let mut is_black = true;
+        let mut transcript = String::new();
+        while board.legal_moves(true) | board.legal_moves(false) != 0 {
+            let legal = board.legal_moves(is_black);
+            if legal != 0 {
+                let mv = legal.trailing_zeros() as usize;
+                board.place(mv, is_black);
+                transcript.push((b'a' + (mv % 8) as u8) as char);
+                transcript.push((b'1' + (mv / 8) as u8) as char);
+            }
+            is_black = !is_black;
+        }
+        transcript
+    }
+
+    #[test]
+    fn fits_a_model_from_a_transcript_file() {
+        let dir = env::temp_dir().join(format!("reversi-supervised-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data = dir.join("games.txt");
+        fs::write(&data, format!("# one game\n{}\n", first_move_transcript())).unwrap();
+        let output = dir.join("weights.bin");
+        let config = parse_args(args(&[
+            "--data",
+            data.to_str().unwrap(),
+            "--epochs",
+            "2",
+            "--solve-empties",
+            "6",
+            "--output",
+            output.to_str().unwrap(),
+        ]))
+        .unwrap();
+
+        let positions = load_positions(&config).unwrap();
+        assert!(!positions.is_empty());
+        train(&config).unwrap();
+        let network = TrainableNTuple::from_bytes(&fs::read(&output).unwrap()).unwrap();
+        assert_eq!(
+            network
+                .metadata()
+                .get(reversi::ai::metadata::KEY_SUPERVISED_EPOCHS),
+            Some("2")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_binary_datasets_whatever_the_format_flag() {
+        let dir = env::temp_dir().join(format!("reversi-supervised-ds-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data = dir.join("positions.bin");
+        // The starting position: d5 and e4 black, d4 and e5 white.
+        let (black, white) = ((1 << 28) | (1 << 35), (1 << 27) | (1 << 36));
+        let rows = [
+            DatasetRow {
+                black,
+                white,
+                is_black: true,
+                ply: 0,
+                search_score: 1.0,
+                final_score: 6,
+                exact_score: None,
+            },
+            DatasetRow {
+                black,
+                white,
+                is_black: false,
+                ply: 0,
+                search_score: f32::NAN,
+                final_score: -6,
+                exact_score: Some(-2),
+            },
+        ];
+        let mut writer =
+            dataset::DatasetWriter::create(&data, dataset::DatasetFormat::Binary).unwrap();
+        writer.write_rows(&rows).unwrap();
+        writer.finish().unwrap();
+
+        let config = parse_args(args(&[
+            "--data",
+            data.to_str().unwrap(),
+            "--format",
+            "transcript",
+        ]))
+        .unwrap();
+        let positions = load_positions(&config).unwrap();
+        assert_eq!(
+            positions
+                .iter()
+                .map(|position| position.score)
+                .collect::<Vec<_>>(),
+            vec![6.0, -2.0]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1083 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use reversi::ai::features::ScalarFeature;
+use reversi::ai::metadata::{KEY_GAMES, KEY_PARENT_HASH, KEY_SEED};
+use reversi::ai::ntuple::decompress_model_bytes;
+use reversi::cli::{parse_flag_value, parse_value};
+use reversi::training::gating::{GatingConfig, GatingLog, GenerationReport, run_gating};
+use reversi::training::{
+    AlphaDecayStrategy, DEFAULT_TRAINING_SEARCH_DEPTH, EpsilonSchedule, ExplorationPolicy,
+    LeagueConfig, NetworkLayout, ParallelMode, TrainingConfig, TrainingLog, TrainingProgress,
+    TrainingRun, TuplePatternSet,
+};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+/// Settings of a run. Config files set the fields as top-level keys, with
+/// `lambda` for `lambda_`, and add one `[[stage]]` table per stage.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    games: usize,
+    alpha: f32,
+    #[serde(deserialize_with = "alpha_decay_from_name")]
+    alpha_decay: AlphaDecayStrategy,
+    alpha_decay_start_game: usize,
+    #[serde(rename = "lambda")]
+    lambda_: f32,
+    epsilon: f64,
+    seed: u64,
+    threads: usize,
+    random_opening_plies: usize,
+    #[serde(deserialize_with = "parallel_mode_from_spec")]
+    parallel_mode: ParallelMode,
+    search_depth: u8,
+    exact_td_empties: u8,
+    #[serde(deserialize_with = "epsilon_schedule_from_spec")]
+    epsilon_schedule: EpsilonSchedule,
+    #[serde(deserialize_with = "exploration_from_spec")]
+    exploration: ExplorationPolicy,
+    #[serde(deserialize_with = "league_from_spec")]
+    league: Option<LeagueConfig>,
+    /// Train candidates and keep only those that beat the incumbent.
+    #[serde(deserialize_with = "gate_from_spec")]
+    gate: Option<GatingConfig>,
+    gate_log: Option<PathBuf>,
+    /// Consecutive phases of the run; empty means one stage of `games`.
+    #[serde(rename = "stage")]
+    stages: Vec<Stage>,
+    initial_model: Option<PathBuf>,
+    patterns: Option<PathBuf>,
+    #[serde(deserialize_with = "scalar_features_from_names")]
+    scalar_features: Vec<ScalarFeature>,
+    output: PathBuf,
+    progress_interval: usize,
+    checkpoint_interval: usize,
+    checkpoint: Option<PathBuf>,
+    resume: bool,
+    metrics_log: Option<PathBuf>,
+}
+
+/// A phase of a schedule; unset values fall back to the top-level settings.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Stage {
+    games: usize,
+    alpha: Option<f32>,
+    epsilon: Option<f64>,
+    #[serde(rename = "lambda")]
+    lambda_: Option<f32>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            games: 100_000,
+            alpha: 0.001,
+            alpha_decay: AlphaDecayStrategy::None,
+            alpha_decay_start_game: 0,
+            lambda_: 0.7,
+            epsilon: 0.1,
+            seed: 42,
+            threads: 0,
+            random_opening_plies: 0,
+            parallel_mode: ParallelMode::Independent,
+            search_depth: DEFAULT_TRAINING_SEARCH_DEPTH,
+            exact_td_empties: 0,
+            epsilon_schedule: EpsilonSchedule::Constant,
+            exploration: ExplorationPolicy::Uniform,
+            league: None,
+            gate: None,
+            gate_log: None,
+            stages: Vec::new(),
+            initial_model: None,
+            patterns: None,
+            scalar_features: Vec::new(),
+            output: PathBuf::from("weights.bin"),
+            progress_interval: 10_000,
+            checkpoint_interval: 0,
+            checkpoint: None,
+            resume: false,
+            metrics_log: None,
+        }
+    }
+}
+
+impl Config {
+    /// Training settings of each stage. Stage `i` starts where the previous
+    /// ones left off: its seed and alpha decay start are offset by the games
+    /// played before it, so a single stage matches a plain training run.
+    fn stage_configs(&self) -> Vec<TrainingConfig> {
+        let stages = if self.stages.is_empty() {
+            vec![Stage {
+                games: self.games,
+                alpha: None,
+                epsilon: None,
+                lambda_: None,
+            }]
+        } else {
+            self.stages.clone()
+        };
+        let mut games_before = 0usize;
+        stages
+            .into_iter()
+            .map(|stage| {
+                let config = TrainingConfig {
+                    games: stage.games,
+                    alpha: stage.alpha.unwrap_or(self.alpha),
+                    alpha_decay: self.alpha_decay,
+                    alpha_decay_start_game: self.alpha_decay_start_game + games_before,
+                    lambda_: stage.lambda_.unwrap_or(self.lambda_),
+                    epsilon: stage.epsilon.unwrap_or(self.epsilon),
+                    seed: self.seed.wrapping_add(games_before as u64),
+                    threads: self.threads,
+                    random_opening_plies: self.random_opening_plies,
+                    parallel_mode: self.parallel_mode,
+                    search_depth: self.search_depth,
+                    exact_td_empties: self.exact_td_empties,
+                    epsilon_schedule: self.epsilon_schedule,
+                    exploration: self.exploration,
+                    league: self.league,
+                };
+                games_before += stage.games;
+                config
+            })
+            .collect()
+    }
+
+    /// Checkpoint file, defaulting to the output path with an `.ntck`
+    /// extension when checkpoints are enabled.
+    fn checkpoint_path(&self) -> Option<PathBuf> {
+        match &self.checkpoint {
+            Some(path) => Some(path.clone()),
+            None if self.checkpoint_interval > 0 || self.resume => {
+                Some(self.output.with_extension("ntck"))
+            }
+            None => None,
+        }
+    }
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_args(env::args().skip(1).collect())?;
+    train(&config)
+}
+
+fn train(config: &Config) -> Result<(), String> {
+    if let Some(gating) = &config.gate {
+        return train_gated(config, gating);
+    }
+    let stages = config.stage_configs();
+    let total_games: usize = stages.iter().map(|stage| stage.games).sum();
+    let layout = load_layout(config)?;
+    let initial_model = match &config.initial_model {
+        Some(path) => Some(read_file(path)?),
+        None => None,
+    };
+    let checkpoint_path = config.checkpoint_path();
+    let mut log = match &config.metrics_log {
+        Some(path) => Some(TrainingLog::open(path)?),
+        None => None,
+    };
+
+    let resumed = match &checkpoint_path {
+        Some(path) if config.resume && path.exists() => {
+            let run = TrainingRun::from_checkpoint_bytes(&read_file(path)?)?;
+            let stage_idx = stages
+                .iter()
+                .position(|stage| stage == run.config())
+                .ok_or_else(|| {
+                    format!(
+                        "checkpoint {} does not match any stage of this schedule",
+                        path.display()
+                    )
+                })?;
+            Some((stage_idx, run))
+        }
+        _ => None,
+    };
+    let (mut stage_idx, mut run) = match resumed {
+        Some((stage_idx, run)) => {
+            let games_before: usize = stages[..stage_idx].iter().map(|stage| stage.games).sum();
+            println!(
+                "Resuming from {} at {}/{total_games} games",
+                checkpoint_path
+                    .as_deref()
+                    .map_or_else(String::new, |path| path.display().to_string()),
+                games_before + run.completed_games()
+            );
+            (stage_idx, run)
+        }
+        None => (
+            0,
+            TrainingRun::new(stages[0].clone(), initial_model.as_deref(), layout.as_ref())?,
+        ),
+    };
+
+    let started = Instant::now();
+    let mut network = loop {
+        let stage = &stages[stage_idx];
+        let games_before: usize = stages[..stage_idx].iter().map(|stage| stage.games).sum();
+        println!(
+            "Stage {}/{}: {} games, alpha={}, epsilon={}, lambda={}, threads={}",
+            stage_idx + 1,
+            stages.len(),
+            stage.games,
+            stage.alpha,
+            stage.epsilon,
+            stage.lambda_,
+            stage.threads
+        );
+
+        let mut on_progress = |progress: &TrainingProgress| -> Result<(), String> {
+            let progress = TrainingProgress {
+                completed: games_before + progress.completed,
+                total: total_games,
+                elapsed_seconds: started.elapsed().as_secs_f64(),
+                metrics: progress.metrics.clone(),
+            };
+            print_progress(&progress);
+            match log.as_mut() {
+                Some(log) => log.write(&progress),
+                None => Ok(()),
+            }
+        };
+        // A finished stage is saved as the start of the next one, or not at
+        // all once the model is written.
+        let mut on_checkpoint = |run: &TrainingRun| -> Result<(), String> {
+            match &checkpoint_path {
+                Some(path) if !run.is_finished() => write_checkpoint(path, run, games_before),
+                _ => Ok(()),
+            }
+        };
+        run.train(
+            config.progress_interval,
+            Some(&mut on_progress),
+            config.checkpoint_interval,
+            Some(&mut on_checkpoint),
+        )?;
+        let network = run.into_network()?;
+
+        stage_idx += 1;
+        if stage_idx == stages.len() {
+            break network;
+        }
+        run = TrainingRun::new(
+            stages[stage_idx].clone(),
+            Some(&network.to_bytes()?),
+            layout.as_ref(),
+        )?;
+        if let Some(path) = &checkpoint_path {
+            write_checkpoint(path, &run, games_before + stages[stage_idx - 1].games)?;
+        }
+    };
+
+    if stages.len() > 1 {
+        let metadata = network.metadata_mut();
+        metadata.insert(KEY_GAMES, total_games);
+        metadata.insert(KEY_SEED, config.seed);
+        match &initial_model {
+            Some(bytes) => metadata.insert(
+                KEY_PARENT_HASH,
+                format!(
+                    "{:08x}",
+                    crc32fast::hash(decompress_model_bytes(bytes)?.as_ref())
+                ),
+            ),
+            None => {
+                metadata.remove(KEY_PARENT_HASH);
+            }
+        }
+    }
+    network.metadata_mut().stamp_created_at();
+    write_atomic(&config.output, &network.to_bytes()?)?;
+    println!(
+        "Wrote {} ({total_games} games in {:.1}s)",
+        config.output.display(),
+        started.elapsed().as_secs_f64()
+    );
+    if let Some(path) = &checkpoint_path
+        && path.exists()
+    {
+        fs::remove_file(path)
+            .map_err(|err| format!("failed to remove {}: {err}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Runs the generations of `gating` and writes the final incumbent, which
+/// is the initial model (or a fresh network) if no candidate was promoted.
+fn train_gated(config: &Config, gating: &GatingConfig) -> Result<(), String> {
+    let training = config.stage_configs().remove(0);
+    let layout = load_layout(config)?;
+    let initial_model = match &config.initial_model {
+        Some(path) => Some(read_file(path)?),
+        None => None,
+    };
+    let mut log = match &config.metrics_log {
+        Some(path) => Some(TrainingLog::open(path)?),
+        None => None,
+    };
+    let mut gate_log = match &config.gate_log {
+        Some(path) => Some(GatingLog::open(path)?),
+        None => None,
+    };
+    println!(
+        "Gating: {} generations of {} games, {}",
+        gating.generations,
+        training.games,
+        gating.name()
+    );
+
+    let started = Instant::now();
+    let mut on_progress = |progress: &TrainingProgress| -> Result<(), String> {
+        print_progress(progress);
+        match log.as_mut() {
+            Some(log) => log.write(progress),
+            None => Ok(()),
+        }
+    };
+    let mut promoted = 0usize;
+    let mut on_generation = |report: &GenerationReport| -> Result<(), String> {
+        print_generation(report, gating.generations);
+        promoted += usize::from(report.result.promoted);
+        match gate_log.as_mut() {
+            Some(log) => log.write(report),
+            None => Ok(()),
+        }
+    };
+    let mut network = run_gating(
+        &training,
+        gating,
+        initial_model.as_deref(),
+        layout.as_ref(),
+        config.progress_interval,
+        Some(&mut on_progress),
+        Some(&mut on_generation),
+    )?;
+    network.metadata_mut().stamp_created_at();
+    write_atomic(&config.output, &network.to_bytes()?)?;
+    println!(
+        "Wrote {} ({promoted}/{} candidates promoted in {:.1}s)",
+        config.output.display(),
+        gating.generations,
+        started.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+fn print_generation(report: &GenerationReport, generations: usize) {
+    let result = &report.result;
+    let llr = result
+        .llr
+        .map_or_else(String::new, |llr| format!(" llr={llr:.2}"));
+    println!(
+        "Generation {}/{generations}: {}W-{}L-{}D score={:.3}{llr} {}",
+        report.generation,
+        result.wins,
+        result.losses,
+        result.draws,
+        result.score(),
+        if result.promoted {
+            "promoted"
+        } else {
+            "rejected"
+        }
+    );
+}
+
+fn load_layout(config: &Config) -> Result<Option<NetworkLayout>, String> {
+    if config.patterns.is_none() && config.scalar_features.is_empty() {
+        return Ok(None);
+    }
+    let mut layout = NetworkLayout::default();
+    if let Some(path) = &config.patterns {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        layout.tuple_patterns = Arc::new(TuplePatternSet::parse(&text)?);
+    }
+    layout.scalar_features = config.scalar_features.clone();
+    Ok(Some(layout))
+}
+
+fn print_progress(progress: &TrainingProgress) {
+    let metrics = &progress.metrics;
+    let games_per_second = if progress.elapsed_seconds > 0.0 {
+        progress.completed as f64 / progress.elapsed_seconds
+    } else {
+        0.0
+    };
+    println!(
+        "[{}/{}] {:.1}% elapsed={:.1}s games/s={:.1} td={:.4} clipped={:.4} black_wins={:.3} norm={:.2}",
+        progress.completed,
+        progress.total,
+        progress.completed as f64 * 100.0 / progress.total.max(1) as f64,
+        progress.elapsed_seconds,
+        games_per_second,
+        metrics.mean_abs_td_error,
+        metrics.clipped_update_fraction,
+        metrics.black_win_rate,
+        metrics.weight_norm
+    );
+}
+
+fn write_checkpoint(path: &Path, run: &TrainingRun, games_before: usize) -> Result<(), String> {
+    write_atomic(path, &run.to_checkpoint_bytes()?)?;
+    println!(
+        "Checkpoint at {} games: {}",
+        games_before + run.completed_games(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Writes through a temporary file so an interrupted write never leaves a
+/// truncated model or checkpoint behind.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = PathBuf::from(temp_name);
+    fs::write(&temp_path, bytes)
+        .map_err(|err| format!("failed to write {}: {err}", temp_path.display()))?;
+    fs::rename(&temp_path, path)
+        .map_err(|err| format!("failed to replace {}: {err}", path.display()))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|err| format!("failed to read {}: {err}", path.display()))
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    // The config file is read first so flags override it wherever they appear.
+    let mut config = match args.iter().position(|arg| arg == "--config") {
+        Some(idx) => {
+            let path = PathBuf::from(parse_value::<String>(&args, idx + 1, "--config")?);
+            let text = fs::read_to_string(&path)
+                .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+            parse_config_file(&text).map_err(|err| format!("{}: {err}", path.display()))?
+        }
+        None => Config::default(),
+    };
+
+    let mut flag_stages = Vec::new();
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--config" => idx += 1,
+            "--games" => {
+                idx += 1;
+                config.games = parse_value(&args, idx, "--games")?;
+            }
+            "--alpha" => {
+                idx += 1;
+                config.alpha = parse_value(&args, idx, "--alpha")?;
+            }
+            "--alpha-decay" => {
+                idx += 1;
+                config.alpha_decay = AlphaDecayStrategy::from_name(&parse_value::<String>(
+                    &args,
+                    idx,
+                    "--alpha-decay",
+                )?)?;
+            }
+            "--alpha-decay-start-game" => {
+                idx += 1;
+                config.alpha_decay_start_game =
+                    parse_value(&args, idx, "--alpha-decay-start-game")?;
+            }
+            "--lambda" => {
+                idx += 1;
+                config.lambda_ = parse_value(&args, idx, "--lambda")?;
+            }
+            "--epsilon" => {
+                idx += 1;
+                config.epsilon = parse_value(&args, idx, "--epsilon")?;
+            }
+            "--seed" => {
+                idx += 1;
+                config.seed = parse_value(&args, idx, "--seed")?;
+            }
+            "--threads" => {
+                idx += 1;
+                config.threads = parse_value(&args, idx, "--threads")?;
+            }
+            "--random-opening-plies" => {
+                idx += 1;
+                config.random_opening_plies = parse_value(&args, idx, "--random-opening-plies")?;
+            }
+            "--parallel-mode" => {
+                idx += 1;
+                config.parallel_mode =
+                    ParallelMode::parse(&parse_value::<String>(&args, idx, "--parallel-mode")?)?;
+            }
+            "--search-depth" => {
+                idx += 1;
+                config.search_depth = parse_value(&args, idx, "--search-depth")?;
+            }
+            "--exact-td-empties" => {
+                idx += 1;
+                config.exact_td_empties = parse_value(&args, idx, "--exact-td-empties")?;
+            }
+            "--epsilon-schedule" => {
+                idx += 1;
+                config.epsilon_schedule = EpsilonSchedule::parse(&parse_value::<String>(
+                    &args,
+                    idx,
+                    "--epsilon-schedule",
+                )?)?;
+            }
+            "--exploration" => {
+                idx += 1;
+                config.exploration =
+                    ExplorationPolicy::parse(&parse_value::<String>(&args, idx, "--exploration")?)?;
+            }
+            "--league" => {
+                idx += 1;
+                config.league = Some(LeagueConfig::parse(&parse_value::<String>(
+                    &args, idx, "--league",
+                )?)?);
+            }
+            "--gate" => {
+                idx += 1;
+                config.gate = Some(GatingConfig::parse(&parse_value::<String>(
+                    &args, idx, "--gate",
+                )?)?);
+            }
+            "--gate-log" => {
+                idx += 1;
+                config.gate_log = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--gate-log",
+                )?));
+            }
+            "--stage" => {
+                idx += 1;
+                flag_stages.push(parse_stage_spec(&parse_value::<String>(
+                    &args, idx, "--stage",
+                )?)?);
+            }
+            "--initial-model" => {
+                idx += 1;
+                config.initial_model = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--initial-model",
+                )?));
+            }
+            "--patterns" => {
+                idx += 1;
+                config.patterns = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--patterns",
+                )?));
+            }
+            "--scalar-features" => {
+                idx += 1;
+                config.scalar_features = parse_value::<String>(&args, idx, "--scalar-features")?
+                    .split(',')
+                    .map(|name| ScalarFeature::from_name(name.trim()))
+                    .collect::<Result<Vec<_>, _>>()?;
+            }
+            "--output" => {
+                idx += 1;
+                config.output = PathBuf::from(parse_value::<String>(&args, idx, "--output")?);
+            }
+            "--progress-interval" => {
+                idx += 1;
+                config.progress_interval = parse_value(&args, idx, "--progress-interval")?;
+            }
+            "--checkpoint-interval" => {
+                idx += 1;
+                config.checkpoint_interval = parse_value(&args, idx, "--checkpoint-interval")?;
+            }
+            "--checkpoint" => {
+                idx += 1;
+                config.checkpoint = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--checkpoint",
+                )?));
+            }
+            "--resume" => config.resume = true,
+            "--metrics-log" => {
+                idx += 1;
+                config.metrics_log = Some(PathBuf::from(parse_value::<String>(
+                    &args,
+                    idx,
+                    "--metrics-log",
+                )?));
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+        idx += 1;
+    }
+    if !flag_stages.is_empty() {
+        config.stages = flag_stages;
+    }
+
+    validate(&config)?;
+    Ok(config)
+}
+
+fn validate(config: &Config) -> Result<(), String> {
+    if !config.alpha.is_finite() || config.alpha <= 0.0 {
+        return Err("alpha must be greater than 0".to_string());
+    }
+    if !(0.0..=1.0).contains(&config.lambda_) {
+        return Err("lambda must be between 0 and 1".to_string());
+    }
+    if !(0.0..=1.0).contains(&config.epsilon) {
+        return Err("epsilon must be between 0 and 1".to_string());
+    }
+    if config.gate.is_some() {
+        if !config.stages.is_empty() {
+            return Err("--gate cannot be combined with stages".to_string());
+        }
+        if config.checkpoint_interval > 0 || config.resume || config.checkpoint.is_some() {
+            return Err("--gate cannot be combined with checkpoints".to_string());
+        }
+    } else if config.gate_log.is_some() {
+        return Err("--gate-log needs --gate".to_string());
+    }
+    for (stage_idx, stage) in config.stages.iter().enumerate() {
+        if stage.games == 0 {
+            return Err(format!(
+                "stage #{} must play at least one game",
+                stage_idx + 1
+            ));
+        }
+        if stage
+            .alpha
+            .is_some_and(|alpha| !alpha.is_finite() || alpha <= 0.0)
+        {
+            return Err(format!(
+                "stage #{} alpha must be greater than 0",
+                stage_idx + 1
+            ));
+        }
+        if stage
+            .lambda_
+            .is_some_and(|lambda_| !(0.0..=1.0).contains(&lambda_))
+        {
+            return Err(format!(
+                "stage #{} lambda must be between 0 and 1",
+                stage_idx + 1
+            ));
+        }
+        if stage
+            .epsilon
+            .is_some_and(|epsilon| !(0.0..=1.0).contains(&epsilon))
+        {
+            return Err(format!(
+                "stage #{} epsilon must be between 0 and 1",
+                stage_idx + 1
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Parses `games=N[,alpha=F][,epsilon=F][,lambda=F]`.
+fn parse_stage_spec(spec: &str) -> Result<Stage, String> {
+    let mut stage = Stage {
+        games: 0,
+        alpha: None,
+        epsilon: None,
+        lambda_: None,
+    };
+    let mut has_games = false;
+    for entry in spec.split(',') {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| format!("invalid stage entry '{entry}' (expected key=value)"))?;
+        let (key, value) = (key.trim(), value.trim());
+        let flag = format!("--stage {key}");
+        match key {
+            "games" => {
+                stage.games = parse_flag_value(value, &flag)?;
+                has_games = true;
+            }
+            "alpha" => stage.alpha = Some(parse_flag_value(value, &flag)?),
+            "epsilon" => stage.epsilon = Some(parse_flag_value(value, &flag)?),
+            "lambda" => stage.lambda_ = Some(parse_flag_value(value, &flag)?),
+            other => return Err(format!("unknown stage key '{other}'")),
+        }
+    }
+    if !has_games {
+        return Err("stage must set games".to_string());
+    }
+    Ok(stage)
+}
+
+fn parse_config_file(text: &str) -> Result<Config, String> {
+    toml::from_str(text).map_err(|err| err.to_string())
+}
+
+fn from_spec<'de, D, T>(
+    deserializer: D,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    parse(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+fn alpha_decay_from_name<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<AlphaDecayStrategy, D::Error> {
+    from_spec(deserializer, AlphaDecayStrategy::from_name)
+}
+
+fn parallel_mode_from_spec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ParallelMode, D::Error> {
+    from_spec(deserializer, ParallelMode::parse)
+}
+
+fn epsilon_schedule_from_spec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<EpsilonSchedule, D::Error> {
+    from_spec(deserializer, EpsilonSchedule::parse)
+}
+
+fn exploration_from_spec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ExplorationPolicy, D::Error> {
+    from_spec(deserializer, ExplorationPolicy::parse)
+}
+
+fn league_from_spec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<LeagueConfig>, D::Error> {
+    from_spec(deserializer, LeagueConfig::parse).map(Some)
+}
+
+fn gate_from_spec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<GatingConfig>, D::Error> {
+    from_spec(deserializer, GatingConfig::parse).map(Some)
+}
+
+fn scalar_features_from_names<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<ScalarFeature>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|name| ScalarFeature::from_name(name))
+        .collect::<Result<_, _>>()
+        .map_err(D::Error::custom)
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --release --manifest-path rust/Cargo.toml --bin train -- [options]\n\
+         \n\
+         Trains an N-tuple network with TD(lambda) self-play and writes the model,\n\
+         without the Python toolchain. Flags override values from --config.\n\
+         \n\
+         Options:\n\
+           --config <PATH>           TOML config file (see below)\n\
+           --games <N>               Self-play games without stages (default: 100000)\n\
+           --alpha <F>               Learning rate (default: 0.001)\n\
+           --alpha-decay <NAME>      none, inverse_game, inverse_visit or tc (default: none)\n\
+           --alpha-decay-start-game <N> Games already played for alpha decay (default: 0)\n\
+           --lambda <F>              Eligibility trace decay (default: 0.7)\n\
+           --epsilon <F>             Exploration rate (default: 0.1)\n\
+           --seed <N>                Random seed (default: 42)\n\
+           --threads <N>             Worker threads, 0 = all CPUs (default: 0)\n\
+           --random-opening-plies <N> Random plies before each game (default: 0)\n\
+           --parallel-mode <MODE>    independent, sync:<games> or hogwild (default: independent)\n\
+           --search-depth <N>        Plies searched per self-play move (default: 2)\n\
+           --exact-td-empties <N>    Train on exact scores at or below N empties (default: 0)\n\
+           --epsilon-schedule <S>    constant, linear:<end>:<games>, exponential:<decay>[:<floor>]\n\
+                                     or phase:<endgame> (default: constant)\n\
+           --exploration <P>         uniform or boltzmann:<temperature> (default: uniform)\n\
+           --league <SPEC>           Play an opponent pool instead of pure self-play:\n\
+                                     snapshot=<games>,keep=<N>,positional=<depth>,\n\
+                                     random,self=<share> (keep defaults to 8)\n\
+           --gate <SPEC>             Train generations of --games games from the incumbent and\n\
+                                     keep a candidate only if it wins a fixed-seed match:\n\
+                                     generations=<N>,games=<N>,depth=<plies>,openings=<plies>,\n\
+                                     seed=<N>,winrate=<score> or sprt=<elo0>:<elo1>[:<a>:<b>]\n\
+                                     (default: 1 generation, 200 games, depth 2, 6 plies,\n\
+                                     winrate=0.55); not with stages or checkpoints\n\
+           --gate-log <PATH>         Append each generation's match to a .csv or .jsonl file\n\
+           --stage <SPEC>            Add a stage: games=N[,alpha=F][,epsilon=F][,lambda=F];\n\
+                                     repeat for a schedule, replacing stages from --config\n\
+           --initial-model <PATH>    Continue training from a model\n\
+           --patterns <PATH>         Tuple pattern file for a fresh network\n\
+           --scalar-features <A,B>   Scalar features for a fresh network\n\
+           --output <PATH>           Model to write (default: weights.bin)\n\
+           --progress-interval <N>   Print progress every N games, 0 = off (default: 10000)\n\
+           --checkpoint-interval <N> Write a checkpoint every N games, 0 = off (default: 0)\n\
+           --checkpoint <PATH>       Checkpoint file (default: output with .ntck extension)\n\
+           --resume                  Continue from the checkpoint file if it exists\n\
+           --metrics-log <PATH>      Append progress metrics to a .csv or .jsonl file\n\
+           --help                    Show this message\n\
+         \n\
+         The config file uses the flag names with underscores as top-level keys,\n\
+         e.g. `alpha_decay = \"inverse_visit\"`, plus one [[stage]] table per stage\n\
+         with games and optional alpha, epsilon and lambda. Stage N continues from\n\
+         the model of stage N-1; the checkpoint is removed once the model is written."
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEDULE: &str = r#"
+# Two-phase schedule
+alpha = 0.01
+lambda = 0.5
+threads = 2
+parallel_mode = "sync:500"
+search_depth = 3
+epsilon_schedule = "linear:0.02:15000"
+scalar_features = ["mobility", "parity"]
+output = "models/weights.bin"  # final model
+
+[[stage]]
+games = 10_000
+epsilon = 0.2
+
+[[ stage ]]
+games = 5000
+alpha = 0.002
+"#;
+
+    #[test]
+    fn config_file_stages_and_flags_combine() {
+        let config = parse_config_file(SCHEDULE).expect("schedule must parse");
+        assert_eq!(config.threads, 2);
+        assert_eq!(config.search_depth, 3);
+        assert_eq!(
+            config.epsilon_schedule,
+            EpsilonSchedule::Linear {
+                end: 0.02,
+                games: 15_000
+            }
+        );
+        assert_eq!(
+            config.parallel_mode,
+            ParallelMode::SyncRounds { round_games: 500 }
+        );
+        assert_eq!(
+            config.scalar_features,
+            vec![ScalarFeature::Mobility, ScalarFeature::Parity]
+        );
+        assert_eq!(config.output, PathBuf::from("models/weights.bin"));
+        assert_eq!(config.checkpoint_path(), None);
+
+        let stages = config.stage_configs();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(
+            (stages[0].games, stages[0].alpha, stages[0].epsilon),
+            (10_000, 0.01, 0.2)
+        );
+        assert_eq!(
+            (stages[1].games, stages[1].alpha, stages[1].epsilon),
+            (5_000, 0.002, 0.1)
+        );
+        assert_eq!(stages[1].lambda_, 0.5);
+        assert_eq!(stages[1].seed, 42 + 10_000);
+        assert_eq!(stages[1].alpha_decay_start_game, 10_000);
+
+        let config = parse_args(vec![
+            "--stage".to_string(),
+            "games=3,alpha=0.05".to_string(),
+            "--checkpoint-interval".to_string(),
+            "100".to_string(),
+        ])
+        .expect("flags must parse");
+        assert_eq!(
+            config.stages,
+            vec![Stage {
+                games: 3,
+                alpha: Some(0.05),
+                epsilon: None,
+                lambda_: None,
+            }]
+        );
+        assert_eq!(
+            config.checkpoint_path(),
+            Some(PathBuf::from("weights.ntck"))
+        );
+    }
+
+    #[test]
+    fn single_stage_matches_plain_training_settings() {
+        let config = parse_args(vec!["--games".to_string(), "7".to_string()]).unwrap();
+        let stages = config.stage_configs();
+        assert_eq!(stages.len(), 1);
+        assert_eq!(stages[0].games, 7);
+        assert_eq!(stages[0].seed, 42);
+        assert_eq!(stages[0].alpha_decay_start_game, 0);
+        assert_eq!(stages[0].league, None);
+
+        let config =
+            parse_args(vec!["--league".to_string(), "random,self=0.5".to_string()]).unwrap();
+        assert_eq!(
+            config.stage_configs()[0].league.map(|league| league.name()),
+            Some("snapshot=0,keep=8,positional=0,random,self=0.5".to_string())
+        );
+        assert!(parse_args(vec!["--league".to_string(), "self=0.5".to_string()]).is_err());
+    }
+
+    #[test]
+    fn staged_run_writes_model_and_resumes_from_later_stage() {
+        let dir = env::temp_dir().join(format!("reversi-train-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let patterns = dir.join("patterns.txt");
+        fs::write(&patterns, "0,1,2,3,4\n0,9,18,27\n").unwrap();
+        let output = dir.join("weights.bin");
+        let mut config = parse_args(vec![
+            "--stage".to_string(),
+            "games=2,epsilon=0.2".to_string(),
+            "--stage".to_string(),
+            "games=2,alpha=0.002".to_string(),
+            "--threads".to_string(),
+            "1".to_string(),
+            "--progress-interval".to_string(),
+            "0".to_string(),
+            "--checkpoint-interval".to_string(),
+            "1".to_string(),
+            "--patterns".to_string(),
+            patterns.display().to_string(),
+            "--output".to_string(),
+            output.display().to_string(),
+        ])
+        .unwrap();
+        let checkpoint = config.checkpoint_path().unwrap();
+
+        train(&config).unwrap();
+        let model =
+            reversi::training::TrainableNTuple::from_bytes(&read_file(&output).unwrap()).unwrap();
+        assert_eq!(model.metadata().get(KEY_GAMES), Some("4"));
+        assert_eq!(model.metadata().get(KEY_PARENT_HASH), None);
+        assert!(!checkpoint.exists());
+
+        let stages = config.stage_configs();
+        let layout = load_layout(&config).unwrap();
+        let first_stage = model.to_bytes().unwrap();
+        let run = TrainingRun::new(stages[1].clone(), Some(&first_stage), layout.as_ref()).unwrap();
+        write_atomic(&checkpoint, &run.to_checkpoint_bytes().unwrap()).unwrap();
+        fs::remove_file(&output).unwrap();
+        config.resume = true;
+        train(&config).unwrap();
+        assert!(output.exists());
+        assert!(!checkpoint.exists());
+
+        let other = TrainingRun::new(
+            TrainingConfig {
+                games: 3,
+                ..stages[1].clone()
+            },
+            Some(&first_stage),
+            layout.as_ref(),
+        )
+        .unwrap();
+        write_atomic(&checkpoint, &other.to_checkpoint_bytes().unwrap()).unwrap();
+        let err = train(&config).unwrap_err();
+        assert!(err.contains("does not match any stage"), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn gated_run_writes_the_incumbent_and_logs_generations() {
+        let dir = env::temp_dir().join(format!("reversi-train-gate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let patterns = dir.join("patterns.txt");
+        fs::write(&patterns, "0,1,2,3,4\n0,9,18,27\n").unwrap();
+        let output = dir.join("weights.bin");
+        let gate_log = dir.join("gate.csv");
+        let args = |extra: &[&str]| {
+            [
+                "--games",
+                "2",
+                "--threads",
+                "1",
+                "--progress-interval",
+                "0",
+                "--patterns",
+                &patterns.display().to_string(),
+                "--output",
+                &output.display().to_string(),
+            ]
+            .iter()
+            .chain(extra)
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+        };
+        let config = parse_args(args(&[
+            "--gate",
+            "generations=2,games=2,depth=1,winrate=0",
+            "--gate-log",
+            &gate_log.display().to_string(),
+        ]))
+        .unwrap();
+
+        train(&config).unwrap();
+        let model =
+            reversi::training::TrainableNTuple::from_bytes(&read_file(&output).unwrap()).unwrap();
+        assert!(
+            model
+                .metadata()
+                .get(reversi::ai::metadata::KEY_GATE)
+                .is_some()
+        );
+        assert_eq!(fs::read_to_string(&gate_log).unwrap().lines().count(), 3);
+
+        for extra in [
+            &["--gate", "winrate=0.5", "--stage", "games=2"][..],
+            &["--gate", "winrate=0.5", "--checkpoint-interval", "1"],
+            &["--gate-log", "gate.csv"],
+        ] {
+            assert!(parse_args(args(extra)).is_err(), "{extra:?}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn config_file_rejects_unknown_and_mistyped_keys() {
+        for (text, expected) in [
+            ("[training]\ngames = 1", "unknown field `training`"),
+            ("games = 1\ngames = 2", "duplicate key"),
+            ("alpha = \"fast\"", "invalid type: string"),
+            ("[[stage]]\nalpha = 0.1", "missing field `games`"),
+            ("gamez = 1", "unknown field `gamez`"),
+            ("search_depth = 300", "invalid value"),
+            ("parallel_mode = \"fast\"", "parallel"),
+        ] {
+            let err = parse_config_file(text).unwrap_err();
+            assert!(err.contains(expected), "{text}: {err}");
+        }
+        let config = parse_config_file("output = \"a#b\" # c\nscalar_features = []").unwrap();
+        assert_eq!(config.output, PathBuf::from("a#b"));
+        assert!(config.scalar_features.is_empty());
+        assert!(parse_stage_spec("alpha=0.1").is_err());
+        assert_eq!(
+            parse_stage_spec("games=1,seed=2"),
+            Err("unknown stage key 'seed'".to_string())
+        );
+        assert_eq!(
+            parse_stage_spec("games=1, alpha=fast"),
+            Err("invalid value for --stage alpha".to_string())
+        );
+        assert_eq!(
+            parse_stage_spec(" games = 4 ,lambda=0.5").map(|stage| (stage.games, stage.lambda_)),
+            Ok((4, Some(0.5)))
+        );
+    }
+}
@@ -0,0 +1,596 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use reversi::ai::arena::{self, MatchConfig};
+use reversi::ai::level::LevelConfig;
+use reversi::ai::ntuple::NTupleEvaluator;
+use reversi::cli::parse_value;
+use reversi::training::{
+    MAX_TUPLE_COUNT, MAX_TUPLE_LEN, TDLambdaTrainer, TrainableNTuple, TuplePatternSet,
+    canonical_tuple_shape,
+};
+
+const SHAPE_ATTEMPTS_PER_CANDIDATE: usize = 200;
+
+#[derive(Clone, Debug)]
+struct Config {
+    base_path: Option<PathBuf>,
+    output_path: Option<PathBuf>,
+    candidates: usize,
+    min_size: usize,
+    max_size: usize,
+    mode: SearchMode,
+    swap_indices: Option<Vec<usize>>,
+    train_games: usize,
+    alpha: f32,
+    lambda_: f32,
+    epsilon: f64,
+    random_opening_plies: usize,
+    games: usize,
+    level: u8,
+    timeout_ms: u64,
+    seed: u64,
+    threads: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SearchMode {
+    Add,
+    Swap,
+    Both,
+}
+
+#[derive(Clone, Debug)]
+struct Variant {
+    label: String,
+    patterns: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Debug)]
+struct VariantResult {
+    variant: Variant,
+    wins: usize,
+    losses: usize,
+    draws: usize,
+    mean_diff: f64,
+}
+
+impl VariantResult {
+    fn score(&self) -> f64 {
+        let games = self.wins + self.losses + self.draws;
+        if games == 0 {
+            0.0
+        } else {
+            (self.wins as f64 + self.draws as f64 * 0.5) / games as f64
+        }
+    }
+}
+
+fn main() -> Result<(), String> {
+    let config = parse_args(env::args().skip(1).collect())?;
+    let base = match &config.base_path {
+        Some(path) => TuplePatternSet::parse(
+            &fs::read_to_string(path)
+                .map_err(|err| format!("failed to read {}: {err}", path.display()))?,
+        )?,
+        None => TuplePatternSet::new(TuplePatternSet::default_patterns().patterns().to_vec())?,
+    };
+
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let candidates = generate_candidates(base.patterns(), &config, &mut rng);
+    let variants = build_variants(base.patterns(), &candidates, &config)?;
+    println!(
+        "Searching {} candidate shapes ({} variants): size={}..={}, train_games={}, games={}, level={}",
+        candidates.len(),
+        variants.len(),
+        config.min_size,
+        config.max_size,
+        config.train_games,
+        config.games,
+        config.level
+    );
+
+    let started = Instant::now();
+    let baseline = train_evaluator(base.patterns().to_vec(), &config)?;
+    println!(
+        "Trained base set of {} patterns in {:.1}s",
+        base.len(),
+        started.elapsed().as_secs_f64()
+    );
+
+    let mut results = evaluate_variants(&variants, &baseline, &config)?;
+    results.sort_by(|left, right| {
+        right
+            .score()
+            .total_cmp(&left.score())
+            .then_with(|| right.mean_diff.total_cmp(&left.mean_diff))
+    });
+
+    println!();
+    println!("rank  score    W/L/D        mean_diff  variant");
+    for (rank, result) in results.iter().enumerate() {
+        println!(
+            "{:>4}  {:>6.2}%  {:>3}/{:>3}/{:>3}  {:>+9.2}  {}",
+            rank + 1,
+            result.score() * 100.0,
+            result.wins,
+            result.losses,
+            result.draws,
+            result.mean_diff,
+            result.variant.label
+        );
+    }
+    println!("Total time: {:.1}s", started.elapsed().as_secs_f64());
+
+    if let Some(path) = &config.output_path {
+        let Some(best) = results.first() else {
+            return Err("no candidate variants were evaluated".to_string());
+        };
+        let best_set = TuplePatternSet::new(best.variant.patterns.clone())?;
+        let text = format!(
+            "# {} (score {:.2}% vs base set)\n{}",
+            best.variant.label,
+            best.score() * 100.0,
+            best_set.to_text()
+        );
+        fs::write(path, text)
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+        println!("Wrote best pattern set to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn parse_args(args: Vec<String>) -> Result<Config, String> {
+    let mut config = Config {
+        base_path: None,
+        output_path: None,
+        candidates: 16,
+        min_size: 4,
+        max_size: 8,
+        mode: SearchMode::Both,
+        swap_indices: None,
+        train_games: 2_000,
+        alpha: 0.01,
+        lambda_: 0.7,
+        epsilon: 0.1,
+        random_opening_plies: 4,
+        games: 40,
+        level: 2,
+        timeout_ms: 1_000,
+        seed: 42,
+        threads: 0,
+    };
+
+    let mut idx = 0usize;
+    while idx < args.len() {
+        match args[idx].as_str() {
+            "--base" => {
+                idx += 1;
+                config.base_path =
+                    Some(PathBuf::from(parse_value::<String>(&args, idx, "--base")?));
+            }
+            "--output" => {
+                idx += 1;
+                config.output_path = Some(PathBuf::from(parse_value::<String>(
+                    &args, idx, "--output",
+                )?));
+            }
+            "--candidates" => {
+                idx += 1;
+                config.candidates = parse_value(&args, idx, "--candidates")?;
+            }
+            "--min-size" => {
+                idx += 1;
+                config.min_size = parse_value(&args, idx, "--min-size")?;
+            }
+            "--max-size" => {
+                idx += 1;
+                config.max_size = parse_value(&args, idx, "--max-size")?;
+            }
+            "--mode" => {
+                idx += 1;
+                config.mode = match parse_value::<String>(&args, idx, "--mode")?.as_str() {
+                    "add" => SearchMode::Add,
+                    "swap" => SearchMode::Swap,
+                    "both" => SearchMode::Both,
+                    other => {
+                        return Err(format!(
+                            "unsupported mode '{other}' (expected add, swap or both)"
+                        ));
+                    }
+                };
+            }
+            "--swap-indices" => {
+                idx += 1;
+                let raw = parse_value::<String>(&args, idx, "--swap-indices")?;
+                let indices = raw
+                    .split(',')
+                    .map(|value| {
+                        value
+                            .trim()
+                            .parse::<usize>()
+                            .map_err(|_| "invalid value for --swap-indices".to_string())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                config.swap_indices = Some(indices);
+            }
+            "--train-games" => {
+                idx += 1;
+                config.train_games = parse_value(&args, idx, "--train-games")?;
+            }
+            "--alpha" => {
+                idx += 1;
+                config.alpha = parse_value(&args, idx, "--alpha")?;
+            }
+            "--lambda" => {
+                idx += 1;
+                config.lambda_ = parse_value(&args, idx, "--lambda")?;
+            }
+            "--epsilon" => {
+                idx += 1;
+                config.epsilon = parse_value(&args, idx, "--epsilon")?;
+            }
+            "--random-opening-plies" => {
+                idx += 1;
+                config.random_opening_plies = parse_value(&args, idx, "--random-opening-plies")?;
+            }
+            "--games" => {
+                idx += 1;
+                config.games = parse_value(&args, idx, "--games")?;
+            }
+            "--level" => {
+                idx += 1;
+                config.level = parse_value(&args, idx, "--level")?;
+            }
+            "--timeout-ms" => {
+                idx += 1;
+                config.timeout_ms = parse_value(&args, idx, "--timeout-ms")?;
+            }
+            "--seed" => {
+                idx += 1;
+                config.seed = parse_value(&args, idx, "--seed")?;
+            }
+            "--threads" => {
+                idx += 1;
+                config.threads = parse_value(&args, idx, "--threads")?;
+            }
+            "--help" | "-h" => {
+                print_usage();
+                std::process::exit(0);
+            }
+            other => return Err(format!("unknown argument: {other}")),
+        }
+        idx += 1;
+    }
+
+    LevelConfig::try_for_level(config.level)?;
+    if config.min_size == 0 || config.min_size > config.max_size {
+        return Err("min-size must be between 1 and max-size".to_string());
+    }
+    if config.max_size > MAX_TUPLE_LEN {
+        return Err(format!("max-size must not exceed {MAX_TUPLE_LEN}"));
+    }
+    if config.timeout_ms == 0 {
+        return Err("timeout-ms must be greater than 0".to_string());
+    }
+    if config.games == 0 {
+        return Err("games must be greater than 0".to_string());
+    }
+
+    Ok(config)
+}
+
+fn print_usage() {
+    println!(
+        "Usage: cargo run --release --manifest-path rust/Cargo.toml --bin tuple_search -- [options]\n\
+         \n\
+         Proposes connected N-tuple shapes, trains a short TD(lambda) run with each one\n\
+         added to or swapped into the base set, and ranks the runs by match results\n\
+         against the base set trained with the same budget.\n\
+         \n\
+         Options:\n\
+           --base <PATH>             Base pattern file (default: built-in TUPLE_PATTERNS)\n\
+           --output <PATH>           Write the best pattern set to PATH\n\
+           --candidates <N>          Distinct candidate shapes to try (default: 16)\n\
+           --min-size <N>            Smallest candidate size (default: 4)\n\
+           --max-size <N>            Largest candidate size, at most 10 (default: 8)\n\
+           --mode <add|swap|both>    How candidates enter the base set (default: both)\n\
+           --swap-indices <I,J,...>  Base patterns a candidate may replace (default: all)\n\
+           --train-games <N>         Self-play games per training run (default: 2000)\n\
+           --alpha <F>               Learning rate (default: 0.01)\n\
+           --lambda <F>              Eligibility trace decay (default: 0.7)\n\
+           --epsilon <F>             Exploration rate (default: 0.1)\n\
+           --random-opening-plies <N> Random plies before each game (default: 4)\n\
+           --games <N>               Benchmark games per variant, colors alternate (default: 40)\n\
+           --level <1-10>            Search level for benchmark games (default: 2)\n\
+           --timeout-ms <N>          Per-move search timeout in milliseconds (default: 1000)\n\
+           --seed <N>                Seed for candidates, training and openings (default: 42)\n\
+           --threads <N>             Variants evaluated in parallel, 0 = all CPUs (default: 0)\n\
+           --help                    Show this message\n\
+         \n\
+         Pattern files hold one pattern per line, squares 0-63 separated by commas."
+    );
+}
+
+/// Samples distinct connected shapes (8-neighbourhood, so diagonal lines
+/// count) that are not already in `base` up to symmetry.
+fn generate_candidates(base: &[Vec<u8>], config: &Config, rng: &mut ChaCha8Rng) -> Vec<Vec<u8>> {
+    let mut seen: HashSet<Vec<u8>> = base
+        .iter()
+        .map(|pattern| canonical_tuple_shape(pattern))
+        .collect();
+    let mut candidates = Vec::with_capacity(config.candidates);
+    let attempts = config
+        .candidates
+        .saturating_mul(SHAPE_ATTEMPTS_PER_CANDIDATE);
+    for _ in 0..attempts {
+        if candidates.len() >= config.candidates {
+            break;
+        }
+        let size = rng.gen_range(config.min_size..=config.max_size);
+        let shape = canonical_tuple_shape(&random_connected_shape(size, rng));
+        if seen.insert(shape.clone()) {
+            candidates.push(shape);
+        }
+    }
+    candidates
+}
+
+fn random_connected_shape(size: usize, rng: &mut ChaCha8Rng) -> Vec<u8> {
+    let mut cells = vec![rng.gen_range(0..64u8)];
+    let mut occupied = 1u64 << cells[0];
+    while cells.len() < size {
+        let frontier = cells.iter().fold(0u64, |mask, &pos| mask | neighbours(pos)) & !occupied;
+        let mut remaining = frontier;
+        for _ in 0..rng.gen_range(0..frontier.count_ones()) {
+            remaining &= remaining - 1;
+        }
+        let pos = remaining.trailing_zeros() as u8;
+        occupied |= 1u64 << pos;
+        cells.push(pos);
+    }
+    cells
+}
+
+fn neighbours(pos: u8) -> u64 {
+    let (row, col) = ((pos / 8) as i32, (pos % 8) as i32);
+    let mut mask = 0u64;
+    for dr in -1..=1 {
+        for dc in -1..=1 {
+            let (r, c) = (row + dr, col + dc);
+            if (dr, dc) != (0, 0) && (0..8).contains(&r) && (0..8).contains(&c) {
+                mask |= 1u64 << (r * 8 + c);
+            }
+        }
+    }
+    mask
+}
+
+fn build_variants(
+    base: &[Vec<u8>],
+    candidates: &[Vec<u8>],
+    config: &Config,
+) -> Result<Vec<Variant>, String> {
+    let swap_indices = config
+        .swap_indices
+        .clone()
+        .unwrap_or_else(|| (0..base.len()).collect());
+    if let Some(&idx) = swap_indices.iter().find(|&&idx| idx >= base.len()) {
+        return Err(format!(
+            "swap index {idx} is out of range for {} base patterns",
+            base.len()
+        ));
+    }
+
+    let mut variants = Vec::new();
+    for candidate in candidates {
+        let shape = format!("{candidate:?}");
+        if config.mode != SearchMode::Swap && base.len() < MAX_TUPLE_COUNT {
+            let mut patterns = base.to_vec();
+            patterns.push(candidate.clone());
+            variants.push(Variant {
+                label: format!("add {shape}"),
+                patterns,
+            });
+        }
+        if config.mode != SearchMode::Add {
+            for &idx in &swap_indices {
+                let mut patterns = base.to_vec();
+                patterns[idx] = candidate.clone();
+                variants.push(Variant {
+                    label: format!("swap #{idx} -> {shape}"),
+                    patterns,
+                });
+            }
+        }
+    }
+    Ok(variants)
+}
+
+fn train_evaluator(patterns: Vec<Vec<u8>>, config: &Config) -> Result<NTupleEvaluator, String> {
+    let network = TrainableNTuple::with_patterns(Arc::new(TuplePatternSet::new(patterns)?));
+    let mut trainer = TDLambdaTrainer::new(
+        network,
+        config.alpha,
+        config.lambda_,
+        config.epsilon,
+        config.seed,
+        config.random_opening_plies,
+    )?;
+    trainer.train(config.train_games, 0, None)?;
+    NTupleEvaluator::from_bytes(&trainer.into_network().to_uncompressed_bytes()?)
+}
+
+fn evaluate_variants(
+    variants: &[Variant],
+    baseline: &NTupleEvaluator,
+    config: &Config,
+) -> Result<Vec<VariantResult>, String> {
+    let threads = if config.threads == 0 {
+        std::thread::available_parallelism()
+            .map(usize::from)
+            .unwrap_or(1)
+    } else {
+        config.threads
+    }
+    .clamp(1, variants.len().max(1));
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+
+    let mut results = std::thread::scope(|scope| -> Result<Vec<VariantResult>, String> {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| -> Result<Vec<VariantResult>, String> {
+                    let mut results = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(variant) = variants.get(idx) else {
+                            return Ok(results);
+                        };
+                        let evaluator = train_evaluator(variant.patterns.clone(), config)?;
+                        let result = play_match(variant, &evaluator, baseline, config)?;
+                        let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                        println!(
+                            "[{done}/{}] {:.2}% {}",
+                            variants.len(),
+                            result.score() * 100.0,
+                            variant.label
+                        );
+                        results.push(result);
+                    }
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(variants.len());
+        for handle in handles {
+            results.extend(
+                handle
+                    .join()
+                    .map_err(|_| "search worker thread panicked".to_string())??,
+            );
+        }
+        Ok(results)
+    })?;
+    results.sort_by(|left, right| left.variant.label.cmp(&right.variant.label));
+    Ok(results)
+}
+
+/// Plays the variant against the base set; game `i` uses the same opening
+/// for every variant so results are comparable.
+fn play_match(
+    variant: &Variant,
+    candidate: &NTupleEvaluator,
+    baseline: &NTupleEvaluator,
+    config: &Config,
+) -> Result<VariantResult, String> {
+    let level = LevelConfig {
+        time_budget_ms: u32::try_from(config.timeout_ms).unwrap_or(u32::MAX),
+        ..LevelConfig::try_for_level(config.level)?
+    };
+    let record = arena::play_match(
+        candidate,
+        baseline,
+        &MatchConfig {
+            games: config.games,
+            opening_plies: config.random_opening_plies,
+            seed: config.seed,
+            level,
+        },
+        |_| true,
+    )?;
+    Ok(VariantResult {
+        variant: variant.clone(),
+        wins: record.wins,
+        losses: record.losses,
+        draws: record.draws,
+        mean_diff: record.mean_diff(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_connected(shape: &[u8]) -> bool {
+        let cells = shape.iter().fold(0u64, |mask, &pos| mask | (1u64 << pos));
+        let mut reached = 1u64 << shape[0];
+        loop {
+            let mut grown = reached;
+            let mut remaining = reached;
+            while remaining != 0 {
+                grown |= neighbours(remaining.trailing_zeros() as u8) & cells;
+                remaining &= remaining - 1;
+            }
+            if grown == reached {
+                return reached == cells;
+            }
+            reached = grown;
+        }
+    }
+
+    #[test]
+    fn candidates_are_connected_distinct_and_new() {
+        let config = parse_args(vec![
+            "--candidates".to_string(),
+            "24".to_string(),
+            "--min-size".to_string(),
+            "3".to_string(),
+            "--max-size".to_string(),
+            "6".to_string(),
+        ])
+        .expect("args should parse");
+        let base = vec![vec![0, 1, 2]];
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let candidates = generate_candidates(&base, &config, &mut rng);
+
+        assert_eq!(candidates.len(), 24);
+        let shapes: HashSet<_> = candidates.iter().cloned().collect();
+        assert_eq!(shapes.len(), candidates.len());
+        assert!(!shapes.contains(&canonical_tuple_shape(&base[0])));
+        for shape in &candidates {
+            assert!((3..=6).contains(&shape.len()), "{shape:?}");
+            assert_eq!(&canonical_tuple_shape(shape), shape);
+            assert!(is_connected(shape), "{shape:?}");
+        }
+    }
+
+    #[test]
+    fn variants_add_and_swap_candidates() {
+        let mut config = parse_args(vec!["--swap-indices".to_string(), "1".to_string()])
+            .expect("args should parse");
+        let base = vec![vec![0, 1], vec![8, 9]];
+        let candidate = vec![vec![27, 28, 35]];
+
+        let variants = build_variants(&base, &candidate, &config).unwrap();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(
+            variants[0].patterns,
+            vec![vec![0, 1], vec![8, 9], vec![27, 28, 35]]
+        );
+        assert_eq!(variants[1].patterns, vec![vec![0, 1], vec![27, 28, 35]]);
+
+        config.swap_indices = Some(vec![2]);
+        assert!(build_variants(&base, &candidate, &config).is_err());
+    }
+
+    #[test]
+    fn parse_args_rejects_invalid_sizes() {
+        let err = parse_args(vec!["--max-size".to_string(), "11".to_string()]).unwrap_err();
+        assert!(err.contains("max-size"));
+
+        let err = parse_args(vec!["--min-size".to_string(), "9".to_string()]).unwrap_err();
+        assert!(err.contains("min-size"));
+
+        let err = parse_args(vec!["--mode".to_string(), "grow".to_string()]).unwrap_err();
+        assert!(err.contains("unsupported mode"));
+    }
+}
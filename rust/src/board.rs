@@ -1,7 +1,19 @@
+use std::sync::LazyLock;
+
 const BOARD_SIZE: usize = 8;
 const NUM_SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
 const NOT_A_FILE: u64 = 0xfefefefefefefefe;
 const NOT_H_FILE: u64 = 0x7f7f7f7f7f7f7f7f;
+const EDGES: u64 = 0xff81_8181_8181_81ff;
+const A_FILE: u64 = 0x0101_0101_0101_0101;
+
+type Shift = fn(u64) -> u64;
+
+/// Stable discs of the side owning `p` on one 8-square edge, indexed by
+/// `p * 256 + o` with both sides packed as 8-bit lines.
+static EDGE_STABILITY: LazyLock<Vec<u8>> = LazyLock::new(build_edge_stability);
+/// Every board line as `[horizontal, vertical, a1-h8 diagonal, h1-a8 diagonal]`.
+static LINES: LazyLock<[Vec<u64>; 4]> = LazyLock::new(build_lines);
 
 /// Reversi board state represented by two bitboards.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,8 +32,9 @@ impl Board {
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn from_bitboards(black: u64, white: u64) -> Self {
+    /// Builds a board from black and white disc masks.
+    /// Caller contract: the masks must not overlap.
+    pub fn from_bitboards(black: u64, white: u64) -> Self {
         debug_assert_eq!(black & white, 0);
         Self { black, white }
     }
@@ -100,7 +113,33 @@ impl Board {
         board
     }
 
-    pub(crate) fn bitboards(&self) -> (u64, u64) {
+    /// Returns the mask of `is_black`'s discs that can never be flipped.
+    /// Edge discs are exact; interior discs are a conservative subset (full
+    /// lines or a stable neighbour along every line).
+    pub fn stable_discs(&self, is_black: bool) -> u64 {
+        let (me, opp) = if is_black {
+            (self.black, self.white)
+        } else {
+            (self.white, self.black)
+        };
+        let mut stable = edge_stable_discs(me, opp);
+        let full = full_lines(me | opp);
+        let interior = me & !EDGES;
+        loop {
+            let supported = interior
+                & (full[0] | shift_east(stable) | shift_west(stable))
+                & (full[1] | shift_north(stable) | shift_south(stable))
+                & (full[2] | shift_north_west(stable) | shift_south_east(stable))
+                & (full[3] | shift_north_east(stable) | shift_south_west(stable));
+            if supported & !stable == 0 {
+                return stable;
+            }
+            stable |= supported;
+        }
+    }
+
+    /// Returns the `(black, white)` disc masks.
+    pub fn bitboards(&self) -> (u64, u64) {
         (self.black, self.white)
     }
 
@@ -135,6 +174,105 @@ fn bit(pos: usize) -> u64 {
     if pos < NUM_SQUARES { 1u64 << pos } else { 0 }
 }
 
+fn edge_stable_discs(me: u64, opp: u64) -> u64 {
+    let edge = |line: fn(u64) -> u8, unpack: fn(u8) -> u64| {
+        let index = line(me) as usize * 256 + line(opp) as usize;
+        unpack(EDGE_STABILITY[index])
+    };
+    edge(|bits| bits as u8, |line| line as u64)
+        | edge(|bits| (bits >> 56) as u8, |line| (line as u64) << 56)
+        | edge(pack_file, unpack_file)
+        | edge(|bits| pack_file(bits >> 7), |line| unpack_file(line) << 7)
+}
+
+fn pack_file(bits: u64) -> u8 {
+    ((bits & A_FILE).wrapping_mul(0x0102_0408_1020_4080) >> 56) as u8
+}
+
+fn unpack_file(line: u8) -> u64 {
+    (0..BOARD_SIZE)
+        .filter(|row| line & (1 << row) != 0)
+        .fold(0, |bits, row| bits | bit(row * BOARD_SIZE))
+}
+
+/// Solves every edge configuration bottom-up by empty count: a disc is stable
+/// if it survives every sequence of edge moves by either side. Moves are
+/// allowed on any empty square since the rest of the board can make them legal.
+fn build_edge_stability() -> Vec<u8> {
+    let mut table = vec![0u8; 256 * 256];
+    let mut states: Vec<(u8, u8)> = (0..=255u8)
+        .flat_map(|p| (0..=255u8).map(move |o| (p, o)))
+        .filter(|(p, o)| p & o == 0)
+        .collect();
+    states.sort_by_key(|(p, o)| std::cmp::Reverse((p | o).count_ones()));
+
+    for (p, o) in states {
+        let mut stable = p;
+        let empty = !(p | o);
+        for x in (0..BOARD_SIZE).filter(|x| empty & (1 << x) != 0) {
+            let (next_p, next_o) = play_edge_move(p, o, x);
+            stable &= table[next_p as usize * 256 + next_o as usize];
+            let (next_o, next_p) = play_edge_move(o, p, x);
+            stable &= table[next_p as usize * 256 + next_o as usize];
+        }
+        table[p as usize * 256 + o as usize] = stable;
+    }
+    table
+}
+
+fn play_edge_move(me: u8, opp: u8, x: usize) -> (u8, u8) {
+    let mut flips = 0u8;
+    for step in [-1i32, 1] {
+        let mut run = 0u8;
+        let mut pos = x as i32 + step;
+        while (0..BOARD_SIZE as i32).contains(&pos) && opp & (1 << pos) != 0 {
+            run |= 1 << pos;
+            pos += step;
+        }
+        if (0..BOARD_SIZE as i32).contains(&pos) && me & (1 << pos) != 0 {
+            flips |= run;
+        }
+    }
+    (me | flips | (1 << x), opp & !flips)
+}
+
+/// Returns the squares of completely filled lines in each direction of [`LINES`].
+fn full_lines(occupied: u64) -> [u64; 4] {
+    let mut full = [0u64; 4];
+    for (direction, lines) in LINES.iter().enumerate() {
+        for &line in lines {
+            if occupied & line == line {
+                full[direction] |= line;
+            }
+        }
+    }
+    full
+}
+
+fn build_lines() -> [Vec<u64>; 4] {
+    let directions: [(Shift, Shift); 4] = [
+        (shift_east, shift_west),
+        (shift_south, shift_north),
+        (shift_south_east, shift_north_west),
+        (shift_south_west, shift_north_east),
+    ];
+    directions.map(|(forward, backward)| {
+        (0..NUM_SQUARES)
+            .map(bit)
+            .filter(|&start| backward(start) == 0)
+            .map(|start| {
+                let mut line = 0u64;
+                let mut cursor = start;
+                while cursor != 0 {
+                    line |= cursor;
+                    cursor = forward(cursor);
+                }
+                line
+            })
+            .collect()
+    })
+}
+
 fn legal_moves_dir(me: u64, opp: u64, empty: u64, shift: fn(u64) -> u64) -> u64 {
     let mut ray = shift(me) & opp;
     for _ in 0..5 {
@@ -236,4 +374,83 @@ mod tests {
         assert_eq!(flips, 0);
         assert_eq!(board, before);
     }
+
+    #[test]
+    fn stable_discs_are_exact_on_edges() {
+        // a1-c1 black, e1-h1 white, d1 empty: both runs are corner-anchored.
+        let black = bit(idx(0, 0)) | bit(idx(0, 1)) | bit(idx(0, 2));
+        let white = bit(idx(0, 4)) | bit(idx(0, 5)) | bit(idx(0, 6)) | bit(idx(0, 7));
+        let board = Board::from_bitboards(black, white);
+        assert_eq!(board.stable_discs(true), black);
+        assert_eq!(board.stable_discs(false), white);
+
+        // a8 white anchors nothing for black's b8-g8 run, which h8 can flip.
+        let black = (1..7).fold(0, |bits, col| bits | bit(idx(7, col)));
+        let board = Board::from_bitboards(black, bit(idx(7, 0)));
+        assert_eq!(board.stable_discs(true), 0);
+        assert_eq!(board.stable_discs(false), bit(idx(7, 0)));
+
+        // A full mixed column is stable for both sides.
+        let black = (0..8)
+            .filter(|row| row % 3 == 0)
+            .fold(0, |bits, row| bits | bit(idx(row, 7)));
+        let white = (0..8).fold(0, |bits, row| bits | bit(idx(row, 7))) & !black;
+        let board = Board::from_bitboards(black, white);
+        assert_eq!(board.stable_discs(true), black);
+        assert_eq!(board.stable_discs(false), white);
+    }
+
+    #[test]
+    fn stable_discs_extend_into_interior_and_full_board() {
+        assert_eq!(Board::new().stable_discs(true), 0);
+        assert_eq!(Board::new().stable_discs(false), 0);
+
+        let board = Board::from_bitboards(0xaaaa_aaaa_aaaa_aaaa, 0x5555_5555_5555_5555);
+        assert_eq!(board.stable_discs(true), 0xaaaa_aaaa_aaaa_aaaa);
+
+        // A black a1 corner block: b2 is supported by stable neighbours on
+        // every line through it.
+        let black = bit(idx(0, 0))
+            | bit(idx(0, 1))
+            | bit(idx(0, 2))
+            | bit(idx(1, 0))
+            | bit(idx(1, 1))
+            | bit(idx(2, 0));
+        let board = Board::from_bitboards(black, bit(idx(3, 3)));
+        assert_eq!(board.stable_discs(true), black);
+    }
+
+    #[test]
+    fn stable_discs_are_never_flipped_in_random_games() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..200 {
+            let mut board = Board::new();
+            let mut is_black = true;
+            let mut stable_black = 0u64;
+            let mut stable_white = 0u64;
+            loop {
+                stable_black |= board.stable_discs(true);
+                stable_white |= board.stable_discs(false);
+                let (black, white) = board.bitboards();
+                assert_eq!(black & stable_black, stable_black);
+                assert_eq!(white & stable_white, stable_white);
+
+                let mut legal = board.legal_moves(is_black);
+                if legal == 0 {
+                    is_black = !is_black;
+                    legal = board.legal_moves(is_black);
+                    if legal == 0 {
+                        break;
+                    }
+                }
+                seed = seed
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                let choice = (seed >> 33) as u32 % legal.count_ones();
+                let pos = (0..choice).fold(legal, |bits, _| bits & (bits - 1));
+                assert_ne!(board.place(pos.trailing_zeros() as usize, is_black), 0);
+                is_black = !is_black;
+            }
+        }
+    }
 }
@@ -0,0 +1,42 @@
+/// Parses `args[idx]` as the value of `flag` for the command-line tools.
+pub fn parse_value<T: std::str::FromStr>(
+    args: &[String],
+    idx: usize,
+    flag: &str,
+) -> Result<T, String> {
+    parse_flag_value(
+        args.get(idx)
+            .ok_or_else(|| format!("missing value for {flag}"))?,
+        flag,
+    )
+}
+
+/// Parses `value` as the value of `flag`, e.g. one entry of a compound flag.
+pub fn parse_flag_value<T: std::str::FromStr>(value: &str, flag: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("invalid value for {flag}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_value_reports_missing_and_invalid_values() {
+        let args = vec![
+            "--games".to_string(),
+            "12".to_string(),
+            "--alpha".to_string(),
+        ];
+        assert_eq!(parse_value::<usize>(&args, 1, "--games"), Ok(12));
+        assert_eq!(
+            parse_value::<f32>(&args, 3, "--alpha"),
+            Err("missing value for --alpha".to_string())
+        );
+        assert_eq!(
+            parse_value::<u8>(&args, 2, "--level"),
+            Err("invalid value for --level".to_string())
+        );
+    }
+}
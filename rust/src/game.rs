@@ -1,5 +1,6 @@
+use crate::ai::level::LevelConfig;
 use crate::board::Board;
-use crate::types::{GameResult, GameState, Position};
+use crate::types::{GameResult, GameState, MoveAnalysis, Position, StableDiscs};
 
 const BOARD_WIDTH: usize = 8;
 const BOARD_LEN: usize = BOARD_WIDTH * BOARD_WIDTH;
@@ -7,14 +8,36 @@ pub const PLAYER_BLACK: u8 = 1;
 pub const PLAYER_WHITE: u8 = 2;
 
 pub trait MoveSelector: Send + Sync {
-    fn select_move(&self, board: &Board, is_black: bool, level: u8) -> Option<usize>;
+    fn select_move(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Option<usize>;
+
+    /// Uses up to `budget_ms` of the opponent's thinking time; `is_black` is
+    /// the side to move in `board`, i.e. the opponent.
+    /// Returns `true` while another call would make further progress.
+    fn ponder(
+        &self,
+        _board: &Board,
+        _is_black: bool,
+        _level: &LevelConfig,
+        _budget_ms: u32,
+    ) -> bool {
+        false
+    }
+
+    /// Discards any pondering work.
+    fn cancel_ponder(&self) {}
+
+    /// Scores every legal move of `is_black`; empty when the selector cannot
+    /// analyze positions.
+    fn analyze(&self, _board: &Board, _is_black: bool, _level: &LevelConfig) -> Vec<MoveAnalysis> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct FirstLegalMoveSelector;
 
 impl MoveSelector for FirstLegalMoveSelector {
-    fn select_move(&self, board: &Board, is_black: bool, _level: u8) -> Option<usize> {
+    fn select_move(&self, board: &Board, is_black: bool, _level: &LevelConfig) -> Option<usize> {
         let legal = board.legal_moves(is_black);
         if legal == 0 {
             None
@@ -28,7 +51,7 @@ pub struct GameInstance {
     board: Board,
     player_color: u8,
     pub current_player: u8,
-    pub level: u8,
+    pub level: LevelConfig,
     pub is_game_over: bool,
     pub is_pass: bool,
     pub flipped: Vec<u8>,
@@ -41,6 +64,15 @@ impl GameInstance {
         player_color: u8,
         evaluator: Box<dyn MoveSelector>,
     ) -> Result<Self, String> {
+        Self::with_level_config(LevelConfig::try_for_level(level)?, player_color, evaluator)
+    }
+
+    pub fn with_level_config(
+        level: LevelConfig,
+        player_color: u8,
+        evaluator: Box<dyn MoveSelector>,
+    ) -> Result<Self, String> {
+        level.validate()?;
         if !is_valid_player(player_color) {
             return Err("player color must be 1 (black) or 2 (white)".to_string());
         }
@@ -85,6 +117,25 @@ impl GameInstance {
 
     pub fn end_game(&mut self) {
         self.is_game_over = true;
+        self.evaluator.cancel_ponder();
+    }
+
+    /// Ponders for up to `budget_ms` while the player is to move.
+    /// Returns `true` while another call would make further progress.
+    pub fn ponder(&self, budget_ms: u32) -> bool {
+        if self.is_game_over || self.current_player != self.player_color {
+            return false;
+        }
+        self.evaluator.ponder(
+            &self.board,
+            self.player_color == PLAYER_BLACK,
+            &self.level,
+            budget_ms,
+        )
+    }
+
+    pub fn cancel_ponder(&self) {
+        self.evaluator.cancel_ponder();
     }
 
     pub fn do_ai_move(&mut self) -> Result<(), String> {
@@ -103,7 +154,7 @@ impl GameInstance {
 
         let selected = self
             .evaluator
-            .select_move(&self.board, ai_is_black, self.level)
+            .select_move(&self.board, ai_is_black, &self.level)
             .ok_or_else(|| "AI could not select a move".to_string())?;
 
         if selected >= BOARD_LEN {
@@ -118,13 +169,28 @@ impl GameInstance {
 
     pub fn get_legal_moves(&self) -> Vec<Position> {
         let legal = self.board.legal_moves(self.current_player == PLAYER_BLACK);
-        bitmask_to_indices(legal)
-            .into_iter()
-            .map(|idx| Position {
-                row: idx / BOARD_WIDTH as u8,
-                col: idx % BOARD_WIDTH as u8,
-            })
-            .collect()
+        bitmask_to_positions(legal)
+    }
+
+    /// Analysis of the current player's moves, best first.
+    pub fn analyze_moves(&self) -> Result<Vec<MoveAnalysis>, String> {
+        if self.is_game_over {
+            return Err("game is already over".to_string());
+        }
+        let is_black = self.current_player == PLAYER_BLACK;
+        if self.board.legal_moves(is_black) == 0 {
+            return Ok(Vec::new());
+        }
+        let mut analysis = self.evaluator.analyze(&self.board, is_black, &self.level);
+        analysis.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Ok(analysis)
+    }
+
+    pub fn get_stable_discs(&self) -> StableDiscs {
+        StableDiscs {
+            black: bitmask_to_positions(self.board.stable_discs(true)),
+            white: bitmask_to_positions(self.board.stable_discs(false)),
+        }
     }
 
     pub fn to_game_state(&self) -> GameState {
@@ -202,6 +268,16 @@ fn row_col_to_pos(row: u8, col: u8) -> Result<usize, String> {
     Ok((row as usize) * BOARD_WIDTH + col as usize)
 }
 
+fn bitmask_to_positions(mask: u64) -> Vec<Position> {
+    bitmask_to_indices(mask)
+        .into_iter()
+        .map(|idx| Position {
+            row: idx / BOARD_WIDTH as u8,
+            col: idx % BOARD_WIDTH as u8,
+        })
+        .collect()
+}
+
 fn bitmask_to_indices(mask: u64) -> Vec<u8> {
     let mut bits = mask;
     let mut out = Vec::new();
@@ -229,6 +305,9 @@ fn is_valid_player(player: u8) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use super::*;
 
     const FULL_BOARD: u64 = u64::MAX;
@@ -238,11 +317,44 @@ mod tests {
     }
 
     impl MoveSelector for FixedMoveSelector {
-        fn select_move(&self, _board: &Board, _is_black: bool, _level: u8) -> Option<usize> {
+        fn select_move(
+            &self,
+            _board: &Board,
+            _is_black: bool,
+            _level: &LevelConfig,
+        ) -> Option<usize> {
             Some(self.mv)
         }
     }
 
+    #[derive(Default)]
+    struct PonderCountingSelector {
+        ponders: Arc<AtomicUsize>,
+        cancels: Arc<AtomicUsize>,
+    }
+
+    impl MoveSelector for PonderCountingSelector {
+        fn select_move(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Option<usize> {
+            FirstLegalMoveSelector.select_move(board, is_black, level)
+        }
+
+        fn ponder(
+            &self,
+            _board: &Board,
+            is_black: bool,
+            _level: &LevelConfig,
+            _budget: u32,
+        ) -> bool {
+            assert!(!is_black, "ponder must run for the white player");
+            self.ponders.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+
+        fn cancel_ponder(&self) {
+            self.cancels.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
     fn bit(row: usize, col: usize) -> u64 {
         1u64 << (row * BOARD_WIDTH + col)
     }
@@ -286,6 +398,41 @@ mod tests {
         assert!(game.has_legal_moves_for_current());
     }
 
+    #[test]
+    fn stable_discs_are_reported_per_color() {
+        let mut game = GameInstance::new_with_default_selector(1, PLAYER_BLACK).unwrap();
+        assert_eq!(
+            game.get_stable_discs(),
+            StableDiscs {
+                black: Vec::new(),
+                white: Vec::new(),
+            }
+        );
+
+        let black = bit(0, 0) | bit(0, 1);
+        let white = bit(7, 7) | bit(3, 3);
+        game.set_board_for_test(Board::from_bitboards(black, white), PLAYER_BLACK);
+
+        let stable = game.get_stable_discs();
+        assert_eq!(
+            stable.black,
+            vec![Position { row: 0, col: 0 }, Position { row: 0, col: 1 }]
+        );
+        assert_eq!(stable.white, vec![Position { row: 7, col: 7 }]);
+    }
+
+    #[test]
+    fn analyze_moves_is_empty_without_analysis_and_fails_after_game_over() {
+        let mut game = GameInstance::new_with_default_selector(1, PLAYER_BLACK).unwrap();
+        assert_eq!(game.analyze_moves(), Ok(Vec::new()));
+
+        game.end_game();
+        assert_eq!(
+            game.analyze_moves(),
+            Err("game is already over".to_string())
+        );
+    }
+
     #[test]
     fn t04_both_passes_end_game() {
         let mut game = GameInstance::new_with_default_selector(1, PLAYER_BLACK).unwrap();
@@ -335,6 +482,61 @@ mod tests {
         assert!(!game.get_legal_moves().is_empty());
     }
 
+    #[test]
+    fn ponder_is_forwarded_only_on_the_players_turn() {
+        let selector = PonderCountingSelector::default();
+        let (ponders, cancels) = (Arc::clone(&selector.ponders), Arc::clone(&selector.cancels));
+        let mut game = GameInstance::new(3, PLAYER_WHITE, Box::new(selector)).unwrap();
+
+        assert!(!game.ponder(10), "AI to move: nothing to ponder");
+        game.do_ai_move().unwrap();
+        assert!(game.ponder(10));
+        assert_eq!(ponders.load(Ordering::Relaxed), 1);
+
+        game.cancel_ponder();
+        game.end_game();
+        assert!(!game.ponder(10));
+        assert_eq!(ponders.load(Ordering::Relaxed), 1);
+        assert_eq!(cancels.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn out_of_table_level_is_rejected() {
+        let err = GameInstance::new_with_default_selector(0, PLAYER_BLACK)
+            .err()
+            .expect("level 0 must fail");
+        assert!(err.contains("level must be in"));
+    }
+
+    #[test]
+    fn custom_level_config_is_validated_and_kept() {
+        let custom = LevelConfig {
+            depth: 9,
+            exact_solve_empties: 20,
+            wld_empties: 24,
+            time_budget_ms: 1_500,
+            selectivity: 5,
+            randomness: 0.0,
+        };
+        let game =
+            GameInstance::with_level_config(custom, PLAYER_BLACK, Box::new(FirstLegalMoveSelector))
+                .unwrap();
+        assert_eq!(game.level, custom);
+
+        let invalid = LevelConfig {
+            time_budget_ms: 0,
+            ..custom
+        };
+        let err = GameInstance::with_level_config(
+            invalid,
+            PLAYER_BLACK,
+            Box::new(FirstLegalMoveSelector),
+        )
+        .err()
+        .expect("invalid config must fail");
+        assert!(err.contains("time_budget_ms"));
+    }
+
     #[test]
     fn invalid_player_color_is_rejected() {
         let err = GameInstance::new_with_default_selector(1, 0)
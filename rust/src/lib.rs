@@ -1,46 +1,92 @@
 use std::sync::Mutex;
 
 use once_cell::sync::Lazy;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 use wasm_bindgen::prelude::*;
+use web_time::{SystemTime, UNIX_EPOCH};
 
+use crate::ai::level::LevelConfig;
 use crate::ai::ntuple::NTupleEvaluator;
+use crate::ai::ponder::Ponderer;
+use crate::ai::sampling::softmax_sample;
 use crate::ai::search::Searcher;
 use crate::board::Board;
 use crate::game::{GameInstance, MoveSelector};
 pub use crate::game::{PLAYER_BLACK, PLAYER_WHITE};
+use crate::types::MoveAnalysis;
 
 pub mod ai;
 pub mod board;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
 pub mod game;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod training;
 pub mod types;
 
-const MIN_LEVEL: u8 = 1;
-const MAX_LEVEL: u8 = 6;
-
 static MODEL_BYTES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/embedded_weights.bin"));
 static GAME: Lazy<Mutex<Option<GameInstance>>> = Lazy::new(|| Mutex::new(None));
 
 struct SearchMoveSelector {
     evaluator: NTupleEvaluator,
+    rng: Mutex<ChaCha8Rng>,
+    ponderer: Mutex<Ponderer>,
 }
 
 impl SearchMoveSelector {
-    fn new(evaluator: NTupleEvaluator) -> Self {
-        Self { evaluator }
+    fn new(evaluator: NTupleEvaluator, seed: u64) -> Self {
+        Self {
+            evaluator,
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+            ponderer: Mutex::new(Ponderer::default()),
+        }
     }
 }
 
 impl MoveSelector for SearchMoveSelector {
-    fn select_move(&self, board: &Board, is_black: bool, level: u8) -> Option<usize> {
+    fn select_move(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Option<usize> {
         let legal = board.legal_moves(is_black);
         if legal == 0 {
             return None;
         }
 
-        let mut searcher = Searcher::new(&self.evaluator, level);
-        Some(searcher.search(board, is_black))
+        let mut ponderer = self.ponderer.lock().ok()?;
+        if level.randomness > 0.0 {
+            // Sampled moves are not predictable, so there is nothing to ponder.
+            ponderer.cancel();
+            let scored_moves =
+                Searcher::with_level_config(&self.evaluator, level).analyze_discs(board, is_black);
+            let mut rng = self.rng.lock().ok()?;
+            return softmax_sample(&scored_moves, level.randomness, &mut *rng);
+        }
+        Some(ponderer.search(&self.evaluator, level, board, is_black))
+    }
+
+    fn ponder(&self, board: &Board, is_black: bool, level: &LevelConfig, budget_ms: u32) -> bool {
+        self.ponderer.lock().is_ok_and(|mut ponderer| {
+            ponderer.ponder(&self.evaluator, level, board, is_black, budget_ms)
+        })
+    }
+
+    fn cancel_ponder(&self) {
+        if let Ok(mut ponderer) = self.ponderer.lock() {
+            ponderer.cancel();
+        }
+    }
+
+    fn analyze(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Vec<MoveAnalysis> {
+        Searcher::with_level_config(&self.evaluator, level)
+            .analyze_calibrated(board, is_black)
+            .into_iter()
+            .map(|(mv, score, calibrated)| MoveAnalysis {
+                row: (mv / 8) as u8,
+                col: (mv % 8) as u8,
+                score,
+                disc_difference: calibrated.map(|calibrated| calibrated.disc_difference),
+                win_probability: calibrated.map(|calibrated| calibrated.win_probability),
+            })
+            .collect()
     }
 }
 
@@ -49,15 +95,35 @@ pub fn wasm_ready() -> bool {
     true
 }
 
+/// `seed` makes stochastic levels reproducible; when omitted a time-based seed is used.
 #[wasm_bindgen]
-pub fn init_game(level: u8, player: u8) -> Result<JsValue, JsValue> {
-    if !(MIN_LEVEL..=MAX_LEVEL).contains(&level) {
-        return Err(JsValue::from_str("level must be in 1..=6"));
-    }
+pub fn init_game(level: u8, player: u8, seed: Option<u32>) -> Result<JsValue, JsValue> {
+    let config = LevelConfig::try_for_level(level).map_err(string_to_js)?;
+    start_game(config, player, seed)
+}
+
+/// Starts a game with a custom level object
+/// (`{ depth, exact_solve_empties, wld_empties, time_budget_ms, selectivity, randomness }`).
+#[wasm_bindgen]
+pub fn init_game_with_config(
+    config: JsValue,
+    player: u8,
+    seed: Option<u32>,
+) -> Result<JsValue, JsValue> {
+    let config: LevelConfig = serde_wasm_bindgen::from_value(config)
+        .map_err(|e| JsValue::from_str(&format!("invalid level config: {e}")))?;
+    start_game(config, player, seed)
+}
 
+fn start_game(config: LevelConfig, player: u8, seed: Option<u32>) -> Result<JsValue, JsValue> {
     let evaluator = NTupleEvaluator::from_bytes(MODEL_BYTES).map_err(string_to_js)?;
-    let instance = GameInstance::new(level, player, Box::new(SearchMoveSelector::new(evaluator)))
-        .map_err(string_to_js)?;
+    let seed = seed.map(u64::from).unwrap_or_else(time_seed);
+    let instance = GameInstance::with_level_config(
+        config,
+        player,
+        Box::new(SearchMoveSelector::new(evaluator, seed)),
+    )
+    .map_err(string_to_js)?;
 
     let mut guard = GAME
         .lock()
@@ -79,6 +145,34 @@ pub fn get_legal_moves() -> Result<JsValue, JsValue> {
     to_js_value(&game.get_legal_moves())
 }
 
+/// Scores the current player's moves, best first, as
+/// `{ row, col, score, disc_difference, win_probability }`. The last two are
+/// `undefined` when the model has no calibration.
+#[wasm_bindgen]
+pub fn analyze_moves() -> Result<JsValue, JsValue> {
+    let guard = GAME
+        .lock()
+        .map_err(|_| JsValue::from_str("failed to lock game state"))?;
+    let game = guard
+        .as_ref()
+        .ok_or_else(|| JsValue::from_str("game is not initialized"))?;
+
+    to_js_value(&game.analyze_moves().map_err(string_to_js)?)
+}
+
+/// Returns `{ black, white }` lists of discs that can no longer be flipped.
+#[wasm_bindgen]
+pub fn get_stable_discs() -> Result<JsValue, JsValue> {
+    let guard = GAME
+        .lock()
+        .map_err(|_| JsValue::from_str("failed to lock game state"))?;
+    let game = guard
+        .as_ref()
+        .ok_or_else(|| JsValue::from_str("game is not initialized"))?;
+
+    to_js_value(&game.get_stable_discs())
+}
+
 #[wasm_bindgen]
 pub fn place_stone(row: u8, col: u8) -> Result<JsValue, JsValue> {
     let mut guard = GAME
@@ -130,6 +224,35 @@ pub fn ai_move() -> Result<JsValue, JsValue> {
     to_js_value(&game.to_game_state())
 }
 
+/// Searches the AI's reply to the predicted player move for up to `budget_ms`.
+/// The worker calls this repeatedly while the player is thinking; it returns
+/// `true` while another call would make further progress.
+#[wasm_bindgen]
+pub fn ponder(budget_ms: u32) -> Result<bool, JsValue> {
+    let guard = GAME
+        .lock()
+        .map_err(|_| JsValue::from_str("failed to lock game state"))?;
+    let game = guard
+        .as_ref()
+        .ok_or_else(|| JsValue::from_str("game is not initialized"))?;
+
+    Ok(game.ponder(budget_ms))
+}
+
+/// Drops any pondering work, e.g. when the player leaves the game.
+#[wasm_bindgen]
+pub fn stop_pondering() -> Result<(), JsValue> {
+    let guard = GAME
+        .lock()
+        .map_err(|_| JsValue::from_str("failed to lock game state"))?;
+    let game = guard
+        .as_ref()
+        .ok_or_else(|| JsValue::from_str("game is not initialized"))?;
+
+    game.cancel_ponder();
+    Ok(())
+}
+
 #[wasm_bindgen]
 pub fn get_result() -> Result<JsValue, JsValue> {
     let guard = GAME
@@ -145,6 +268,13 @@ pub fn get_result() -> Result<JsValue, JsValue> {
     to_js_value(&game.to_game_result())
 }
 
+fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
 fn string_to_js(message: String) -> JsValue {
     JsValue::from_str(&message)
 }
@@ -168,6 +298,7 @@ mod tests {
     use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
 
     use super::*;
+    use crate::ai::level::{MAX_LEVEL, MIN_LEVEL};
     use crate::ai::ntuple::NTupleEvaluator;
     use crate::game::GameInstance;
     use crate::types::{GameState, Position};
@@ -178,7 +309,7 @@ mod tests {
     const AI_MOVE_LIMIT_MS: f64 = 3_000.0;
     const ERROR_GAME_NOT_INITIALIZED: &str = "game is not initialized";
     const ERROR_PLAYER_TURN: &str = "it is not the player's turn";
-    const ERROR_INVALID_LEVEL: &str = "level must be in 1..=6";
+    const ERROR_INVALID_LEVEL: &str = "level must be in 1..=10";
     const ERROR_INVALID_PLAYER_COLOR: &str = "player color must be 1 (black) or 2 (white)";
 
     const T11_BLACK: u64 = 0xffc3_e7b9_98c8_80bf;
@@ -192,10 +323,14 @@ mod tests {
     const PERFORMANCE_WARMUP_COUNT: usize = 5;
     const PERFORMANCE_MOVE_MIN: usize = 20;
     const PERFORMANCE_MOVE_MAX: usize = 40;
+    const TEST_SEED: u64 = 42;
+    const STOCHASTIC_SAMPLE_GAMES: u32 = 8;
+    // REQUIREMENTS.md 2.3 sets the 3s target for the UI levels 1-6 only.
+    const PERFORMANCE_MAX_LEVEL: u8 = 6;
 
     #[wasm_bindgen_test]
     fn api_flow_init_place_ai_get_result_works_end_to_end() {
-        init_game(1, PLAYER_BLACK).expect("init_game must succeed");
+        init_game(1, PLAYER_BLACK, None).expect("init_game must succeed");
         assert!(
             get_legal_moves().is_ok(),
             "get_legal_moves must succeed right after init"
@@ -218,19 +353,55 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn init_game_rejects_out_of_range_levels() {
-        expect_err_message(init_game(0, PLAYER_BLACK), ERROR_INVALID_LEVEL);
-        expect_err_message(init_game(7, PLAYER_BLACK), ERROR_INVALID_LEVEL);
+        expect_err_message(init_game(0, PLAYER_BLACK, None), ERROR_INVALID_LEVEL);
+        expect_err_message(
+            init_game(MAX_LEVEL + 1, PLAYER_BLACK, None),
+            ERROR_INVALID_LEVEL,
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn init_game_with_config_accepts_custom_level_object() {
+        let config = LevelConfig {
+            depth: 2,
+            exact_solve_empties: 8,
+            wld_empties: 10,
+            time_budget_ms: 1_000,
+            selectivity: 4,
+            randomness: 0.0,
+        };
+        let value = serde_wasm_bindgen::to_value(&config).expect("config must serialize");
+
+        init_game_with_config(value, PLAYER_BLACK, None).expect("custom config must be accepted");
+        let opening = first_internal_legal_move().expect("opening move must exist");
+        place_stone(opening.row, opening.col).expect("place_stone must succeed");
+        ai_move().expect("ai_move must succeed with custom config");
+    }
+
+    #[wasm_bindgen_test]
+    fn init_game_with_config_rejects_invalid_config() {
+        let config = LevelConfig {
+            depth: 0,
+            ..LevelConfig::for_level(1).expect("level 1 must exist")
+        };
+        let value = serde_wasm_bindgen::to_value(&config).expect("config must serialize");
+
+        expect_err_message(init_game_with_config(value, PLAYER_BLACK, None), "depth");
+        expect_err_message(
+            init_game_with_config(JsValue::from_str("strong"), PLAYER_BLACK, None),
+            "invalid level config",
+        );
     }
 
     #[wasm_bindgen_test]
     fn init_game_rejects_invalid_player_color() {
-        expect_err_message(init_game(1, 0), ERROR_INVALID_PLAYER_COLOR);
-        expect_err_message(init_game(1, 3), ERROR_INVALID_PLAYER_COLOR);
+        expect_err_message(init_game(1, 0, None), ERROR_INVALID_PLAYER_COLOR);
+        expect_err_message(init_game(1, 3, None), ERROR_INVALID_PLAYER_COLOR);
     }
 
     #[wasm_bindgen_test]
     fn get_result_returns_error_while_game_is_active() {
-        init_game(1, PLAYER_BLACK).expect("init_game must succeed");
+        init_game(1, PLAYER_BLACK, None).expect("init_game must succeed");
         expect_err_message(get_result(), "game is not over");
     }
 
@@ -247,8 +418,8 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn ai_move_smoke_meets_level_performance_target() {
-        for level in MIN_LEVEL..=MAX_LEVEL {
-            init_game(level, PLAYER_BLACK).expect("init_game must succeed");
+        for level in MIN_LEVEL..=PERFORMANCE_MAX_LEVEL {
+            init_game(level, PLAYER_BLACK, None).expect("init_game must succeed");
             let opening = first_internal_legal_move().expect("opening move must exist");
             place_stone(opening.row, opening.col).expect("place_stone must succeed");
 
@@ -275,7 +446,7 @@ mod tests {
             );
         }
 
-        for level in MIN_LEVEL..=MAX_LEVEL {
+        for level in MIN_LEVEL..=PERFORMANCE_MAX_LEVEL {
             let mut durations_ms = Vec::with_capacity(PERFORMANCE_SAMPLE_COUNT);
             for sample in &positions {
                 let elapsed_ms = measure_ai_move_from_position(level, *sample);
@@ -296,7 +467,7 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn init_game_reinitializes_global_state() {
-        init_game(1, PLAYER_BLACK).expect("first init_game must succeed");
+        init_game(1, PLAYER_BLACK, None).expect("first init_game must succeed");
         let opening = first_internal_legal_move().expect("opening move must exist");
         place_stone(opening.row, opening.col).expect("player move must succeed");
         assert_ne!(
@@ -305,7 +476,7 @@ mod tests {
             "state should change before reset"
         );
 
-        init_game(6, PLAYER_BLACK).expect("second init_game must succeed");
+        init_game(6, PLAYER_BLACK, None).expect("second init_game must succeed");
         let reset = snapshot_state();
         assert_eq!(reset.current_player, PLAYER_BLACK);
         assert_eq!(reset.black_count, 2);
@@ -349,6 +520,21 @@ mod tests {
         assert!(after.white_count > 0);
     }
 
+    #[wasm_bindgen_test]
+    fn stochastic_level_is_reproducible_for_fixed_seed_and_varies_across_seeds() {
+        let first = seeded_level_one_reply(7);
+        let second = seeded_level_one_reply(7);
+        assert_eq!(first, second, "same seed must reproduce the same reply");
+
+        let replies: std::collections::HashSet<_> = (0..STOCHASTIC_SAMPLE_GAMES)
+            .map(seeded_level_one_reply)
+            .collect();
+        assert!(
+            replies.len() > 1,
+            "level 1 must vary its reply across seeds"
+        );
+    }
+
     #[wasm_bindgen_test]
     fn ai_move_is_deterministic_for_100_repeated_runs() {
         let expected = ai_move_index_after_opening(4);
@@ -361,6 +547,24 @@ mod tests {
         }
     }
 
+    #[wasm_bindgen_test]
+    fn ponder_runs_on_players_turn_and_can_be_stopped() {
+        init_game(4, PLAYER_WHITE, None).expect("init_game must succeed");
+        assert!(!ponder(10).expect("ponder must succeed"), "AI to move");
+
+        ai_move().expect("ai_move must succeed");
+        let mut slices = 0;
+        while ponder(AI_MOVE_TIMEOUT_MS as u32).expect("ponder must succeed") {
+            slices += 1;
+            assert!(slices < MAX_GAME_STEPS, "pondering must finish");
+        }
+
+        let opening = first_internal_legal_move().expect("player move must exist");
+        place_stone(opening.row, opening.col).expect("place_stone must succeed");
+        ai_move().expect("ai_move after pondering must succeed");
+        stop_pondering().expect("stop_pondering must succeed");
+    }
+
     #[wasm_bindgen_test]
     fn api_returns_uninitialized_error_before_init_game() {
         clear_game();
@@ -369,11 +573,13 @@ mod tests {
         expect_err_message(get_legal_moves(), ERROR_GAME_NOT_INITIALIZED);
         expect_err_message(place_stone(2, 3), ERROR_GAME_NOT_INITIALIZED);
         expect_err_message(ai_move(), ERROR_GAME_NOT_INITIALIZED);
+        assert!(ponder(10).is_err());
+        assert!(stop_pondering().is_err());
     }
 
     #[wasm_bindgen_test]
     fn place_stone_rejects_wrong_player_turn() {
-        init_game(1, PLAYER_BLACK).expect("init_game must succeed");
+        init_game(1, PLAYER_BLACK, None).expect("init_game must succeed");
         let opening = first_internal_legal_move().expect("opening move must exist");
         place_stone(opening.row, opening.col).expect("first player move must succeed");
 
@@ -392,15 +598,22 @@ mod tests {
     }
 
     fn play_one_opening_and_ai_step(level: u8) -> GameState {
-        init_game(level, PLAYER_BLACK).expect("init_game must succeed");
+        init_game(level, PLAYER_BLACK, None).expect("init_game must succeed");
         let opening = first_internal_legal_move().expect("opening move must exist");
         place_stone(opening.row, opening.col).expect("place_stone must succeed");
         ai_move().expect("ai_move must succeed");
         snapshot_state()
     }
 
+    fn seeded_level_one_reply(seed: u32) -> Vec<u8> {
+        init_game(1, PLAYER_BLACK, Some(seed)).expect("init_game must succeed");
+        place_stone(2, 3).expect("d3 opening must be legal");
+        ai_move().expect("ai_move must succeed");
+        snapshot_state().board
+    }
+
     fn ai_move_index_after_opening(level: u8) -> usize {
-        init_game(level, PLAYER_BLACK).expect("init_game must succeed");
+        init_game(level, PLAYER_BLACK, None).expect("init_game must succeed");
         let opening = first_internal_legal_move().expect("opening move must exist");
         place_stone(opening.row, opening.col).expect("place_stone must succeed");
         let before = snapshot_state().board;
@@ -508,7 +721,7 @@ mod tests {
         let mut game = GameInstance::new(
             level,
             PLAYER_BLACK,
-            Box::new(SearchMoveSelector::new(evaluator)),
+            Box::new(SearchMoveSelector::new(evaluator, TEST_SEED)),
         )
         .expect("test game must initialize");
         game.set_board_for_test(board, current_player);
@@ -636,10 +849,18 @@ mod tests {
     }
 
     fn run_tie_break_step() -> TieBreakResult {
-        let mut game = GameInstance::new(
-            1,
+        // Level 1 samples moves, so pin a deterministic depth-1 config.
+        let deterministic = LevelConfig {
+            randomness: 0.0,
+            ..LevelConfig::for_level(1).expect("level 1 must exist")
+        };
+        let mut game = GameInstance::with_level_config(
+            deterministic,
             PLAYER_BLACK,
-            Box::new(SearchMoveSelector::new(build_constant_evaluator())),
+            Box::new(SearchMoveSelector::new(
+                build_constant_evaluator(),
+                TEST_SEED,
+            )),
         )
         .expect("test game must initialize");
         game.place(2, 3)
@@ -1,15 +1,36 @@
-use std::sync::LazyLock;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::mpsc;
+use std::sync::{Arc, LazyLock};
 use std::time::Instant;
 
 use rand::Rng;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 
-use crate::ai::ntuple::{compress_model_bytes, decompress_model_bytes};
+use crate::ai::calibration::Calibration;
+use crate::ai::features::{ScalarFeature, ScalarFeatureWeights};
+use crate::ai::metadata::{
+    KEY_ALPHA, KEY_ALPHA_DECAY, KEY_EPSILON, KEY_EPSILON_SCHEDULE, KEY_EXACT_TD_EMPTIES,
+    KEY_EXPLORATION, KEY_GAMES, KEY_LAMBDA, KEY_LEAGUE, KEY_PARALLEL_MODE, KEY_PARENT_HASH,
+    KEY_SEARCH_DEPTH, KEY_SEED, ModelMetadata,
+};
+use crate::ai::ntuple::{
+    NTupleEvaluator, compress_model_bytes, decompress_model_bytes, encode_quantized_model,
+};
+use crate::ai::positional::choose_positional_move;
+use crate::ai::search::{Searcher, TranspositionTable};
 use crate::board::Board;
 
-pub type ProgressCallback<'a> = &'a mut dyn FnMut(usize, usize, f64) -> Result<(), String>;
+pub mod dataset;
+pub mod gating;
+pub mod supervised;
+
+pub type ProgressCallback<'a> = &'a mut dyn FnMut(&TrainingProgress) -> Result<(), String>;
+pub type CheckpointCallback<'a> = &'a mut dyn FnMut(&TrainingRun) -> Result<(), String>;
 
 pub const TUPLE_PATTERNS: &[&[u8]] = &[
     &[0, 1, 8, 9, 10, 17, 18, 19, 26, 27],
@@ -31,16 +52,27 @@ pub const TUPLE_PATTERNS: &[&[u8]] = &[
 const MAGIC: &[u8; 4] = b"NTRV";
 const VERSION_V3: u32 = 3;
 const VERSION_V4: u32 = 4;
+/// v4 plus the TC-learning accumulators, written only by networks that have them.
+const VERSION_V6: u32 = 6;
 const VERSION: u32 = VERSION_V4;
 const HEADER_SIZE: usize = 20;
+const CHECKPOINT_MAGIC: &[u8; 4] = b"NTCK";
+const CHECKPOINT_VERSION: u32 = 5;
+const CHECKPOINT_HEADER_SIZE: usize = 12;
 const SYMMETRY_COUNT: usize = 8;
-const TUPLE_COUNT: usize = 14;
-const MAX_TUPLE_LEN: usize = 10;
-const TRAINING_SEARCH_DEPTH: u8 = 2;
+/// Upper bound on the number of patterns in a [`TuplePatternSet`].
+pub const MAX_TUPLE_COUNT: usize = 32;
+/// Longest supported pattern; `3^10` table entries keep indices within `u16`.
+pub const MAX_TUPLE_LEN: usize = 10;
+/// Self-play search depth used unless a trainer is given another one.
+pub const DEFAULT_TRAINING_SEARCH_DEPTH: u8 = 2;
+/// Largest `exact_td_empties` a trainer accepts; each solve grows
+/// exponentially with the number of empties.
+pub const MAX_EXACT_TD_EMPTIES: u8 = 20;
 const SYMMETRY_NORMALIZATION: f32 = 1.0 / (SYMMETRY_COUNT as f32);
 const MAX_ABS_WEIGHT_UPDATE: f32 = 0.1;
 pub const PHASE_COUNT: usize = 30;
-type FeatureIndices = [[u16; TUPLE_COUNT]; SYMMETRY_COUNT];
+type FeatureIndices = [[u16; MAX_TUPLE_COUNT]; SYMMETRY_COUNT];
 
 #[derive(Debug, Clone, Copy)]
 struct CompiledTuple {
@@ -75,6 +107,10 @@ pub enum AlphaDecayStrategy {
     None,
     InverseGame,
     InverseVisit,
+    /// Temporal coherence learning: each weight's rate is `alpha` times
+    /// `|sum of its updates| / sum of |its updates|`, so weights whose updates
+    /// keep agreeing learn fast and those that oscillate slow down.
+    TemporalCoherence,
 }
 
 impl AlphaDecayStrategy {
@@ -83,12 +119,22 @@ impl AlphaDecayStrategy {
             "none" => Ok(Self::None),
             "inverse_game" => Ok(Self::InverseGame),
             "inverse_visit" => Ok(Self::InverseVisit),
+            "tc" => Ok(Self::TemporalCoherence),
             _ => Err(format!(
-                "unsupported alpha_decay '{name}' (expected one of: none, inverse_game, inverse_visit)"
+                "unsupported alpha_decay '{name}' (expected one of: none, inverse_game, inverse_visit, tc)"
             )),
         }
     }
 
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::InverseGame => "inverse_game",
+            Self::InverseVisit => "inverse_visit",
+            Self::TemporalCoherence => "tc",
+        }
+    }
+
     fn alpha_for_completed_games(
         self,
         base_alpha: f32,
@@ -96,7 +142,7 @@ impl AlphaDecayStrategy {
         start_game: usize,
     ) -> f32 {
         match self {
-            Self::None | Self::InverseVisit => base_alpha,
+            Self::None | Self::InverseVisit | Self::TemporalCoherence => base_alpha,
             Self::InverseGame => {
                 let denominator = (start_game + completed_games + 1) as f32;
                 base_alpha / denominator
@@ -107,67 +153,591 @@ impl AlphaDecayStrategy {
     fn requires_visit_counts(self) -> bool {
         matches!(self, Self::InverseVisit)
     }
+
+    fn requires_tc_accumulators(self) -> bool {
+        matches!(self, Self::TemporalCoherence)
+    }
+}
+
+/// Running sums of the updates one weight was asked to make under
+/// [`AlphaDecayStrategy::TemporalCoherence`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TcAccumulator {
+    signed: f32,
+    absolute: f32,
 }
 
-static COMPILED_TUPLES: LazyLock<[CompiledTuple; TUPLE_COUNT]> = LazyLock::new(|| {
-    std::array::from_fn(|tuple_idx| {
-        let pattern = TUPLE_PATTERNS[tuple_idx];
-        let mut transformed_positions = [[0u8; MAX_TUPLE_LEN]; SYMMETRY_COUNT];
-        let mut radix_weights = [0usize; MAX_TUPLE_LEN];
+impl TcAccumulator {
+    /// Learning rate multiplier in `[0, 1]`, `1` before the first update.
+    fn rate(self) -> f32 {
+        if self.absolute > 0.0 {
+            self.signed.abs() / self.absolute
+        } else {
+            1.0
+        }
+    }
+
+    fn record(&mut self, update: f32) {
+        self.signed += update;
+        self.absolute += update.abs();
+    }
+}
 
-        for symmetry in 0..SYMMETRY_COUNT {
-            for (cell_idx, &pos) in pattern.iter().enumerate() {
-                transformed_positions[symmetry][cell_idx] =
-                    transform_pos(pos, symmetry as u8) as u8;
+/// How the exploration rate moves away from the configured `epsilon`.
+///
+/// Game-based schedules count the games of the whole run from
+/// `alpha_decay_start_game`, so a continued run picks up where the previous
+/// one stopped. Parallel workers all follow the run's count rather than their
+/// own alpha decay offsets, so they explore alike.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EpsilonSchedule {
+    Constant,
+    /// Moves linearly from `epsilon` to `end` over the first `games` games,
+    /// then stays at `end`.
+    Linear {
+        end: f64,
+        games: usize,
+    },
+    /// Multiplies `epsilon` by `decay` every game, never going below `floor`.
+    Exponential {
+        decay: f64,
+        floor: f64,
+    },
+    /// Uses `epsilon` in the opening and moves linearly to `endgame` over the
+    /// [`PHASE_COUNT`] phases of each game.
+    PerPhase {
+        endgame: f64,
+    },
+}
+
+impl EpsilonSchedule {
+    /// Parses `constant`, `linear:<end>:<games>`, `exponential:<decay>[:<floor>]`
+    /// or `phase:<endgame>`.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "unsupported epsilon_schedule '{name}' (expected one of: constant, \
+                 linear:<end>:<games>, exponential:<decay>[:<floor>], phase:<endgame>)"
+            )
+        };
+        let mut parts = name.split(':');
+        let kind = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+        let rate = |value: &str| value.parse::<f64>().map_err(|_| invalid());
+        let schedule = match (kind, args.as_slice()) {
+            ("constant", []) => Self::Constant,
+            ("linear", [end, games]) => Self::Linear {
+                end: rate(end)?,
+                games: games.parse().map_err(|_| invalid())?,
+            },
+            ("exponential", [decay]) => Self::Exponential {
+                decay: rate(decay)?,
+                floor: 0.0,
+            },
+            ("exponential", [decay, floor]) => Self::Exponential {
+                decay: rate(decay)?,
+                floor: rate(floor)?,
+            },
+            ("phase", [endgame]) => Self::PerPhase {
+                endgame: rate(endgame)?,
+            },
+            _ => return Err(invalid()),
+        };
+        schedule.validate()?;
+        Ok(schedule)
+    }
+
+    pub fn validate(self) -> Result<(), String> {
+        let rates = match self {
+            Self::Constant => Vec::new(),
+            Self::Linear { games: 0, .. } => {
+                return Err(format!(
+                    "epsilon_schedule '{}' needs games > 0",
+                    self.name()
+                ));
+            }
+            Self::Linear { end, .. } => vec![end],
+            Self::Exponential { decay, floor } => vec![decay, floor],
+            Self::PerPhase { endgame } => vec![endgame],
+        };
+        if rates.iter().any(|rate| !(0.0..=1.0).contains(rate)) {
+            return Err(format!(
+                "epsilon_schedule '{}' needs rates in [0.0, 1.0]",
+                self.name()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Constant => "constant".to_string(),
+            Self::Linear { end, games } => format!("linear:{end}:{games}"),
+            Self::Exponential { decay, floor } => format!("exponential:{decay}:{floor}"),
+            Self::PerPhase { endgame } => format!("phase:{endgame}"),
+        }
+    }
+
+    fn epsilon_for(self, base_epsilon: f64, game: usize, board: &Board) -> f64 {
+        match self {
+            Self::Constant => base_epsilon,
+            Self::Linear { end, games } => {
+                let progress = (game as f64 / games as f64).min(1.0);
+                base_epsilon + (end - base_epsilon) * progress
+            }
+            Self::Exponential { decay, floor } => {
+                (base_epsilon * decay.powf(game as f64)).max(floor)
+            }
+            Self::PerPhase { endgame } => {
+                let phase = phase_index_for_board(board, PHASE_COUNT);
+                let progress = phase as f64 / (PHASE_COUNT - 1) as f64;
+                base_epsilon + (endgame - base_epsilon) * progress
             }
         }
-        for cell_idx in 0..pattern.len() {
-            radix_weights[cell_idx] =
-                pow3(pattern.len() - 1 - cell_idx).expect("tuple radix weight must fit usize");
+    }
+}
+
+/// How an exploratory self-play move is picked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExplorationPolicy {
+    /// Any legal move with equal probability.
+    Uniform,
+    /// Softmax over the searched move scores (in discs) at `temperature`, so
+    /// exploration still prefers the better moves.
+    Boltzmann { temperature: f64 },
+}
+
+impl ExplorationPolicy {
+    /// Parses `uniform` or `boltzmann:<temperature>`.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "unsupported exploration '{name}' (expected uniform or boltzmann:<temperature> \
+                 with temperature > 0)"
+            )
+        };
+        if name == "uniform" {
+            return Ok(Self::Uniform);
+        }
+        let policy = name
+            .strip_prefix("boltzmann:")
+            .and_then(|temperature| temperature.parse::<f64>().ok())
+            .map(|temperature| Self::Boltzmann { temperature })
+            .ok_or_else(invalid)?;
+        policy.validate().map_err(|_| invalid())?;
+        Ok(policy)
+    }
+
+    pub fn validate(self) -> Result<(), String> {
+        match self {
+            Self::Boltzmann { temperature } if !(temperature.is_finite() && temperature > 0.0) => {
+                Err(format!(
+                    "exploration temperature must be finite and > 0, got {temperature}"
+                ))
+            }
+            _ => Ok(()),
         }
+    }
 
-        CompiledTuple {
-            len: pattern.len(),
-            transformed_positions,
-            radix_weights,
+    pub fn name(self) -> String {
+        match self {
+            Self::Uniform => "uniform".to_string(),
+            Self::Boltzmann { temperature } => format!("boltzmann:{temperature}"),
         }
-    })
+    }
+}
+
+/// Opponent pool of league training. Each game the learner either plays
+/// itself or, with one color, an opponent drawn uniformly from the pool;
+/// only the learner's own positions update the weights then.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeagueConfig {
+    /// A worker adds a frozen copy of its network to the pool every this
+    /// many of its games; `0` adds none.
+    pub snapshot_interval: usize,
+    /// Snapshots kept in the pool; the oldest leaves first.
+    pub max_snapshots: usize,
+    /// Depth of the positional-search player, `0` to leave it out.
+    pub positional_depth: u8,
+    /// Whether a uniformly random player joins the pool.
+    pub random: bool,
+    /// Share of games the learner still plays against itself.
+    pub self_play: f64,
+}
+
+impl LeagueConfig {
+    /// Parses comma-separated `snapshot=<games>`, `keep=<snapshots>`,
+    /// `positional=<depth>`, `random` and `self=<share>` entries, e.g.
+    /// `snapshot=5000,keep=8,positional=2,random,self=0.2`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut config = Self {
+            snapshot_interval: 0,
+            max_snapshots: 8,
+            positional_depth: 0,
+            random: false,
+            self_play: 0.0,
+        };
+        for entry in spec.split(',').map(str::trim) {
+            let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+            let invalid = || format!("invalid league entry '{entry}'");
+            match key.trim() {
+                "snapshot" => config.snapshot_interval = value.parse().map_err(|_| invalid())?,
+                "keep" => config.max_snapshots = value.parse().map_err(|_| invalid())?,
+                "positional" => config.positional_depth = value.parse().map_err(|_| invalid())?,
+                "random" if value.is_empty() => config.random = true,
+                "self" => config.self_play = value.parse().map_err(|_| invalid())?,
+                _ => {
+                    return Err(format!(
+                        "{} (expected snapshot=<games>, keep=<snapshots>, positional=<depth>, \
+                         random or self=<share>)",
+                        invalid()
+                    ));
+                }
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn name(self) -> String {
+        let mut name = format!(
+            "snapshot={},keep={},positional={}",
+            self.snapshot_interval, self.max_snapshots, self.positional_depth
+        );
+        if self.random {
+            name.push_str(",random");
+        }
+        name.push_str(&format!(",self={}", self.self_play));
+        name
+    }
+
+    fn validate(self) -> Result<(), String> {
+        if self.snapshot_interval == 0 && self.positional_depth == 0 && !self.random {
+            return Err(
+                "league needs snapshots, the positional player or the random player".to_string(),
+            );
+        }
+        if self.max_snapshots == 0 {
+            return Err("league must keep at least one snapshot".to_string());
+        }
+        if !(0.0..1.0).contains(&self.self_play) {
+            return Err(format!(
+                "league self-play share must be in [0.0, 1.0), got {}",
+                self.self_play
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// League state of one worker.
+#[derive(Clone)]
+struct League {
+    config: LeagueConfig,
+    /// Frozen copies of the learner, oldest first.
+    snapshots: VecDeque<TrainableNTuple>,
+}
+
+/// Who plays the other color of a league game.
+enum LeagueOpponent<'a> {
+    Snapshot(Box<TDLambdaTrainer<FrozenNetwork<'a>>>),
+    Positional { depth: u8 },
+    Random,
+}
+
+static DEFAULT_TUPLE_PATTERNS: LazyLock<Arc<TuplePatternSet>> = LazyLock::new(|| {
+    Arc::new(
+        TuplePatternSet::new(
+            TUPLE_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_vec())
+                .collect(),
+        )
+        .expect("built-in tuple patterns must be valid"),
+    )
 });
 
-static POSITION_OCCURRENCES: LazyLock<[[Vec<PositionOccurrence>; 64]; SYMMETRY_COUNT]> =
-    LazyLock::new(|| {
-        std::array::from_fn(|symmetry| {
-            std::array::from_fn(|pos| {
-                let mut occurrences = Vec::new();
-                for tuple_idx in 0..TUPLE_COUNT {
-                    let compiled = &COMPILED_TUPLES[tuple_idx];
-                    for cell_idx in 0..compiled.len {
-                        if compiled.transformed_positions[symmetry][cell_idx] as usize == pos {
-                            occurrences.push(PositionOccurrence {
-                                tuple_idx,
-                                cell_idx,
-                            });
-                        }
+/// Validated N-tuple patterns together with the symmetry lookups used to
+/// compute and incrementally update feature indices during training.
+#[derive(Debug)]
+pub struct TuplePatternSet {
+    patterns: Vec<Vec<u8>>,
+    compiled: Vec<CompiledTuple>,
+    position_occurrences: Vec<Vec<Vec<PositionOccurrence>>>,
+}
+
+impl TuplePatternSet {
+    /// Checks that there are 1..=[`MAX_TUPLE_COUNT`] patterns, each made of
+    /// 1..=[`MAX_TUPLE_LEN`] distinct squares in `0..64`, and that no pattern
+    /// covers the same squares as another one up to board symmetry.
+    pub fn new(patterns: Vec<Vec<u8>>) -> Result<Self, String> {
+        if patterns.is_empty() {
+            return Err("tuple pattern set must not be empty".to_string());
+        }
+        if patterns.len() > MAX_TUPLE_COUNT {
+            return Err(format!(
+                "too many tuple patterns: expected at most {MAX_TUPLE_COUNT}, got {}",
+                patterns.len()
+            ));
+        }
+
+        let mut canonical_shapes: HashMap<Vec<u8>, usize> = HashMap::new();
+        for (tuple_idx, pattern) in patterns.iter().enumerate() {
+            if pattern.is_empty() || pattern.len() > MAX_TUPLE_LEN {
+                return Err(format!(
+                    "tuple pattern #{tuple_idx} must have 1 to {MAX_TUPLE_LEN} squares, got {}",
+                    pattern.len()
+                ));
+            }
+            if let Some(&pos) = pattern.iter().find(|&&pos| pos >= 64) {
+                return Err(format!(
+                    "tuple pattern #{tuple_idx} contains out-of-range board position {pos}"
+                ));
+            }
+            let mut squares = 0u64;
+            for &pos in pattern {
+                if squares & (1u64 << pos) != 0 {
+                    return Err(format!(
+                        "tuple pattern #{tuple_idx} repeats board position {pos}"
+                    ));
+                }
+                squares |= 1u64 << pos;
+            }
+
+            let shape = canonical_tuple_shape(pattern);
+            if let Some(previous_idx) = canonical_shapes.insert(shape, tuple_idx) {
+                return Err(format!(
+                    "tuple pattern #{tuple_idx} duplicates pattern #{previous_idx} up to symmetry"
+                ));
+            }
+        }
+
+        let compiled: Vec<CompiledTuple> = patterns
+            .iter()
+            .map(|pattern| {
+                let mut transformed_positions = [[0u8; MAX_TUPLE_LEN]; SYMMETRY_COUNT];
+                let mut radix_weights = [0usize; MAX_TUPLE_LEN];
+
+                for (symmetry, positions) in transformed_positions.iter_mut().enumerate() {
+                    for (cell_idx, &pos) in pattern.iter().enumerate() {
+                        positions[cell_idx] = transform_pos(pos, symmetry as u8) as u8;
                     }
                 }
-                occurrences
+                for cell_idx in 0..pattern.len() {
+                    radix_weights[cell_idx] = pow3(pattern.len() - 1 - cell_idx)
+                        .expect("tuple radix weight must fit usize");
+                }
+
+                CompiledTuple {
+                    len: pattern.len(),
+                    transformed_positions,
+                    radix_weights,
+                }
+            })
+            .collect();
+
+        let position_occurrences = (0..SYMMETRY_COUNT)
+            .map(|symmetry| {
+                (0..64)
+                    .map(|pos| {
+                        let mut occurrences = Vec::new();
+                        for (tuple_idx, tuple) in compiled.iter().enumerate() {
+                            for cell_idx in 0..tuple.len {
+                                if tuple.transformed_positions[symmetry][cell_idx] as usize == pos {
+                                    occurrences.push(PositionOccurrence {
+                                        tuple_idx,
+                                        cell_idx,
+                                    });
+                                }
+                            }
+                        }
+                        occurrences
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            patterns,
+            compiled,
+            position_occurrences,
+        })
+    }
+
+    /// The built-in [`TUPLE_PATTERNS`].
+    pub fn default_patterns() -> Arc<Self> {
+        Arc::clone(&DEFAULT_TUPLE_PATTERNS)
+    }
+
+    /// Parses a pattern file: one pattern per line, squares separated by
+    /// commas or whitespace. Blank lines and `#` comments are ignored.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut patterns = Vec::new();
+        for (line_idx, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let pattern = line
+                .split(|ch: char| ch == ',' || ch.is_whitespace())
+                .filter(|token| !token.is_empty())
+                .map(|token| {
+                    token.parse::<u8>().map_err(|_| {
+                        format!(
+                            "invalid square '{token}' on pattern file line {}",
+                            line_idx + 1
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            patterns.push(pattern);
+        }
+        Self::new(patterns)
+    }
+
+    /// Formats the set in the layout read by [`TuplePatternSet::parse`].
+    pub fn to_text(&self) -> String {
+        self.patterns
+            .iter()
+            .map(|pattern| {
+                let squares: Vec<String> = pattern.iter().map(u8::to_string).collect();
+                format!("{}\n", squares.join(","))
+            })
+            .collect()
+    }
+
+    pub fn patterns(&self) -> &[Vec<u8>] {
+        &self.patterns
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// One zeroed table per pattern, sized `3^len`.
+    fn table_template<T: Clone + Default>(&self) -> Vec<Vec<T>> {
+        self.patterns
+            .iter()
+            .map(|pattern| {
+                vec![T::default(); pow3(pattern.len()).expect("tuple size must fit usize")]
             })
+            .collect()
+    }
+
+    fn feature_indices(&self, board: &Board, is_black: bool) -> FeatureIndices {
+        let (black, white) = board.bitboards();
+        let (me, opp) = if is_black {
+            (black, white)
+        } else {
+            (white, black)
+        };
+        let mut indices = [[0u16; MAX_TUPLE_COUNT]; SYMMETRY_COUNT];
+
+        for (symmetry, symmetry_indices) in indices.iter_mut().enumerate() {
+            for (index, compiled) in symmetry_indices.iter_mut().zip(&self.compiled) {
+                *index = tuple_index(
+                    &compiled.transformed_positions[symmetry],
+                    compiled.len,
+                    me,
+                    opp,
+                ) as u16;
+            }
+        }
+
+        indices
+    }
+
+    fn update_feature_indices(
+        &self,
+        previous: &FeatureIndices,
+        old_board: &Board,
+        new_board: &Board,
+        is_black: bool,
+    ) -> FeatureIndices {
+        let (old_black, old_white) = old_board.bitboards();
+        let (new_black, new_white) = new_board.bitboards();
+        let mut indices = *previous;
+        let mut changed = (old_black ^ new_black) | (old_white ^ new_white);
+
+        while changed != 0 {
+            let pos = changed.trailing_zeros() as usize;
+            changed &= changed - 1;
+
+            let old_state =
+                cell_state_from_bitboards_for_player_view(old_black, old_white, pos, is_black);
+            let new_state =
+                cell_state_from_bitboards_for_player_view(new_black, new_white, pos, is_black);
+            if old_state == new_state {
+                continue;
+            }
+
+            let delta = (new_state - old_state) as i32;
+            for symmetry in 0..SYMMETRY_COUNT {
+                for occurrence in &self.position_occurrences[symmetry][pos] {
+                    let radix = self.compiled[occurrence.tuple_idx].radix_weights
+                        [occurrence.cell_idx] as i32;
+                    indices[symmetry][occurrence.tuple_idx] =
+                        ((indices[symmetry][occurrence.tuple_idx] as i32) + delta * radix) as u16;
+                }
+            }
+        }
+
+        indices
+    }
+}
+
+/// Sorted squares of `pattern` under the symmetry that yields the smallest
+/// list, so shapes equal up to board symmetry compare equal.
+pub fn canonical_tuple_shape(pattern: &[u8]) -> Vec<u8> {
+    (0..SYMMETRY_COUNT as u8)
+        .map(|symmetry| {
+            let mut cells: Vec<u8> = pattern
+                .iter()
+                .map(|&pos| transform_pos(pos, symmetry) as u8)
+                .collect();
+            cells.sort_unstable();
+            cells
         })
-    });
+        .min()
+        .expect("symmetry count must be non-zero")
+}
+
+impl PartialEq for TuplePatternSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns == other.patterns
+    }
+}
 
 pub trait TrainingNetwork {
     fn evaluate(&self, board: &Board, is_black: bool) -> f32;
     fn update(&mut self, board: &Board, is_black: bool, delta: f32);
+    /// Patterns whose feature indices the trainer maintains during self-play.
+    fn tuple_patterns(&self) -> &TuplePatternSet {
+        &DEFAULT_TUPLE_PATTERNS
+    }
+    /// Per-phase L2 norms of the tuple weights, reported in [`TrainingMetrics`].
+    fn phase_weight_norms(&self) -> Vec<f64> {
+        Vec::new()
+    }
     fn prepare_for_alpha_decay(&mut self, alpha_decay: AlphaDecayStrategy) -> Result<(), String> {
-        if alpha_decay.requires_visit_counts() {
-            Err(
-                "inverse_visit alpha decay is only supported for the trainable N-tuple network"
-                    .to_string(),
-            )
+        if alpha_decay.requires_visit_counts() || alpha_decay.requires_tc_accumulators() {
+            Err(format!(
+                "{} alpha decay is only supported for the trainable N-tuple network",
+                alpha_decay.name()
+            ))
         } else {
             Ok(())
         }
     }
+    /// Weights-only copy for a league opponent pool; `None` when the network
+    /// cannot be frozen, in which case league runs take no snapshots.
+    fn league_snapshot(&self) -> Option<TrainableNTuple> {
+        None
+    }
     fn evaluate_precomputed(
         &self,
         board: &Board,
@@ -231,26 +801,87 @@ pub trait TrainingNetwork {
     }
 }
 
+/// Tuple patterns and scalar features of a freshly created network.
+#[derive(Debug, Clone)]
+pub struct NetworkLayout {
+    pub tuple_patterns: Arc<TuplePatternSet>,
+    pub scalar_features: Vec<ScalarFeature>,
+}
+
+impl NetworkLayout {
+    pub fn build(&self) -> Result<TrainableNTuple, String> {
+        let network = TrainableNTuple::with_patterns(Arc::clone(&self.tuple_patterns));
+        if self.scalar_features.is_empty() {
+            Ok(network)
+        } else {
+            network.with_scalar_features(self.scalar_features.clone())
+        }
+    }
+
+    fn check_matches(&self, network: &TrainableNTuple) -> Result<(), String> {
+        if *self.tuple_patterns != **network.patterns() {
+            return Err("tuple patterns do not match the initial model".to_string());
+        }
+        let network_features = network
+            .scalar_features()
+            .map_or(&[][..], |features| features.features());
+        if self.scalar_features != network_features {
+            return Err("scalar features do not match the initial model".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for NetworkLayout {
+    fn default() -> Self {
+        Self {
+            tuple_patterns: TuplePatternSet::default_patterns(),
+            scalar_features: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TrainableNTuple {
+    patterns: Arc<TuplePatternSet>,
     phase_count: usize,
     weights: Vec<Vec<Vec<f32>>>,
     visit_counts: Option<Vec<Vec<Vec<u32>>>>,
+    tc_accumulators: Option<Vec<Vec<Vec<TcAccumulator>>>>,
+    features: Option<ScalarFeatureWeights>,
+    metadata: ModelMetadata,
 }
 
 impl TrainableNTuple {
     pub fn new() -> Self {
-        let template: Vec<Vec<f32>> = TUPLE_PATTERNS
-            .iter()
-            .map(|pattern| vec![0.0; pow3(pattern.len()).expect("tuple size must fit usize")])
-            .collect();
+        Self::with_patterns(TuplePatternSet::default_patterns())
+    }
+
+    /// Creates a zero-initialized network over a custom pattern set.
+    pub fn with_patterns(patterns: Arc<TuplePatternSet>) -> Self {
+        let template = patterns.table_template::<f32>();
         Self {
+            patterns,
             phase_count: PHASE_COUNT,
             weights: vec![template; PHASE_COUNT],
             visit_counts: None,
+            tc_accumulators: None,
+            features: None,
+            metadata: ModelMetadata::new(),
         }
     }
 
+    /// Adds zero-initialized per-phase weights for `features`, trained by the
+    /// same TD(lambda) step as the tuples.
+    pub fn with_scalar_features(mut self, features: Vec<ScalarFeature>) -> Result<Self, String> {
+        self.features = Some(ScalarFeatureWeights::new(features, self.phase_count)?);
+        Ok(self)
+    }
+
+    pub fn scalar_features(&self) -> Option<&ScalarFeatureWeights> {
+        self.features.as_ref()
+    }
+
     pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
         let bytes = decompress_model_bytes(data)?;
         Self::from_uncompressed_bytes(bytes.as_ref())
@@ -268,7 +899,8 @@ impl TrainableNTuple {
             ));
         }
 
-        let tuple_defs_len: usize = TUPLE_PATTERNS.iter().map(|pattern| 1 + pattern.len()).sum();
+        let patterns = self.patterns.patterns();
+        let tuple_defs_len: usize = patterns.iter().map(|pattern| 1 + pattern.len()).sum();
         let weights_bytes: usize = self
             .weights
             .iter()
@@ -283,7 +915,7 @@ impl TrainableNTuple {
             .visit_counts
             .as_ref()
             .cloned()
-            .unwrap_or_else(Self::zero_visit_counts);
+            .unwrap_or_else(|| self.zero_visit_counts());
         if visit_counts.len() != self.phase_count {
             return Err(format!(
                 "visit_counts phase length must match phase_count: expected {}, got {}",
@@ -302,20 +934,20 @@ impl TrainableNTuple {
             .sum();
         let mut data = Vec::with_capacity(tuple_defs_len + weights_bytes + visit_count_bytes);
 
-        for pattern in TUPLE_PATTERNS {
+        for pattern in patterns {
             data.push(pattern.len() as u8);
             data.extend_from_slice(pattern);
         }
 
         for (phase_idx, phase_weights) in self.weights.iter().enumerate() {
-            if phase_weights.len() != TUPLE_PATTERNS.len() {
+            if phase_weights.len() != patterns.len() {
                 return Err(format!(
                     "weights[{phase_idx}] tuple length must match tuple patterns length"
                 ));
             }
 
             for (tuple_idx, weights) in phase_weights.iter().enumerate() {
-                let expected_len = pow3(TUPLE_PATTERNS[tuple_idx].len())?;
+                let expected_len = pow3(patterns[tuple_idx].len())?;
                 if weights.len() != expected_len {
                     return Err(format!(
                         "weights[{phase_idx}][{tuple_idx}] length must be {expected_len}, got {}",
@@ -334,14 +966,14 @@ impl TrainableNTuple {
         }
 
         for (phase_idx, phase_counts) in visit_counts.iter().enumerate() {
-            if phase_counts.len() != TUPLE_PATTERNS.len() {
+            if phase_counts.len() != patterns.len() {
                 return Err(format!(
                     "visit_counts[{phase_idx}] tuple length must match tuple patterns length"
                 ));
             }
 
             for (tuple_idx, counts) in phase_counts.iter().enumerate() {
-                let expected_len = pow3(TUPLE_PATTERNS[tuple_idx].len())?;
+                let expected_len = pow3(patterns[tuple_idx].len())?;
                 if counts.len() != expected_len {
                     return Err(format!(
                         "visit_counts[{phase_idx}][{tuple_idx}] length must be {expected_len}, got {}",
@@ -354,15 +986,58 @@ impl TrainableNTuple {
             }
         }
 
-        let crc32 = crc32fast::hash(&data);
-        let mut output = Vec::with_capacity(20 + data.len());
-        output.extend_from_slice(MAGIC);
-        output.extend_from_slice(&VERSION.to_le_bytes());
-        output.extend_from_slice(&(TUPLE_PATTERNS.len() as u32).to_le_bytes());
-        output.extend_from_slice(&crc32.to_le_bytes());
-        output.extend_from_slice(&(self.phase_count as u32).to_le_bytes());
-        output.extend_from_slice(&data);
-        Ok(output)
+        if let Some(tc_accumulators) = &self.tc_accumulators {
+            if tc_accumulators.len() != self.phase_count {
+                return Err(format!(
+                    "tc_accumulators phase length must match phase_count: expected {}, got {}",
+                    self.phase_count,
+                    tc_accumulators.len()
+                ));
+            }
+            for (phase_idx, phase_accumulators) in tc_accumulators.iter().enumerate() {
+                if phase_accumulators.len() != patterns.len() {
+                    return Err(format!(
+                        "tc_accumulators[{phase_idx}] tuple length must match tuple patterns length"
+                    ));
+                }
+                for (tuple_idx, accumulators) in phase_accumulators.iter().enumerate() {
+                    let expected_len = pow3(patterns[tuple_idx].len())?;
+                    if accumulators.len() != expected_len {
+                        return Err(format!(
+                            "tc_accumulators[{phase_idx}][{tuple_idx}] length must be {expected_len}, got {}",
+                            accumulators.len()
+                        ));
+                    }
+                    for accumulator in accumulators {
+                        data.extend_from_slice(&accumulator.signed.to_le_bytes());
+                        data.extend_from_slice(&accumulator.absolute.to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        if let Some(features) = &self.features {
+            data.extend_from_slice(&features.to_block()?);
+        }
+
+        if !self.metadata.is_empty() {
+            data.extend_from_slice(&self.metadata.to_block()?);
+        }
+
+        let version = if self.tc_accumulators.is_some() {
+            VERSION_V6
+        } else {
+            VERSION
+        };
+        let crc32 = crc32fast::hash(&data);
+        let mut output = Vec::with_capacity(20 + data.len());
+        output.extend_from_slice(MAGIC);
+        output.extend_from_slice(&version.to_le_bytes());
+        output.extend_from_slice(&(patterns.len() as u32).to_le_bytes());
+        output.extend_from_slice(&crc32.to_le_bytes());
+        output.extend_from_slice(&(self.phase_count as u32).to_le_bytes());
+        output.extend_from_slice(&data);
+        Ok(output)
     }
 
     pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
@@ -370,15 +1045,41 @@ impl TrainableNTuple {
         compress_model_bytes(&output)
     }
 
+    /// Exports the weights as a zstd-compressed int16 NTRV v5 model for inference.
+    pub fn to_quantized_bytes(&self) -> Result<Vec<u8>, String> {
+        let metadata = (!self.metadata.is_empty()).then_some(&self.metadata);
+        let output = encode_quantized_model(
+            self.patterns.patterns(),
+            &self.weights,
+            self.features.as_ref(),
+            metadata,
+        )?;
+        compress_model_bytes(&output)
+    }
+
+    pub fn patterns(&self) -> &Arc<TuplePatternSet> {
+        &self.patterns
+    }
+
     pub fn raw_weights(&self) -> &[Vec<Vec<f32>>] {
         &self.weights
     }
 
+    /// Metadata written after the weights by `to_bytes`.
+    pub fn metadata(&self) -> &ModelMetadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut ModelMetadata {
+        &mut self.metadata
+    }
+
     fn merge_weighted(
-        workers: &[(TrainableNTuple, usize)],
+        workers: &[(&TrainableNTuple, usize)],
         total_games: usize,
     ) -> Result<Self, String> {
-        let mut merged = Self::new();
+        let mut merged = Self::with_patterns(merge_patterns(workers));
+        merged.features = merge_scalar_features(workers, total_games);
         if total_games == 0 {
             return Ok(merged);
         }
@@ -389,13 +1090,14 @@ impl TrainableNTuple {
             }
             merged.accumulate_scaled_from(network, (*games as f32) / (total_games as f32))?;
             merged.accumulate_visit_counts_from(network)?;
+            merged.accumulate_tc_accumulators_from(network);
         }
 
         Ok(merged)
     }
 
     fn merge_weighted_parallel(
-        workers: &[(TrainableNTuple, usize)],
+        workers: &[(&TrainableNTuple, usize)],
         total_games: usize,
         threads: usize,
     ) -> Result<Self, String> {
@@ -403,6 +1105,7 @@ impl TrainableNTuple {
             return Self::merge_weighted(workers, total_games);
         }
 
+        let patterns = merge_patterns(workers);
         let scales: Vec<f32> = workers
             .iter()
             .map(|(_, games)| (*games as f32) / (total_games as f32))
@@ -423,20 +1126,13 @@ impl TrainableNTuple {
                     }
                     let workers = workers;
                     let scales = &scales;
+                    let patterns = &patterns;
 
                     handles.push(scope.spawn(
                         move || -> Result<Vec<(usize, Vec<Vec<f32>>, Option<Vec<Vec<u32>>>)>, String> {
                             let mut phases = Vec::with_capacity(phase_count);
                             for phase_idx in phase_start..(phase_start + phase_count) {
-                                let mut phase_weights: Vec<Vec<f32>> = TUPLE_PATTERNS
-                                    .iter()
-                                    .map(|pattern| {
-                                        vec![
-                                            0.0;
-                                            pow3(pattern.len()).expect("tuple size must fit usize")
-                                        ]
-                                    })
-                                    .collect();
+                                let mut phase_weights = patterns.table_template::<f32>();
 
                                 for ((network, games), scale) in workers.iter().zip(scales.iter()) {
                                     if *games == 0 {
@@ -462,18 +1158,8 @@ impl TrainableNTuple {
                                     let Some(source_counts) = network.visit_counts.as_ref() else {
                                         continue;
                                     };
-                                    let target_counts = phase_counts.get_or_insert_with(|| {
-                                        TUPLE_PATTERNS
-                                            .iter()
-                                            .map(|pattern| {
-                                                vec![
-                                                    0u32;
-                                                    pow3(pattern.len())
-                                                        .expect("tuple size must fit usize")
-                                                ]
-                                            })
-                                            .collect()
-                                    });
+                                    let target_counts = phase_counts
+                                        .get_or_insert_with(|| patterns.table_template::<u32>());
                                     for (target_tuple_counts, source_tuple_counts) in target_counts
                                         .iter_mut()
                                         .zip(source_counts[phase_idx].iter())
@@ -505,7 +1191,8 @@ impl TrainableNTuple {
             },
         )?;
 
-        let mut merged = Self::new();
+        let mut merged = Self::with_patterns(patterns);
+        merged.features = merge_scalar_features(workers, total_games);
         for (phase_idx, phase_weights, phase_counts) in merged_phases {
             merged.weights[phase_idx] = phase_weights;
             if let Some(phase_counts) = phase_counts {
@@ -516,6 +1203,11 @@ impl TrainableNTuple {
                     .expect("visit counts must exist")[phase_idx] = phase_counts;
             }
         }
+        for (network, games) in workers {
+            if *games > 0 {
+                merged.accumulate_tc_accumulators_from(network);
+            }
+        }
         Ok(merged)
     }
 
@@ -536,18 +1228,16 @@ impl TrainableNTuple {
         }
 
         let version = read_u32_le(data, 4)?;
-        if version != VERSION_V3 && version != VERSION_V4 {
+        if !matches!(version, VERSION_V3 | VERSION_V4 | VERSION_V6) {
             return Err(format!(
-                "unsupported training weights version: expected {VERSION_V3} or {VERSION_V4}, got {version}"
+                "unsupported training weights version: expected {VERSION_V3}, {VERSION_V4} or {VERSION_V6}, got {version}"
             ));
         }
 
         let num_tuples = read_u32_le(data, 8)? as usize;
-        if num_tuples != TUPLE_PATTERNS.len() {
+        if num_tuples == 0 || num_tuples > MAX_TUPLE_COUNT {
             return Err(format!(
-                "tuple count mismatch: expected {}, got {}",
-                TUPLE_PATTERNS.len(),
-                num_tuples
+                "tuple count must be between 1 and {MAX_TUPLE_COUNT}, got {num_tuples}"
             ));
         }
 
@@ -568,7 +1258,8 @@ impl TrainableNTuple {
         }
 
         let mut offset = 0usize;
-        for (tuple_idx, pattern) in TUPLE_PATTERNS.iter().enumerate() {
+        let mut tuples = Vec::with_capacity(num_tuples);
+        for tuple_idx in 0..num_tuples {
             if offset >= payload.len() {
                 return Err(format!(
                     "unexpected EOF while reading tuple definition #{tuple_idx}"
@@ -577,34 +1268,21 @@ impl TrainableNTuple {
 
             let tuple_size = payload[offset] as usize;
             offset += 1;
-            if tuple_size != pattern.len() {
-                return Err(format!(
-                    "tuple_size mismatch at index {tuple_idx}: expected {}, got {tuple_size}",
-                    pattern.len()
-                ));
-            }
-
             let end = offset + tuple_size;
             if end > payload.len() {
                 return Err(format!(
                     "unexpected EOF while reading tuple positions #{tuple_idx}"
                 ));
             }
-
-            if &payload[offset..end] != *pattern {
-                return Err(format!(
-                    "tuple positions mismatch at index {tuple_idx}: expected {:?}, got {:?}",
-                    pattern,
-                    &payload[offset..end]
-                ));
-            }
+            tuples.push(payload[offset..end].to_vec());
             offset = end;
         }
+        let patterns = Arc::new(TuplePatternSet::new(tuples)?);
 
         let mut weights = Vec::with_capacity(PHASE_COUNT);
         for phase_idx in 0..PHASE_COUNT {
-            let mut phase_weights = Vec::with_capacity(TUPLE_PATTERNS.len());
-            for (tuple_idx, pattern) in TUPLE_PATTERNS.iter().enumerate() {
+            let mut phase_weights = Vec::with_capacity(patterns.len());
+            for (tuple_idx, pattern) in patterns.patterns().iter().enumerate() {
                 let entries = pow3(pattern.len())?;
                 let bytes_len = entries
                     .checked_mul(std::mem::size_of::<f32>())
@@ -635,11 +1313,11 @@ impl TrainableNTuple {
             weights.push(phase_weights);
         }
 
-        let visit_counts = if version == VERSION_V4 {
+        let visit_counts = if version != VERSION_V3 {
             let mut phase_counts_all = Vec::with_capacity(PHASE_COUNT);
             for phase_idx in 0..PHASE_COUNT {
-                let mut phase_counts = Vec::with_capacity(TUPLE_PATTERNS.len());
-                for (tuple_idx, pattern) in TUPLE_PATTERNS.iter().enumerate() {
+                let mut phase_counts = Vec::with_capacity(patterns.len());
+                for (tuple_idx, pattern) in patterns.patterns().iter().enumerate() {
                     let entries = pow3(pattern.len())?;
                     let bytes_len = entries
                         .checked_mul(std::mem::size_of::<u32>())
@@ -668,90 +1346,107 @@ impl TrainableNTuple {
             None
         };
 
-        if offset != payload.len() {
-            return Err("weights payload has trailing bytes".to_string());
-        }
+        let tc_accumulators = if version == VERSION_V6 {
+            let mut phase_accumulators_all = Vec::with_capacity(PHASE_COUNT);
+            for phase_idx in 0..PHASE_COUNT {
+                let mut phase_accumulators = Vec::with_capacity(patterns.len());
+                for (tuple_idx, pattern) in patterns.patterns().iter().enumerate() {
+                    let entries = pow3(pattern.len())?;
+                    let bytes_len = entries
+                        .checked_mul(2 * std::mem::size_of::<f32>())
+                        .ok_or_else(|| "TC accumulator byte length overflow".to_string())?;
+                    if offset + bytes_len > payload.len() {
+                        return Err(format!(
+                            "unexpected EOF while reading TC accumulators for phase #{phase_idx}, tuple #{tuple_idx}"
+                        ));
+                    }
+
+                    let mut tuple_accumulators = Vec::with_capacity(entries);
+                    for chunk in payload[offset..offset + bytes_len].chunks_exact(8) {
+                        let accumulator = TcAccumulator {
+                            signed: f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+                            absolute: f32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+                        };
+                        if !accumulator.signed.is_finite() || !accumulator.absolute.is_finite() {
+                            return Err(format!(
+                                "non-finite TC accumulator at phase #{phase_idx}, tuple #{tuple_idx}"
+                            ));
+                        }
+                        tuple_accumulators.push(accumulator);
+                    }
+
+                    phase_accumulators.push(tuple_accumulators);
+                    offset += bytes_len;
+                }
+                phase_accumulators_all.push(phase_accumulators);
+            }
+            Some(phase_accumulators_all)
+        } else {
+            None
+        };
+
+        let features = ScalarFeatureWeights::read_block(payload, &mut offset, phase_count)?;
+        // Training changes the scores a calibration was fitted to, so it is dropped.
+        Calibration::read_block(payload, &mut offset, phase_count)?;
+
+        let metadata = if offset == payload.len() {
+            ModelMetadata::new()
+        } else {
+            ModelMetadata::from_block(&payload[offset..])?.unwrap_or_default()
+        };
 
         Ok(Self {
+            patterns,
             phase_count,
             weights,
             visit_counts,
+            tc_accumulators,
+            features,
+            metadata,
         })
     }
 
-    fn zero_visit_counts() -> Vec<Vec<Vec<u32>>> {
-        let template = TUPLE_PATTERNS
-            .iter()
-            .map(|pattern| vec![0u32; pow3(pattern.len()).expect("tuple size must fit usize")])
-            .collect::<Vec<_>>();
-        vec![template; PHASE_COUNT]
+    fn zero_visit_counts(&self) -> Vec<Vec<Vec<u32>>> {
+        vec![self.patterns.table_template::<u32>(); PHASE_COUNT]
     }
 
     fn ensure_visit_counts(&mut self) {
         if self.visit_counts.is_none() {
-            self.visit_counts = Some(Self::zero_visit_counts());
+            self.visit_counts = Some(self.zero_visit_counts());
         }
     }
 
-    fn compute_feature_indices(board: &Board, is_black: bool) -> FeatureIndices {
-        let (black, white) = board.bitboards();
-        let (me, opp) = if is_black {
-            (black, white)
-        } else {
-            (white, black)
-        };
-        let mut indices = [[0u16; TUPLE_COUNT]; SYMMETRY_COUNT];
-
-        for symmetry in 0..SYMMETRY_COUNT {
-            for tuple_idx in 0..TUPLE_COUNT {
-                let compiled = &COMPILED_TUPLES[tuple_idx];
-                indices[symmetry][tuple_idx] = tuple_index(
-                    &compiled.transformed_positions[symmetry],
-                    compiled.len,
-                    me,
-                    opp,
-                ) as u16;
-            }
+    fn ensure_tc_accumulators(&mut self) {
+        if self.tc_accumulators.is_none() {
+            self.tc_accumulators = Some(vec![
+                self.patterns.table_template::<TcAccumulator>();
+                PHASE_COUNT
+            ]);
         }
-
-        indices
     }
 
-    fn update_feature_indices_from_transition(
-        previous: &FeatureIndices,
-        old_board: &Board,
-        new_board: &Board,
+    fn score(
+        &self,
+        board: &Board,
         is_black: bool,
-    ) -> FeatureIndices {
-        let (old_black, old_white) = old_board.bitboards();
-        let (new_black, new_white) = new_board.bitboards();
-        let mut indices = *previous;
-        let mut changed = (old_black ^ new_black) | (old_white ^ new_white);
-
-        while changed != 0 {
-            let pos = changed.trailing_zeros() as usize;
-            changed &= changed - 1;
-
-            let old_state =
-                cell_state_from_bitboards_for_player_view(old_black, old_white, pos, is_black);
-            let new_state =
-                cell_state_from_bitboards_for_player_view(new_black, new_white, pos, is_black);
-            if old_state == new_state {
-                continue;
-            }
+        phase_idx: usize,
+        indices: &FeatureIndices,
+    ) -> f32 {
+        let scalar_score = self.features.as_ref().map_or(0.0, |features| {
+            features.evaluate(board, is_black, phase_idx)
+        });
+        self.sum_feature_indices(phase_idx, indices) + scalar_score
+    }
 
-            let delta = (new_state - old_state) as i32;
-            for symmetry in 0..SYMMETRY_COUNT {
-                for occurrence in &POSITION_OCCURRENCES[symmetry][pos] {
-                    let radix = COMPILED_TUPLES[occurrence.tuple_idx].radix_weights
-                        [occurrence.cell_idx] as i32;
-                    indices[symmetry][occurrence.tuple_idx] =
-                        ((indices[symmetry][occurrence.tuple_idx] as i32) + delta * radix) as u16;
-                }
-            }
+    /// Moves each scalar feature weight by `delta` times the feature value.
+    fn apply_scalar_delta(&mut self, board: &Board, is_black: bool, phase_idx: usize, delta: f32) {
+        let Some(features) = self.features.as_mut() else {
+            return;
+        };
+        let values = features.values(board, is_black);
+        for (weight, value) in features.weights_mut()[phase_idx].iter_mut().zip(values) {
+            *weight += delta * value;
         }
-
-        indices
     }
 
     fn sum_feature_indices(&self, phase_idx: usize, indices: &FeatureIndices) -> f32 {
@@ -759,8 +1454,8 @@ impl TrainableNTuple {
         let phase_weights = &self.weights[phase_idx];
 
         for tuple_indices in indices {
-            for (tuple_idx, &index) in tuple_indices.iter().enumerate() {
-                score += phase_weights[tuple_idx][index as usize];
+            for (weights, &index) in phase_weights.iter().zip(tuple_indices) {
+                score += weights[index as usize];
             }
         }
 
@@ -771,8 +1466,8 @@ impl TrainableNTuple {
         let normalized_delta = delta * SYMMETRY_NORMALIZATION;
         let phase_weights = &mut self.weights[phase_idx];
         for tuple_indices in indices {
-            for (tuple_idx, &index) in tuple_indices.iter().enumerate() {
-                phase_weights[tuple_idx][index as usize] += normalized_delta;
+            for (weights, &index) in phase_weights.iter_mut().zip(tuple_indices) {
+                weights[index as usize] += normalized_delta;
             }
         }
     }
@@ -785,6 +1480,26 @@ impl TrainableNTuple {
         cumulative_td: f32,
         alpha_decay: AlphaDecayStrategy,
     ) {
+        if alpha_decay.requires_tc_accumulators() {
+            self.ensure_tc_accumulators();
+            let phase_weights = &mut self.weights[phase_idx];
+            let phase_accumulators = &mut self
+                .tc_accumulators
+                .as_mut()
+                .expect("TC accumulators must be initialized")[phase_idx];
+            for tuple_indices in indices {
+                for (tuple_idx, &index) in
+                    tuple_indices.iter().take(phase_weights.len()).enumerate()
+                {
+                    let accumulator = &mut phase_accumulators[tuple_idx][index as usize];
+                    let tc_alpha = alpha * accumulator.rate();
+                    accumulator.record(cumulative_td);
+                    phase_weights[tuple_idx][index as usize] +=
+                        clip_weight_update(tc_alpha * cumulative_td) * SYMMETRY_NORMALIZATION;
+                }
+            }
+            return;
+        }
         if !alpha_decay.requires_visit_counts() {
             self.apply_delta(
                 phase_idx,
@@ -801,7 +1516,7 @@ impl TrainableNTuple {
             .as_mut()
             .expect("visit counts must be initialized")[phase_idx];
         for tuple_indices in indices {
-            for (tuple_idx, &index) in tuple_indices.iter().enumerate() {
+            for (tuple_idx, &index) in tuple_indices.iter().take(phase_weights.len()).enumerate() {
                 let visit_count = &mut phase_counts[tuple_idx][index as usize];
                 *visit_count = visit_count.saturating_add(1);
                 let visit_alpha = alpha / (*visit_count as f32);
@@ -888,6 +1603,27 @@ impl TrainableNTuple {
         }
         Ok(())
     }
+
+    /// Adds `other`'s TC sums, which like visit counts add up across workers.
+    /// Both networks must share a pattern set.
+    fn accumulate_tc_accumulators_from(&mut self, other: &Self) {
+        let Some(other_accumulators) = other.tc_accumulators.as_ref() else {
+            return;
+        };
+        self.ensure_tc_accumulators();
+        for (target, source) in self
+            .tc_accumulators
+            .as_mut()
+            .expect("TC accumulators must exist")
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .zip(other_accumulators.iter().flatten().flatten())
+        {
+            target.signed += source.signed;
+            target.absolute += source.absolute;
+        }
+    }
 }
 
 impl Default for TrainableNTuple {
@@ -897,23 +1633,57 @@ impl Default for TrainableNTuple {
 }
 
 impl TrainingNetwork for TrainableNTuple {
+    fn tuple_patterns(&self) -> &TuplePatternSet {
+        &self.patterns
+    }
+
+    fn league_snapshot(&self) -> Option<TrainableNTuple> {
+        Some(Self {
+            patterns: Arc::clone(&self.patterns),
+            phase_count: self.phase_count,
+            weights: self.weights.clone(),
+            visit_counts: None,
+            tc_accumulators: None,
+            features: self.features.clone(),
+            metadata: ModelMetadata::new(),
+        })
+    }
+
+    fn phase_weight_norms(&self) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|phase_weights| {
+                phase_weights
+                    .iter()
+                    .flatten()
+                    .map(|weight| f64::from(*weight) * f64::from(*weight))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .collect()
+    }
+
     fn prepare_for_alpha_decay(&mut self, alpha_decay: AlphaDecayStrategy) -> Result<(), String> {
         if alpha_decay.requires_visit_counts() {
             self.ensure_visit_counts();
         }
+        if alpha_decay.requires_tc_accumulators() {
+            self.ensure_tc_accumulators();
+        }
         Ok(())
     }
 
     fn evaluate(&self, board: &Board, is_black: bool) -> f32 {
         let phase_idx = self.phase_index(board);
-        let indices = Self::compute_feature_indices(board, is_black);
-        self.sum_feature_indices(phase_idx, &indices)
+        let indices = self.patterns.feature_indices(board, is_black);
+        self.score(board, is_black, phase_idx, &indices)
     }
 
     fn update(&mut self, board: &Board, is_black: bool, delta: f32) {
         let phase_idx = self.phase_index(board);
-        let indices = Self::compute_feature_indices(board, is_black);
+        let indices = self.patterns.feature_indices(board, is_black);
         self.apply_delta(phase_idx, &indices, delta);
+        self.apply_scalar_delta(board, is_black, phase_idx, delta);
     }
 
     fn td_lambda_step(
@@ -928,8 +1698,8 @@ impl TrainingNetwork for TrainableNTuple {
         lambda_: f32,
     ) -> (f32, f32) {
         let phase_idx = self.phase_index(board);
-        let indices = Self::compute_feature_indices(board, is_black);
-        let current_value = self.sum_feature_indices(phase_idx, &indices);
+        let indices = self.patterns.feature_indices(board, is_black);
+        let current_value = self.score(board, is_black, phase_idx, &indices);
         let td_error = next_value - current_value;
         let next_cumulative_td = if let Some(previous_player) = next_player {
             let signed_lambda = if is_black == previous_player {
@@ -949,22 +1719,28 @@ impl TrainingNetwork for TrainableNTuple {
             next_cumulative_td,
             alpha_decay,
         );
+        self.apply_scalar_delta(
+            board,
+            is_black,
+            phase_idx,
+            clip_weight_update(alpha * next_cumulative_td),
+        );
         (current_value, next_cumulative_td)
     }
 
     fn evaluate_precomputed(
         &self,
-        _board: &Board,
-        _is_black: bool,
+        board: &Board,
+        is_black: bool,
         phase_idx: usize,
         feature_indices: &FeatureIndices,
     ) -> f32 {
-        self.sum_feature_indices(phase_idx, feature_indices)
+        self.score(board, is_black, phase_idx, feature_indices)
     }
 
     fn td_lambda_step_precomputed(
         &mut self,
-        _board: &Board,
+        board: &Board,
         is_black: bool,
         phase_idx: usize,
         feature_indices: &FeatureIndices,
@@ -975,7 +1751,7 @@ impl TrainingNetwork for TrainableNTuple {
         alpha_decay: AlphaDecayStrategy,
         lambda_: f32,
     ) -> (f32, f32) {
-        let current_value = self.sum_feature_indices(phase_idx, feature_indices);
+        let current_value = self.score(board, is_black, phase_idx, feature_indices);
         let td_error = next_value - current_value;
         let next_cumulative_td = if let Some(previous_player) = next_player {
             let signed_lambda = if is_black == previous_player {
@@ -995,110 +1771,602 @@ impl TrainingNetwork for TrainableNTuple {
             next_cumulative_td,
             alpha_decay,
         );
+        self.apply_scalar_delta(
+            board,
+            is_black,
+            phase_idx,
+            clip_weight_update(alpha * next_cumulative_td),
+        );
         (current_value, next_cumulative_td)
     }
 }
 
-pub struct TDLambdaTrainer<N> {
-    network: N,
-    base_alpha: f32,
-    alpha_decay: AlphaDecayStrategy,
-    alpha_decay_start_game: usize,
-    completed_games: usize,
-    lambda_: f32,
-    epsilon: f64,
-    random_opening_plies: usize,
-    rng: ChaCha8Rng,
-}
+/// Lets a trainer choose moves over a network it does not own, for league
+/// opponents and dataset generation.
+struct FrozenNetwork<'a>(&'a TrainableNTuple);
 
-#[derive(Debug, Clone, Copy)]
-enum WorkerMessage {
-    Progress(usize),
-    Done,
-}
+impl TrainingNetwork for FrozenNetwork<'_> {
+    fn evaluate(&self, board: &Board, is_black: bool) -> f32 {
+        self.0.evaluate(board, is_black)
+    }
 
-impl<N: TrainingNetwork> TDLambdaTrainer<N> {
-    pub fn new(
-        network: N,
-        alpha: f32,
-        lambda_: f32,
-        epsilon: f64,
-        seed: u64,
-        random_opening_plies: usize,
-    ) -> Result<Self, String> {
-        Self::new_with_alpha_decay(
-            network,
-            alpha,
-            AlphaDecayStrategy::None,
-            0,
-            lambda_,
-            epsilon,
-            seed,
-            random_opening_plies,
-        )
+    fn update(&mut self, _board: &Board, _is_black: bool, _delta: f32) {
+        unreachable!("frozen networks are never trained")
     }
 
-    pub fn new_with_alpha_decay(
-        network: N,
-        alpha: f32,
-        alpha_decay: AlphaDecayStrategy,
-        alpha_decay_start_game: usize,
-        lambda_: f32,
-        epsilon: f64,
-        seed: u64,
-        random_opening_plies: usize,
-    ) -> Result<Self, String> {
-        let mut network = network;
-        if alpha < 0.0 {
-            return Err(format!("alpha must be >= 0.0, got {alpha}"));
-        }
-        if !(0.0..=1.0).contains(&lambda_) {
-            return Err(format!("lambda_ must be in [0.0, 1.0], got {lambda_}"));
-        }
-        if !(0.0..=1.0).contains(&epsilon) {
-            return Err(format!("epsilon must be in [0.0, 1.0], got {epsilon}"));
-        }
-        network.prepare_for_alpha_decay(alpha_decay)?;
+    fn tuple_patterns(&self) -> &TuplePatternSet {
+        self.0.tuple_patterns()
+    }
 
-        Ok(Self {
-            network,
-            base_alpha: alpha,
-            alpha_decay,
-            alpha_decay_start_game,
-            completed_games: 0,
-            lambda_,
-            epsilon,
-            random_opening_plies,
-            rng: ChaCha8Rng::seed_from_u64(seed),
-        })
+    fn evaluate_precomputed(
+        &self,
+        board: &Board,
+        is_black: bool,
+        phase_idx: usize,
+        feature_indices: &FeatureIndices,
+    ) -> f32 {
+        self.0
+            .evaluate_precomputed(board, is_black, phase_idx, feature_indices)
     }
+}
 
-    pub fn train(
-        &mut self,
-        num_games: usize,
-        progress_interval: usize,
-        mut progress_callback: Option<ProgressCallback<'_>>,
-    ) -> Result<(), String> {
-        let start_time = Instant::now();
-        for game_idx in 1..=num_games {
-            self.play_one_game()?;
-            self.completed_games = self.completed_games.saturating_add(1);
-            if let Some(callback) = progress_callback.as_mut() {
-                if progress_interval > 0 && game_idx % progress_interval == 0 {
-                    callback(game_idx, num_games, start_time.elapsed().as_secs_f64())?;
-                }
-            }
+/// Running sums over a window of training games, additive across workers.
+#[derive(Debug, Clone, Default)]
+struct TrainingStats {
+    games: usize,
+    plies: usize,
+    black_wins: usize,
+    white_wins: usize,
+    updates: usize,
+    clipped_updates: usize,
+    abs_td_error_sums: [f64; PHASE_COUNT],
+    phase_updates: [usize; PHASE_COUNT],
+}
+
+impl TrainingStats {
+    fn record_game(&mut self, final_board: &Board) {
+        let (black, white) = final_board.count();
+        self.games += 1;
+        self.plies += (black + white).saturating_sub(4) as usize;
+        match black.cmp(&white) {
+            std::cmp::Ordering::Greater => self.black_wins += 1,
+            std::cmp::Ordering::Less => self.white_wins += 1,
+            std::cmp::Ordering::Equal => {}
+        }
+    }
+
+    fn record_update(&mut self, phase_idx: usize, td_error: f32, clipped: bool) {
+        self.updates += 1;
+        self.clipped_updates += usize::from(clipped);
+        self.abs_td_error_sums[phase_idx] += f64::from(td_error.abs());
+        self.phase_updates[phase_idx] += 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.games += other.games;
+        self.plies += other.plies;
+        self.black_wins += other.black_wins;
+        self.white_wins += other.white_wins;
+        self.updates += other.updates;
+        self.clipped_updates += other.clipped_updates;
+        for phase_idx in 0..PHASE_COUNT {
+            self.abs_td_error_sums[phase_idx] += other.abs_td_error_sums[phase_idx];
+            self.phase_updates[phase_idx] += other.phase_updates[phase_idx];
+        }
+    }
+}
+
+/// Statistics over the games played since the previous progress report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingMetrics {
+    pub games: usize,
+    /// Plies per game, including random opening plies.
+    pub mean_game_length: f64,
+    pub black_win_rate: f64,
+    pub white_win_rate: f64,
+    pub mean_abs_td_error: f64,
+    /// `None` for phases without any position in the window.
+    pub phase_mean_abs_td_error: Vec<Option<f64>>,
+    /// Fraction of updates whose step `alpha * td` exceeded `MAX_ABS_WEIGHT_UPDATE`.
+    pub clipped_update_fraction: f64,
+    /// L2 norm of all tuple weights at the time of the report.
+    pub weight_norm: f64,
+    pub phase_weight_norms: Vec<f64>,
+}
+
+impl TrainingMetrics {
+    fn new(stats: &TrainingStats, phase_weight_norms: &[f64]) -> Self {
+        let ratio = |count: usize, total: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f64 / total as f64
+            }
+        };
+        let total_abs_td_error: f64 = stats.abs_td_error_sums.iter().sum();
+        Self {
+            games: stats.games,
+            mean_game_length: ratio(stats.plies, stats.games),
+            black_win_rate: ratio(stats.black_wins, stats.games),
+            white_win_rate: ratio(stats.white_wins, stats.games),
+            mean_abs_td_error: if stats.updates == 0 {
+                0.0
+            } else {
+                total_abs_td_error / stats.updates as f64
+            },
+            phase_mean_abs_td_error: stats
+                .abs_td_error_sums
+                .iter()
+                .zip(stats.phase_updates)
+                .map(|(sum, count)| (count > 0).then(|| sum / count as f64))
+                .collect(),
+            clipped_update_fraction: ratio(stats.clipped_updates, stats.updates),
+            weight_norm: phase_weight_norms
+                .iter()
+                .map(|norm| norm * norm)
+                .sum::<f64>()
+                .sqrt(),
+            phase_weight_norms: phase_weight_norms.to_vec(),
+        }
+    }
+}
+
+/// Payload of a [`ProgressCallback`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub elapsed_seconds: f64,
+    pub metrics: TrainingMetrics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrainingLogFormat {
+    Csv,
+    Jsonl,
+}
+
+/// Appends one [`TrainingProgress`] record per report to a `.csv` or `.jsonl`
+/// file, flushing after every record so the log can be followed live.
+pub struct TrainingLog {
+    writer: BufWriter<File>,
+    format: TrainingLogFormat,
+    needs_header: bool,
+}
+
+impl TrainingLog {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let (writer, format, needs_header) = open_log(path, "training log")?;
+        Ok(Self {
+            writer,
+            format,
+            needs_header,
+        })
+    }
+
+    pub fn write(&mut self, progress: &TrainingProgress) -> Result<(), String> {
+        let line = match self.format {
+            TrainingLogFormat::Csv => {
+                if self.needs_header {
+                    let header = csv_header(progress.metrics.phase_weight_norms.len());
+                    writeln!(self.writer, "{header}")
+                        .map_err(|err| format!("failed to write training log: {err}"))?;
+                    self.needs_header = false;
+                }
+                csv_record(progress)
+            }
+            TrainingLogFormat::Jsonl => json_record(progress),
+        };
+        writeln!(self.writer, "{line}")
+            .and_then(|()| self.writer.flush())
+            .map_err(|err| format!("failed to write training log: {err}"))
+    }
+}
+
+/// Opens a `.csv` or `.jsonl` log for appending. The flag is set when a CSV
+/// log is still empty and needs its header.
+fn open_log(path: &Path, kind: &str) -> Result<(BufWriter<File>, TrainingLogFormat, bool), String> {
+    let format = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => TrainingLogFormat::Csv,
+        Some("jsonl") => TrainingLogFormat::Jsonl,
+        _ => {
+            return Err(format!(
+                "{kind} '{}' must end in .csv or .jsonl",
+                path.display()
+            ));
+        }
+    };
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| format!("failed to open {kind} '{}': {err}", path.display()))?;
+    let needs_header = format == TrainingLogFormat::Csv
+        && file
+            .metadata()
+            .map_err(|err| format!("failed to stat '{}': {err}", path.display()))?
+            .len()
+            == 0;
+    Ok((BufWriter::new(file), format, needs_header))
+}
+
+const LOG_SCALAR_FIELDS: [&str; 10] = [
+    "completed",
+    "total",
+    "elapsed_seconds",
+    "games",
+    "mean_game_length",
+    "black_win_rate",
+    "white_win_rate",
+    "mean_abs_td_error",
+    "clipped_update_fraction",
+    "weight_norm",
+];
+
+fn log_scalar_values(progress: &TrainingProgress) -> [String; 10] {
+    let metrics = &progress.metrics;
+    [
+        progress.completed.to_string(),
+        progress.total.to_string(),
+        progress.elapsed_seconds.to_string(),
+        metrics.games.to_string(),
+        metrics.mean_game_length.to_string(),
+        metrics.black_win_rate.to_string(),
+        metrics.white_win_rate.to_string(),
+        metrics.mean_abs_td_error.to_string(),
+        metrics.clipped_update_fraction.to_string(),
+        metrics.weight_norm.to_string(),
+    ]
+}
+
+fn csv_header(phase_count: usize) -> String {
+    let mut columns: Vec<String> = LOG_SCALAR_FIELDS
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    columns.extend((0..PHASE_COUNT).map(|phase| format!("phase_{phase}_mean_abs_td_error")));
+    columns.extend((0..phase_count).map(|phase| format!("phase_{phase}_weight_norm")));
+    columns.join(",")
+}
+
+fn csv_record(progress: &TrainingProgress) -> String {
+    let metrics = &progress.metrics;
+    let mut values = log_scalar_values(progress).to_vec();
+    values.extend(
+        metrics
+            .phase_mean_abs_td_error
+            .iter()
+            .map(|value| value.map(|value| value.to_string()).unwrap_or_default()),
+    );
+    values.extend(
+        metrics
+            .phase_weight_norms
+            .iter()
+            .map(|value| value.to_string()),
+    );
+    values.join(",")
+}
+
+fn json_record(progress: &TrainingProgress) -> String {
+    let metrics = &progress.metrics;
+    let mut fields: Vec<String> = LOG_SCALAR_FIELDS
+        .iter()
+        .zip(log_scalar_values(progress))
+        .map(|(name, value)| format!("\"{name}\":{}", json_number(&value)))
+        .collect();
+    let td_errors: Vec<String> = metrics
+        .phase_mean_abs_td_error
+        .iter()
+        .map(|value| {
+            value.map_or_else(
+                || "null".to_string(),
+                |value| json_number(&value.to_string()),
+            )
+        })
+        .collect();
+    fields.push(format!(
+        "\"phase_mean_abs_td_error\":[{}]",
+        td_errors.join(",")
+    ));
+    let norms: Vec<String> = metrics
+        .phase_weight_norms
+        .iter()
+        .map(|value| json_number(&value.to_string()))
+        .collect();
+    fields.push(format!("\"phase_weight_norms\":[{}]", norms.join(",")));
+    format!("{{{}}}", fields.join(","))
+}
+
+/// JSON has no NaN or infinity literals.
+fn json_number(value: &str) -> String {
+    if value.parse::<f64>().is_ok_and(f64::is_finite) {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+/// Settings of one [`TDLambdaTrainer`]. The default trainer never learns
+/// and always plays its best move at [`DEFAULT_TRAINING_SEARCH_DEPTH`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainerConfig {
+    pub alpha: f32,
+    pub alpha_decay: AlphaDecayStrategy,
+    /// Games the alpha decay and epsilon schedules count as already played.
+    pub alpha_decay_start_game: usize,
+    pub lambda_: f32,
+    pub epsilon: f64,
+    pub random_opening_plies: usize,
+    pub search_depth: u8,
+    pub exact_td_empties: u8,
+    pub epsilon_schedule: EpsilonSchedule,
+    pub exploration: ExplorationPolicy,
+}
+
+impl Default for TrainerConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.0,
+            alpha_decay: AlphaDecayStrategy::None,
+            alpha_decay_start_game: 0,
+            lambda_: 0.0,
+            epsilon: 0.0,
+            random_opening_plies: 0,
+            search_depth: DEFAULT_TRAINING_SEARCH_DEPTH,
+            exact_td_empties: 0,
+            epsilon_schedule: EpsilonSchedule::Constant,
+            exploration: ExplorationPolicy::Uniform,
+        }
+    }
+}
+
+pub struct TDLambdaTrainer<N> {
+    network: N,
+    base_alpha: f32,
+    alpha_decay: AlphaDecayStrategy,
+    alpha_decay_start_game: usize,
+    completed_games: usize,
+    /// Run game at which the epsilon schedule starts and the run games each
+    /// of this trainer's games stands for; shared by every worker of a run.
+    schedule_start_game: usize,
+    schedule_games_per_game: usize,
+    lambda_: f32,
+    epsilon: f64,
+    epsilon_schedule: EpsilonSchedule,
+    exploration: ExplorationPolicy,
+    random_opening_plies: usize,
+    /// Plies searched when choosing a self-play move.
+    search_depth: u8,
+    /// Positions with at most this many empties are solved exactly and the
+    /// solved score replaces the bootstrapped TD target; `0` disables it.
+    exact_td_empties: u8,
+    /// Opponent pool; `None` trains by plain self-play.
+    league: Option<League>,
+    rng: ChaCha8Rng,
+    stats: TrainingStats,
+}
+
+#[derive(Debug)]
+enum WorkerMessage {
+    Progress {
+        worker_idx: usize,
+        games: usize,
+        stats: Box<TrainingStats>,
+        phase_weight_norms: Vec<f64>,
+    },
+    Done,
+}
+
+impl<N: TrainingNetwork> TDLambdaTrainer<N> {
+    pub fn new(
+        network: N,
+        alpha: f32,
+        lambda_: f32,
+        epsilon: f64,
+        seed: u64,
+        random_opening_plies: usize,
+    ) -> Result<Self, String> {
+        let config = TrainerConfig {
+            alpha,
+            lambda_,
+            epsilon,
+            random_opening_plies,
+            ..TrainerConfig::default()
+        };
+        Self::with_config(network, &config, seed)
+    }
+
+    pub fn with_config(network: N, config: &TrainerConfig, seed: u64) -> Result<Self, String> {
+        let TrainerConfig {
+            alpha,
+            alpha_decay,
+            alpha_decay_start_game,
+            lambda_,
+            epsilon,
+            random_opening_plies,
+            search_depth,
+            exact_td_empties,
+            epsilon_schedule,
+            exploration,
+        } = *config;
+        let mut network = network;
+        if alpha < 0.0 {
+            return Err(format!("alpha must be >= 0.0, got {alpha}"));
+        }
+        if !(0.0..=1.0).contains(&lambda_) {
+            return Err(format!("lambda_ must be in [0.0, 1.0], got {lambda_}"));
+        }
+        if !(0.0..=1.0).contains(&epsilon) {
+            return Err(format!("epsilon must be in [0.0, 1.0], got {epsilon}"));
+        }
+        if search_depth == 0 {
+            return Err("search_depth must be at least 1".to_string());
+        }
+        if exact_td_empties > MAX_EXACT_TD_EMPTIES {
+            return Err(format!(
+                "exact_td_empties must be at most {MAX_EXACT_TD_EMPTIES}, got {exact_td_empties}"
+            ));
+        }
+        epsilon_schedule.validate()?;
+        exploration.validate()?;
+        network.prepare_for_alpha_decay(alpha_decay)?;
+
+        Ok(Self {
+            network,
+            base_alpha: alpha,
+            alpha_decay,
+            alpha_decay_start_game,
+            completed_games: 0,
+            schedule_start_game: alpha_decay_start_game,
+            schedule_games_per_game: 1,
+            lambda_,
+            epsilon,
+            epsilon_schedule,
+            exploration,
+            random_opening_plies,
+            search_depth,
+            exact_td_empties,
+            league: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            stats: TrainingStats::default(),
+        })
+    }
+
+    /// Run games the epsilon schedule has advanced by.
+    fn schedule_game(&self) -> usize {
+        self.schedule_start_game.saturating_add(
+            self.completed_games
+                .saturating_mul(self.schedule_games_per_game),
+        )
+    }
+
+    /// Follows the epsilon schedule of a run with `worker_count` workers
+    /// starting at `start_game`.
+    fn share_schedule(&mut self, start_game: usize, worker_count: usize) {
+        self.schedule_start_game = start_game;
+        self.schedule_games_per_game = worker_count.max(1);
+    }
+
+    /// Trains against the opponent pool of `league` instead of pure self-play.
+    pub fn with_league(mut self, league: LeagueConfig) -> Result<Self, String> {
+        league.validate()?;
+        self.league = Some(League {
+            config: league,
+            snapshots: VecDeque::new(),
+        });
+        Ok(self)
+    }
+
+    pub fn train(
+        &mut self,
+        num_games: usize,
+        progress_interval: usize,
+        mut progress_callback: Option<ProgressCallback<'_>>,
+    ) -> Result<(), String> {
+        let start_time = Instant::now();
+        for game_idx in 1..=num_games {
+            self.train_one_game()?;
+            if let Some(callback) = progress_callback.as_mut() {
+                if progress_interval > 0 && game_idx % progress_interval == 0 {
+                    callback(&self.take_progress(game_idx, num_games, start_time))?;
+                }
+            }
         }
 
         if let Some(callback) = progress_callback.as_mut() {
             if progress_interval > 0 && num_games > 0 && num_games % progress_interval != 0 {
-                callback(num_games, num_games, start_time.elapsed().as_secs_f64())?;
+                callback(&self.take_progress(num_games, num_games, start_time))?;
             }
         }
 
         Ok(())
     }
 
+    /// Builds a progress report from the games since the previous one.
+    fn take_progress(
+        &mut self,
+        completed: usize,
+        total: usize,
+        start_time: Instant,
+    ) -> TrainingProgress {
+        let stats = std::mem::take(&mut self.stats);
+        TrainingProgress {
+            completed,
+            total,
+            elapsed_seconds: start_time.elapsed().as_secs_f64(),
+            metrics: TrainingMetrics::new(&stats, &self.network.phase_weight_norms()),
+        }
+    }
+
+    fn train_one_game(&mut self) -> Result<(), String> {
+        // The pool is taken out for the game so snapshot opponents can borrow
+        // it while the learner's network trains.
+        let league = self.league.take();
+        let result = match &league {
+            Some(league) => {
+                let opponent = self.sample_league_opponent(league);
+                self.play_one_game(opponent)
+            }
+            None => self.play_one_game(None),
+        };
+        self.league = league;
+        result?;
+        self.completed_games = self.completed_games.saturating_add(1);
+
+        if let Some(league) = &mut self.league {
+            let interval = league.config.snapshot_interval;
+            if interval > 0
+                && self.completed_games.is_multiple_of(interval)
+                && let Some(snapshot) = self.network.league_snapshot()
+            {
+                if league.snapshots.len() == league.config.max_snapshots {
+                    league.snapshots.pop_front();
+                }
+                league.snapshots.push_back(snapshot);
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the opponent of the next game and the learner's color, or
+    /// `None` for a self-play game.
+    fn sample_league_opponent<'a>(
+        &mut self,
+        league: &'a League,
+    ) -> Option<(LeagueOpponent<'a>, bool)> {
+        let config = league.config;
+        if self.rng.gen_bool(config.self_play) {
+            return None;
+        }
+        let positional = usize::from(config.positional_depth > 0);
+        let pool_size = league.snapshots.len() + positional + usize::from(config.random);
+        if pool_size == 0 {
+            return None;
+        }
+        let choice = self.rng.gen_range(0..pool_size);
+        let learner_is_black = self.rng.gen_bool(0.5);
+        let opponent = if let Some(snapshot) = league.snapshots.get(choice) {
+            LeagueOpponent::Snapshot(Box::new(
+                TDLambdaTrainer::with_config(
+                    FrozenNetwork(snapshot),
+                    &TrainerConfig {
+                        search_depth: self.search_depth,
+                        ..TrainerConfig::default()
+                    },
+                    0,
+                )
+                .expect("snapshot opponent settings are valid"),
+            ))
+        } else if choice == league.snapshots.len() && positional > 0 {
+            LeagueOpponent::Positional {
+                depth: config.positional_depth,
+            }
+        } else {
+            LeagueOpponent::Random
+        };
+        Some((opponent, learner_is_black))
+    }
+
     pub fn into_network(self) -> N {
         self.network
     }
@@ -1111,19 +2379,29 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         )
     }
 
-    fn play_one_game(&mut self) -> Result<(), String> {
+    /// Plays one game and trains on it. Against a league opponent, given with
+    /// the learner's color, only the learner's positions are trained on.
+    fn play_one_game(
+        &mut self,
+        mut opponent: Option<(LeagueOpponent<'_>, bool)>,
+    ) -> Result<(), String> {
+        let learner = opponent
+            .as_ref()
+            .map(|(_, learner_is_black)| *learner_is_black);
         let mut board = Board::new();
         let mut is_black = true;
         let mut consecutive_passes = 0usize;
         let mut history: Vec<TrainingHistoryEntry> = Vec::with_capacity(60);
-        let mut black_feature_indices = TrainableNTuple::compute_feature_indices(&board, true);
-        let mut white_feature_indices = TrainableNTuple::compute_feature_indices(&board, false);
+        let mut black_feature_indices = self.network.tuple_patterns().feature_indices(&board, true);
+        let mut white_feature_indices =
+            self.network.tuple_patterns().feature_indices(&board, false);
 
         self.apply_random_opening(
             &mut board,
             &mut is_black,
             &mut consecutive_passes,
             &mut history,
+            learner,
             &mut black_feature_indices,
             &mut white_feature_indices,
         )?;
@@ -1142,25 +2420,49 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
             } else {
                 &white_feature_indices
             };
-            let mv = self.select_move_with_feature_indices(
-                &board,
-                is_black,
-                legal,
-                current_feature_indices,
-            )?;
-            self.push_history_entry(&mut history, &board, is_black, current_feature_indices);
+            let mv = match &mut opponent {
+                Some((opponent, learner_is_black)) if *learner_is_black != is_black => {
+                    match opponent {
+                        LeagueOpponent::Snapshot(trainer) => {
+                            trainer.select_move(&board, is_black, legal)?
+                        }
+                        LeagueOpponent::Positional { depth } => {
+                            choose_positional_move(&board, is_black, *depth, 0)
+                                .expect("positional player moves when it has legal moves")
+                        }
+                        LeagueOpponent::Random => {
+                            nth_move_from_mask(legal, self.rng.gen_range(0..legal.count_ones()))
+                        }
+                    }
+                }
+                _ => {
+                    let mv = self.select_move_with_feature_indices(
+                        &board,
+                        is_black,
+                        legal,
+                        current_feature_indices,
+                    )?;
+                    self.push_history_entry(
+                        &mut history,
+                        &board,
+                        is_black,
+                        current_feature_indices,
+                    );
+                    mv
+                }
+            };
             let previous_board = board;
             let flipped = board.place(mv, is_black);
             if flipped == 0 {
                 return Err(format!("selected illegal move: {mv}"));
             }
-            black_feature_indices = TrainableNTuple::update_feature_indices_from_transition(
+            black_feature_indices = self.network.tuple_patterns().update_feature_indices(
                 &black_feature_indices,
                 &previous_board,
                 &board,
                 true,
             );
-            white_feature_indices = TrainableNTuple::update_feature_indices_from_transition(
+            white_feature_indices = self.network.tuple_patterns().update_feature_indices(
                 &white_feature_indices,
                 &previous_board,
                 &board,
@@ -1170,6 +2472,7 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         }
 
         self.update_weights(&history, &board)?;
+        self.stats.record_game(&board);
         Ok(())
     }
 
@@ -1179,6 +2482,7 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         is_black: &mut bool,
         consecutive_passes: &mut usize,
         history: &mut Vec<TrainingHistoryEntry>,
+        learner: Option<bool>,
         black_feature_indices: &mut FeatureIndices,
         white_feature_indices: &mut FeatureIndices,
     ) -> Result<(), String> {
@@ -1199,19 +2503,21 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
             } else {
                 &*white_feature_indices
             };
-            self.push_history_entry(history, board, *is_black, current_feature_indices);
+            if learner.is_none_or(|learner_is_black| learner_is_black == *is_black) {
+                self.push_history_entry(history, board, *is_black, current_feature_indices);
+            }
             let previous_board = *board;
             let flipped = board.place(mv, *is_black);
             if flipped == 0 {
                 return Err(format!("selected illegal random opening move: {mv}"));
             }
-            *black_feature_indices = TrainableNTuple::update_feature_indices_from_transition(
+            *black_feature_indices = self.network.tuple_patterns().update_feature_indices(
                 &*black_feature_indices,
                 &previous_board,
                 board,
                 true,
             );
-            *white_feature_indices = TrainableNTuple::update_feature_indices_from_transition(
+            *white_feature_indices = self.network.tuple_patterns().update_feature_indices(
                 &*white_feature_indices,
                 &previous_board,
                 board,
@@ -1224,9 +2530,11 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         Ok(())
     }
 
-    #[allow(dead_code)]
     fn select_move(&mut self, board: &Board, is_black: bool, legal: u64) -> Result<usize, String> {
-        let player_feature_indices = TrainableNTuple::compute_feature_indices(board, is_black);
+        let player_feature_indices = self
+            .network
+            .tuple_patterns()
+            .feature_indices(board, is_black);
         self.select_move_with_feature_indices(board, is_black, legal, &player_feature_indices)
     }
 
@@ -1241,13 +2549,22 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
             return Err("legal move mask contains no moves".to_string());
         }
 
-        let move_count = legal.count_ones();
-        if self.rng.gen_bool(self.epsilon) {
-            let choice = self.rng.gen_range(0..move_count);
-            return Ok(nth_move_from_mask(legal, choice));
+        let epsilon = self
+            .epsilon_schedule
+            .epsilon_for(self.epsilon, self.schedule_game(), board);
+        if self.rng.gen_bool(epsilon) {
+            return match self.exploration {
+                ExplorationPolicy::Uniform => {
+                    let choice = self.rng.gen_range(0..legal.count_ones());
+                    Ok(nth_move_from_mask(legal, choice))
+                }
+                ExplorationPolicy::Boltzmann { temperature } => {
+                    self.sample_boltzmann_move(board, is_black, legal, temperature)
+                }
+            };
         }
 
-        if TRAINING_SEARCH_DEPTH == 2 {
+        if self.search_depth == 2 {
             return self.select_move_depth_two(board, is_black, legal, player_feature_indices);
         }
 
@@ -1258,13 +2575,13 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         let mut alpha = f32::NEG_INFINITY;
 
         for mv in moves {
-            let score = if TRAINING_SEARCH_DEPTH == 1 {
+            let score = if self.search_depth == 1 {
                 -mv.next_player_eval
             } else {
                 -self.search_training_position(
                     &mv.next_board,
                     !is_black,
-                    TRAINING_SEARCH_DEPTH - 1,
+                    self.search_depth - 1,
                     -beta,
                     -alpha,
                 )?
@@ -1279,6 +2596,53 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         Ok(best_move)
     }
 
+    /// Samples a move with probability proportional to
+    /// `exp(score / temperature)`, scoring every move with a full-window search.
+    fn sample_boltzmann_move(
+        &mut self,
+        board: &Board,
+        is_black: bool,
+        legal: u64,
+        temperature: f64,
+    ) -> Result<usize, String> {
+        let mut moves = Vec::with_capacity(legal.count_ones() as usize);
+        let mut remaining = legal;
+        while remaining != 0 {
+            let mv = remaining.trailing_zeros() as usize;
+            remaining &= remaining - 1;
+
+            let mut next_board = *board;
+            if next_board.place(mv, is_black) == 0 {
+                return Err(format!("selected illegal move: {mv}"));
+            }
+            let score = -self.search_training_position(
+                &next_board,
+                !is_black,
+                self.search_depth - 1,
+                f32::NEG_INFINITY,
+                f32::INFINITY,
+            )?;
+            moves.push((mv, f64::from(score)));
+        }
+
+        let max_score = moves
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = moves
+            .iter()
+            .map(|(_, score)| ((score - max_score) / temperature).exp())
+            .collect();
+        let mut threshold = self.rng.gen_range(0.0..weights.iter().sum::<f64>());
+        for ((mv, _), weight) in moves.iter().zip(&weights) {
+            if threshold < *weight {
+                return Ok(*mv);
+            }
+            threshold -= weight;
+        }
+        Ok(moves[moves.len() - 1].0)
+    }
+
     fn select_move_depth_two(
         &self,
         board: &Board,
@@ -1301,7 +2665,7 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
             if flipped == 0 {
                 return Err(format!("selected illegal move: {mv}"));
             }
-            let child_next_player_indices = TrainableNTuple::update_feature_indices_from_transition(
+            let child_next_player_indices = self.network.tuple_patterns().update_feature_indices(
                 player_feature_indices,
                 board,
                 &next_board,
@@ -1404,7 +2768,10 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
         let next_player_indices = if let Some(indices) = next_player_indices {
             indices
         } else {
-            owned_next_player_indices = TrainableNTuple::compute_feature_indices(board, !is_black);
+            owned_next_player_indices = self
+                .network
+                .tuple_patterns()
+                .feature_indices(board, !is_black);
             &owned_next_player_indices
         };
         let mut remaining = legal;
@@ -1421,7 +2788,7 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
                 return Err(format!("selected illegal move: {mv}"));
             }
             let phase_idx = phase_index_for_board(&next_board, PHASE_COUNT);
-            let delta_indices = TrainableNTuple::update_feature_indices_from_transition(
+            let delta_indices = self.network.tuple_patterns().update_feature_indices(
                 &next_player_indices,
                 board,
                 &next_board,
@@ -1458,17 +2825,31 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
             return Ok(());
         }
 
-        let reward = terminal_training_score(final_board, true);
-
-        let mut next_value = if history.last().expect("history must be non-empty").is_black {
-            reward
-        } else {
-            -reward
-        };
+        // The value of the later position and whose view it is from; the
+        // previous entry is usually the other player, but not after a pass
+        // or when only a league learner's positions are recorded.
+        let mut later_value = terminal_training_score(final_board, true);
+        let mut later_player = true;
         let mut cumulative_td = 0.0f32;
         let mut next_player: Option<bool> = None;
+        let alpha = self.current_alpha();
+        let mut solver = exact_game_solver();
 
         for entry in history.iter().rev() {
+            let mut next_value = if entry.is_black == later_player {
+                later_value
+            } else {
+                -later_value
+            };
+            // A solved position needs no later return, and its predecessor
+            // bootstraps from the exact score without continuing the trace.
+            let exact_value = (self.exact_td_empties > 0
+                && entry.board.empty_count() <= self.exact_td_empties)
+                .then(|| exact_training_score(&mut solver, &entry.board, entry.is_black));
+            if let Some(exact_value) = exact_value {
+                next_value = exact_value;
+                next_player = None;
+            }
             ensure_finite(next_value, "td-lambda next_value")?;
             ensure_finite(cumulative_td, "td-lambda cumulative_td")?;
             let (current_value, next_cumulative_td) = self.network.td_lambda_step_precomputed(
@@ -1479,13 +2860,20 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
                 next_value,
                 cumulative_td,
                 next_player,
-                self.current_alpha(),
+                alpha,
                 self.alpha_decay,
                 self.lambda_,
             );
             cumulative_td = ensure_finite(next_cumulative_td, "td-lambda cumulative_td")?;
-            next_value = -ensure_finite(current_value, "td-lambda current_value")?;
-            next_player = Some(entry.is_black);
+            let current_value = ensure_finite(current_value, "td-lambda current_value")?;
+            self.stats.record_update(
+                entry.phase_idx,
+                next_value - current_value,
+                (alpha * cumulative_td).abs() > MAX_ABS_WEIGHT_UPDATE,
+            );
+            later_value = exact_value.unwrap_or(current_value);
+            later_player = entry.is_black;
+            next_player = exact_value.is_none().then_some(entry.is_black);
         }
 
         Ok(())
@@ -1515,7 +2903,10 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
     ) -> Result<Vec<ScoredTrainingMove>, String> {
         let mut moves = Vec::with_capacity(legal.count_ones() as usize);
         let mut remaining = legal;
-        let next_player_indices = TrainableNTuple::compute_feature_indices(board, !is_black);
+        let next_player_indices = self
+            .network
+            .tuple_patterns()
+            .feature_indices(board, !is_black);
 
         while remaining != 0 {
             let mv = remaining.trailing_zeros() as usize;
@@ -1527,7 +2918,7 @@ impl<N: TrainingNetwork> TDLambdaTrainer<N> {
                 return Err(format!("selected illegal move: {mv}"));
             }
             let phase_idx = phase_index_for_board(&next_board, PHASE_COUNT);
-            let delta_indices = TrainableNTuple::update_feature_indices_from_transition(
+            let delta_indices = self.network.tuple_patterns().update_feature_indices(
                 &next_player_indices,
                 board,
                 &next_board,
@@ -1600,7 +2991,7 @@ pub fn train_to_bytes_with_alpha_decay(
     progress_interval: usize,
     progress_callback: Option<ProgressCallback<'_>>,
 ) -> Result<Vec<u8>, String> {
-    let network = train_network(
+    train_to_bytes_with_layout(
         games,
         alpha,
         alpha_decay,
@@ -1610,46 +3001,52 @@ pub fn train_to_bytes_with_alpha_decay(
         seed,
         threads,
         initial_model,
+        None,
         random_opening_plies,
         progress_interval,
         progress_callback,
-    )?;
-    network.to_bytes()
+    )
 }
 
-pub fn train_to_uncompressed_bytes(
+/// Like `train_to_bytes_with_alpha_decay`, but a fresh network is built from
+/// `layout` instead of [`TUPLE_PATTERNS`] alone. When continuing from
+/// `initial_model`, the layout must match the one stored in that model.
+pub fn train_to_bytes_with_layout(
     games: usize,
     alpha: f32,
+    alpha_decay: AlphaDecayStrategy,
+    alpha_decay_start_game: usize,
     lambda_: f32,
     epsilon: f64,
     seed: u64,
     threads: usize,
     initial_model: Option<&[u8]>,
+    layout: Option<&NetworkLayout>,
     random_opening_plies: usize,
     progress_interval: usize,
     progress_callback: Option<ProgressCallback<'_>>,
 ) -> Result<Vec<u8>, String> {
-    train_to_uncompressed_bytes_with_alpha_decay(
+    let network = train_network(
         games,
         alpha,
-        AlphaDecayStrategy::None,
-        0,
+        alpha_decay,
+        alpha_decay_start_game,
         lambda_,
         epsilon,
         seed,
         threads,
         initial_model,
+        layout,
         random_opening_plies,
         progress_interval,
         progress_callback,
-    )
+    )?;
+    network.to_bytes()
 }
 
-pub fn train_to_uncompressed_bytes_with_alpha_decay(
+pub fn train_to_uncompressed_bytes(
     games: usize,
     alpha: f32,
-    alpha_decay: AlphaDecayStrategy,
-    alpha_decay_start_game: usize,
     lambda_: f32,
     epsilon: f64,
     seed: u64,
@@ -1659,11 +3056,11 @@ pub fn train_to_uncompressed_bytes_with_alpha_decay(
     progress_interval: usize,
     progress_callback: Option<ProgressCallback<'_>>,
 ) -> Result<Vec<u8>, String> {
-    let network = train_network(
+    train_to_uncompressed_bytes_with_alpha_decay(
         games,
         alpha,
-        alpha_decay,
-        alpha_decay_start_game,
+        AlphaDecayStrategy::None,
+        0,
         lambda_,
         epsilon,
         seed,
@@ -1672,11 +3069,10 @@ pub fn train_to_uncompressed_bytes_with_alpha_decay(
         random_opening_plies,
         progress_interval,
         progress_callback,
-    )?;
-    network.to_uncompressed_bytes()
+    )
 }
 
-fn train_network(
+pub fn train_to_uncompressed_bytes_with_alpha_decay(
     games: usize,
     alpha: f32,
     alpha_decay: AlphaDecayStrategy,
@@ -1689,33 +3085,8 @@ fn train_network(
     random_opening_plies: usize,
     progress_interval: usize,
     progress_callback: Option<ProgressCallback<'_>>,
-) -> Result<TrainableNTuple, String> {
-    let base_network = if let Some(bytes) = initial_model {
-        TrainableNTuple::from_bytes(bytes)?
-    } else {
-        TrainableNTuple::new()
-    };
-    let resolved_threads = resolve_thread_count(threads);
-    let active_threads = resolved_threads.min(games.max(1));
-
-    if active_threads <= 1 || games == 0 {
-        return train_network_sequential(
-            base_network,
-            games,
-            alpha,
-            alpha_decay,
-            alpha_decay_start_game,
-            lambda_,
-            epsilon,
-            seed,
-            random_opening_plies,
-            progress_interval,
-            progress_callback,
-        );
-    }
-
-    train_network_parallel(
-        base_network,
+) -> Result<Vec<u8>, String> {
+    train_to_uncompressed_bytes_with_layout(
         games,
         alpha,
         alpha_decay,
@@ -1723,15 +3094,19 @@ fn train_network(
         lambda_,
         epsilon,
         seed,
-        active_threads,
+        threads,
+        initial_model,
+        None,
         random_opening_plies,
         progress_interval,
         progress_callback,
     )
 }
 
-fn train_network_sequential(
-    network: TrainableNTuple,
+/// Like `train_to_uncompressed_bytes_with_alpha_decay`, but a fresh network is built from
+/// `layout` instead of [`TUPLE_PATTERNS`] alone. When continuing from
+/// `initial_model`, the layout must match the one stored in that model.
+pub fn train_to_uncompressed_bytes_with_layout(
     games: usize,
     alpha: f32,
     alpha_decay: AlphaDecayStrategy,
@@ -1739,26 +3114,32 @@ fn train_network_sequential(
     lambda_: f32,
     epsilon: f64,
     seed: u64,
+    threads: usize,
+    initial_model: Option<&[u8]>,
+    layout: Option<&NetworkLayout>,
     random_opening_plies: usize,
     progress_interval: usize,
     progress_callback: Option<ProgressCallback<'_>>,
-) -> Result<TrainableNTuple, String> {
-    let mut trainer = TDLambdaTrainer::new_with_alpha_decay(
-        network,
+) -> Result<Vec<u8>, String> {
+    let network = train_network(
+        games,
         alpha,
         alpha_decay,
         alpha_decay_start_game,
         lambda_,
         epsilon,
         seed,
+        threads,
+        initial_model,
+        layout,
         random_opening_plies,
+        progress_interval,
+        progress_callback,
     )?;
-    trainer.train(games, progress_interval, progress_callback)?;
-    Ok(trainer.into_network())
+    network.to_uncompressed_bytes()
 }
 
-fn train_network_parallel(
-    base_network: TrainableNTuple,
+fn train_network(
     games: usize,
     alpha: f32,
     alpha_decay: AlphaDecayStrategy,
@@ -1767,132 +3148,1555 @@ fn train_network_parallel(
     epsilon: f64,
     seed: u64,
     threads: usize,
+    initial_model: Option<&[u8]>,
+    layout: Option<&NetworkLayout>,
     random_opening_plies: usize,
     progress_interval: usize,
-    mut progress_callback: Option<ProgressCallback<'_>>,
+    progress_callback: Option<ProgressCallback<'_>>,
 ) -> Result<TrainableNTuple, String> {
-    let worker_game_counts = split_games(games, threads);
-    let worker_progress_interval = if progress_interval == 0 {
-        0
-    } else {
-        (progress_interval / threads).max(1)
+    let config = TrainingConfig {
+        games,
+        alpha,
+        alpha_decay,
+        alpha_decay_start_game,
+        lambda_,
+        epsilon,
+        seed,
+        threads,
+        random_opening_plies,
+        parallel_mode: ParallelMode::Independent,
+        search_depth: DEFAULT_TRAINING_SEARCH_DEPTH,
+        exact_td_empties: 0,
+        epsilon_schedule: EpsilonSchedule::Constant,
+        exploration: ExplorationPolicy::Uniform,
+        league: None,
     };
-    let start_time = Instant::now();
-    let (tx, rx) = mpsc::channel::<WorkerMessage>();
+    let mut run = TrainingRun::new(config, initial_model, layout)?;
+    run.train(progress_interval, progress_callback, 0, None)?;
+    run.into_network()
+}
 
-    std::thread::scope(|scope| -> Result<TrainableNTuple, String> {
-        let mut handles = Vec::with_capacity(worker_game_counts.len());
-        let mut worker_start_game = alpha_decay_start_game;
-        for (worker_idx, worker_games) in worker_game_counts.iter().copied().enumerate() {
-            let worker_tx = tx.clone();
-            let worker_network = base_network.clone();
-            let current_worker_start_game = worker_start_game;
-            worker_start_game = worker_start_game.saturating_add(worker_games);
-            handles.push(
-                scope.spawn(move || -> Result<(TrainableNTuple, usize), String> {
-                    let result = train_worker_network(
-                        worker_network,
-                        worker_games,
-                        alpha,
-                        alpha_decay,
-                        current_worker_start_game,
-                        lambda_,
-                        epsilon,
-                        worker_seed(seed, worker_idx),
-                        random_opening_plies,
-                        worker_progress_interval,
-                        worker_tx.clone(),
-                    );
-                    let _ = worker_tx.send(WorkerMessage::Done);
-                    result.map(|network| (network, worker_games))
+/// How the workers of a multi-threaded [`TrainingRun`] share what they learn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParallelMode {
+    /// Every worker trains its own copy; the copies are averaged once at the end.
+    Independent,
+    /// The copies are averaged, weighted by games, and handed back to every
+    /// worker each time the run completes a multiple of `round_games` games.
+    SyncRounds { round_games: usize },
+    /// All workers update one shared table without locks (Hogwild!). Racing
+    /// updates make the result depend on thread scheduling, so runs are not
+    /// reproducible and a resumed checkpoint does not repeat the original run.
+    Hogwild,
+}
+
+impl ParallelMode {
+    /// Parses `independent`, `hogwild` or `sync:<games>`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "independent" => Ok(Self::Independent),
+            "hogwild" => Ok(Self::Hogwild),
+            _ => spec
+                .strip_prefix("sync:")
+                .and_then(|games| games.parse::<usize>().ok())
+                .filter(|games| *games > 0)
+                .map(|round_games| Self::SyncRounds { round_games })
+                .ok_or_else(|| {
+                    format!(
+                        "unsupported parallel mode '{spec}' (expected independent, hogwild or sync:<games>)"
+                    )
                 }),
-            );
         }
-        drop(tx);
+    }
 
-        let mut completed_games = 0usize;
-        let mut last_reported = 0usize;
-        let mut finished_workers = 0usize;
-        while finished_workers < worker_game_counts.len() {
-            match rx
-                .recv()
-                .map_err(|_| "training worker progress channel closed unexpectedly".to_string())?
+    pub fn name(self) -> String {
+        match self {
+            Self::Independent => "independent".to_string(),
+            Self::SyncRounds { round_games } => format!("sync:{round_games}"),
+            Self::Hogwild => "hogwild".to_string(),
+        }
+    }
+}
+
+/// Settings of a TD(lambda) training run, as stored in its checkpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingConfig {
+    pub games: usize,
+    pub alpha: f32,
+    pub alpha_decay: AlphaDecayStrategy,
+    pub alpha_decay_start_game: usize,
+    pub lambda_: f32,
+    pub epsilon: f64,
+    pub seed: u64,
+    /// Requested worker threads; `0` uses the available parallelism.
+    pub threads: usize,
+    pub random_opening_plies: usize,
+    /// Ignored when the run has a single worker.
+    pub parallel_mode: ParallelMode,
+    /// Plies searched per self-play move, [`DEFAULT_TRAINING_SEARCH_DEPTH`]
+    /// by default.
+    pub search_depth: u8,
+    /// Solve positions with at most this many empties exactly and train on
+    /// the solved score; `0` keeps plain TD targets.
+    pub exact_td_empties: u8,
+    pub epsilon_schedule: EpsilonSchedule,
+    pub exploration: ExplorationPolicy,
+    /// Opponent pool, `None` for plain self-play. Every worker keeps its own
+    /// snapshots.
+    pub league: Option<LeagueConfig>,
+}
+
+impl TrainingConfig {
+    /// Settings every worker of the run shares.
+    fn trainer_config(&self) -> TrainerConfig {
+        TrainerConfig {
+            alpha: self.alpha,
+            alpha_decay: self.alpha_decay,
+            alpha_decay_start_game: self.alpha_decay_start_game,
+            lambda_: self.lambda_,
+            epsilon: self.epsilon,
+            random_opening_plies: self.random_opening_plies,
+            search_depth: self.search_depth,
+            exact_td_empties: self.exact_td_empties,
+            epsilon_schedule: self.epsilon_schedule,
+            exploration: self.exploration,
+        }
+    }
+}
+
+struct TrainingWorker<N = TrainableNTuple> {
+    trainer: TDLambdaTrainer<N>,
+    games: usize,
+}
+
+enum TrainingWorkers {
+    /// Workers with their own copy of the network.
+    Replicated(Vec<TrainingWorker>),
+    /// Hogwild workers updating one [`SharedNTuple`].
+    Shared(Vec<TrainingWorker<SharedNTuple>>),
+}
+
+/// A TD(lambda) run that can be checkpointed between games.
+///
+/// Each worker keeps its own weights, visit counts, ChaCha8 state and game
+/// count, and all of it is written by [`TrainingRun::to_checkpoint_bytes`], so
+/// a run restored with [`TrainingRun::from_checkpoint_bytes`] finishes with
+/// exactly the weights an uninterrupted run would have produced. The one
+/// exception is [`ParallelMode::Hogwild`], which is not deterministic.
+pub struct TrainingRun {
+    config: TrainingConfig,
+    parent_hash: Option<u32>,
+    workers: TrainingWorkers,
+    /// Network handed to every worker at the last sync of a
+    /// [`ParallelMode::SyncRounds`] run.
+    round_base: Option<TrainableNTuple>,
+}
+
+impl TrainingRun {
+    pub fn new(
+        config: TrainingConfig,
+        initial_model: Option<&[u8]>,
+        layout: Option<&NetworkLayout>,
+    ) -> Result<Self, String> {
+        let (mut base_network, parent_hash) = if let Some(bytes) = initial_model {
+            let parent_hash = crc32fast::hash(decompress_model_bytes(bytes)?.as_ref());
+            let network = TrainableNTuple::from_bytes(bytes)?;
+            if let Some(layout) = layout {
+                layout.check_matches(&network)?;
+            }
+            (network, Some(parent_hash))
+        } else {
+            let network = match layout {
+                Some(layout) => layout.build()?,
+                None => TrainableNTuple::new(),
+            };
+            (network, None)
+        };
+        base_network.prepare_for_alpha_decay(config.alpha_decay)?;
+
+        let active_threads = resolve_thread_count(config.threads).min(config.games.max(1));
+        let worker_game_counts = if active_threads <= 1 {
+            vec![config.games]
+        } else {
+            split_games(config.games, active_threads)
+        };
+        let (workers, round_base) = match config.parallel_mode {
+            ParallelMode::Hogwild if active_threads > 1 => {
+                let shared = SharedNTuple::new(&base_network);
+                let workers = new_workers(&config, &worker_game_counts, || shared.clone())?;
+                (TrainingWorkers::Shared(workers), None)
+            }
+            mode => {
+                let workers = new_workers(&config, &worker_game_counts, || base_network.clone())?;
+                let round_base = (active_threads > 1
+                    && matches!(mode, ParallelMode::SyncRounds { .. }))
+                .then_some(base_network);
+                (TrainingWorkers::Replicated(workers), round_base)
+            }
+        };
+
+        Ok(Self {
+            config,
+            parent_hash,
+            workers,
+            round_base,
+        })
+    }
+
+    pub fn config(&self) -> &TrainingConfig {
+        &self.config
+    }
+
+    pub fn completed_games(&self) -> usize {
+        match &self.workers {
+            TrainingWorkers::Replicated(workers) => completed_worker_games(workers),
+            TrainingWorkers::Shared(workers) => completed_worker_games(workers),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.completed_games() >= self.config.games
+    }
+
+    fn worker_count(&self) -> usize {
+        match &self.workers {
+            TrainingWorkers::Replicated(workers) => workers.len(),
+            TrainingWorkers::Shared(workers) => workers.len(),
+        }
+    }
+
+    /// Round length of a [`ParallelMode::SyncRounds`] run with several workers.
+    fn sync_round_games(&self) -> Option<usize> {
+        match (self.config.parallel_mode, &self.round_base) {
+            (ParallelMode::SyncRounds { round_games }, Some(_)) => Some(round_games),
+            _ => None,
+        }
+    }
+
+    /// Plays the remaining games. With a non-zero `checkpoint_interval`,
+    /// `checkpoint_callback` is handed the run each time the total number of
+    /// completed games reaches a multiple of the interval.
+    pub fn train(
+        &mut self,
+        progress_interval: usize,
+        progress_callback: Option<ProgressCallback<'_>>,
+        checkpoint_interval: usize,
+        mut checkpoint_callback: Option<CheckpointCallback<'_>>,
+    ) -> Result<(), String> {
+        let games = self.config.games;
+        let mut reporter = ProgressReporter {
+            callback: progress_callback,
+            interval: progress_interval,
+            total: games,
+            start_time: Instant::now(),
+            last_reported: self.completed_games(),
+            stats: TrainingStats::default(),
+            worker_norms: vec![Vec::new(); self.worker_count()],
+        };
+        while !self.is_finished() {
+            let completed = self.completed_games();
+            let mut target = if checkpoint_interval > 0 && checkpoint_callback.is_some() {
+                games.min((completed / checkpoint_interval + 1) * checkpoint_interval)
+            } else {
+                games
+            };
+            let round_games = self.sync_round_games();
+            if let Some(round_games) = round_games {
+                target = target.min((completed / round_games + 1) * round_games);
+            }
+            match &mut self.workers {
+                TrainingWorkers::Replicated(workers) if workers.len() == 1 => {
+                    train_sequential_until(&mut workers[0].trainer, target, &mut reporter)?;
+                }
+                TrainingWorkers::Replicated(workers) => {
+                    train_parallel_until(workers, target, &mut reporter)?;
+                }
+                TrainingWorkers::Shared(workers) => {
+                    train_parallel_until(workers, target, &mut reporter)?;
+                }
+            }
+            if let Some(round_games) = round_games
+                && (target.is_multiple_of(round_games) || target == games)
             {
-                WorkerMessage::Progress(delta) => {
-                    completed_games = completed_games.saturating_add(delta);
-                    if let Some(callback) = progress_callback.as_mut()
-                        && progress_interval > 0
-                        && (completed_games - last_reported >= progress_interval
-                            || completed_games == games)
-                    {
-                        last_reported = completed_games;
-                        callback(completed_games, games, start_time.elapsed().as_secs_f64())?;
-                    }
+                self.synchronize()?;
+            }
+            if let Some(callback) = checkpoint_callback.as_mut()
+                && checkpoint_interval > 0
+                && target.is_multiple_of(checkpoint_interval)
+            {
+                callback(self)?;
+            }
+        }
+
+        if reporter.is_active() && reporter.last_reported != games {
+            match &mut self.workers {
+                TrainingWorkers::Replicated(workers) => record_worker_stats(workers, &mut reporter),
+                TrainingWorkers::Shared(workers) => record_worker_stats(workers, &mut reporter),
+            }
+            reporter.report(games)?;
+        }
+
+        Ok(())
+    }
+
+    /// Averages the round that just ended and hands the result to every worker.
+    fn synchronize(&mut self) -> Result<(), String> {
+        let Some(merged) = self.merge_round()? else {
+            return Ok(());
+        };
+        if let TrainingWorkers::Replicated(workers) = &mut self.workers {
+            for worker in workers.iter_mut() {
+                worker.trainer.network = merged.clone();
+            }
+        }
+        self.round_base = Some(merged);
+        Ok(())
+    }
+
+    /// Average of the worker networks over the current sync round, weighted by
+    /// the games each played in it, or `None` when no round games were played.
+    fn merge_round(&self) -> Result<Option<TrainableNTuple>, String> {
+        let (
+            TrainingWorkers::Replicated(workers),
+            Some(base),
+            ParallelMode::SyncRounds { round_games },
+        ) = (&self.workers, &self.round_base, self.config.parallel_mode)
+        else {
+            return Ok(None);
+        };
+        let completed = completed_worker_games(workers);
+        if completed == 0 {
+            return Ok(None);
+        }
+        // Workers always advance to their `split_games` share of a target, so
+        // at the previous sync worker `i` stood at its share of the round start.
+        let round_start = ((completed - 1) / round_games) * round_games;
+        let round: Vec<(&TrainableNTuple, usize)> = workers
+            .iter()
+            .zip(split_games(round_start, workers.len()))
+            .map(|(worker, start)| {
+                (
+                    &worker.trainer.network,
+                    worker.trainer.completed_games.saturating_sub(start),
+                )
+            })
+            .collect();
+        let round_total: usize = round.iter().map(|(_, games)| games).sum();
+        if round_total == 0 {
+            return Ok(None);
+        }
+
+        let mut merged =
+            TrainableNTuple::merge_weighted_parallel(&round, round_total, workers.len())?;
+        merged.visit_counts = base.visit_counts.as_ref().map(|base_counts| {
+            let mut counts = base_counts.clone();
+            for network_counts in round
+                .iter()
+                .filter_map(|(network, _)| network.visit_counts.as_ref())
+            {
+                for ((count, network_count), base_count) in counts
+                    .iter_mut()
+                    .flatten()
+                    .flatten()
+                    .zip(network_counts.iter().flatten().flatten())
+                    .zip(base_counts.iter().flatten().flatten())
+                {
+                    *count = count.saturating_add(network_count.saturating_sub(*base_count));
                 }
-                WorkerMessage::Done => finished_workers += 1,
             }
+            counts
+        });
+        merged.tc_accumulators = base.tc_accumulators.as_ref().map(|base_accumulators| {
+            let mut accumulators = base_accumulators.clone();
+            for network_accumulators in round
+                .iter()
+                .filter_map(|(network, _)| network.tc_accumulators.as_ref())
+            {
+                for ((accumulator, network_accumulator), base_accumulator) in accumulators
+                    .iter_mut()
+                    .flatten()
+                    .flatten()
+                    .zip(network_accumulators.iter().flatten().flatten())
+                    .zip(base_accumulators.iter().flatten().flatten())
+                {
+                    accumulator.signed += network_accumulator.signed - base_accumulator.signed;
+                    accumulator.absolute +=
+                        network_accumulator.absolute - base_accumulator.absolute;
+                }
+            }
+            accumulators
+        });
+        Ok(Some(merged))
+    }
+
+    /// The network trained so far, without run metadata. Unlike
+    /// [`Self::into_network`] this works on unfinished runs.
+    pub fn current_network(&self) -> Result<TrainableNTuple, String> {
+        match &self.workers {
+            TrainingWorkers::Replicated(workers) if workers.len() == 1 => {
+                Ok(workers[0].trainer.network.clone())
+            }
+            TrainingWorkers::Replicated(workers) => {
+                if let Some(base) = &self.round_base {
+                    return Ok(self.merge_round()?.unwrap_or_else(|| base.clone()));
+                }
+                let completed = completed_worker_games(workers);
+                if completed == 0 {
+                    return Ok(workers[0].trainer.network.clone());
+                }
+                let networks: Vec<(&TrainableNTuple, usize)> = workers
+                    .iter()
+                    .map(|worker| (&worker.trainer.network, worker.trainer.completed_games))
+                    .collect();
+                TrainableNTuple::merge_weighted_parallel(&networks, completed, workers.len())
+            }
+            TrainingWorkers::Shared(workers) => Ok(workers[0].trainer.network.snapshot()),
+        }
+    }
+
+    /// Merges the workers into the trained network and records the run metadata.
+    pub fn into_network(self) -> Result<TrainableNTuple, String> {
+        if !self.is_finished() {
+            return Err(format!(
+                "training run is not finished: {} of {} games played",
+                self.completed_games(),
+                self.config.games
+            ));
+        }
+
+        let config = self.config;
+        let threads = match &self.workers {
+            TrainingWorkers::Replicated(workers) => workers.len(),
+            TrainingWorkers::Shared(workers) => workers.len(),
+        };
+        let mut network = match self.workers {
+            TrainingWorkers::Replicated(mut workers) if threads == 1 => workers
+                .pop()
+                .expect("training run must have a worker")
+                .trainer
+                .into_network(),
+            // The final sync left the round base equal to every worker.
+            TrainingWorkers::Replicated(_) if self.round_base.is_some() => self
+                .round_base
+                .expect("sync rounds run must have a round base"),
+            TrainingWorkers::Replicated(workers) => {
+                let networks: Vec<(&TrainableNTuple, usize)> = workers
+                    .iter()
+                    .map(|worker| (&worker.trainer.network, worker.games))
+                    .collect();
+                TrainableNTuple::merge_weighted_parallel(&networks, config.games, threads)?
+            }
+            TrainingWorkers::Shared(workers) => workers[0].trainer.network.snapshot(),
+        };
+
+        let mut metadata = ModelMetadata::new();
+        metadata.insert(KEY_ALPHA, config.alpha);
+        metadata.insert(KEY_LAMBDA, config.lambda_);
+        metadata.insert(KEY_EPSILON, config.epsilon);
+        metadata.insert(KEY_ALPHA_DECAY, config.alpha_decay.name());
+        metadata.insert(KEY_GAMES, config.games);
+        metadata.insert(KEY_SEED, config.seed);
+        if threads > 1 && config.parallel_mode != ParallelMode::Independent {
+            metadata.insert(KEY_PARALLEL_MODE, config.parallel_mode.name());
+        }
+        if config.search_depth != DEFAULT_TRAINING_SEARCH_DEPTH {
+            metadata.insert(KEY_SEARCH_DEPTH, config.search_depth);
+        }
+        if config.exact_td_empties > 0 {
+            metadata.insert(KEY_EXACT_TD_EMPTIES, config.exact_td_empties);
+        }
+        if config.epsilon_schedule != EpsilonSchedule::Constant {
+            metadata.insert(KEY_EPSILON_SCHEDULE, config.epsilon_schedule.name());
+        }
+        if config.exploration != ExplorationPolicy::Uniform {
+            metadata.insert(KEY_EXPLORATION, config.exploration.name());
+        }
+        if let Some(league) = config.league {
+            metadata.insert(KEY_LEAGUE, league.name());
+        }
+        if let Some(parent_hash) = self.parent_hash {
+            metadata.insert(KEY_PARENT_HASH, format!("{parent_hash:08x}"));
+        }
+        network.metadata = metadata;
+        Ok(network)
+    }
+
+    /// Serializes the run as a zstd-compressed NTCK checkpoint.
+    ///
+    /// Version 2 adds the parallel mode after the config and a shared network
+    /// block (the sync round base or the Hogwild table) before the workers;
+    /// Hogwild workers then store an empty network. Version 3 appends the
+    /// search depth and exact TD empties to the config, version 4 the
+    /// epsilon schedule and exploration policy names, and version 5 the
+    /// league spec (empty for self-play) and each worker's league snapshots
+    /// ahead of its network.
+    pub fn to_checkpoint_bytes(&self) -> Result<Vec<u8>, String> {
+        let config = &self.config;
+        let mut data = Vec::new();
+        data.extend_from_slice(&(config.games as u64).to_le_bytes());
+        data.extend_from_slice(&config.alpha.to_le_bytes());
+        let decay_name = config.alpha_decay.name().as_bytes();
+        data.push(decay_name.len() as u8);
+        data.extend_from_slice(decay_name);
+        data.extend_from_slice(&(config.alpha_decay_start_game as u64).to_le_bytes());
+        data.extend_from_slice(&config.lambda_.to_le_bytes());
+        data.extend_from_slice(&config.epsilon.to_le_bytes());
+        data.extend_from_slice(&config.seed.to_le_bytes());
+        data.extend_from_slice(&(config.threads as u64).to_le_bytes());
+        data.extend_from_slice(&(config.random_opening_plies as u64).to_le_bytes());
+        let (mode_id, round_games) = match config.parallel_mode {
+            ParallelMode::Independent => (0u8, 0usize),
+            ParallelMode::SyncRounds { round_games } => (1, round_games),
+            ParallelMode::Hogwild => (2, 0),
+        };
+        data.push(mode_id);
+        data.extend_from_slice(&(round_games as u64).to_le_bytes());
+        data.push(config.search_depth);
+        data.push(config.exact_td_empties);
+        for name in [
+            config.epsilon_schedule.name(),
+            config.exploration.name(),
+            config.league.map(LeagueConfig::name).unwrap_or_default(),
+        ] {
+            data.push(name.len() as u8);
+            data.extend_from_slice(name.as_bytes());
+        }
+        match self.parent_hash {
+            Some(parent_hash) => {
+                data.push(1);
+                data.extend_from_slice(&parent_hash.to_le_bytes());
+            }
+            None => data.push(0),
+        }
+
+        let shared_network = match (&self.workers, &self.round_base) {
+            (TrainingWorkers::Shared(workers), _) => {
+                Some((SHARED_HOGWILD, workers[0].trainer.network.snapshot()))
+            }
+            (TrainingWorkers::Replicated(_), Some(base)) => Some((SHARED_ROUND_BASE, base.clone())),
+            (TrainingWorkers::Replicated(_), None) => None,
+        };
+        match shared_network {
+            Some((kind, network)) => {
+                data.push(kind);
+                write_checkpoint_network(&mut data, Some(&network))?;
+            }
+            None => data.push(SHARED_NONE),
+        }
+
+        data.extend_from_slice(&(self.worker_count() as u32).to_le_bytes());
+        match &self.workers {
+            TrainingWorkers::Replicated(workers) => {
+                for worker in workers {
+                    write_checkpoint_worker(&mut data, worker)?;
+                    write_checkpoint_network(&mut data, Some(&worker.trainer.network))?;
+                }
+            }
+            TrainingWorkers::Shared(workers) => {
+                for worker in workers {
+                    write_checkpoint_worker(&mut data, worker)?;
+                    write_checkpoint_network(&mut data, None)?;
+                }
+            }
+        }
+
+        let mut output = Vec::with_capacity(CHECKPOINT_HEADER_SIZE + data.len());
+        output.extend_from_slice(CHECKPOINT_MAGIC);
+        output.extend_from_slice(&CHECKPOINT_VERSION.to_le_bytes());
+        output.extend_from_slice(&crc32fast::hash(&data).to_le_bytes());
+        output.extend_from_slice(&data);
+        compress_model_bytes(&output)
+    }
+
+    pub fn from_checkpoint_bytes(data: &[u8]) -> Result<Self, String> {
+        let bytes = decompress_model_bytes(data)?;
+        let data = bytes.as_ref();
+        if data.len() < CHECKPOINT_HEADER_SIZE || &data[0..4] != CHECKPOINT_MAGIC {
+            return Err("invalid checkpoint magic (expected NTCK)".to_string());
+        }
+        let version = read_u32_le(data, 4)?;
+        if !(1..=CHECKPOINT_VERSION).contains(&version) {
+            return Err(format!("unsupported checkpoint version: {version}"));
+        }
+        let payload = &data[CHECKPOINT_HEADER_SIZE..];
+        if crc32fast::hash(payload) != read_u32_le(data, 8)? {
+            return Err("checkpoint CRC32 mismatch".to_string());
+        }
+
+        let mut reader = CheckpointReader::new(payload);
+        let games = reader.read_usize()?;
+        let alpha = f32::from_le_bytes(reader.read_array()?);
+        let alpha_decay = AlphaDecayStrategy::from_name(&reader.read_name("alpha_decay")?)?;
+        let alpha_decay_start_game = reader.read_usize()?;
+        let lambda_ = f32::from_le_bytes(reader.read_array()?);
+        let epsilon = f64::from_le_bytes(reader.read_array()?);
+        let seed = u64::from_le_bytes(reader.read_array()?);
+        let threads = reader.read_usize()?;
+        let random_opening_plies = reader.read_usize()?;
+        let parallel_mode = if version == 1 {
+            ParallelMode::Independent
+        } else {
+            let mode_id = reader.read_array::<1>()?[0];
+            let round_games = reader.read_usize()?;
+            match (mode_id, round_games) {
+                (0, _) => ParallelMode::Independent,
+                (1, round_games) if round_games > 0 => ParallelMode::SyncRounds { round_games },
+                (2, _) => ParallelMode::Hogwild,
+                _ => return Err(format!("invalid checkpoint parallel mode: {mode_id}")),
+            }
+        };
+        let (search_depth, exact_td_empties) = if version < 3 {
+            (DEFAULT_TRAINING_SEARCH_DEPTH, 0)
+        } else {
+            let [search_depth, exact_td_empties] = reader.read_array::<2>()?;
+            (search_depth, exact_td_empties)
+        };
+        let (epsilon_schedule, exploration) = if version < 4 {
+            (EpsilonSchedule::Constant, ExplorationPolicy::Uniform)
+        } else {
+            (
+                EpsilonSchedule::parse(&reader.read_name("epsilon_schedule")?)?,
+                ExplorationPolicy::parse(&reader.read_name("exploration")?)?,
+            )
+        };
+        let league = match version {
+            ..5 => None,
+            _ => match reader.read_name("league")?.as_str() {
+                "" => None,
+                spec => Some(LeagueConfig::parse(spec)?),
+            },
+        };
+        let config = TrainingConfig {
+            games,
+            alpha,
+            alpha_decay,
+            alpha_decay_start_game,
+            lambda_,
+            epsilon,
+            seed,
+            threads,
+            random_opening_plies,
+            parallel_mode,
+            search_depth,
+            exact_td_empties,
+            epsilon_schedule,
+            exploration,
+            league,
+        };
+        let parent_hash = match reader.read_array::<1>()?[0] {
+            0 => None,
+            1 => Some(u32::from_le_bytes(reader.read_array()?)),
+            flag => return Err(format!("invalid checkpoint parent hash flag: {flag}")),
+        };
+        let shared_kind = if version == 1 {
+            SHARED_NONE
+        } else {
+            reader.read_array::<1>()?[0]
+        };
+        let shared_network = match shared_kind {
+            SHARED_NONE => None,
+            SHARED_ROUND_BASE | SHARED_HOGWILD => Some(
+                read_checkpoint_network(&mut reader)?
+                    .ok_or_else(|| "checkpoint shared network is empty".to_string())?,
+            ),
+            kind => return Err(format!("invalid checkpoint shared network kind: {kind}")),
+        };
+
+        let worker_count = u32::from_le_bytes(reader.read_array()?) as usize;
+        if worker_count == 0 {
+            return Err("checkpoint must contain at least one worker".to_string());
+        }
+        let expected_kind = match parallel_mode {
+            ParallelMode::SyncRounds { .. } if worker_count > 1 => SHARED_ROUND_BASE,
+            ParallelMode::Hogwild if worker_count > 1 => SHARED_HOGWILD,
+            _ => SHARED_NONE,
+        };
+        if shared_kind != expected_kind {
+            return Err(format!(
+                "checkpoint shared network does not match parallel mode {}",
+                parallel_mode.name()
+            ));
+        }
+
+        let (workers, round_base) = if shared_kind == SHARED_HOGWILD {
+            let shared = SharedNTuple::new(
+                shared_network
+                    .as_ref()
+                    .expect("hogwild checkpoint must have a shared network"),
+            );
+            let mut workers = Vec::with_capacity(worker_count);
+            for worker_idx in 0..worker_count {
+                let state = CheckpointWorker::read(&mut reader, version, worker_idx)?;
+                if read_checkpoint_network(&mut reader)?.is_some() {
+                    return Err(format!(
+                        "checkpoint hogwild worker #{worker_idx} must not store a network"
+                    ));
+                }
+                workers.push(state.restore(&config, worker_count, shared.clone())?);
+            }
+            (TrainingWorkers::Shared(workers), None)
+        } else {
+            let mut workers: Vec<TrainingWorker> = Vec::with_capacity(worker_count);
+            for worker_idx in 0..worker_count {
+                let state = CheckpointWorker::read(&mut reader, version, worker_idx)?;
+                let network = read_checkpoint_network(&mut reader)?.ok_or_else(|| {
+                    format!("checkpoint worker #{worker_idx} is missing its network")
+                })?;
+                if let Some(first) = workers.first()
+                    && first.trainer.network.patterns != network.patterns
+                {
+                    return Err("checkpoint workers use different tuple patterns".to_string());
+                }
+                workers.push(state.restore(&config, worker_count, network)?);
+            }
+            (TrainingWorkers::Replicated(workers), shared_network)
+        };
+        if !reader.is_empty() {
+            return Err("unexpected trailing bytes in checkpoint".to_string());
         }
+        let run = Self {
+            config,
+            parent_hash,
+            workers,
+            round_base,
+        };
+        let total_games: usize = match &run.workers {
+            TrainingWorkers::Replicated(workers) => workers.iter().map(|worker| worker.games).sum(),
+            TrainingWorkers::Shared(workers) => workers.iter().map(|worker| worker.games).sum(),
+        };
+        if total_games != run.config.games {
+            return Err(format!(
+                "checkpoint workers cover {total_games} games, expected {}",
+                run.config.games
+            ));
+        }
+
+        Ok(run)
+    }
+}
+
+const SHARED_NONE: u8 = 0;
+const SHARED_ROUND_BASE: u8 = 1;
+const SHARED_HOGWILD: u8 = 2;
+
+fn new_workers<N: TrainingNetwork>(
+    config: &TrainingConfig,
+    worker_game_counts: &[usize],
+    mut network: impl FnMut() -> N,
+) -> Result<Vec<TrainingWorker<N>>, String> {
+    let single_worker = worker_game_counts.len() <= 1;
+    let mut workers = Vec::with_capacity(worker_game_counts.len());
+    let mut worker_start_game = config.alpha_decay_start_game;
+    for (worker_idx, games) in worker_game_counts.iter().copied().enumerate() {
+        let seed = if single_worker {
+            config.seed
+        } else {
+            worker_seed(config.seed, worker_idx)
+        };
+        let trainer_config = TrainerConfig {
+            alpha_decay_start_game: worker_start_game,
+            ..config.trainer_config()
+        };
+        let trainer = TDLambdaTrainer::with_config(network(), &trainer_config, seed)?;
+        let mut trainer = match config.league {
+            Some(league) => trainer.with_league(league)?,
+            None => trainer,
+        };
+        trainer.share_schedule(config.alpha_decay_start_game, worker_game_counts.len());
+        worker_start_game = worker_start_game.saturating_add(games);
+        workers.push(TrainingWorker { trainer, games });
+    }
+    Ok(workers)
+}
+
+fn completed_worker_games<N>(workers: &[TrainingWorker<N>]) -> usize {
+    workers
+        .iter()
+        .map(|worker| worker.trainer.completed_games)
+        .sum()
+}
+
+fn record_worker_stats<N: TrainingNetwork>(
+    workers: &mut [TrainingWorker<N>],
+    reporter: &mut ProgressReporter<'_>,
+) {
+    for (worker_idx, worker) in workers.iter_mut().enumerate() {
+        let stats = std::mem::take(&mut worker.trainer.stats);
+        reporter.record(
+            worker_idx,
+            &stats,
+            worker.trainer.network.phase_weight_norms(),
+        );
+    }
+}
+
+fn train_sequential_until<N: TrainingNetwork>(
+    trainer: &mut TDLambdaTrainer<N>,
+    target: usize,
+    reporter: &mut ProgressReporter<'_>,
+) -> Result<(), String> {
+    while trainer.completed_games < target {
+        trainer.train_one_game()?;
+        let completed = trainer.completed_games;
+        if reporter.is_active() && completed.is_multiple_of(reporter.interval) {
+            let stats = std::mem::take(&mut trainer.stats);
+            reporter.record(0, &stats, trainer.network.phase_weight_norms());
+            reporter.report(completed)?;
+        }
+    }
+    Ok(())
+}
+
+fn train_parallel_until<N: TrainingNetwork + Send>(
+    workers: &mut [TrainingWorker<N>],
+    target: usize,
+    reporter: &mut ProgressReporter<'_>,
+) -> Result<(), String> {
+    let threads = workers.len();
+    let worker_progress_interval = if reporter.is_active() {
+        (reporter.interval / threads).max(1)
+    } else {
+        0
+    };
+    // `split_games` is monotone in its first argument, so every worker's
+    // share of `target` lies between its current count and its total.
+    let worker_targets = split_games(target, threads);
+    let mut completed_games = completed_worker_games(workers);
+    let (tx, rx) = mpsc::channel::<WorkerMessage>();
+
+    std::thread::scope(|scope| -> Result<(), String> {
+        let mut handles = Vec::with_capacity(threads);
+        for (worker_idx, (worker, worker_target)) in
+            workers.iter_mut().zip(worker_targets).enumerate()
+        {
+            let worker_games = worker_target
+                .min(worker.games)
+                .saturating_sub(worker.trainer.completed_games);
+            let worker_tx = tx.clone();
+            handles.push(scope.spawn(move || -> Result<(), String> {
+                let result = train_worker(
+                    &mut worker.trainer,
+                    worker_idx,
+                    worker_games,
+                    worker_progress_interval,
+                    &worker_tx,
+                );
+                let _ = worker_tx.send(WorkerMessage::Done);
+                result
+            }));
+        }
+        drop(tx);
+
+        let mut finished_workers = 0usize;
+        while finished_workers < threads {
+            match rx
+                .recv()
+                .map_err(|_| "training worker progress channel closed unexpectedly".to_string())?
+            {
+                WorkerMessage::Progress {
+                    worker_idx,
+                    games,
+                    stats,
+                    phase_weight_norms,
+                } => {
+                    completed_games = completed_games.saturating_add(games);
+                    reporter.record(worker_idx, &stats, phase_weight_norms);
+                    if reporter.is_active()
+                        && (completed_games - reporter.last_reported >= reporter.interval
+                            || completed_games == reporter.total)
+                    {
+                        reporter.report(completed_games)?;
+                    }
+                }
+                WorkerMessage::Done => finished_workers += 1,
+            }
+        }
+
+        for handle in handles {
+            handle
+                .join()
+                .map_err(|_| "training worker thread panicked".to_string())??;
+        }
+        Ok(())
+    })
+}
+
+fn write_checkpoint_worker<N>(
+    data: &mut Vec<u8>,
+    worker: &TrainingWorker<N>,
+) -> Result<(), String> {
+    let trainer = &worker.trainer;
+    data.extend_from_slice(&(worker.games as u64).to_le_bytes());
+    data.extend_from_slice(&(trainer.completed_games as u64).to_le_bytes());
+    data.extend_from_slice(&(trainer.alpha_decay_start_game as u64).to_le_bytes());
+    data.extend_from_slice(&trainer.rng.get_seed());
+    data.extend_from_slice(&trainer.rng.get_stream().to_le_bytes());
+    data.extend_from_slice(&trainer.rng.get_word_pos().to_le_bytes());
+    let snapshots: Vec<&TrainableNTuple> = match &trainer.league {
+        Some(league) => league.snapshots.iter().collect(),
+        None => Vec::new(),
+    };
+    data.extend_from_slice(&(snapshots.len() as u32).to_le_bytes());
+    for snapshot in snapshots {
+        write_checkpoint_network(data, Some(snapshot))?;
+    }
+    Ok(())
+}
+
+/// Writes a length-prefixed uncompressed network; `None` writes length 0.
+fn write_checkpoint_network(
+    data: &mut Vec<u8>,
+    network: Option<&TrainableNTuple>,
+) -> Result<(), String> {
+    let bytes = match network {
+        Some(network) => network.to_uncompressed_bytes()?,
+        None => Vec::new(),
+    };
+    data.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    data.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn read_checkpoint_network(
+    reader: &mut CheckpointReader<'_>,
+) -> Result<Option<TrainableNTuple>, String> {
+    let network_len = reader.read_usize()?;
+    if network_len == 0 {
+        return Ok(None);
+    }
+    TrainableNTuple::from_uncompressed_bytes(reader.read_bytes(network_len)?).map(Some)
+}
+
+/// Per-worker trainer state of a checkpoint, apart from the network.
+struct CheckpointWorker {
+    games: usize,
+    completed_games: usize,
+    alpha_decay_start_game: usize,
+    rng: ChaCha8Rng,
+    /// League snapshots, oldest first.
+    snapshots: Vec<TrainableNTuple>,
+}
+
+impl CheckpointWorker {
+    fn read(
+        reader: &mut CheckpointReader<'_>,
+        version: u32,
+        worker_idx: usize,
+    ) -> Result<Self, String> {
+        let games = reader.read_usize()?;
+        let completed_games = reader.read_usize()?;
+        if completed_games > games {
+            return Err(format!(
+                "checkpoint worker #{worker_idx} completed {completed_games} of {games} games"
+            ));
+        }
+        let alpha_decay_start_game = reader.read_usize()?;
+        let mut rng = ChaCha8Rng::from_seed(reader.read_array()?);
+        rng.set_stream(u64::from_le_bytes(reader.read_array()?));
+        rng.set_word_pos(u128::from_le_bytes(reader.read_array()?));
+        let snapshot_count = if version < 5 {
+            0
+        } else {
+            u32::from_le_bytes(reader.read_array()?) as usize
+        };
+        let mut snapshots = Vec::with_capacity(snapshot_count.min(64));
+        for _ in 0..snapshot_count {
+            snapshots.push(read_checkpoint_network(reader)?.ok_or_else(|| {
+                format!("checkpoint worker #{worker_idx} has an empty league snapshot")
+            })?);
+        }
+        Ok(Self {
+            games,
+            completed_games,
+            alpha_decay_start_game,
+            rng,
+            snapshots,
+        })
+    }
+
+    fn restore<N: TrainingNetwork>(
+        self,
+        config: &TrainingConfig,
+        worker_count: usize,
+        network: N,
+    ) -> Result<TrainingWorker<N>, String> {
+        let trainer_config = TrainerConfig {
+            alpha_decay_start_game: self.alpha_decay_start_game,
+            ..config.trainer_config()
+        };
+        let mut trainer = TDLambdaTrainer::with_config(network, &trainer_config, 0)?;
+        if let Some(league) = config.league {
+            trainer = trainer.with_league(league)?;
+            if let Some(state) = &mut trainer.league {
+                state.snapshots = self.snapshots.into();
+            }
+        } else if !self.snapshots.is_empty() {
+            return Err("checkpoint stores league snapshots for a self-play run".to_string());
+        }
+        trainer.completed_games = self.completed_games;
+        trainer.share_schedule(config.alpha_decay_start_game, worker_count);
+        trainer.rng = self.rng;
+        Ok(TrainingWorker {
+            trainer,
+            games: self.games,
+        })
+    }
+}
+
+/// Tuple and scalar feature weights shared by the Hogwild workers of a run.
+///
+/// Weights are stored as `f32` bit patterns in relaxed atomics: reads never
+/// tear, but two workers adding to the same weight at once can lose one of
+/// the updates, which Hogwild accepts in exchange for lock-free training.
+struct SharedTables {
+    patterns: Arc<TuplePatternSet>,
+    weights: Vec<Vec<Vec<AtomicU32>>>,
+    visit_counts: Option<Vec<Vec<Vec<AtomicU32>>>>,
+    /// `[signed, absolute]` TC sums as `f32` bits.
+    tc_accumulators: Option<Vec<Vec<Vec<[AtomicU32; 2]>>>>,
+    /// Feature list of the network; its own weights are not used.
+    features: Option<ScalarFeatureWeights>,
+    feature_weights: Vec<Vec<AtomicU32>>,
+}
+
+/// Handle to [`SharedTables`], cloned into every Hogwild worker.
+#[derive(Clone)]
+struct SharedNTuple {
+    tables: Arc<SharedTables>,
+}
+
+impl SharedNTuple {
+    fn new(network: &TrainableNTuple) -> Self {
+        let atomic_weight = |weight: &f32| AtomicU32::new(weight.to_bits());
+        let weights = network
+            .weights
+            .iter()
+            .map(|phase| {
+                phase
+                    .iter()
+                    .map(|tuple| tuple.iter().map(atomic_weight).collect())
+                    .collect()
+            })
+            .collect();
+        let visit_counts = network.visit_counts.as_ref().map(|counts| {
+            counts
+                .iter()
+                .map(|phase| {
+                    phase
+                        .iter()
+                        .map(|tuple| tuple.iter().map(|count| AtomicU32::new(*count)).collect())
+                        .collect()
+                })
+                .collect()
+        });
+        let tc_accumulators = network.tc_accumulators.as_ref().map(|accumulators| {
+            accumulators
+                .iter()
+                .map(|phase| {
+                    phase
+                        .iter()
+                        .map(|tuple| {
+                            tuple
+                                .iter()
+                                .map(|accumulator| {
+                                    [
+                                        atomic_weight(&accumulator.signed),
+                                        atomic_weight(&accumulator.absolute),
+                                    ]
+                                })
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+        let feature_weights = network
+            .features
+            .as_ref()
+            .map(|features| {
+                features
+                    .weights()
+                    .iter()
+                    .map(|phase| phase.iter().map(atomic_weight).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            tables: Arc::new(SharedTables {
+                patterns: Arc::clone(&network.patterns),
+                weights,
+                visit_counts,
+                tc_accumulators,
+                features: network.features.clone(),
+                feature_weights,
+            }),
+        }
+    }
+
+    /// Copies the current shared weights into a regular network.
+    fn snapshot(&self) -> TrainableNTuple {
+        let tables = &*self.tables;
+        let mut network = TrainableNTuple::with_patterns(Arc::clone(&tables.patterns));
+        network.weights = tables
+            .weights
+            .iter()
+            .map(|phase| {
+                phase
+                    .iter()
+                    .map(|tuple| tuple.iter().map(load_weight).collect())
+                    .collect()
+            })
+            .collect();
+        network.visit_counts = tables.visit_counts.as_ref().map(|counts| {
+            counts
+                .iter()
+                .map(|phase| {
+                    phase
+                        .iter()
+                        .map(|tuple| {
+                            tuple
+                                .iter()
+                                .map(|count| count.load(Ordering::Relaxed))
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+        network.tc_accumulators = tables.tc_accumulators.as_ref().map(|accumulators| {
+            accumulators
+                .iter()
+                .map(|phase| {
+                    phase
+                        .iter()
+                        .map(|tuple| {
+                            tuple
+                                .iter()
+                                .map(|[signed, absolute]| TcAccumulator {
+                                    signed: load_weight(signed),
+                                    absolute: load_weight(absolute),
+                                })
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+        network.features = tables.features.clone().map(|mut features| {
+            for (weight, shared) in features
+                .weights_mut()
+                .iter_mut()
+                .flatten()
+                .zip(tables.feature_weights.iter().flatten())
+            {
+                *weight = load_weight(shared);
+            }
+            features
+        });
+        network
+    }
+
+    fn score(
+        &self,
+        board: &Board,
+        is_black: bool,
+        phase_idx: usize,
+        indices: &FeatureIndices,
+    ) -> f32 {
+        let tables = &*self.tables;
+        let phase_weights = &tables.weights[phase_idx];
+        let mut score = 0.0f32;
+        for tuple_indices in indices {
+            for (weights, &index) in phase_weights.iter().zip(tuple_indices) {
+                score += load_weight(&weights[index as usize]);
+            }
+        }
+        let scalar_score = tables.features.as_ref().map_or(0.0, |features| {
+            features
+                .values(board, is_black)
+                .iter()
+                .zip(&tables.feature_weights[phase_idx])
+                .map(|(value, weight)| load_weight(weight) * value)
+                .sum()
+        });
+        score * SYMMETRY_NORMALIZATION + scalar_score
+    }
+
+    fn apply_delta(&self, phase_idx: usize, indices: &FeatureIndices, delta: f32) {
+        let normalized_delta = delta * SYMMETRY_NORMALIZATION;
+        let phase_weights = &self.tables.weights[phase_idx];
+        for tuple_indices in indices {
+            for (weights, &index) in phase_weights.iter().zip(tuple_indices) {
+                add_weight(&weights[index as usize], normalized_delta);
+            }
+        }
+    }
+
+    fn apply_delta_with_alpha_decay(
+        &self,
+        phase_idx: usize,
+        indices: &FeatureIndices,
+        alpha: f32,
+        cumulative_td: f32,
+        alpha_decay: AlphaDecayStrategy,
+    ) {
+        if let Some(accumulators) = &self.tables.tc_accumulators
+            && alpha_decay.requires_tc_accumulators()
+        {
+            let phase_weights = &self.tables.weights[phase_idx];
+            for tuple_indices in indices {
+                for (tuple_idx, &index) in
+                    tuple_indices.iter().take(phase_weights.len()).enumerate()
+                {
+                    let [signed, absolute] = &accumulators[phase_idx][tuple_idx][index as usize];
+                    let accumulator = TcAccumulator {
+                        signed: load_weight(signed),
+                        absolute: load_weight(absolute),
+                    };
+                    add_weight(signed, cumulative_td);
+                    add_weight(absolute, cumulative_td.abs());
+                    add_weight(
+                        &phase_weights[tuple_idx][index as usize],
+                        clip_weight_update(alpha * accumulator.rate() * cumulative_td)
+                            * SYMMETRY_NORMALIZATION,
+                    );
+                }
+            }
+            return;
+        }
+        let phase_counts = match &self.tables.visit_counts {
+            Some(counts) if alpha_decay.requires_visit_counts() => &counts[phase_idx],
+            _ => {
+                self.apply_delta(
+                    phase_idx,
+                    indices,
+                    clip_weight_update(alpha * cumulative_td),
+                );
+                return;
+            }
+        };
+        let phase_weights = &self.tables.weights[phase_idx];
+        for tuple_indices in indices {
+            for (tuple_idx, &index) in tuple_indices.iter().take(phase_weights.len()).enumerate() {
+                let visit_count = phase_counts[tuple_idx][index as usize]
+                    .fetch_add(1, Ordering::Relaxed)
+                    .saturating_add(1);
+                let visit_alpha = alpha / (visit_count as f32);
+                add_weight(
+                    &phase_weights[tuple_idx][index as usize],
+                    clip_weight_update(visit_alpha * cumulative_td) * SYMMETRY_NORMALIZATION,
+                );
+            }
+        }
+    }
+
+    fn apply_scalar_delta(&self, board: &Board, is_black: bool, phase_idx: usize, delta: f32) {
+        let Some(features) = self.tables.features.as_ref() else {
+            return;
+        };
+        let values = features.values(board, is_black);
+        for (weight, value) in self.tables.feature_weights[phase_idx].iter().zip(values) {
+            add_weight(weight, delta * value);
+        }
+    }
+}
+
+impl TrainingNetwork for SharedNTuple {
+    fn tuple_patterns(&self) -> &TuplePatternSet {
+        &self.tables.patterns
+    }
+
+    fn league_snapshot(&self) -> Option<TrainableNTuple> {
+        let mut network = self.snapshot();
+        network.visit_counts = None;
+        network.tc_accumulators = None;
+        Some(network)
+    }
+
+    fn phase_weight_norms(&self) -> Vec<f64> {
+        self.tables
+            .weights
+            .iter()
+            .map(|phase_weights| {
+                phase_weights
+                    .iter()
+                    .flatten()
+                    .map(|weight| f64::from(load_weight(weight)).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .collect()
+    }
+
+    fn prepare_for_alpha_decay(&mut self, alpha_decay: AlphaDecayStrategy) -> Result<(), String> {
+        if alpha_decay.requires_visit_counts() && self.tables.visit_counts.is_none() {
+            Err("shared weights were created without visit counts".to_string())
+        } else if alpha_decay.requires_tc_accumulators() && self.tables.tc_accumulators.is_none() {
+            Err("shared weights were created without TC accumulators".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn evaluate(&self, board: &Board, is_black: bool) -> f32 {
+        let phase_idx = phase_index_for_board(board, PHASE_COUNT);
+        let indices = self.tables.patterns.feature_indices(board, is_black);
+        self.score(board, is_black, phase_idx, &indices)
+    }
+
+    fn update(&mut self, board: &Board, is_black: bool, delta: f32) {
+        let phase_idx = phase_index_for_board(board, PHASE_COUNT);
+        let indices = self.tables.patterns.feature_indices(board, is_black);
+        self.apply_delta(phase_idx, &indices, delta);
+        self.apply_scalar_delta(board, is_black, phase_idx, delta);
+    }
+
+    fn td_lambda_step(
+        &mut self,
+        board: &Board,
+        is_black: bool,
+        next_value: f32,
+        cumulative_td: f32,
+        next_player: Option<bool>,
+        alpha: f32,
+        alpha_decay: AlphaDecayStrategy,
+        lambda_: f32,
+    ) -> (f32, f32) {
+        let phase_idx = phase_index_for_board(board, PHASE_COUNT);
+        let indices = self.tables.patterns.feature_indices(board, is_black);
+        self.td_lambda_step_precomputed(
+            board,
+            is_black,
+            phase_idx,
+            &indices,
+            next_value,
+            cumulative_td,
+            next_player,
+            alpha,
+            alpha_decay,
+            lambda_,
+        )
+    }
+
+    fn evaluate_precomputed(
+        &self,
+        board: &Board,
+        is_black: bool,
+        phase_idx: usize,
+        feature_indices: &FeatureIndices,
+    ) -> f32 {
+        self.score(board, is_black, phase_idx, feature_indices)
+    }
+
+    fn td_lambda_step_precomputed(
+        &mut self,
+        board: &Board,
+        is_black: bool,
+        phase_idx: usize,
+        feature_indices: &FeatureIndices,
+        next_value: f32,
+        cumulative_td: f32,
+        next_player: Option<bool>,
+        alpha: f32,
+        alpha_decay: AlphaDecayStrategy,
+        lambda_: f32,
+    ) -> (f32, f32) {
+        let current_value = self.score(board, is_black, phase_idx, feature_indices);
+        let td_error = next_value - current_value;
+        let next_cumulative_td = if let Some(previous_player) = next_player {
+            let signed_lambda = if is_black == previous_player {
+                lambda_
+            } else {
+                -lambda_
+            };
+            td_error + signed_lambda * cumulative_td
+        } else {
+            td_error
+        };
+
+        self.apply_delta_with_alpha_decay(
+            phase_idx,
+            feature_indices,
+            alpha,
+            next_cumulative_td,
+            alpha_decay,
+        );
+        self.apply_scalar_delta(
+            board,
+            is_black,
+            phase_idx,
+            clip_weight_update(alpha * next_cumulative_td),
+        );
+        (current_value, next_cumulative_td)
+    }
+}
+
+fn load_weight(weight: &AtomicU32) -> f32 {
+    f32::from_bits(weight.load(Ordering::Relaxed))
+}
+
+/// Unsynchronized add; a concurrent add to the same weight may be lost.
+fn add_weight(weight: &AtomicU32, delta: f32) {
+    weight.store((load_weight(weight) + delta).to_bits(), Ordering::Relaxed);
+}
+
+/// Collects worker statistics between progress reports of a [`TrainingRun`].
+struct ProgressReporter<'a> {
+    callback: Option<ProgressCallback<'a>>,
+    interval: usize,
+    total: usize,
+    start_time: Instant,
+    last_reported: usize,
+    stats: TrainingStats,
+    /// Latest per-phase weight norms of each worker.
+    worker_norms: Vec<Vec<f64>>,
+}
+
+impl ProgressReporter<'_> {
+    fn is_active(&self) -> bool {
+        self.callback.is_some() && self.interval > 0
+    }
+
+    fn record(&mut self, worker_idx: usize, stats: &TrainingStats, phase_weight_norms: Vec<f64>) {
+        self.stats.merge(stats);
+        self.worker_norms[worker_idx] = phase_weight_norms;
+    }
+
+    /// Reports the games since the previous report, with weight norms averaged
+    /// over the workers.
+    fn report(&mut self, completed: usize) -> Result<(), String> {
+        let phase_count = self.worker_norms.iter().map(Vec::len).max().unwrap_or(0);
+        let phase_weight_norms: Vec<f64> = (0..phase_count)
+            .map(|phase_idx| {
+                let norms: Vec<f64> = self
+                    .worker_norms
+                    .iter()
+                    .filter_map(|norms| norms.get(phase_idx).copied())
+                    .collect();
+                norms.iter().sum::<f64>() / norms.len() as f64
+            })
+            .collect();
+        let stats = std::mem::take(&mut self.stats);
+        self.last_reported = completed;
+        let progress = TrainingProgress {
+            completed,
+            total: self.total,
+            elapsed_seconds: self.start_time.elapsed().as_secs_f64(),
+            metrics: TrainingMetrics::new(&stats, &phase_weight_norms),
+        };
+        match self.callback.as_mut() {
+            Some(callback) => callback(&progress),
+            None => Ok(()),
+        }
+    }
+}
+
+struct CheckpointReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> CheckpointReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
 
-        if let Some(callback) = progress_callback.as_mut()
-            && progress_interval > 0
-            && last_reported != games
-        {
-            callback(games, games, start_time.elapsed().as_secs_f64())?;
-        }
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| "unexpected EOF while reading checkpoint".to_string())?;
+        let bytes = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
 
-        let mut workers = Vec::with_capacity(handles.len());
-        for handle in handles {
-            let worker = handle
-                .join()
-                .map_err(|_| "training worker thread panicked".to_string())??;
-            workers.push(worker);
-        }
-        TrainableNTuple::merge_weighted_parallel(&workers, games, threads)
-    })
+    fn read_array<const LEN: usize>(&mut self) -> Result<[u8; LEN], String> {
+        let mut bytes = [0u8; LEN];
+        bytes.copy_from_slice(self.read_bytes(LEN)?);
+        Ok(bytes)
+    }
+
+    fn read_usize(&mut self) -> Result<usize, String> {
+        let value = u64::from_le_bytes(self.read_array()?);
+        usize::try_from(value).map_err(|_| format!("checkpoint value {value} overflows usize"))
+    }
+
+    /// Reads a `u8` length-prefixed UTF-8 name.
+    fn read_name(&mut self, field: &str) -> Result<String, String> {
+        let len = self.read_array::<1>()?[0] as usize;
+        std::str::from_utf8(self.read_bytes(len)?)
+            .map(str::to_string)
+            .map_err(|_| format!("checkpoint {field} is not valid UTF-8"))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset == self.data.len()
+    }
 }
 
-fn train_worker_network(
-    network: TrainableNTuple,
+fn train_worker<N: TrainingNetwork>(
+    trainer: &mut TDLambdaTrainer<N>,
+    worker_idx: usize,
     games: usize,
-    alpha: f32,
-    alpha_decay: AlphaDecayStrategy,
-    alpha_decay_start_game: usize,
-    lambda_: f32,
-    epsilon: f64,
-    seed: u64,
-    random_opening_plies: usize,
     progress_interval: usize,
-    progress_tx: mpsc::Sender<WorkerMessage>,
-) -> Result<TrainableNTuple, String> {
-    let mut trainer = TDLambdaTrainer::new_with_alpha_decay(
-        network,
-        alpha,
-        alpha_decay,
-        alpha_decay_start_game,
-        lambda_,
-        epsilon,
-        seed,
-        random_opening_plies,
-    )?;
+    progress_tx: &mpsc::Sender<WorkerMessage>,
+) -> Result<(), String> {
+    let mut reported = 0usize;
+    for game_idx in 1..=games {
+        trainer.train_one_game()?;
+        if progress_interval > 0
+            && (game_idx.is_multiple_of(progress_interval) || game_idx == games)
+        {
+            progress_tx
+                .send(WorkerMessage::Progress {
+                    worker_idx,
+                    games: game_idx - reported,
+                    stats: Box::new(std::mem::take(&mut trainer.stats)),
+                    phase_weight_norms: trainer.network.phase_weight_norms(),
+                })
+                .map_err(|_| "failed to send worker progress".to_string())?;
+            reported = game_idx;
+        }
+    }
+    Ok(())
+}
 
-    if progress_interval > 0 {
-        let mut reported = 0usize;
-        let mut progress = |completed: usize, _total: usize, _elapsed: f64| -> Result<(), String> {
-            let delta = completed.saturating_sub(reported);
-            reported = completed;
-            if delta > 0 {
-                progress_tx
-                    .send(WorkerMessage::Progress(delta))
-                    .map_err(|_| "failed to send worker progress".to_string())?;
-            }
-            Ok(())
+fn merge_patterns(workers: &[(&TrainableNTuple, usize)]) -> Arc<TuplePatternSet> {
+    workers
+        .first()
+        .map(|(network, _)| Arc::clone(&network.patterns))
+        .unwrap_or_else(TuplePatternSet::default_patterns)
+}
+
+/// Games-weighted average of the workers' scalar feature weights.
+fn merge_scalar_features(
+    workers: &[(&TrainableNTuple, usize)],
+    total_games: usize,
+) -> Option<ScalarFeatureWeights> {
+    let mut merged = workers.first()?.0.features.clone()?;
+    for weight in merged.weights_mut().iter_mut().flatten() {
+        *weight = 0.0;
+    }
+    if total_games == 0 {
+        return Some(merged);
+    }
+    for (network, games) in workers {
+        let Some(features) = network.features.as_ref() else {
+            continue;
         };
-        trainer.train(games, progress_interval, Some(&mut progress))?;
-    } else {
-        trainer.train(games, 0, None)?;
+        let scale = (*games as f32) / (total_games as f32);
+        for (target, source) in merged
+            .weights_mut()
+            .iter_mut()
+            .flatten()
+            .zip(features.weights().iter().flatten())
+        {
+            *target += source * scale;
+        }
     }
-
-    Ok(trainer.into_network())
+    Some(merged)
 }
 
 fn nth_move_from_mask(mask: u64, target: u32) -> usize {
@@ -1922,6 +4726,38 @@ fn terminal_training_score(board: &Board, is_black: bool) -> f32 {
     }
 }
 
+/// Orders moves for exact solves of training positions, opponent mobility
+/// first; the solved scores do not depend on it.
+static EXACT_SOLVE_EVALUATOR: LazyLock<NTupleEvaluator> = LazyLock::new(|| {
+    let patterns = TuplePatternSet::new(vec![vec![0]]).expect("one-square pattern must be valid");
+    let mut network = TrainableNTuple::with_patterns(Arc::new(patterns))
+        .with_scalar_features(vec![ScalarFeature::Mobility])
+        .expect("mobility feature must be valid");
+    if let Some(features) = network.features.as_mut() {
+        for phase in features.weights_mut() {
+            phase[0] = 1.0;
+        }
+    }
+    let bytes = network
+        .to_uncompressed_bytes()
+        .expect("move ordering network must serialize");
+    NTupleEvaluator::from_bytes(&bytes).expect("move ordering network must deserialize")
+});
+
+/// Solver for the endgame positions of one game. Later positions of a game
+/// repeat subtrees of the earlier solves, so its table is kept between them.
+fn exact_game_solver() -> Searcher<'static> {
+    Searcher::exact_solver(&EXACT_SOLVE_EVALUATOR)
+        .with_transposition_table(TranspositionTable::default())
+}
+
+/// Final disc difference for the side to move under perfect play.
+fn exact_training_score(solver: &mut Searcher<'_>, board: &Board, is_black: bool) -> f32 {
+    solver
+        .exact_score(board, is_black)
+        .expect("exact solves without a time budget complete")
+}
+
 fn clip_weight_update(delta: f32) -> f32 {
     delta.clamp(-MAX_ABS_WEIGHT_UPDATE, MAX_ABS_WEIGHT_UPDATE)
 }
@@ -2040,7 +4876,8 @@ fn read_u32_le(data: &[u8], offset: usize) -> Result<u32, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ai::ntuple::{NTupleEvaluator, decompress_model_bytes};
+    use crate::ai::metadata::KEY_CREATED_AT;
+    use crate::ai::ntuple::{ModelFile, NTupleEvaluator, decompress_model_bytes};
 
     struct RecordingNetwork {
         value: f32,
@@ -2072,7 +4909,7 @@ mod tests {
             board,
             is_black,
             phase_idx: phase_index_for_board(&board, PHASE_COUNT),
-            feature_indices: TrainableNTuple::compute_feature_indices(&board, is_black),
+            feature_indices: DEFAULT_TUPLE_PATTERNS.feature_indices(&board, is_black),
         }
     }
 
@@ -2199,6 +5036,131 @@ mod tests {
         assert_eq!(trainer.network.updates[1], (true, 32.0));
     }
 
+    #[test]
+    fn update_weights_keeps_the_sign_between_same_player_positions() {
+        let network = RecordingNetwork {
+            value: 3.0,
+            updates: Vec::new(),
+        };
+        let mut trainer = TDLambdaTrainer::new(network, 1.0, 0.5, 0.0, 13, 0).unwrap();
+        let history = vec![
+            history_entry(Board::new(), true),
+            history_entry(Board::new(), true),
+        ];
+        let final_board = Board::from_bitboards(u64::MAX, 0);
+
+        trainer.update_weights(&history, &final_board).unwrap();
+
+        assert_eq!(trainer.network.updates, vec![(true, 61.0), (true, 30.5)]);
+    }
+
+    #[test]
+    fn league_config_round_trips_and_rejects_bad_specs() {
+        let league = LeagueConfig::parse("snapshot=100,positional=2,random,self=0.25").unwrap();
+        assert_eq!(
+            league,
+            LeagueConfig {
+                snapshot_interval: 100,
+                max_snapshots: 8,
+                positional_depth: 2,
+                random: true,
+                self_play: 0.25,
+            }
+        );
+        assert_eq!(
+            league.name(),
+            "snapshot=100,keep=8,positional=2,random,self=0.25"
+        );
+        assert_eq!(LeagueConfig::parse(&league.name()), Ok(league));
+
+        for spec in [
+            "",
+            "self=0.5",
+            "random,self=1",
+            "snapshot=10,keep=0",
+            "bogus=1",
+        ] {
+            assert!(LeagueConfig::parse(spec).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn league_games_only_update_the_learners_positions() {
+        let network = RecordingNetwork {
+            value: 0.0,
+            updates: Vec::new(),
+        };
+        let mut trainer = TDLambdaTrainer::new(network, 1.0, 0.5, 0.1, 17, 0)
+            .unwrap()
+            .with_league(LeagueConfig::parse("random").unwrap())
+            .unwrap();
+        let mut learner_colors = Vec::new();
+
+        for _ in 0..8 {
+            trainer.network.updates.clear();
+            trainer.train_one_game().unwrap();
+            let learner = trainer.network.updates[0].0;
+            assert!(
+                trainer
+                    .network
+                    .updates
+                    .iter()
+                    .all(|&(is_black, _)| is_black == learner)
+            );
+            learner_colors.push(learner);
+        }
+
+        assert!(learner_colors.contains(&true));
+        assert!(learner_colors.contains(&false));
+    }
+
+    #[test]
+    fn league_targets_keep_the_learners_sign() {
+        // Every recorded position is the learner's, so with a constant value
+        // only the final result adds TD error and the trace just decays.
+        let network = RecordingNetwork {
+            value: 3.0,
+            updates: Vec::new(),
+        };
+        let mut trainer = TDLambdaTrainer::new(network, 1.0, 0.5, 0.0, 23, 0)
+            .unwrap()
+            .with_league(LeagueConfig::parse("random").unwrap())
+            .unwrap();
+
+        for _ in 0..4 {
+            trainer.network.updates.clear();
+            trainer.train_one_game().unwrap();
+            let updates = &trainer.network.updates;
+            assert!(updates.len() > 1);
+            for pair in updates.windows(2) {
+                assert_eq!(pair[1].1, pair[0].1 * 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn league_keeps_the_latest_snapshots() {
+        let mut trainer = TDLambdaTrainer::new(TrainableNTuple::new(), 0.01, 0.7, 0.1, 19, 0)
+            .unwrap()
+            .with_league(LeagueConfig::parse("snapshot=2,keep=2,positional=1,random").unwrap())
+            .unwrap();
+
+        for _ in 0..6 {
+            trainer.train_one_game().unwrap();
+        }
+        let latest = trainer.network.weights.clone();
+        trainer.train_one_game().unwrap();
+
+        let snapshots = &trainer.league.as_ref().unwrap().snapshots;
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[1].weights, latest);
+        assert!(
+            snapshots
+                .iter()
+                .all(|snapshot| snapshot.visit_counts.is_none())
+        );
+    }
+
     #[test]
     fn phase_index_uses_two_plies_per_phase() {
         assert_eq!(phase_index_for_board(&Board::new(), PHASE_COUNT), 0);
@@ -2225,8 +5187,8 @@ mod tests {
         let mut network = TrainableNTuple::new();
         let phase0_board = board_with_empty_count(60);
         let phase1_board = board_with_empty_count(58);
-        let phase0_indices = TrainableNTuple::compute_feature_indices(&phase0_board, true);
-        let phase1_indices = TrainableNTuple::compute_feature_indices(&phase1_board, true);
+        let phase0_indices = DEFAULT_TUPLE_PATTERNS.feature_indices(&phase0_board, true);
+        let phase1_indices = DEFAULT_TUPLE_PATTERNS.feature_indices(&phase1_board, true);
 
         for symmetry in 0..SYMMETRY_COUNT {
             network.weights[0][0][phase0_indices[symmetry][0] as usize] = 1.0;
@@ -2241,7 +5203,7 @@ mod tests {
     fn update_only_touches_active_phase() {
         let mut network = TrainableNTuple::new();
         let board = board_with_empty_count(58);
-        let indices = TrainableNTuple::compute_feature_indices(&board, true);
+        let indices = DEFAULT_TUPLE_PATTERNS.feature_indices(&board, true);
 
         network.update(&board, true, 0.25);
 
@@ -2255,14 +5217,10 @@ mod tests {
         let mut next_board = board;
         assert_ne!(next_board.place(19, true), 0);
 
-        let previous = TrainableNTuple::compute_feature_indices(&board, false);
-        let delta = TrainableNTuple::update_feature_indices_from_transition(
-            &previous,
-            &board,
-            &next_board,
-            false,
-        );
-        let recomputed = TrainableNTuple::compute_feature_indices(&next_board, false);
+        let previous = DEFAULT_TUPLE_PATTERNS.feature_indices(&board, false);
+        let delta =
+            DEFAULT_TUPLE_PATTERNS.update_feature_indices(&previous, &board, &next_board, false);
+        let recomputed = DEFAULT_TUPLE_PATTERNS.feature_indices(&next_board, false);
 
         assert_eq!(delta, recomputed);
     }
@@ -2274,8 +5232,8 @@ mod tests {
         let mut trainer_b =
             TDLambdaTrainer::new(TrainableNTuple::new(), 0.01, 0.7, 0.3, 2026, 0).unwrap();
 
-        trainer_a.play_one_game().unwrap();
-        trainer_b.play_one_game().unwrap();
+        trainer_a.play_one_game(None).unwrap();
+        trainer_b.play_one_game(None).unwrap();
 
         assert_eq!(
             trainer_a.network.raw_weights(),
@@ -2295,8 +5253,8 @@ mod tests {
         let mut trainer_b =
             TDLambdaTrainer::new(TrainableNTuple::new(), 0.01, 0.7, 0.3, 2026, 4).unwrap();
 
-        trainer_a.play_one_game().unwrap();
-        trainer_b.play_one_game().unwrap();
+        trainer_a.play_one_game(None).unwrap();
+        trainer_b.play_one_game(None).unwrap();
 
         assert_eq!(
             trainer_a.network.raw_weights(),
@@ -2309,70 +5267,386 @@ mod tests {
         let mut trainer =
             TDLambdaTrainer::new(TrainableNTuple::new(), 0.0, 0.0, 0.0, 5, 0).unwrap();
         let mut updates = Vec::new();
-        let mut callback = |done: usize, total: usize, elapsed: f64| {
-            updates.push((done, total, elapsed));
+        let mut callback = |progress: &TrainingProgress| {
+            updates.push(progress.clone());
             Ok(())
         };
 
         trainer.train(5, 2, Some(&mut callback)).unwrap();
 
         assert_eq!(updates.len(), 3);
-        assert_eq!(updates[0].0, 2);
-        assert_eq!(updates[1].0, 4);
-        assert_eq!(updates[2].0, 5);
-        assert!(updates.iter().all(|(_, total, _)| *total == 5));
-        assert!(updates.iter().all(|(_, _, elapsed)| *elapsed >= 0.0));
+        assert_eq!(
+            updates
+                .iter()
+                .map(|progress| progress.completed)
+                .collect::<Vec<_>>(),
+            vec![2, 4, 5]
+        );
+        assert!(updates.iter().all(|progress| progress.total == 5));
+        assert!(
+            updates
+                .iter()
+                .all(|progress| progress.elapsed_seconds >= 0.0)
+        );
+        assert_eq!(
+            updates
+                .iter()
+                .map(|progress| progress.metrics.games)
+                .collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        for TrainingProgress { metrics, .. } in &updates {
+            assert!(metrics.mean_game_length > 50.0 && metrics.mean_game_length <= 60.0);
+            assert!(metrics.black_win_rate + metrics.white_win_rate <= 1.0);
+            // With alpha = 0 the weights stay zero, so only the terminal rewards produce errors.
+            assert!(metrics.mean_abs_td_error > 0.0);
+            assert_eq!(metrics.clipped_update_fraction, 0.0);
+            assert_eq!(metrics.weight_norm, 0.0);
+            assert_eq!(metrics.phase_weight_norms.len(), PHASE_COUNT);
+            assert_eq!(metrics.phase_mean_abs_td_error.len(), PHASE_COUNT);
+            assert!(metrics.phase_mean_abs_td_error[0].is_some());
+        }
+    }
+
+    #[test]
+    fn training_metrics_summarize_window_statistics() {
+        let mut stats = TrainingStats::default();
+        stats.record_game(&Board::from_bitboards(u64::MAX, 0));
+        stats.record_game(&Board::from_bitboards(0xFFFF_FFFF, 0xFFFF_FFFF_0000_0000));
+        stats.record_update(0, -2.0, true);
+        stats.record_update(0, 1.0, false);
+        stats.record_update(3, 0.5, false);
+        stats.record_update(3, 0.5, false);
+
+        let metrics = TrainingMetrics::new(&stats, &[3.0, 4.0]);
+        assert_eq!(metrics.games, 2);
+        assert_eq!(metrics.mean_game_length, 60.0);
+        assert_eq!(metrics.black_win_rate, 0.5);
+        assert_eq!(metrics.white_win_rate, 0.0);
+        assert_eq!(metrics.mean_abs_td_error, 1.0);
+        assert_eq!(metrics.phase_mean_abs_td_error[0], Some(1.5));
+        assert_eq!(metrics.phase_mean_abs_td_error[1], None);
+        assert_eq!(metrics.phase_mean_abs_td_error[3], Some(0.5));
+        assert_eq!(metrics.clipped_update_fraction, 0.25);
+        assert_eq!(metrics.weight_norm, 5.0);
+    }
+
+    #[test]
+    fn training_log_appends_csv_and_jsonl_records() {
+        let mut stats = TrainingStats::default();
+        stats.record_game(&Board::from_bitboards(u64::MAX, 0));
+        stats.record_update(0, 1.0, false);
+        let progress = TrainingProgress {
+            completed: 10,
+            total: 20,
+            elapsed_seconds: 1.5,
+            metrics: TrainingMetrics::new(&stats, &[3.0, 4.0]),
+        };
+        let dir = std::env::temp_dir().join(format!("reversi-training-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let csv_path = dir.join("metrics.csv");
+        let _ = std::fs::remove_file(&csv_path);
+        TrainingLog::open(&csv_path)
+            .unwrap()
+            .write(&progress)
+            .unwrap();
+        TrainingLog::open(&csv_path)
+            .unwrap()
+            .write(&progress)
+            .unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("completed,total,elapsed_seconds,games,"));
+        assert!(rows[0].ends_with("phase_0_weight_norm,phase_1_weight_norm"));
+        assert_eq!(rows[0].split(',').count(), rows[1].split(',').count());
+        assert!(rows[1].starts_with("10,20,1.5,1,60,1,0,1,0,5,1,,"));
+        assert_eq!(rows[1], rows[2]);
+
+        let jsonl_path = dir.join("metrics.jsonl");
+        let _ = std::fs::remove_file(&jsonl_path);
+        TrainingLog::open(&jsonl_path)
+            .unwrap()
+            .write(&progress)
+            .unwrap();
+        let jsonl = std::fs::read_to_string(&jsonl_path).unwrap();
+        assert!(jsonl.starts_with("{\"completed\":10,\"total\":20,\"elapsed_seconds\":1.5,"));
+        assert!(jsonl.contains("\"phase_mean_abs_td_error\":[1,null,"));
+        assert!(jsonl.trim_end().ends_with("\"phase_weight_norms\":[3,4]}"));
+
+        assert!(TrainingLog::open(&dir.join("metrics.txt")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn train_to_bytes_records_run_metadata() {
+        let parent = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
+        let bytes = train_to_bytes(2, 0.02, 0.5, 0.25, 7, 1, Some(&parent), 0, 0, None).unwrap();
+
+        let evaluator = NTupleEvaluator::from_bytes(&bytes).unwrap();
+        let metadata = evaluator.metadata().expect("metadata must be written");
+        assert_eq!(metadata.get(KEY_ALPHA), Some("0.02"));
+        assert_eq!(metadata.get(KEY_LAMBDA), Some("0.5"));
+        assert_eq!(metadata.get(KEY_EPSILON), Some("0.25"));
+        assert_eq!(metadata.get(KEY_ALPHA_DECAY), Some("none"));
+        assert_eq!(metadata.get(KEY_GAMES), Some("2"));
+        assert_eq!(metadata.get(KEY_SEED), Some("7"));
+        assert_eq!(metadata.get(KEY_CREATED_AT), None);
+        let parent_hash = crc32fast::hash(&decompress_model_bytes(&parent).unwrap());
+        assert_eq!(
+            metadata.get(KEY_PARENT_HASH),
+            Some(format!("{parent_hash:08x}").as_str())
+        );
+
+        let mut network = TrainableNTuple::from_bytes(&bytes).unwrap();
+        assert_eq!(network.metadata(), metadata);
+        network
+            .metadata_mut()
+            .set_benchmark("vs_random.win_rate", 0.9);
+        let quantized =
+            NTupleEvaluator::from_bytes(&network.to_quantized_bytes().unwrap()).unwrap();
+        assert_eq!(quantized.metadata(), Some(network.metadata()));
+    }
+
+    #[test]
+    fn train_to_bytes_writes_v4_header_with_phase_count() {
+        let bytes = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
+        let bytes = decompress_model_bytes(&bytes).unwrap();
+        assert_eq!(&bytes[0..4], MAGIC);
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), VERSION);
+        assert_eq!(
+            u32::from_le_bytes(bytes[16..20].try_into().unwrap()),
+            PHASE_COUNT as u32
+        );
+    }
+
+    #[test]
+    fn exported_bytes_are_readable_by_inference_evaluator() {
+        let bytes = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
+        let evaluator = NTupleEvaluator::from_bytes(&bytes).unwrap();
+        let score = evaluator.evaluate(&Board::new(), true);
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn to_bytes_rejects_non_finite_weights() {
+        let mut network = TrainableNTuple::new();
+        network.weights[0][0][0] = f32::NAN;
+
+        let err = network.to_bytes().unwrap_err();
+        assert!(err.contains("non-finite"));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_finite_weights() {
+        let valid_bytes = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
+        let mut bytes = decompress_model_bytes(&valid_bytes).unwrap().into_owned();
+        let tuple_defs_len: usize = TUPLE_PATTERNS.iter().map(|pattern| 1 + pattern.len()).sum();
+        let first_weight_offset = HEADER_SIZE + tuple_defs_len;
+        bytes[first_weight_offset..first_weight_offset + 4]
+            .copy_from_slice(&f32::NAN.to_le_bytes());
+        let crc32 = crc32fast::hash(&bytes[HEADER_SIZE..]);
+        bytes[12..16].copy_from_slice(&crc32.to_le_bytes());
+        let corrupted = compress_model_bytes(&bytes).unwrap();
+
+        let err = TrainableNTuple::from_bytes(&corrupted).unwrap_err();
+        assert!(err.contains("non-finite"));
+    }
+
+    #[test]
+    fn short_training_produces_only_finite_weights() {
+        let bytes = train_to_bytes(64, 0.01, 0.7, 0.1, 42, 1, None, 4, 0, None).unwrap();
+        assert!(TrainableNTuple::from_bytes(&bytes).is_ok());
+    }
+
+    #[test]
+    fn tuple_pattern_set_rejects_invalid_patterns() {
+        let cases: [(Vec<Vec<u8>>, &str); 6] = [
+            (vec![], "must not be empty"),
+            (vec![vec![]], "1 to 10 squares"),
+            (vec![(0..11).collect()], "1 to 10 squares"),
+            (vec![vec![0, 64]], "out-of-range"),
+            (vec![vec![0, 1, 0]], "repeats board position 0"),
+            (vec![vec![0, 1], vec![63, 62]], "duplicates pattern #0"),
+        ];
+        for (patterns, expected) in cases {
+            let err = TuplePatternSet::new(patterns.clone()).unwrap_err();
+            assert!(err.contains(expected), "{patterns:?}: {err}");
+        }
+
+        let too_many = (0..=MAX_TUPLE_COUNT as u8).map(|pos| vec![pos]).collect();
+        assert!(TuplePatternSet::new(too_many).is_err());
+        assert_eq!(DEFAULT_TUPLE_PATTERNS.len(), TUPLE_PATTERNS.len());
+    }
+
+    #[test]
+    fn tuple_pattern_files_round_trip() {
+        let text = "# corner\n0, 1, 8 9\n\n27,28,35 # center\n";
+        let patterns = TuplePatternSet::parse(text).unwrap();
+        assert_eq!(patterns.patterns(), &[vec![0, 1, 8, 9], vec![27, 28, 35]]);
+        assert_eq!(
+            TuplePatternSet::parse(&patterns.to_text()).unwrap(),
+            patterns
+        );
+        assert_eq!(
+            canonical_tuple_shape(&[63, 62]),
+            canonical_tuple_shape(&[1, 0])
+        );
+
+        let err = TuplePatternSet::parse("0,1\n2,x\n").unwrap_err();
+        assert!(err.contains("line 2"), "{err}");
+    }
+
+    #[test]
+    fn custom_tuple_patterns_are_trained_and_persisted() {
+        let patterns = vec![vec![0, 1, 2, 3], vec![9, 18, 27], vec![19, 20, 27, 28]];
+        let layout = NetworkLayout {
+            tuple_patterns: Arc::new(TuplePatternSet::new(patterns.clone()).unwrap()),
+            scalar_features: Vec::new(),
+        };
+        let bytes = train_to_bytes_with_layout(
+            16,
+            0.01,
+            AlphaDecayStrategy::None,
+            0,
+            0.7,
+            0.1,
+            42,
+            2,
+            None,
+            Some(&layout),
+            0,
+            0,
+            None,
+        )
+        .unwrap();
+
+        let network = TrainableNTuple::from_bytes(&bytes).unwrap();
+        assert_eq!(network.patterns().patterns(), patterns.as_slice());
+        assert_eq!(network.raw_weights()[0].len(), patterns.len());
+        let evaluator = NTupleEvaluator::from_bytes(&bytes).unwrap();
+        let mut board = Board::new();
+        assert_ne!(board.place(19, true), 0);
+        assert!((evaluator.evaluate(&board, false) - network.evaluate(&board, false)).abs() < 1e-5);
+
+        let resumed = train_to_bytes_with_layout(
+            4,
+            0.01,
+            AlphaDecayStrategy::None,
+            0,
+            0.7,
+            0.1,
+            7,
+            1,
+            Some(&bytes),
+            None,
+            0,
+            0,
+            None,
+        )
+        .unwrap();
+        let resumed = TrainableNTuple::from_bytes(&resumed).unwrap();
+        assert_eq!(resumed.patterns().patterns(), patterns.as_slice());
+
+        let err = train_to_bytes_with_layout(
+            4,
+            0.01,
+            AlphaDecayStrategy::None,
+            0,
+            0.7,
+            0.1,
+            7,
+            1,
+            Some(&bytes),
+            Some(&NetworkLayout::default()),
+            0,
+            0,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("do not match"));
     }
 
     #[test]
-    fn train_to_bytes_writes_v4_header_with_phase_count() {
-        let bytes = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
-        let bytes = decompress_model_bytes(&bytes).unwrap();
-        assert_eq!(&bytes[0..4], MAGIC);
-        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), VERSION);
+    fn scalar_features_are_trained_and_persisted() {
+        let layout = NetworkLayout {
+            scalar_features: ScalarFeature::ALL.to_vec(),
+            ..NetworkLayout::default()
+        };
+        let bytes = train_to_bytes_with_layout(
+            16,
+            0.01,
+            AlphaDecayStrategy::None,
+            0,
+            0.7,
+            0.1,
+            42,
+            2,
+            None,
+            Some(&layout),
+            0,
+            0,
+            None,
+        )
+        .unwrap();
+
+        let network = TrainableNTuple::from_bytes(&bytes).unwrap();
+        let features = network.scalar_features().unwrap();
+        assert_eq!(features.features(), ScalarFeature::ALL.as_slice());
+        assert!(
+            features
+                .weights()
+                .iter()
+                .flatten()
+                .any(|weight| *weight != 0.0)
+        );
         assert_eq!(
-            u32::from_le_bytes(bytes[16..20].try_into().unwrap()),
-            PHASE_COUNT as u32
+            TrainableNTuple::from_bytes(&network.to_bytes().unwrap())
+                .unwrap()
+                .scalar_features(),
+            Some(features)
         );
-    }
 
-    #[test]
-    fn exported_bytes_are_readable_by_inference_evaluator() {
-        let bytes = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
         let evaluator = NTupleEvaluator::from_bytes(&bytes).unwrap();
-        let score = evaluator.evaluate(&Board::new(), true);
-        assert_eq!(score, 0.0);
-    }
-
-    #[test]
-    fn to_bytes_rejects_non_finite_weights() {
-        let mut network = TrainableNTuple::new();
-        network.weights[0][0][0] = f32::NAN;
-
-        let err = network.to_bytes().unwrap_err();
-        assert!(err.contains("non-finite"));
+        let mut board = Board::new();
+        assert_ne!(board.place(19, true), 0);
+        assert_ne!(board.place(18, false), 0);
+        assert!((evaluator.evaluate(&board, true) - network.evaluate(&board, true)).abs() < 1e-4);
+        let ablated = evaluator.without_scalar_features(&ScalarFeature::ALL);
+        assert!(ablated.scalar_features().is_none());
+
+        let err = train_to_bytes_with_layout(
+            4,
+            0.01,
+            AlphaDecayStrategy::None,
+            0,
+            0.7,
+            0.1,
+            7,
+            1,
+            Some(&bytes),
+            Some(&NetworkLayout::default()),
+            0,
+            0,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.contains("scalar features"), "{err}");
     }
 
     #[test]
-    fn from_bytes_rejects_non_finite_weights() {
-        let valid_bytes = train_to_bytes(0, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
-        let mut bytes = decompress_model_bytes(&valid_bytes).unwrap().into_owned();
-        let tuple_defs_len: usize = TUPLE_PATTERNS.iter().map(|pattern| 1 + pattern.len()).sum();
-        let first_weight_offset = HEADER_SIZE + tuple_defs_len;
-        bytes[first_weight_offset..first_weight_offset + 4]
-            .copy_from_slice(&f32::NAN.to_le_bytes());
-        let crc32 = crc32fast::hash(&bytes[HEADER_SIZE..]);
-        bytes[12..16].copy_from_slice(&crc32.to_le_bytes());
-        let corrupted = compress_model_bytes(&bytes).unwrap();
-
-        let err = TrainableNTuple::from_bytes(&corrupted).unwrap_err();
-        assert!(err.contains("non-finite"));
-    }
+    fn incremental_feature_indices_match_full_recompute_for_custom_patterns() {
+        let patterns =
+            TuplePatternSet::new(vec![vec![0, 9, 18, 27, 36, 45, 54, 63], vec![19, 20, 26]])
+                .unwrap();
+        let board = Board::new();
+        let mut next_board = board;
+        assert_ne!(next_board.place(19, true), 0);
 
-    #[test]
-    fn short_training_produces_only_finite_weights() {
-        let bytes = train_to_bytes(64, 0.01, 0.7, 0.1, 42, 1, None, 4, 0, None).unwrap();
-        assert!(TrainableNTuple::from_bytes(&bytes).is_ok());
+        let previous = patterns.feature_indices(&board, false);
+        let delta = patterns.update_feature_indices(&previous, &board, &next_board, false);
+        assert_eq!(delta, patterns.feature_indices(&next_board, false));
     }
 
     #[test]
@@ -2401,6 +5675,11 @@ mod tests {
             AlphaDecayStrategy::from_name("inverse_visit").unwrap(),
             AlphaDecayStrategy::InverseVisit
         );
+        assert_eq!(
+            AlphaDecayStrategy::from_name("tc").unwrap(),
+            AlphaDecayStrategy::TemporalCoherence
+        );
+        assert_eq!(AlphaDecayStrategy::TemporalCoherence.name(), "tc");
         assert!(AlphaDecayStrategy::from_name("linear").is_err());
     }
 
@@ -2436,7 +5715,7 @@ mod tests {
             .unwrap();
         let board = Board::new();
         let phase_idx = phase_index_for_board(&board, PHASE_COUNT);
-        let indices = TrainableNTuple::compute_feature_indices(&board, true);
+        let indices = DEFAULT_TUPLE_PATTERNS.feature_indices(&board, true);
         let first_index = indices[0][0] as usize;
 
         network.apply_delta_with_alpha_decay(
@@ -2525,6 +5804,81 @@ mod tests {
         assert!(resumed_total_visits >= total_visits);
     }
 
+    #[test]
+    fn tc_alpha_decay_slows_weights_whose_updates_disagree() {
+        let mut network = TrainableNTuple::new();
+        network
+            .prepare_for_alpha_decay(AlphaDecayStrategy::TemporalCoherence)
+            .unwrap();
+        let board = Board::new();
+        let phase_idx = phase_index_for_board(&board, PHASE_COUNT);
+        let indices = DEFAULT_TUPLE_PATTERNS.feature_indices(&board, true);
+        let first_index = indices[0][0] as usize;
+        let weight = |network: &TrainableNTuple| network.weights[phase_idx][0][first_index];
+        let apply = |network: &mut TrainableNTuple, td: f32| {
+            network.apply_delta_with_alpha_decay(
+                phase_idx,
+                &indices,
+                0.05,
+                td,
+                AlphaDecayStrategy::TemporalCoherence,
+            );
+        };
+
+        apply(&mut network, 1.0);
+        let first_step = weight(&network);
+        apply(&mut network, 1.0);
+        let agreeing_step = weight(&network) - first_step;
+        assert!(first_step > 0.0);
+        assert!((agreeing_step - first_step).abs() < 1e-6);
+
+        apply(&mut network, -1.0);
+        let before = weight(&network);
+        apply(&mut network, 1.0);
+        let slowed_step = weight(&network) - before;
+        assert!(slowed_step > 0.0);
+        assert!(slowed_step < first_step);
+
+        let accumulator = network.tc_accumulators.as_ref().unwrap()[phase_idx][0][first_index];
+        assert!(accumulator.absolute > accumulator.signed.abs());
+        assert!(accumulator.rate() < 1.0);
+    }
+
+    #[test]
+    fn tc_alpha_decay_serializes_accumulators_as_model_v6() {
+        let bytes = train_to_bytes_with_alpha_decay(
+            2,
+            0.01,
+            AlphaDecayStrategy::TemporalCoherence,
+            0,
+            0.7,
+            0.1,
+            42,
+            1,
+            None,
+            0,
+            0,
+            None,
+        )
+        .unwrap();
+        let model = ModelFile::from_bytes(&bytes).unwrap();
+        assert_eq!(model.version, VERSION_V6);
+        assert!(model.visit_counts.is_some());
+        assert!(model.tc_accumulators.is_some());
+
+        let loaded = TrainableNTuple::from_bytes(&bytes).unwrap();
+        let accumulators = loaded.tc_accumulators.as_ref().unwrap();
+        let total_absolute: f64 = accumulators
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|accumulator| f64::from(accumulator.absolute))
+            .sum();
+        assert!(total_absolute > 0.0);
+        assert_eq!(loaded.to_bytes().unwrap(), bytes);
+        assert!(NTupleEvaluator::from_bytes(&bytes).is_ok());
+    }
+
     #[test]
     fn parallel_training_is_reproducible_for_fixed_seed_and_thread_count() {
         let first = train_to_bytes(8, 0.01, 0.7, 0.1, 42, 2, None, 0, 0, None).unwrap();
@@ -2540,7 +5894,218 @@ mod tests {
             train_to_bytes(8, 0.01, 0.7, 0.1, 42, 1, Some(&checkpoint), 0, 0, None).unwrap();
         let fresh = train_to_bytes(8, 0.01, 0.7, 0.1, 42, 1, None, 0, 0, None).unwrap();
 
-        assert_eq!(resumed, fresh);
+        // The only difference is the lineage: `resumed` names its parent.
+        let mut fresh = TrainableNTuple::from_bytes(&fresh).unwrap();
+        let parent_hash = crc32fast::hash(&decompress_model_bytes(&checkpoint).unwrap());
+        fresh
+            .metadata_mut()
+            .insert(KEY_PARENT_HASH, format!("{parent_hash:08x}"));
+        assert_eq!(resumed, fresh.to_bytes().unwrap());
+    }
+
+    fn checkpoint_test_config(threads: usize, parallel_mode: ParallelMode) -> TrainingConfig {
+        TrainingConfig {
+            games: 4,
+            alpha: 0.01,
+            alpha_decay: AlphaDecayStrategy::InverseVisit,
+            alpha_decay_start_game: 0,
+            lambda_: 0.7,
+            epsilon: 0.1,
+            seed: 42,
+            threads,
+            random_opening_plies: 2,
+            parallel_mode,
+            search_depth: DEFAULT_TRAINING_SEARCH_DEPTH,
+            exact_td_empties: 0,
+            epsilon_schedule: EpsilonSchedule::Constant,
+            exploration: ExplorationPolicy::Uniform,
+            league: None,
+        }
+    }
+
+    fn checkpoint_test_layout() -> NetworkLayout {
+        NetworkLayout {
+            tuple_patterns: Arc::new(
+                TuplePatternSet::new(vec![vec![0, 1, 2, 3, 4], vec![0, 9, 18, 27]]).unwrap(),
+            ),
+            scalar_features: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn interrupted_training_resumes_bit_for_bit_from_checkpoint() {
+        let layout = checkpoint_test_layout();
+        let exact_endgame = TrainingConfig {
+            search_depth: 1,
+            exact_td_empties: 6,
+            epsilon_schedule: EpsilonSchedule::Linear { end: 0.0, games: 3 },
+            exploration: ExplorationPolicy::Boltzmann { temperature: 2.0 },
+            ..checkpoint_test_config(1, ParallelMode::Independent)
+        };
+        let temporal_coherence = TrainingConfig {
+            alpha_decay: AlphaDecayStrategy::TemporalCoherence,
+            ..checkpoint_test_config(2, ParallelMode::SyncRounds { round_games: 3 })
+        };
+        let league = TrainingConfig {
+            league: Some(LeagueConfig::parse("snapshot=1,keep=2,positional=1,random").unwrap()),
+            ..checkpoint_test_config(2, ParallelMode::Independent)
+        };
+        for config in [
+            checkpoint_test_config(1, ParallelMode::Independent),
+            checkpoint_test_config(2, ParallelMode::Independent),
+            checkpoint_test_config(2, ParallelMode::SyncRounds { round_games: 3 }),
+            exact_endgame,
+            temporal_coherence,
+            league,
+        ] {
+            let mut uninterrupted = TrainingRun::new(config.clone(), None, Some(&layout)).unwrap();
+            uninterrupted.train(0, None, 0, None).unwrap();
+            let expected = uninterrupted.into_network().unwrap().to_bytes().unwrap();
+
+            let mut checkpoints = Vec::new();
+            let mut interrupt = |run: &TrainingRun| -> Result<(), String> {
+                checkpoints.push((run.completed_games(), run.to_checkpoint_bytes()?));
+                Err("interrupted".to_string())
+            };
+            let mut interrupted = TrainingRun::new(config.clone(), None, Some(&layout)).unwrap();
+            assert_eq!(
+                interrupted.train(0, None, 2, Some(&mut interrupt)),
+                Err("interrupted".to_string())
+            );
+            assert_eq!(checkpoints.len(), 1);
+            assert_eq!(checkpoints[0].0, 2);
+
+            let restored = TrainingRun::from_checkpoint_bytes(&checkpoints[0].1).unwrap();
+            assert_eq!(restored.config(), &config);
+            assert_eq!(restored.completed_games(), 2);
+            assert!(restored.into_network().is_err());
+
+            let mut resumed = TrainingRun::from_checkpoint_bytes(&checkpoints[0].1).unwrap();
+            let mut reported = Vec::new();
+            let mut progress = |progress: &TrainingProgress| {
+                reported.push((progress.completed, progress.total));
+                Ok(())
+            };
+            let mut later_checkpoints = Vec::new();
+            let mut record = |run: &TrainingRun| -> Result<(), String> {
+                later_checkpoints.push(run.completed_games());
+                Ok(())
+            };
+            resumed
+                .train(1, Some(&mut progress), 2, Some(&mut record))
+                .unwrap();
+            assert_eq!(later_checkpoints, vec![4]);
+            assert_eq!(reported.last(), Some(&(4, 4)));
+            let actual = resumed.into_network().unwrap().to_bytes().unwrap();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn parallel_mode_parses_its_names() {
+        for mode in [
+            ParallelMode::Independent,
+            ParallelMode::SyncRounds { round_games: 500 },
+            ParallelMode::Hogwild,
+        ] {
+            assert_eq!(ParallelMode::parse(&mode.name()), Ok(mode));
+        }
+        assert!(ParallelMode::parse("sync:0").is_err());
+        assert!(ParallelMode::parse("sync").is_err());
+        assert!(ParallelMode::parse("shared").is_err());
+    }
+
+    #[test]
+    fn sync_rounds_hand_every_worker_the_merged_network() {
+        let layout = checkpoint_test_layout();
+        let config = checkpoint_test_config(2, ParallelMode::SyncRounds { round_games: 2 });
+        let mut run = TrainingRun::new(config, None, Some(&layout)).unwrap();
+        let mut synced_rounds = 0;
+        let mut check = |run: &TrainingRun| -> Result<(), String> {
+            let TrainingWorkers::Replicated(workers) = &run.workers else {
+                return Err("sync rounds must replicate the network".to_string());
+            };
+            let base = run.round_base.as_ref().expect("round base must be kept");
+            let base_bytes = base.to_uncompressed_bytes()?;
+            for worker in workers {
+                assert_eq!(worker.trainer.network.to_uncompressed_bytes()?, base_bytes);
+            }
+            assert_eq!(run.current_network()?.to_uncompressed_bytes()?, base_bytes);
+            let visits: u64 = base
+                .visit_counts
+                .iter()
+                .flatten()
+                .flatten()
+                .flatten()
+                .map(|count| u64::from(*count))
+                .sum();
+            assert!(visits > 0);
+            synced_rounds += 1;
+            Ok(())
+        };
+        run.train(0, None, 2, Some(&mut check)).unwrap();
+        assert_eq!(synced_rounds, 2);
+
+        let network = run.into_network().unwrap();
+        assert_eq!(network.metadata().get(KEY_PARALLEL_MODE), Some("sync:2"));
+    }
+
+    #[test]
+    fn hogwild_workers_train_one_shared_network() {
+        let layout = checkpoint_test_layout();
+        let config = checkpoint_test_config(2, ParallelMode::Hogwild);
+        let mut checkpoint = None;
+        let mut interrupt = |run: &TrainingRun| -> Result<(), String> {
+            checkpoint = Some(run.to_checkpoint_bytes()?);
+            Err("interrupted".to_string())
+        };
+        let mut run = TrainingRun::new(config.clone(), None, Some(&layout)).unwrap();
+        assert!(run.train(0, None, 2, Some(&mut interrupt)).is_err());
+        let snapshot = run.current_network().unwrap();
+        assert!(snapshot.phase_weight_norms().iter().any(|norm| *norm > 0.0));
+
+        let mut resumed = TrainingRun::from_checkpoint_bytes(&checkpoint.unwrap()).unwrap();
+        assert_eq!(resumed.config(), &config);
+        assert_eq!(resumed.completed_games(), 2);
+        assert_eq!(
+            resumed.current_network().unwrap().to_uncompressed_bytes(),
+            snapshot.to_uncompressed_bytes()
+        );
+        resumed.train(0, None, 0, None).unwrap();
+
+        let network = resumed.into_network().unwrap();
+        assert_eq!(network.metadata().get(KEY_PARALLEL_MODE), Some("hogwild"));
+        assert!(
+            network
+                .phase_weight_norms()
+                .iter()
+                .all(|norm| norm.is_finite())
+        );
+    }
+
+    #[test]
+    fn checkpoint_bytes_reject_corruption() {
+        let run = TrainingRun::new(
+            checkpoint_test_config(1, ParallelMode::Independent),
+            None,
+            Some(&checkpoint_test_layout()),
+        )
+        .unwrap();
+        let bytes = decompress_model_bytes(&run.to_checkpoint_bytes().unwrap())
+            .unwrap()
+            .into_owned();
+        assert!(TrainingRun::from_checkpoint_bytes(&bytes).is_ok());
+
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        assert_eq!(
+            TrainingRun::from_checkpoint_bytes(&corrupted).err(),
+            Some("checkpoint CRC32 mismatch".to_string())
+        );
+        assert!(TrainingRun::from_checkpoint_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(TrainingRun::from_checkpoint_bytes(b"NTRV").is_err());
     }
 
     #[test]
@@ -2593,8 +6158,12 @@ mod tests {
         let mut trainer =
             TDLambdaTrainer::new(TrainableNTuple::new(), 0.01, 0.7, 0.0, 19, 0).unwrap();
         let board = Board::new();
-        let expected =
-            exhaustive_select_move(&trainer.network, &board, true, TRAINING_SEARCH_DEPTH);
+        let expected = exhaustive_select_move(
+            &trainer.network,
+            &board,
+            true,
+            DEFAULT_TRAINING_SEARCH_DEPTH,
+        );
 
         let actual = trainer
             .select_move(&board, true, board.legal_moves(true))
@@ -2614,13 +6183,17 @@ mod tests {
             "white should still have a legal move"
         );
 
-        let expected =
-            exhaustive_training_search(&trainer.network, &board, true, TRAINING_SEARCH_DEPTH);
+        let expected = exhaustive_training_search(
+            &trainer.network,
+            &board,
+            true,
+            DEFAULT_TRAINING_SEARCH_DEPTH,
+        );
         let actual = trainer
             .search_training_position(
                 &board,
                 true,
-                TRAINING_SEARCH_DEPTH,
+                DEFAULT_TRAINING_SEARCH_DEPTH,
                 f32::NEG_INFINITY,
                 f32::INFINITY,
             )
@@ -2628,4 +6201,279 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn select_move_honors_configured_search_depth() {
+        let board = Board::new();
+        let legal = board.legal_moves(true);
+        for search_depth in [1u8, 3] {
+            let mut trainer = TDLambdaTrainer::with_config(
+                TrainableNTuple::new(),
+                &TrainerConfig {
+                    alpha: 0.01,
+                    lambda_: 0.7,
+                    search_depth,
+                    ..TrainerConfig::default()
+                },
+                29,
+            )
+            .unwrap();
+            let expected = exhaustive_select_move(&trainer.network, &board, true, search_depth);
+
+            assert_eq!(trainer.select_move(&board, true, legal).unwrap(), expected);
+        }
+
+        let mut greedy = TDLambdaTrainer::with_config(
+            CountingNetwork::new(),
+            &TrainerConfig {
+                alpha: 0.01,
+                lambda_: 0.7,
+                search_depth: 1,
+                ..TrainerConfig::default()
+            },
+            31,
+        )
+        .unwrap();
+        greedy.select_move(&board, true, legal).unwrap();
+        assert_eq!(
+            greedy.network.evaluations.get(),
+            legal.count_ones() as usize
+        );
+    }
+
+    #[test]
+    fn trainer_rejects_invalid_search_settings() {
+        for (search_depth, exact_td_empties) in [(0, 0), (2, MAX_EXACT_TD_EMPTIES + 1)] {
+            assert!(
+                TDLambdaTrainer::with_config(
+                    TrainableNTuple::new(),
+                    &TrainerConfig {
+                        alpha: 0.01,
+                        lambda_: 0.7,
+                        search_depth,
+                        exact_td_empties,
+                        ..TrainerConfig::default()
+                    },
+                    37
+                )
+                .is_err()
+            );
+        }
+    }
+
+    fn minimax_score(board: &Board, is_black: bool) -> i32 {
+        let legal = board.legal_moves(is_black);
+        if legal == 0 {
+            if board.legal_moves(!is_black) == 0 {
+                return terminal_training_score(board, is_black) as i32;
+            }
+            return -minimax_score(board, !is_black);
+        }
+        let mut best = i32::MIN;
+        let mut remaining = legal;
+        while remaining != 0 {
+            let mv = remaining.trailing_zeros() as usize;
+            remaining &= remaining - 1;
+            let mut next_board = *board;
+            next_board.place(mv, is_black);
+            best = best.max(-minimax_score(&next_board, !is_black));
+        }
+        best
+    }
+
+    #[test]
+    fn exact_training_score_matches_minimax_on_random_endgames() {
+        let mut rng = ChaCha8Rng::seed_from_u64(41);
+        for _ in 0..4 {
+            // One solver per game, as in training, so later positions are
+            // solved on the table the earlier ones filled.
+            let mut solver = exact_game_solver();
+            let mut board = Board::new();
+            let mut is_black = true;
+            loop {
+                if board.empty_count() <= 8 {
+                    assert_eq!(
+                        exact_training_score(&mut solver, &board, is_black),
+                        minimax_score(&board, is_black) as f32
+                    );
+                }
+                let legal = board.legal_moves(is_black);
+                if legal == 0 {
+                    if board.legal_moves(!is_black) == 0 {
+                        break;
+                    }
+                } else {
+                    let mv = nth_move_from_mask(legal, rng.gen_range(0..legal.count_ones()));
+                    board.place(mv, is_black);
+                }
+                is_black = !is_black;
+            }
+        }
+    }
+
+    #[test]
+    fn exact_td_targets_replace_the_game_result_near_the_end() {
+        // Black must pass and white then captures everything, while the
+        // recorded game claims a black wipeout.
+        let history = vec![
+            history_entry(Board::new(), false),
+            history_entry(black_pass_board(), true),
+        ];
+        let final_board = Board::from_bitboards(u64::MAX, 0);
+        let updates = |exact_td_empties| {
+            let network = RecordingNetwork {
+                value: 0.0,
+                updates: Vec::new(),
+            };
+            let mut trainer = TDLambdaTrainer::with_config(
+                network,
+                &TrainerConfig {
+                    alpha: 1.0,
+                    lambda_: 0.5,
+                    exact_td_empties,
+                    ..TrainerConfig::default()
+                },
+                43,
+            )
+            .unwrap();
+            trainer.update_weights(&history, &final_board).unwrap();
+            trainer.network.updates
+        };
+
+        assert_eq!(updates(0), vec![(true, 64.0), (false, -32.0)]);
+        assert_eq!(updates(1), vec![(true, -64.0), (false, 64.0)]);
+    }
+
+    #[test]
+    fn epsilon_schedules_parse_and_follow_their_shape() {
+        for name in [
+            "constant",
+            "linear:0.01:1000",
+            "exponential:0.999:0.05",
+            "phase:0",
+        ] {
+            let schedule = EpsilonSchedule::parse(name).unwrap();
+            assert_eq!(EpsilonSchedule::parse(&schedule.name()), Ok(schedule));
+        }
+        for name in [
+            "linear:0.1",
+            "linear:0.1:0",
+            "exponential:1.5",
+            "phase:",
+            "decay",
+        ] {
+            assert!(EpsilonSchedule::parse(name).is_err(), "{name}");
+        }
+        for schedule in [
+            EpsilonSchedule::Linear { end: 0.1, games: 0 },
+            EpsilonSchedule::Exponential {
+                decay: 0.9,
+                floor: -0.1,
+            },
+            EpsilonSchedule::PerPhase { endgame: f64::NAN },
+        ] {
+            assert!(schedule.validate().is_err(), "{schedule:?}");
+        }
+
+        let opening = Board::new();
+        let endgame = board_with_empty_count(2);
+        let linear = EpsilonSchedule::Linear {
+            end: 0.1,
+            games: 100,
+        };
+        assert!((linear.epsilon_for(0.5, 50, &opening) - 0.3).abs() < 1e-12);
+        assert!((linear.epsilon_for(0.5, 500, &opening) - 0.1).abs() < 1e-12);
+        let exponential = EpsilonSchedule::Exponential {
+            decay: 0.5,
+            floor: 0.1,
+        };
+        assert!((exponential.epsilon_for(0.8, 2, &opening) - 0.2).abs() < 1e-12);
+        assert_eq!(exponential.epsilon_for(0.8, 10, &opening), 0.1);
+        let per_phase = EpsilonSchedule::PerPhase { endgame: 0.0 };
+        assert_eq!(per_phase.epsilon_for(0.4, 0, &opening), 0.4);
+        assert_eq!(per_phase.epsilon_for(0.4, 0, &endgame), 0.0);
+    }
+
+    #[test]
+    fn parallel_workers_follow_one_epsilon_schedule() {
+        let config = TrainingConfig {
+            games: 9,
+            alpha_decay_start_game: 10,
+            epsilon_schedule: EpsilonSchedule::Linear {
+                end: 0.0,
+                games: 100,
+            },
+            ..checkpoint_test_config(3, ParallelMode::Independent)
+        };
+        let layout = checkpoint_test_layout();
+        let mut workers = new_workers(&config, &[3, 3, 3], || layout.build().unwrap()).unwrap();
+        assert_eq!(workers[2].trainer.alpha_decay_start_game, 16);
+        for worker in &mut workers {
+            assert_eq!(worker.trainer.schedule_game(), 10);
+            worker.trainer.completed_games = 2;
+            assert_eq!(worker.trainer.schedule_game(), 16);
+        }
+    }
+
+    #[test]
+    fn exploration_policy_parses_its_names() {
+        assert_eq!(
+            ExplorationPolicy::parse("uniform"),
+            Ok(ExplorationPolicy::Uniform)
+        );
+        let boltzmann = ExplorationPolicy::parse("boltzmann:1.5").unwrap();
+        assert_eq!(boltzmann, ExplorationPolicy::Boltzmann { temperature: 1.5 });
+        assert_eq!(ExplorationPolicy::parse(&boltzmann.name()), Ok(boltzmann));
+        for name in ["boltzmann", "boltzmann:0", "boltzmann:-1", "softmax:1"] {
+            assert!(ExplorationPolicy::parse(name).is_err(), "{name}");
+        }
+        let frozen = ExplorationPolicy::Boltzmann { temperature: 0.0 };
+        assert!(frozen.validate().is_err());
+    }
+
+    /// Gives every position a distinct-looking score so move choices differ.
+    struct HashingNetwork;
+
+    impl TrainingNetwork for HashingNetwork {
+        fn evaluate(&self, board: &Board, is_black: bool) -> f32 {
+            let (black, white) = board.bitboards();
+            let hash = (black ^ white.rotate_left(17) ^ u64::from(is_black))
+                .wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            (hash >> 58) as f32
+        }
+
+        fn update(&mut self, _board: &Board, _is_black: bool, _delta: f32) {}
+    }
+
+    #[test]
+    fn boltzmann_exploration_concentrates_on_the_best_move_as_temperature_drops() {
+        let mut board = Board::new();
+        board.place(19, true);
+        let legal = board.legal_moves(false);
+        let explore = |temperature| {
+            let mut trainer = TDLambdaTrainer::with_config(
+                HashingNetwork,
+                &TrainerConfig {
+                    alpha: 0.01,
+                    lambda_: 0.7,
+                    epsilon: 1.0,
+                    search_depth: 1,
+                    exploration: ExplorationPolicy::Boltzmann { temperature },
+                    ..TrainerConfig::default()
+                },
+                47,
+            )
+            .unwrap();
+            (0..200)
+                .map(|_| trainer.select_move(&board, false, legal).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let best_move = exhaustive_select_move(&HashingNetwork, &board, false, 1);
+        assert!(explore(1e-3).iter().all(|mv| *mv == best_move));
+        let distinct = explore(1e6)
+            .iter()
+            .fold(0u64, |seen, mv| seen | (1u64 << mv));
+        assert_eq!(distinct, legal);
+    }
 }
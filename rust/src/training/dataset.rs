@@ -0,0 +1,614 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::supervised::LabelledPosition;
+use super::{
+    ExplorationPolicy, FrozenNetwork, MAX_EXACT_TD_EMPTIES, TDLambdaTrainer, TrainableNTuple,
+    TrainerConfig, exact_game_solver, exact_training_score, nth_move_from_mask,
+    resolve_thread_count, terminal_training_score, worker_seed,
+};
+use crate::ai::arena;
+use crate::ai::level::LevelConfig;
+use crate::board::Board;
+use crate::game::MoveSelector;
+
+pub const DATASET_MAGIC: &[u8; 4] = b"NTDS";
+pub const DATASET_VERSION: u16 = 1;
+pub const DATASET_HEADER_SIZE: usize = 8;
+/// Bytes per row of a binary dataset, see [`DatasetRow::to_bytes`].
+pub const DATASET_RECORD_SIZE: usize = 24;
+/// `exact_score` of a binary row whose position was not solved.
+pub const NO_EXACT_SCORE: i8 = i8::MIN;
+const CSV_HEADER: &str = "black,white,is_black,ply,search_score,final_score,exact_score";
+
+/// One position of a generated game. Scores are disc differences for the
+/// side to move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DatasetRow {
+    pub black: u64,
+    pub white: u64,
+    pub is_black: bool,
+    /// Moves played before this position, passes excluded.
+    pub ply: u8,
+    /// Best score the policy's search found, `NaN` when it reports none.
+    pub search_score: f32,
+    pub final_score: i8,
+    pub exact_score: Option<i8>,
+}
+
+impl DatasetRow {
+    pub fn board(&self) -> Board {
+        Board::from_bitboards(self.black, self.white)
+    }
+
+    /// The position labelled with its exact score when solved, otherwise with
+    /// the final disc difference of its game.
+    pub fn labelled(&self) -> LabelledPosition {
+        LabelledPosition {
+            board: self.board(),
+            is_black: self.is_black,
+            score: f32::from(self.exact_score.unwrap_or(self.final_score)),
+        }
+    }
+
+    /// Little-endian `black: u64, white: u64, search_score: f32, ply: u8,
+    /// is_black: u8, final_score: i8, exact_score: i8` with
+    /// [`NO_EXACT_SCORE`] for unsolved positions, so numpy can map a file
+    /// with one structured dtype.
+    pub fn to_bytes(self) -> [u8; DATASET_RECORD_SIZE] {
+        let mut bytes = [0u8; DATASET_RECORD_SIZE];
+        bytes[0..8].copy_from_slice(&self.black.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.white.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.search_score.to_le_bytes());
+        bytes[20] = self.ply;
+        bytes[21] = u8::from(self.is_black);
+        bytes[22] = self.final_score.to_le_bytes()[0];
+        bytes[23] = self.exact_score.unwrap_or(NO_EXACT_SCORE).to_le_bytes()[0];
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; DATASET_RECORD_SIZE]) -> Result<Self, String> {
+        let black = u64::from_le_bytes(bytes[0..8].try_into().expect("slice has 8 bytes"));
+        let white = u64::from_le_bytes(bytes[8..16].try_into().expect("slice has 8 bytes"));
+        if black & white != 0 {
+            return Err("black and white discs overlap".to_string());
+        }
+        let exact_score = i8::from_le_bytes([bytes[23]]);
+        Ok(Self {
+            black,
+            white,
+            search_score: f32::from_le_bytes(bytes[16..20].try_into().expect("slice has 4 bytes")),
+            ply: bytes[20],
+            is_black: match bytes[21] {
+                0 => false,
+                1 => true,
+                other => return Err(format!("invalid side to move {other}")),
+            },
+            final_score: i8::from_le_bytes([bytes[22]]),
+            exact_score: (exact_score != NO_EXACT_SCORE).then_some(exact_score),
+        })
+    }
+
+    fn to_csv(self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.black,
+            self.white,
+            u8::from(self.is_black),
+            self.ply,
+            self.search_score,
+            self.final_score,
+            self.exact_score
+                .map_or_else(String::new, |score| score.to_string())
+        )
+    }
+}
+
+/// Whether `data` starts like a binary dataset.
+pub fn is_dataset(data: &[u8]) -> bool {
+    data.starts_with(DATASET_MAGIC)
+}
+
+/// Reads every row of a binary dataset.
+pub fn read_dataset(data: &[u8]) -> Result<Vec<DatasetRow>, String> {
+    if data.len() < DATASET_HEADER_SIZE || !is_dataset(data) {
+        return Err("invalid dataset magic".to_string());
+    }
+    let version = u16::from_le_bytes([data[4], data[5]]);
+    if version != DATASET_VERSION {
+        return Err(format!("unsupported dataset version {version}"));
+    }
+    let record_size = usize::from(u16::from_le_bytes([data[6], data[7]]));
+    if record_size != DATASET_RECORD_SIZE {
+        return Err(format!("unsupported dataset record size {record_size}"));
+    }
+    let rows = &data[DATASET_HEADER_SIZE..];
+    if !rows.len().is_multiple_of(DATASET_RECORD_SIZE) {
+        return Err("dataset ends in a truncated row".to_string());
+    }
+    rows.chunks_exact(DATASET_RECORD_SIZE)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            DatasetRow::from_bytes(chunk.try_into().expect("chunk has the record size"))
+                .map_err(|err| format!("dataset row #{idx}: {err}"))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    /// Header plus fixed-size rows, see [`DatasetRow::to_bytes`].
+    Binary,
+    /// One row per line with a header line; unsolved positions leave
+    /// `exact_score` empty.
+    Csv,
+}
+
+impl DatasetFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "binary" => Ok(Self::Binary),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "unsupported dataset format '{name}' (expected one of: binary, csv)"
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Streams rows to a dataset file.
+pub struct DatasetWriter {
+    out: BufWriter<File>,
+    format: DatasetFormat,
+    rows: usize,
+}
+
+impl DatasetWriter {
+    pub fn create(path: &Path, format: DatasetFormat) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|err| format!("failed to create {}: {err}", path.display()))?;
+        let mut writer = Self {
+            out: BufWriter::new(file),
+            format,
+            rows: 0,
+        };
+        match format {
+            DatasetFormat::Binary => {
+                let mut header = Vec::with_capacity(DATASET_HEADER_SIZE);
+                header.extend_from_slice(DATASET_MAGIC);
+                header.extend_from_slice(&DATASET_VERSION.to_le_bytes());
+                header.extend_from_slice(&(DATASET_RECORD_SIZE as u16).to_le_bytes());
+                writer.write(&header)?;
+            }
+            DatasetFormat::Csv => writer.write(format!("{CSV_HEADER}\n").as_bytes())?,
+        }
+        Ok(writer)
+    }
+
+    pub fn write_rows(&mut self, rows: &[DatasetRow]) -> Result<(), String> {
+        for row in rows {
+            match self.format {
+                DatasetFormat::Binary => self.write(&row.to_bytes())?,
+                DatasetFormat::Csv => self.write(format!("{}\n", row.to_csv()).as_bytes())?,
+            }
+        }
+        self.rows += rows.len();
+        Ok(())
+    }
+
+    /// Flushes the file and returns the number of rows written.
+    pub fn finish(mut self) -> Result<usize, String> {
+        self.out
+            .flush()
+            .map_err(|err| format!("failed to write dataset: {err}"))?;
+        Ok(self.rows)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.out
+            .write_all(bytes)
+            .map_err(|err| format!("failed to write dataset: {err}"))
+    }
+}
+
+/// How generated games pick their moves.
+#[derive(Clone, Copy)]
+pub enum DatasetPolicy<'a> {
+    /// Any move selector at a fixed level; search scores come from its
+    /// `analyze`. Selectors that share a random generator between calls
+    /// only reproduce their games with one thread.
+    Selector {
+        selector: &'a (dyn MoveSelector + 'a),
+        level: LevelConfig,
+    },
+    /// The TD trainer's self-play policy over `network`, scored by its search.
+    Trainer {
+        network: &'a TrainableNTuple,
+        search_depth: u8,
+        epsilon: f64,
+        exploration: ExplorationPolicy,
+    },
+}
+
+/// Settings for [`generate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetConfig {
+    pub games: usize,
+    pub seed: u64,
+    /// Worker threads, `0` for every CPU.
+    pub threads: usize,
+    /// Uniformly random moves at the start of every game.
+    pub random_opening_plies: usize,
+    /// Positions with at most this many empties get an exact score; `0`
+    /// disables solving.
+    pub exact_empties: u8,
+}
+
+/// Plays `config.games` games in parallel and hands each game's rows to
+/// `sink` in game order. Game `i` is seeded from `config.seed` and `i`
+/// alone, so the output does not depend on the thread count.
+pub fn generate(
+    policy: &DatasetPolicy<'_>,
+    config: &DatasetConfig,
+    sink: &mut dyn FnMut(&[DatasetRow]) -> Result<(), String>,
+) -> Result<usize, String> {
+    if config.exact_empties > MAX_EXACT_TD_EMPTIES {
+        return Err(format!(
+            "exact_empties must be at most {MAX_EXACT_TD_EMPTIES}, got {}",
+            config.exact_empties
+        ));
+    }
+    if let DatasetPolicy::Selector { level, .. } = policy {
+        level.validate()?;
+    }
+    let threads = resolve_thread_count(config.threads).clamp(1, config.games.max(1));
+    let next_game = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let (next_game, failed) = (&next_game, &failed);
+            scope.spawn(move || {
+                while !failed.load(Ordering::Relaxed) {
+                    let game_idx = next_game.fetch_add(1, Ordering::Relaxed);
+                    if game_idx >= config.games {
+                        break;
+                    }
+                    let rows = play_game(policy, config, game_idx);
+                    if rows.is_err() {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                    if sender.send((game_idx, rows)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        // Games finish out of order; hold them back until their turn.
+        let mut pending = BTreeMap::new();
+        let mut next_to_write = 0usize;
+        let mut written = 0usize;
+        for (game_idx, rows) in receiver {
+            let rows = rows.map_err(|err| format!("game #{game_idx}: {err}"));
+            if rows.is_err() {
+                failed.store(true, Ordering::Relaxed);
+            }
+            pending.insert(game_idx, rows?);
+            while let Some(rows) = pending.remove(&next_to_write) {
+                if let Err(err) = sink(&rows) {
+                    failed.store(true, Ordering::Relaxed);
+                    return Err(err);
+                }
+                written += rows.len();
+                next_to_write += 1;
+            }
+        }
+        Ok(written)
+    })
+}
+
+fn play_game(
+    policy: &DatasetPolicy<'_>,
+    config: &DatasetConfig,
+    game_idx: usize,
+) -> Result<Vec<DatasetRow>, String> {
+    let seed = worker_seed(config.seed, game_idx);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut trainer = match *policy {
+        DatasetPolicy::Trainer {
+            network,
+            search_depth,
+            epsilon,
+            exploration,
+        } => Some(TDLambdaTrainer::with_config(
+            FrozenNetwork(network),
+            &TrainerConfig {
+                epsilon,
+                search_depth,
+                exploration,
+                ..TrainerConfig::default()
+            },
+            seed.rotate_left(32),
+        )?),
+        DatasetPolicy::Selector { .. } => None,
+    };
+
+    let mut rows = Vec::with_capacity(60);
+    let mut solver = exact_game_solver();
+    let board = arena::play_out(Board::new(), true, |board, is_black| {
+        let legal = board.legal_moves(is_black);
+        let ply = rows.len();
+        let search_score = match (policy, trainer.as_ref()) {
+            (_, Some(trainer)) => trainer.search_training_position(
+                board,
+                is_black,
+                trainer.search_depth,
+                f32::NEG_INFINITY,
+                f32::INFINITY,
+            )?,
+            (DatasetPolicy::Selector { selector, level }, None) => selector
+                .analyze(board, is_black, level)
+                .iter()
+                .map(|analysis| analysis.score)
+                .reduce(f32::max)
+                .unwrap_or(f32::NAN),
+            (DatasetPolicy::Trainer { .. }, None) => {
+                unreachable!("trainer policies build a trainer")
+            }
+        };
+        let mv = if ply < config.random_opening_plies {
+            nth_move_from_mask(legal, rng.gen_range(0..legal.count_ones()))
+        } else {
+            match (policy, trainer.as_mut()) {
+                (_, Some(trainer)) => trainer.select_move(board, is_black, legal)?,
+                (DatasetPolicy::Selector { selector, level }, None) => selector
+                    .select_move(board, is_black, level)
+                    .ok_or_else(|| {
+                        "selector found no move in a position with legal moves".to_string()
+                    })?,
+                (DatasetPolicy::Trainer { .. }, None) => {
+                    unreachable!("trainer policies build a trainer")
+                }
+            }
+        };
+        let (black, white) = board.bitboards();
+        rows.push(DatasetRow {
+            black,
+            white,
+            is_black,
+            ply: ply as u8,
+            search_score,
+            final_score: 0,
+            exact_score: (board.empty_count() <= config.exact_empties)
+                .then(|| exact_training_score(&mut solver, board, is_black) as i8),
+        });
+        Ok(mv)
+    })?;
+
+    let black_score = terminal_training_score(&board, true) as i8;
+    for row in &mut rows {
+        row.final_score = if row.is_black {
+            black_score
+        } else {
+            -black_score
+        };
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::FirstLegalMoveSelector;
+
+    fn trainer_policy(network: &TrainableNTuple) -> DatasetPolicy<'_> {
+        DatasetPolicy::Trainer {
+            network,
+            search_depth: 1,
+            epsilon: 0.2,
+            exploration: ExplorationPolicy::Uniform,
+        }
+    }
+
+    fn collect(policy: &DatasetPolicy<'_>, config: &DatasetConfig) -> Vec<Vec<DatasetRow>> {
+        let mut games = Vec::new();
+        let mut sink = |rows: &[DatasetRow]| {
+            games.push(rows.to_vec());
+            Ok(())
+        };
+        let written = generate(policy, config, &mut sink).unwrap();
+        assert_eq!(written, games.iter().map(Vec::len).sum::<usize>());
+        games
+    }
+
+    #[test]
+    fn generated_games_do_not_depend_on_the_thread_count() {
+        let network = TrainableNTuple::new();
+        let config = DatasetConfig {
+            games: 5,
+            seed: 9,
+            threads: 1,
+            random_opening_plies: 2,
+            exact_empties: 6,
+        };
+        let single = collect(&trainer_policy(&network), &config);
+        let parallel = collect(
+            &trainer_policy(&network),
+            &DatasetConfig {
+                threads: 3,
+                ..config.clone()
+            },
+        );
+        assert_eq!(single.len(), 5);
+        assert_eq!(single, parallel);
+        assert_ne!(single[0], single[1]);
+
+        for rows in &single {
+            let mut solver = exact_game_solver();
+            let mut board = Board::new();
+            for (ply, row) in rows.iter().enumerate() {
+                assert_eq!(row.ply as usize, ply);
+                assert!(row.search_score.is_finite());
+                let position = row.board();
+                assert_eq!(usize::from(64 - position.empty_count()), ply + 4);
+                assert_ne!(position.legal_moves(row.is_black), 0);
+                if position.empty_count() <= 6 {
+                    let exact = exact_training_score(&mut solver, &position, row.is_black) as i8;
+                    assert_eq!(row.exact_score, Some(exact));
+                } else {
+                    assert_eq!(row.exact_score, None);
+                }
+                board = position;
+            }
+            let first = rows[0];
+            assert_eq!(first.board(), Board::new());
+            assert!(rows.iter().all(|row| {
+                let black_score = if row.is_black {
+                    row.final_score
+                } else {
+                    -row.final_score
+                };
+                black_score
+                    == if first.is_black {
+                        first.final_score
+                    } else {
+                        -first.final_score
+                    }
+            }));
+            assert!(board.empty_count() <= 60);
+        }
+    }
+
+    #[test]
+    fn selector_policies_report_missing_scores_as_nan() {
+        let selector = FirstLegalMoveSelector;
+        let policy = DatasetPolicy::Selector {
+            selector: &selector,
+            level: LevelConfig::for_level(3).unwrap(),
+        };
+        let games = collect(
+            &policy,
+            &DatasetConfig {
+                games: 3,
+                seed: 1,
+                threads: 2,
+                random_opening_plies: 4,
+                exact_empties: 0,
+            },
+        );
+        assert_eq!(games.len(), 3);
+        assert_ne!(games[0], games[1]);
+        for rows in &games {
+            assert!(rows.iter().all(|row| row.search_score.is_nan()));
+            assert!(rows.iter().all(|row| row.exact_score.is_none()));
+            // After the random opening every move is the lowest legal square.
+            for pair in rows[4..].windows(2) {
+                let mv = pair[0]
+                    .board()
+                    .legal_moves(pair[0].is_black)
+                    .trailing_zeros() as usize;
+                let mut expected = pair[0].board();
+                expected.place(mv, pair[0].is_black);
+                assert_eq!(pair[1].board(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn binary_and_csv_writers_store_every_row() {
+        let network = TrainableNTuple::new();
+        let games = collect(
+            &trainer_policy(&network),
+            &DatasetConfig {
+                games: 2,
+                seed: 3,
+                threads: 2,
+                random_opening_plies: 0,
+                exact_empties: 4,
+            },
+        );
+        let rows: Vec<DatasetRow> = games.concat();
+        let dir = std::env::temp_dir().join(format!("reversi-dataset-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let binary_path = dir.join("positions.bin");
+        let mut writer = DatasetWriter::create(&binary_path, DatasetFormat::Binary).unwrap();
+        writer.write_rows(&rows[..10]).unwrap();
+        writer.write_rows(&rows[10..]).unwrap();
+        assert_eq!(writer.finish().unwrap(), rows.len());
+        let bytes = std::fs::read(&binary_path).unwrap();
+        assert_eq!(
+            bytes.len(),
+            DATASET_HEADER_SIZE + rows.len() * DATASET_RECORD_SIZE
+        );
+        assert!(is_dataset(&bytes));
+        assert_eq!(read_dataset(&bytes).unwrap(), rows);
+        assert!(read_dataset(&bytes[..bytes.len() - 1]).is_err());
+
+        let csv_path = dir.join("positions.csv");
+        let mut writer = DatasetWriter::create(&csv_path, DatasetFormat::Csv).unwrap();
+        writer.write_rows(&rows).unwrap();
+        writer.finish().unwrap();
+        let text = std::fs::read_to_string(&csv_path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines.len(), rows.len() + 1);
+        let last = rows[rows.len() - 1];
+        let fields: Vec<&str> = lines[lines.len() - 1].split(',').collect();
+        assert_eq!(fields[0], last.black.to_string());
+        assert_eq!(fields[6], last.exact_score.unwrap().to_string());
+        assert_eq!(lines[1].split(',').nth(6), Some(""));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rows_round_trip_and_reject_corrupt_bytes() {
+        let row = DatasetRow {
+            black: 0x0000_0008_1000_0000,
+            white: 0x0000_0010_0800_0000,
+            is_black: false,
+            ply: 0,
+            search_score: -1.5,
+            final_score: -12,
+            exact_score: Some(-64),
+        };
+        assert_eq!(DatasetRow::from_bytes(&row.to_bytes()), Ok(row));
+        let unsolved = DatasetRow {
+            exact_score: None,
+            ..row
+        };
+        assert_eq!(DatasetRow::from_bytes(&unsolved.to_bytes()), Ok(unsolved));
+        assert_eq!(unsolved.labelled().score, -12.0);
+        assert_eq!(row.labelled().score, -64.0);
+
+        let mut overlapping = row.to_bytes();
+        overlapping[8] = overlapping[0] | 1;
+        overlapping[0] |= 1;
+        assert!(DatasetRow::from_bytes(&overlapping).is_err());
+        let mut bad_side = row.to_bytes();
+        bad_side[21] = 2;
+        assert!(DatasetRow::from_bytes(&bad_side).is_err());
+        assert!(read_dataset(b"NTRV\x01\x00\x18\x00").is_err());
+        assert!(read_dataset(b"NTDS\x02\x00\x18\x00").is_err());
+        assert_eq!(read_dataset(b"NTDS\x01\x00\x18\x00"), Ok(Vec::new()));
+    }
+}
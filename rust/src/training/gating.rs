@@ -0,0 +1,633 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use super::{
+    NetworkLayout, ProgressCallback, TrainableNTuple, TrainingConfig, TrainingLogFormat,
+    TrainingProgress, TrainingRun, json_number, open_log,
+};
+use crate::ai::arena::{self, MatchConfig};
+use crate::ai::level::LevelConfig;
+use crate::ai::metadata::KEY_GATE;
+use crate::ai::ntuple::NTupleEvaluator;
+
+pub type GenerationCallback<'a> = &'a mut dyn FnMut(&GenerationReport) -> Result<(), String>;
+
+/// When a candidate replaces the incumbent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromotionRule {
+    /// Play the whole match and promote at this score or above; a draw
+    /// counts as half a win.
+    WinRate { threshold: f64 },
+    /// Sequential probability ratio test of `elo0` against `elo1` (the
+    /// candidate's Elo gain) with error rates `alpha` and `beta`. The match
+    /// stops as soon as either hypothesis is accepted; an undecided match
+    /// keeps the incumbent.
+    Sprt {
+        elo0: f64,
+        elo1: f64,
+        alpha: f64,
+        beta: f64,
+    },
+}
+
+/// Settings of a gating run: how many candidates to train and how each one
+/// is matched against the incumbent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GatingConfig {
+    pub generations: usize,
+    /// Games of a match, or the most an SPRT match plays.
+    pub match_games: usize,
+    /// Fixed search depth of both players, so matches do not depend on timing.
+    pub search_depth: u8,
+    /// Random plies of each match opening. Every opening is played twice with
+    /// the colors swapped.
+    pub opening_plies: usize,
+    /// Seed of the openings; every generation plays the same ones.
+    pub seed: u64,
+    pub rule: PromotionRule,
+}
+
+impl GatingConfig {
+    /// Parses comma-separated `generations=<N>`, `games=<N>`, `depth=<plies>`,
+    /// `openings=<plies>`, `seed=<N>` and either `winrate=<score>` or
+    /// `sprt=<elo0>:<elo1>[:<alpha>:<beta>]` entries, e.g.
+    /// `generations=10,games=400,sprt=0:10`. Error rates default to 0.05.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut config = Self {
+            generations: 1,
+            match_games: 200,
+            search_depth: 2,
+            opening_plies: 6,
+            seed: 42,
+            rule: PromotionRule::WinRate { threshold: 0.55 },
+        };
+        for entry in spec.split(',').map(str::trim) {
+            let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+            let invalid = || format!("invalid gate entry '{entry}'");
+            match key.trim() {
+                "generations" => config.generations = value.parse().map_err(|_| invalid())?,
+                "games" => config.match_games = value.parse().map_err(|_| invalid())?,
+                "depth" => config.search_depth = value.parse().map_err(|_| invalid())?,
+                "openings" => config.opening_plies = value.parse().map_err(|_| invalid())?,
+                "seed" => config.seed = value.parse().map_err(|_| invalid())?,
+                "winrate" => {
+                    config.rule = PromotionRule::WinRate {
+                        threshold: value.parse().map_err(|_| invalid())?,
+                    }
+                }
+                "sprt" => {
+                    let values = value
+                        .split(':')
+                        .map(|value| value.parse::<f64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| invalid())?;
+                    let (elo0, elo1, alpha, beta) = match values[..] {
+                        [elo0, elo1] => (elo0, elo1, 0.05, 0.05),
+                        [elo0, elo1, alpha, beta] => (elo0, elo1, alpha, beta),
+                        _ => return Err(invalid()),
+                    };
+                    config.rule = PromotionRule::Sprt {
+                        elo0,
+                        elo1,
+                        alpha,
+                        beta,
+                    };
+                }
+                _ => {
+                    return Err(format!(
+                        "{} (expected generations=<N>, games=<N>, depth=<plies>, \
+                         openings=<plies>, seed=<N>, winrate=<score> or \
+                         sprt=<elo0>:<elo1>[:<alpha>:<beta>])",
+                        invalid()
+                    ));
+                }
+            }
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn name(self) -> String {
+        let rule = match self.rule {
+            PromotionRule::WinRate { threshold } => format!("winrate={threshold}"),
+            PromotionRule::Sprt {
+                elo0,
+                elo1,
+                alpha,
+                beta,
+            } => format!("sprt={elo0}:{elo1}:{alpha}:{beta}"),
+        };
+        format!(
+            "generations={},games={},depth={},openings={},seed={},{rule}",
+            self.generations, self.match_games, self.search_depth, self.opening_plies, self.seed
+        )
+    }
+
+    pub fn validate(self) -> Result<(), String> {
+        if self.generations == 0 {
+            return Err("gating needs at least one generation".to_string());
+        }
+        if self.match_games == 0 {
+            return Err("gating matches need at least one game".to_string());
+        }
+        self.level().validate()?;
+        match self.rule {
+            PromotionRule::WinRate { threshold } if !(0.0..=1.0).contains(&threshold) => Err(
+                format!("gate win rate must be in [0.0, 1.0], got {threshold}"),
+            ),
+            PromotionRule::Sprt {
+                elo0,
+                elo1,
+                alpha,
+                beta,
+            } => {
+                if !elo0.is_finite() || !elo1.is_finite() || elo0 >= elo1 {
+                    return Err(format!(
+                        "gate SPRT needs finite elo0 < elo1, got {elo0} and {elo1}"
+                    ));
+                }
+                if !(alpha > 0.0 && beta > 0.0 && alpha + beta < 1.0) {
+                    return Err(format!(
+                        "gate SPRT error rates must be positive and sum to less than 1, \
+                         got {alpha} and {beta}"
+                    ));
+                }
+                Ok(())
+            }
+            PromotionRule::WinRate { .. } => Ok(()),
+        }
+    }
+
+    /// Depth-limited search without a usable time limit or endgame solving.
+    fn level(self) -> LevelConfig {
+        LevelConfig {
+            depth: self.search_depth,
+            exact_solve_empties: 0,
+            wld_empties: 0,
+            time_budget_ms: u32::MAX,
+            selectivity: 0,
+            randomness: 0.0,
+        }
+    }
+}
+
+/// Outcome of [`play_match`] from the candidate's side.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MatchResult {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    /// Final log-likelihood ratio of an SPRT match.
+    pub llr: Option<f64>,
+    pub promoted: bool,
+}
+
+impl MatchResult {
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Mean points per game with a draw worth half a win.
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        (self.wins as f64 + 0.5 * self.draws as f64) / self.games() as f64
+    }
+}
+
+/// Plays `candidate` against `incumbent` and applies the promotion rule.
+/// Both sides search to the same fixed depth, so the result only depends on
+/// the two models and `config`.
+pub fn play_match(
+    candidate: &NTupleEvaluator,
+    incumbent: &NTupleEvaluator,
+    config: &GatingConfig,
+) -> Result<MatchResult, String> {
+    config.validate()?;
+    let match_config = MatchConfig {
+        games: config.match_games,
+        opening_plies: config.opening_plies,
+        seed: config.seed,
+        level: config.level(),
+    };
+    let mut llr = None;
+    let mut promoted = false;
+    let record = arena::play_match(candidate, incumbent, &match_config, |record| {
+        let PromotionRule::Sprt {
+            elo0,
+            elo1,
+            alpha,
+            beta,
+        } = config.rule
+        else {
+            return true;
+        };
+        let current = sprt_llr(record.wins, record.losses, record.draws, elo0, elo1);
+        llr = Some(current);
+        promoted = current >= ((1.0 - beta) / alpha).ln();
+        !promoted && current > (beta / (1.0 - alpha)).ln()
+    })?;
+    let mut result = MatchResult {
+        wins: record.wins,
+        losses: record.losses,
+        draws: record.draws,
+        llr,
+        promoted,
+    };
+    if let PromotionRule::WinRate { threshold } = config.rule {
+        result.promoted = result.score() >= threshold;
+    }
+    Ok(result)
+}
+
+/// Log-likelihood ratio of `elo1` over `elo0` under the normal approximation
+/// of the per-game score that engine testing frameworks use. It stays at 0
+/// until the results vary.
+fn sprt_llr(wins: usize, losses: usize, draws: usize, elo0: f64, elo1: f64) -> f64 {
+    let games = (wins + losses + draws) as f64;
+    if games == 0.0 {
+        return 0.0;
+    }
+    let score = (wins as f64 + 0.5 * draws as f64) / games;
+    let variance = (wins as f64 * (1.0 - score).powi(2)
+        + losses as f64 * score.powi(2)
+        + draws as f64 * (0.5 - score).powi(2))
+        / games;
+    if variance <= 0.0 {
+        return 0.0;
+    }
+    let expected_score = |elo: f64| 1.0 / (1.0 + 10f64.powf(-elo / 400.0));
+    let (score0, score1) = (expected_score(elo0), expected_score(elo1));
+    games * (score1 - score0) * (2.0 * score - score0 - score1) / (2.0 * variance)
+}
+
+/// Payload of a [`GenerationCallback`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationReport {
+    /// 1-based generation number.
+    pub generation: usize,
+    /// Training games played so far, promoted or not.
+    pub games_trained: usize,
+    pub result: MatchResult,
+    pub elapsed_seconds: f64,
+}
+
+/// Trains `gating.generations` candidates of `training.games` games, each
+/// from the current incumbent, and promotes those that pass [`play_match`]
+/// against it. Returns the final incumbent, which is `incumbent` (or a fresh
+/// network from `layout`) when no candidate was promoted.
+///
+/// Generation `g` is seeded `g * training.games` past `training.seed`, and
+/// its alpha decay starts after the games of the promoted generations.
+pub fn run_gating(
+    training: &TrainingConfig,
+    gating: &GatingConfig,
+    incumbent: Option<&[u8]>,
+    layout: Option<&NetworkLayout>,
+    progress_interval: usize,
+    mut progress_callback: Option<ProgressCallback<'_>>,
+    mut generation_callback: Option<GenerationCallback<'_>>,
+) -> Result<TrainableNTuple, String> {
+    gating.validate()?;
+    let (mut incumbent_network, mut incumbent_bytes) = match incumbent {
+        Some(bytes) => (TrainableNTuple::from_bytes(bytes)?, bytes.to_vec()),
+        None => {
+            let network = match layout {
+                Some(layout) => layout.build()?,
+                None => TrainableNTuple::new(),
+            };
+            let bytes = network.to_bytes()?;
+            (network, bytes)
+        }
+    };
+    let mut incumbent_evaluator = NTupleEvaluator::from_bytes(&incumbent_bytes)?;
+    let total = gating.generations * training.games;
+    let started = Instant::now();
+    let mut promoted_games = 0usize;
+
+    for generation in 0..gating.generations {
+        let games_before = generation * training.games;
+        let config = TrainingConfig {
+            seed: training.seed.wrapping_add(games_before as u64),
+            alpha_decay_start_game: training.alpha_decay_start_game + promoted_games,
+            ..training.clone()
+        };
+        let mut run = TrainingRun::new(config, Some(&incumbent_bytes), layout)?;
+        let has_progress_callback = progress_callback.is_some();
+        let mut on_progress = |progress: &TrainingProgress| -> Result<(), String> {
+            match progress_callback.as_mut() {
+                Some(callback) => callback(&TrainingProgress {
+                    completed: games_before + progress.completed,
+                    total,
+                    elapsed_seconds: started.elapsed().as_secs_f64(),
+                    metrics: progress.metrics.clone(),
+                }),
+                None => Ok(()),
+            }
+        };
+        let progress: Option<ProgressCallback<'_>> = if has_progress_callback {
+            Some(&mut on_progress)
+        } else {
+            None
+        };
+        run.train(progress_interval, progress, 0, None)?;
+        let mut candidate = run.into_network()?;
+
+        let candidate_evaluator = NTupleEvaluator::from_bytes(&candidate.to_uncompressed_bytes()?)?;
+        let result = play_match(&candidate_evaluator, &incumbent_evaluator, gating)?;
+        if result.promoted {
+            let metadata = candidate.metadata_mut();
+            metadata.insert(KEY_GATE, gating.name());
+            metadata.set_benchmark("vs_incumbent.score", result.score());
+            metadata.set_benchmark(
+                "vs_incumbent.record",
+                format!("{}-{}-{}", result.wins, result.losses, result.draws),
+            );
+            incumbent_bytes = candidate.to_bytes()?;
+            incumbent_evaluator = candidate_evaluator;
+            incumbent_network = candidate;
+            promoted_games += training.games;
+        }
+
+        if let Some(callback) = generation_callback.as_mut() {
+            callback(&GenerationReport {
+                generation: generation + 1,
+                games_trained: games_before + training.games,
+                result,
+                elapsed_seconds: started.elapsed().as_secs_f64(),
+            })?;
+        }
+    }
+
+    Ok(incumbent_network)
+}
+
+const GATING_LOG_FIELDS: [&str; 10] = [
+    "generation",
+    "games_trained",
+    "match_games",
+    "wins",
+    "losses",
+    "draws",
+    "score",
+    "llr",
+    "promoted",
+    "elapsed_seconds",
+];
+
+/// Appends one [`GenerationReport`] per generation to a `.csv` or `.jsonl`
+/// file, like [`super::TrainingLog`] does for progress.
+pub struct GatingLog {
+    writer: BufWriter<File>,
+    format: TrainingLogFormat,
+    needs_header: bool,
+}
+
+impl GatingLog {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let (writer, format, needs_header) = open_log(path, "gating log")?;
+        Ok(Self {
+            writer,
+            format,
+            needs_header,
+        })
+    }
+
+    pub fn write(&mut self, report: &GenerationReport) -> Result<(), String> {
+        let result = &report.result;
+        let values = [
+            report.generation.to_string(),
+            report.games_trained.to_string(),
+            result.games().to_string(),
+            result.wins.to_string(),
+            result.losses.to_string(),
+            result.draws.to_string(),
+            result.score().to_string(),
+            result.llr.map(|llr| llr.to_string()).unwrap_or_default(),
+            result.promoted.to_string(),
+            report.elapsed_seconds.to_string(),
+        ];
+        let line = match self.format {
+            TrainingLogFormat::Csv => {
+                if self.needs_header {
+                    writeln!(self.writer, "{}", GATING_LOG_FIELDS.join(","))
+                        .map_err(|err| format!("failed to write gating log: {err}"))?;
+                    self.needs_header = false;
+                }
+                values.join(",")
+            }
+            TrainingLogFormat::Jsonl => {
+                let fields: Vec<String> = GATING_LOG_FIELDS
+                    .iter()
+                    .zip(&values)
+                    .map(|(name, value)| match *name {
+                        "promoted" => format!("\"{name}\":{value}"),
+                        _ => format!("\"{name}\":{}", json_number(value)),
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        };
+        writeln!(self.writer, "{line}")
+            .and_then(|()| self.writer.flush())
+            .map_err(|err| format!("failed to write gating log: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ai::metadata::KEY_PARENT_HASH;
+    use crate::ai::ntuple::decompress_model_bytes;
+    use crate::training::{
+        AlphaDecayStrategy, DEFAULT_TRAINING_SEARCH_DEPTH, EpsilonSchedule, ExplorationPolicy,
+        ParallelMode, TuplePatternSet,
+    };
+
+    fn small_layout() -> NetworkLayout {
+        NetworkLayout {
+            tuple_patterns: Arc::new(
+                TuplePatternSet::new(vec![vec![0, 1, 2, 3, 4], vec![0, 9, 18, 27]]).unwrap(),
+            ),
+            scalar_features: Vec::new(),
+        }
+    }
+
+    fn training_config(games: usize) -> TrainingConfig {
+        TrainingConfig {
+            games,
+            alpha: 0.01,
+            alpha_decay: AlphaDecayStrategy::None,
+            alpha_decay_start_game: 0,
+            lambda_: 0.7,
+            epsilon: 0.1,
+            seed: 7,
+            threads: 1,
+            random_opening_plies: 2,
+            parallel_mode: ParallelMode::Independent,
+            search_depth: DEFAULT_TRAINING_SEARCH_DEPTH,
+            exact_td_empties: 0,
+            epsilon_schedule: EpsilonSchedule::Constant,
+            exploration: ExplorationPolicy::Uniform,
+            league: None,
+        }
+    }
+
+    #[test]
+    fn gating_config_round_trips_and_rejects_bad_specs() {
+        let config = GatingConfig::parse("generations=3,games=50,depth=1,sprt=0:10").unwrap();
+        assert_eq!(
+            config,
+            GatingConfig {
+                generations: 3,
+                match_games: 50,
+                search_depth: 1,
+                opening_plies: 6,
+                seed: 42,
+                rule: PromotionRule::Sprt {
+                    elo0: 0.0,
+                    elo1: 10.0,
+                    alpha: 0.05,
+                    beta: 0.05,
+                },
+            }
+        );
+        assert_eq!(GatingConfig::parse(&config.name()), Ok(config));
+        let config = GatingConfig::parse("winrate=0.6,openings=0").unwrap();
+        assert_eq!(config.rule, PromotionRule::WinRate { threshold: 0.6 });
+        assert_eq!(GatingConfig::parse(&config.name()), Ok(config));
+
+        for spec in [
+            "generations=0",
+            "games=0",
+            "depth=0",
+            "winrate=1.5",
+            "sprt=10:0",
+            "sprt=0:10:0.5:0.5",
+            "sprt=0",
+            "rounds=3",
+        ] {
+            assert!(GatingConfig::parse(spec).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn sprt_llr_follows_the_score_and_its_spread() {
+        assert_eq!(sprt_llr(0, 0, 0, 0.0, 10.0), 0.0);
+        assert_eq!(sprt_llr(5, 0, 0, 0.0, 10.0), 0.0);
+        let llr = sprt_llr(60, 40, 0, 0.0, 10.0);
+        assert!((llr - 0.556_343).abs() < 1e-5, "{llr}");
+        assert!(sprt_llr(40, 60, 0, 0.0, 10.0) < 0.0);
+        // Draws shrink the variance, so the same score is stronger evidence.
+        assert!(sprt_llr(30, 10, 60, 0.0, 10.0) > llr);
+    }
+
+    #[test]
+    fn self_match_is_deterministic_and_even() {
+        let mut run = TrainingRun::new(training_config(4), None, Some(&small_layout())).unwrap();
+        run.train(0, None, 0, None).unwrap();
+        let bytes = run.into_network().unwrap().to_bytes().unwrap();
+        let evaluator = NTupleEvaluator::from_bytes(&bytes).unwrap();
+        let config = GatingConfig::parse("games=6,depth=1,openings=4,winrate=0.51").unwrap();
+
+        let result = play_match(&evaluator, &evaluator, &config).unwrap();
+        assert_eq!(result.games(), 6);
+        assert_eq!(result.wins, result.losses);
+        assert_eq!(result.score(), 0.5);
+        assert!(!result.promoted);
+        assert_eq!(play_match(&evaluator, &evaluator, &config), Ok(result));
+    }
+
+    #[test]
+    fn gating_log_appends_csv_and_jsonl_records() {
+        let report = GenerationReport {
+            generation: 2,
+            games_trained: 100,
+            result: MatchResult {
+                wins: 3,
+                losses: 1,
+                draws: 0,
+                llr: None,
+                promoted: true,
+            },
+            elapsed_seconds: 1.5,
+        };
+        let dir = std::env::temp_dir().join(format!("reversi-gating-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let csv_path = dir.join("gating.csv");
+        let _ = std::fs::remove_file(&csv_path);
+        for _ in 0..2 {
+            GatingLog::open(&csv_path).unwrap().write(&report).unwrap();
+        }
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            vec![
+                "generation,games_trained,match_games,wins,losses,draws,score,llr,promoted,\
+                 elapsed_seconds",
+                "2,100,4,3,1,0,0.75,,true,1.5",
+                "2,100,4,3,1,0,0.75,,true,1.5",
+            ]
+        );
+
+        let jsonl_path = dir.join("gating.jsonl");
+        let _ = std::fs::remove_file(&jsonl_path);
+        GatingLog::open(&jsonl_path)
+            .unwrap()
+            .write(&report)
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&jsonl_path).unwrap(),
+            "{\"generation\":2,\"games_trained\":100,\"match_games\":4,\"wins\":3,\
+             \"losses\":1,\"draws\":0,\"score\":0.75,\"llr\":null,\"promoted\":true,\
+             \"elapsed_seconds\":1.5}\n"
+        );
+
+        assert!(GatingLog::open(&dir.join("gating.txt")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn promoted_candidates_become_the_next_incumbent() {
+        let layout = small_layout();
+        let gating = GatingConfig::parse("generations=2,games=2,depth=1,winrate=0").unwrap();
+        let mut reports = Vec::new();
+        let mut on_generation = |report: &GenerationReport| {
+            reports.push(report.clone());
+            Ok(())
+        };
+
+        let network = run_gating(
+            &training_config(2),
+            &gating,
+            None,
+            Some(&layout),
+            0,
+            None,
+            Some(&mut on_generation),
+        )
+        .unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().all(|report| report.result.promoted));
+        assert_eq!(reports[1].games_trained, 4);
+        let metadata = network.metadata();
+        assert_eq!(metadata.get(KEY_GATE), Some(gating.name().as_str()));
+        assert!(metadata.get("benchmark.vs_incumbent.score").is_some());
+
+        // The second candidate was trained from the first, not the fresh network.
+        let fresh = layout.build().unwrap().to_bytes().unwrap();
+        let fresh_hash = format!(
+            "{:08x}",
+            crc32fast::hash(decompress_model_bytes(&fresh).unwrap().as_ref())
+        );
+        assert!(metadata.get(KEY_PARENT_HASH).is_some());
+        assert_ne!(metadata.get(KEY_PARENT_HASH), Some(fresh_hash.as_str()));
+    }
+}
@@ -0,0 +1,872 @@
+use std::ops::RangeInclusive;
+
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+use super::{
+    FeatureIndices, PHASE_COUNT, TrainableNTuple, ensure_finite, exact_game_solver,
+    exact_training_score, terminal_training_score,
+};
+use crate::ai::metadata::{
+    KEY_ALPHA, KEY_L2, KEY_SEED, KEY_SUPERVISED_EPOCHS, KEY_SUPERVISED_PHASES,
+    KEY_SUPERVISED_POSITIONS, ModelMetadata,
+};
+use crate::board::Board;
+
+pub type EpochCallback<'a> = &'a mut dyn FnMut(&EpochReport) -> Result<(), String>;
+
+/// A position labelled with the final disc difference for the side to move,
+/// taken from the game it was played in or from an exact solve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelledPosition {
+    pub board: Board,
+    pub is_black: bool,
+    pub score: f32,
+}
+
+/// Layouts [`parse_positions`] reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// One game per line as concatenated moves from the initial position,
+    /// e.g. `f5d6c3d3c4`. Passes are implied; games must be played out.
+    Transcript,
+    /// Generic Game Format, `(;GM[Othello]...;)`. Unfinished games are
+    /// labelled from their `RE` result.
+    Ggf,
+    /// One position per line: 64 squares in a1..h8 order (`X`/`*` black,
+    /// `O` white, `-`/`.` empty), the side to move and its score.
+    Positions,
+}
+
+impl RecordFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "transcript" => Ok(Self::Transcript),
+            "ggf" => Ok(Self::Ggf),
+            "positions" => Ok(Self::Positions),
+            _ => Err(format!(
+                "unsupported record format '{name}' (expected one of: transcript, ggf, positions)"
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Transcript => "transcript",
+            Self::Ggf => "ggf",
+            Self::Positions => "positions",
+        }
+    }
+
+    /// Guesses the format from the first non-comment line.
+    pub fn detect(text: &str) -> Self {
+        let first = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or("");
+        if first.starts_with("(;") {
+            Self::Ggf
+        } else if first
+            .split_whitespace()
+            .next()
+            .is_some_and(|token| token.len() == 64)
+        {
+            Self::Positions
+        } else {
+            Self::Transcript
+        }
+    }
+}
+
+/// Settings for [`fit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupervisedConfig {
+    pub epochs: usize,
+    pub batch_size: usize,
+    pub learning_rate: f32,
+    /// Penalty `l2 * w^2 / 2` on every weight a batch touches.
+    pub l2: f32,
+    /// Phases whose weights are fitted; positions of other phases are skipped.
+    pub phases: RangeInclusive<usize>,
+    /// Share of the positions held out to report a validation error.
+    pub validation_fraction: f64,
+    pub seed: u64,
+}
+
+impl Default for SupervisedConfig {
+    fn default() -> Self {
+        Self {
+            epochs: 10,
+            batch_size: 256,
+            learning_rate: 0.5,
+            l2: 1e-4,
+            phases: 0..=PHASE_COUNT - 1,
+            validation_fraction: 0.0,
+            seed: 42,
+        }
+    }
+}
+
+/// Mean squared errors in discs² after one pass over the training positions.
+/// The training error is measured on each batch before it is applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpochReport {
+    pub epoch: usize,
+    pub train_mse: f64,
+    pub validation_mse: Option<f64>,
+}
+
+/// Reads every labelled position of `text`. Game records yield each position
+/// in which a move was played, labelled with the final disc difference.
+pub fn parse_positions(text: &str, format: RecordFormat) -> Result<Vec<LabelledPosition>, String> {
+    let games = match format {
+        RecordFormat::Positions => {
+            return data_lines(text)
+                .map(|(line_no, line)| {
+                    parse_position_line(line).map_err(|err| format!("line {line_no}: {err}"))
+                })
+                .collect();
+        }
+        RecordFormat::Transcript => data_lines(text)
+            .map(|(line_no, line)| {
+                parse_transcript(line).map_err(|err| format!("line {line_no}: {err}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        RecordFormat::Ggf => parse_ggf(text)?,
+    };
+    Ok(games.iter().flat_map(GameRecord::labelled).collect())
+}
+
+/// Replaces the label of every position with at most `max_empties` empties by
+/// its exact score under perfect play.
+pub fn solve_labels(positions: &mut [LabelledPosition], max_empties: u8) {
+    let mut solver = exact_game_solver();
+    let mut last_empties = 0;
+    for position in positions {
+        let empties = position.board.empty_count();
+        if empties > max_empties {
+            continue;
+        }
+        // Positions of a game come in move order, so more empties than the
+        // last solved position means a new game and a fresh table.
+        if empties > last_empties {
+            solver = exact_game_solver();
+        }
+        last_empties = empties;
+        position.score = exact_training_score(&mut solver, &position.board, position.is_black);
+    }
+}
+
+/// Fits the weights of `config.phases` to the labels by mini-batch gradient
+/// descent on the squared error, and records the fit in the model metadata.
+pub fn fit(
+    network: &mut TrainableNTuple,
+    positions: &[LabelledPosition],
+    config: &SupervisedConfig,
+    mut epoch_callback: Option<EpochCallback<'_>>,
+) -> Result<Vec<EpochReport>, String> {
+    validate(config, network.phase_count)?;
+    for (idx, position) in positions.iter().enumerate() {
+        ensure_finite(position.score, &format!("label of position #{idx}"))?;
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let mut selected: Vec<usize> = (0..positions.len())
+        .filter(|&idx| {
+            config
+                .phases
+                .contains(&network.phase_index(&positions[idx].board))
+        })
+        .collect();
+    selected.shuffle(&mut rng);
+    let validation_len = (selected.len() as f64 * config.validation_fraction) as usize;
+    let (validation, training) = selected.split_at(validation_len);
+    if training.is_empty() {
+        return Err(format!(
+            "no training positions fall in phases {}-{}",
+            config.phases.start(),
+            config.phases.end()
+        ));
+    }
+    let mut training = training.to_vec();
+
+    let mut reports = Vec::with_capacity(config.epochs);
+    for epoch in 1..=config.epochs {
+        training.shuffle(&mut rng);
+        let mut squared_error = 0.0f64;
+        for batch in training.chunks(config.batch_size) {
+            squared_error += fit_batch(network, positions, batch, config);
+        }
+        let train_mse = squared_error / training.len() as f64;
+        ensure_finite(train_mse as f32, "training error")?;
+        let report = EpochReport {
+            epoch,
+            train_mse,
+            validation_mse: (!validation.is_empty())
+                .then(|| mean_squared_error(network, positions, validation)),
+        };
+        if let Some(callback) = epoch_callback.as_mut() {
+            callback(&report)?;
+        }
+        reports.push(report);
+    }
+
+    let mut metadata = ModelMetadata::new();
+    metadata.insert(KEY_SUPERVISED_POSITIONS, training.len());
+    metadata.insert(KEY_SUPERVISED_EPOCHS, config.epochs);
+    if config.phases != (0..=network.phase_count - 1) {
+        metadata.insert(
+            KEY_SUPERVISED_PHASES,
+            format!("{}-{}", config.phases.start(), config.phases.end()),
+        );
+    }
+    metadata.insert(KEY_ALPHA, config.learning_rate);
+    metadata.insert(KEY_L2, config.l2);
+    metadata.insert(KEY_SEED, config.seed);
+    network.metadata = metadata;
+    Ok(reports)
+}
+
+fn validate(config: &SupervisedConfig, phase_count: usize) -> Result<(), String> {
+    if config.batch_size == 0 {
+        return Err("batch_size must be greater than 0".to_string());
+    }
+    if !config.learning_rate.is_finite() || config.learning_rate <= 0.0 {
+        return Err("learning_rate must be greater than 0".to_string());
+    }
+    if !config.l2.is_finite() || config.l2 < 0.0 || config.learning_rate * config.l2 >= 1.0 {
+        return Err("l2 must be at least 0 and below 1 / learning_rate".to_string());
+    }
+    if config.phases.is_empty() || *config.phases.end() >= phase_count {
+        return Err(format!(
+            "phases {}-{} must be a non-empty range below {phase_count}",
+            config.phases.start(),
+            config.phases.end()
+        ));
+    }
+    if !(0.0..1.0).contains(&config.validation_fraction) {
+        return Err("validation_fraction must be in [0, 1)".to_string());
+    }
+    Ok(())
+}
+
+/// Applies one averaged gradient step and returns the batch's summed squared
+/// error before the step.
+fn fit_batch(
+    network: &mut TrainableNTuple,
+    positions: &[LabelledPosition],
+    batch: &[usize],
+    config: &SupervisedConfig,
+) -> f64 {
+    let residuals: Vec<(usize, FeatureIndices, f32)> = batch
+        .iter()
+        .map(|&idx| {
+            let position = &positions[idx];
+            let phase_idx = network.phase_index(&position.board);
+            let indices = network
+                .patterns
+                .feature_indices(&position.board, position.is_black);
+            let prediction = network.score(&position.board, position.is_black, phase_idx, &indices);
+            (phase_idx, indices, position.score - prediction)
+        })
+        .collect();
+
+    if config.l2 > 0.0 {
+        decay_touched_weights(network, &residuals, 1.0 - config.learning_rate * config.l2);
+    }
+    let step = config.learning_rate / batch.len() as f32;
+    let mut squared_error = 0.0f64;
+    for (&idx, (phase_idx, indices, residual)) in batch.iter().zip(&residuals) {
+        let position = &positions[idx];
+        network.apply_delta(*phase_idx, indices, step * residual);
+        network.apply_scalar_delta(
+            &position.board,
+            position.is_black,
+            *phase_idx,
+            step * residual,
+        );
+        squared_error += f64::from(*residual) * f64::from(*residual);
+    }
+    squared_error
+}
+
+/// Scales each weight the batch touches once, however often it appears.
+fn decay_touched_weights(
+    network: &mut TrainableNTuple,
+    residuals: &[(usize, FeatureIndices, f32)],
+    factor: f32,
+) {
+    let tuple_count = network.patterns.len();
+    let mut touched: Vec<(usize, usize, u16)> = residuals
+        .iter()
+        .flat_map(|(phase_idx, indices, _)| {
+            indices.iter().flat_map(move |tuple_indices| {
+                tuple_indices[..tuple_count]
+                    .iter()
+                    .enumerate()
+                    .map(move |(tuple_idx, &index)| (*phase_idx, tuple_idx, index))
+            })
+        })
+        .collect();
+    touched.sort_unstable();
+    touched.dedup();
+    for (phase_idx, tuple_idx, index) in touched {
+        network.weights[phase_idx][tuple_idx][index as usize] *= factor;
+    }
+
+    let Some(features) = network.features.as_mut() else {
+        return;
+    };
+    let mut phases: Vec<usize> = residuals
+        .iter()
+        .map(|(phase_idx, _, _)| *phase_idx)
+        .collect();
+    phases.sort_unstable();
+    phases.dedup();
+    for phase_idx in phases {
+        for weight in &mut features.weights_mut()[phase_idx] {
+            *weight *= factor;
+        }
+    }
+}
+
+fn mean_squared_error(
+    network: &TrainableNTuple,
+    positions: &[LabelledPosition],
+    selected: &[usize],
+) -> f64 {
+    let squared_error: f64 = selected
+        .iter()
+        .map(|&idx| {
+            let position = &positions[idx];
+            let phase_idx = network.phase_index(&position.board);
+            let indices = network
+                .patterns
+                .feature_indices(&position.board, position.is_black);
+            let residual = position.score
+                - network.score(&position.board, position.is_black, phase_idx, &indices);
+            f64::from(residual) * f64::from(residual)
+        })
+        .sum();
+    squared_error / selected.len() as f64
+}
+
+/// A replayed game: the positions in which a move was played and the final
+/// disc difference for black.
+#[derive(Debug, Clone, PartialEq)]
+struct GameRecord {
+    positions: Vec<(Board, bool)>,
+    black_score: f32,
+}
+
+impl GameRecord {
+    fn labelled(&self) -> impl Iterator<Item = LabelledPosition> + '_ {
+        self.positions
+            .iter()
+            .map(|&(board, is_black)| LabelledPosition {
+                board,
+                is_black,
+                score: if is_black {
+                    self.black_score
+                } else {
+                    -self.black_score
+                },
+            })
+    }
+}
+
+struct GameReplay {
+    board: Board,
+    is_black: bool,
+    positions: Vec<(Board, bool)>,
+}
+
+impl GameReplay {
+    fn new(board: Board, is_black: bool) -> Self {
+        Self {
+            board,
+            is_black,
+            positions: Vec::new(),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.board.legal_moves(true) == 0 && self.board.legal_moves(false) == 0
+    }
+
+    /// Plays `mv`, passing first when the side to move has no legal move.
+    /// `side` is the colour the record says is moving, if it says.
+    fn play(&mut self, mv: usize, side: Option<bool>) -> Result<(), String> {
+        if self.board.legal_moves(self.is_black) == 0 {
+            self.is_black = !self.is_black;
+        }
+        if side.is_some_and(|is_black| is_black != self.is_black) {
+            return Err(format!(
+                "{} moved out of turn at {}",
+                side_name(!self.is_black),
+                square_name(mv)
+            ));
+        }
+        if self.board.legal_moves(self.is_black) & (1u64 << mv) == 0 {
+            return Err(format!(
+                "illegal move {} for {}",
+                square_name(mv),
+                side_name(self.is_black)
+            ));
+        }
+        self.positions.push((self.board, self.is_black));
+        self.board.place(mv, self.is_black);
+        self.is_black = !self.is_black;
+        Ok(())
+    }
+
+    fn pass(&mut self, is_black: bool) -> Result<(), String> {
+        if is_black != self.is_black || self.board.legal_moves(is_black) != 0 {
+            return Err(format!(
+                "{} passed while {} could move",
+                side_name(is_black),
+                side_name(self.is_black)
+            ));
+        }
+        self.is_black = !self.is_black;
+        Ok(())
+    }
+
+    /// Labels the game from its final board, or from `result` (black's disc
+    /// difference) when the record stops early.
+    fn finish(self, result: Option<f32>) -> Result<GameRecord, String> {
+        let black_score = if self.is_over() {
+            terminal_training_score(&self.board, true)
+        } else {
+            result.ok_or_else(|| "game ends before the board is finished".to_string())?
+        };
+        Ok(GameRecord {
+            positions: self.positions,
+            black_score,
+        })
+    }
+}
+
+fn data_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn parse_position_line(line: &str) -> Result<LabelledPosition, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let [board, side, score] = tokens[..] else {
+        return Err("expected '<64 squares> <side> <score>'".to_string());
+    };
+    let score: f32 = score
+        .parse()
+        .map_err(|_| format!("invalid score '{score}'"))?;
+    Ok(LabelledPosition {
+        board: parse_board(board)?,
+        is_black: parse_side(side)?,
+        score,
+    })
+}
+
+fn parse_board(squares: &str) -> Result<Board, String> {
+    if squares.chars().count() != 64 {
+        return Err(format!(
+            "board must have 64 squares, got {}",
+            squares.chars().count()
+        ));
+    }
+    let (mut black, mut white) = (0u64, 0u64);
+    for (pos, square) in squares.chars().enumerate() {
+        match square {
+            'X' | 'x' | '*' => black |= 1u64 << pos,
+            'O' | 'o' => white |= 1u64 << pos,
+            '-' | '.' => {}
+            other => return Err(format!("invalid square '{other}'")),
+        }
+    }
+    Ok(Board::from_bitboards(black, white))
+}
+
+fn parse_side(side: &str) -> Result<bool, String> {
+    match side {
+        "X" | "x" | "*" | "B" | "b" => Ok(true),
+        "O" | "o" | "W" | "w" => Ok(false),
+        _ => Err(format!("invalid side to move '{side}'")),
+    }
+}
+
+fn parse_transcript(line: &str) -> Result<GameRecord, String> {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err("transcript must consist of two-character moves".to_string());
+    }
+    let mut replay = GameReplay::new(Board::new(), true);
+    for (ply, square) in chars.chunks(2).enumerate() {
+        let square: String = square.iter().collect();
+        let mv = parse_square(&square)?;
+        replay
+            .play(mv, None)
+            .map_err(|err| format!("ply {}: {err}", ply + 1))?;
+    }
+    replay.finish(None)
+}
+
+fn parse_ggf(text: &str) -> Result<Vec<GameRecord>, String> {
+    let mut games = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("(;") {
+        let end = rest[start..]
+            .find(";)")
+            .ok_or_else(|| format!("GGF game #{} is not terminated", games.len() + 1))?;
+        games.push(
+            parse_ggf_game(&rest[start + 2..start + end])
+                .map_err(|err| format!("GGF game #{}: {err}", games.len() + 1))?,
+        );
+        rest = &rest[start + end + 2..];
+    }
+    Ok(games)
+}
+
+fn parse_ggf_game(body: &str) -> Result<GameRecord, String> {
+    let mut replay = None;
+    let mut result = None;
+    let mut rest = body;
+    while let Some(open) = rest.find('[') {
+        let key = rest[..open].trim();
+        let close = rest[open..]
+            .find(']')
+            .ok_or_else(|| format!("property {key} is not closed"))?;
+        let value = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+        match key {
+            "GM" if !value.eq_ignore_ascii_case("othello") => {
+                return Err(format!("unsupported game '{value}'"));
+            }
+            "TY" if !matches!(value.trim(), "8" | "8r") => {
+                return Err(format!("unsupported game type '{value}'"));
+            }
+            "BO" => replay = Some(parse_ggf_board(value)?),
+            "RE" => result = parse_ggf_result(value),
+            "B" | "W" => {
+                let replay = replay
+                    .as_mut()
+                    .ok_or_else(|| "move before the BO board".to_string())?;
+                let is_black = key == "B";
+                let square = value.split('/').next().unwrap_or("").trim();
+                if square.eq_ignore_ascii_case("pa") || square.eq_ignore_ascii_case("pass") {
+                    replay.pass(is_black)?;
+                } else {
+                    replay.play(parse_square(square)?, Some(is_black))?;
+                }
+            }
+            _ => {}
+        }
+    }
+    replay
+        .ok_or_else(|| "missing BO board".to_string())?
+        .finish(result)
+}
+
+/// `8 <64 squares in rows> <side>`, e.g. `8 -------- ... --------- *`.
+fn parse_ggf_board(value: &str) -> Result<GameReplay, String> {
+    let compact: String = value.split_whitespace().collect();
+    let cells = compact
+        .strip_prefix('8')
+        .ok_or_else(|| format!("unsupported board '{value}'"))?;
+    if cells.len() != 65 {
+        return Err(format!(
+            "board must have 64 squares and a side, got '{value}'"
+        ));
+    }
+    let (squares, side) = cells.split_at(64);
+    Ok(GameReplay::new(parse_board(squares)?, parse_side(side)?))
+}
+
+/// Black's disc difference, e.g. `+12.000` or `-4.00:r`.
+fn parse_ggf_result(value: &str) -> Option<f32> {
+    value.split(':').next()?.trim().parse().ok()
+}
+
+fn parse_square(square: &str) -> Result<usize, String> {
+    let bytes = square.as_bytes();
+    let [file, rank] = bytes else {
+        return Err(format!("invalid move '{square}'"));
+    };
+    let file = file.to_ascii_lowercase();
+    if !(b'a'..=b'h').contains(&file) || !(b'1'..=b'8').contains(rank) {
+        return Err(format!("invalid move '{square}'"));
+    }
+    Ok(usize::from(rank - b'1') * 8 + usize::from(file - b'a'))
+}
+
+fn square_name(pos: usize) -> String {
+    format!("{}{}", (b'a' + (pos % 8) as u8) as char, pos / 8 + 1)
+}
+
+fn side_name(is_black: bool) -> &'static str {
+    if is_black { "black" } else { "white" }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    /// A random game played to the end as `(mover, square)` pairs, with
+    /// `None` for a pass.
+    fn random_game(seed: u64) -> (Vec<(bool, Option<usize>)>, Board) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut board = Board::new();
+        let mut is_black = true;
+        let mut moves = Vec::new();
+        loop {
+            let legal = board.legal_moves(is_black);
+            if legal == 0 {
+                if board.legal_moves(!is_black) == 0 {
+                    return (moves, board);
+                }
+                moves.push((is_black, None));
+            } else {
+                let mut remaining = legal;
+                for _ in 0..rng.gen_range(0..legal.count_ones()) {
+                    remaining &= remaining - 1;
+                }
+                let mv = remaining.trailing_zeros() as usize;
+                board.place(mv, is_black);
+                moves.push((is_black, Some(mv)));
+            }
+            is_black = !is_black;
+        }
+    }
+
+    fn transcript(moves: &[(bool, Option<usize>)]) -> String {
+        moves
+            .iter()
+            .filter_map(|(_, mv)| mv.map(square_name))
+            .collect()
+    }
+
+    fn ggf(moves: &[(bool, Option<usize>)], result: &str) -> String {
+        let mut text = String::from("(;GM[Othello]PC[test]PB[a]PW[b]TY[8]RE[");
+        text.push_str(result);
+        text.push_str(
+            "]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]",
+        );
+        for (is_black, mv) in moves {
+            let side = if *is_black { "B" } else { "W" };
+            let square = mv.map_or_else(|| "PA".to_string(), |mv| square_name(mv).to_uppercase());
+            text.push_str(&format!("{side}[{square}//1.5]"));
+        }
+        text.push_str(";)");
+        text
+    }
+
+    #[test]
+    fn squares_use_file_and_rank_notation() {
+        assert_eq!(parse_square("a1"), Ok(0));
+        assert_eq!(parse_square("F5"), Ok(37));
+        assert_eq!(parse_square("h8"), Ok(63));
+        assert!(parse_square("i1").is_err());
+        assert!(parse_square("a9").is_err());
+        for pos in 0..64 {
+            assert_eq!(parse_square(&square_name(pos)), Ok(pos));
+        }
+    }
+
+    #[test]
+    fn transcripts_and_ggf_label_every_move_with_the_final_result() {
+        for seed in 0..4 {
+            let (moves, final_board) = random_game(seed);
+            let black_score = terminal_training_score(&final_board, true);
+            let played = moves.iter().filter(|(_, mv)| mv.is_some()).count();
+
+            let from_transcript =
+                parse_positions(&transcript(&moves), RecordFormat::Transcript).unwrap();
+            assert_eq!(from_transcript.len(), played);
+            assert_eq!(from_transcript[0].board, Board::new());
+            for position in &from_transcript {
+                let expected = if position.is_black {
+                    black_score
+                } else {
+                    -black_score
+                };
+                assert_eq!(position.score, expected);
+            }
+
+            // The result tag is ignored once the game is played out.
+            let from_ggf = parse_positions(&ggf(&moves, "+0.000"), RecordFormat::Ggf).unwrap();
+            assert_eq!(from_ggf, from_transcript);
+        }
+    }
+
+    #[test]
+    fn unfinished_games_need_a_result() {
+        let (moves, _) = random_game(7);
+        let opening = &moves[..10];
+        assert!(parse_positions(&transcript(opening), RecordFormat::Transcript).is_err());
+
+        let positions = parse_positions(&ggf(opening, "-12.00:r"), RecordFormat::Ggf).unwrap();
+        assert_eq!(positions.len(), 10);
+        assert_eq!(positions[0].score, -12.0);
+        assert_eq!(positions[1].score, 12.0);
+        assert!(parse_positions(&ggf(opening, "?"), RecordFormat::Ggf).is_err());
+    }
+
+    #[test]
+    fn records_with_illegal_or_out_of_turn_moves_are_rejected() {
+        let illegal = parse_positions("f5a1", RecordFormat::Transcript).unwrap_err();
+        assert!(illegal.contains("illegal move a1"), "{illegal}");
+
+        let (moves, _) = random_game(3);
+        let mut swapped = moves[..4].to_vec();
+        swapped[1].0 = true;
+        let out_of_turn = parse_positions(&ggf(&swapped, "+2"), RecordFormat::Ggf).unwrap_err();
+        assert!(out_of_turn.contains("out of turn"), "{out_of_turn}");
+
+        let unsupported = ggf(&moves, "+2").replace("TY[8]", "TY[10]");
+        assert!(parse_positions(&unsupported, RecordFormat::Ggf).is_err());
+    }
+
+    #[test]
+    fn position_lines_are_parsed_and_detected() {
+        let text = "# solved positions\n\
+            ---------------------------OX------XO--------------------------- X 0\n\
+            \n\
+            XXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXOOOOOOOOOOOOOOOOOOOOOOOOOOOOOOO- O -3.5\n";
+        assert_eq!(RecordFormat::detect(text), RecordFormat::Positions);
+        let positions = parse_positions(text, RecordFormat::Positions).unwrap();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0].board, Board::new());
+        assert!(positions[0].is_black);
+        assert_eq!(positions[0].score, 0.0);
+        assert_eq!(positions[1].board.empty_count(), 1);
+        assert!(!positions[1].is_black);
+        assert_eq!(positions[1].score, -3.5);
+
+        let bad = parse_positions("XO X 1", RecordFormat::Positions).unwrap_err();
+        assert!(bad.starts_with("line 1:"), "{bad}");
+
+        assert_eq!(RecordFormat::detect("f5d6c3"), RecordFormat::Transcript);
+        assert_eq!(RecordFormat::detect("  (;GM[Othello]"), RecordFormat::Ggf);
+        for format in [
+            RecordFormat::Transcript,
+            RecordFormat::Ggf,
+            RecordFormat::Positions,
+        ] {
+            assert_eq!(RecordFormat::from_name(format.name()), Ok(format));
+        }
+        assert!(RecordFormat::from_name("pgn").is_err());
+    }
+
+    #[test]
+    fn solve_labels_replaces_only_shallow_endgame_labels() {
+        let (moves, _) = random_game(11);
+        let mut positions = parse_positions(&transcript(&moves), RecordFormat::Transcript).unwrap();
+        let original = positions.clone();
+        solve_labels(&mut positions, 8);
+        for (solved, played) in positions.iter().zip(&original) {
+            if played.board.empty_count() <= 8 {
+                let exact =
+                    exact_training_score(&mut exact_game_solver(), &played.board, played.is_black);
+                assert_eq!(solved.score, exact);
+            } else {
+                assert_eq!(solved.score, played.score);
+            }
+        }
+    }
+
+    fn game_positions(games: u64) -> Vec<LabelledPosition> {
+        (0..games)
+            .flat_map(|seed| {
+                let (moves, _) = random_game(seed);
+                parse_positions(&transcript(&moves), RecordFormat::Transcript).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fit_reduces_the_error_and_records_metadata() {
+        let positions = game_positions(40);
+        let mut network = TrainableNTuple::new();
+        let config = SupervisedConfig {
+            epochs: 4,
+            batch_size: 32,
+            validation_fraction: 0.1,
+            ..SupervisedConfig::default()
+        };
+        let mut reported = Vec::new();
+        let mut record = |report: &EpochReport| {
+            reported.push(report.epoch);
+            Ok(())
+        };
+        let reports = fit(&mut network, &positions, &config, Some(&mut record)).unwrap();
+
+        assert_eq!(reported, vec![1, 2, 3, 4]);
+        assert!(reports.iter().all(|report| report.validation_mse.is_some()));
+        assert!(reports[3].train_mse < reports[0].train_mse);
+        let metadata = network.metadata();
+        let training_len = positions.len() - positions.len() / 10;
+        assert_eq!(
+            metadata.get(KEY_SUPERVISED_POSITIONS),
+            Some(training_len.to_string().as_str())
+        );
+        assert_eq!(metadata.get(KEY_SUPERVISED_EPOCHS), Some("4"));
+        assert_eq!(metadata.get(KEY_SUPERVISED_PHASES), None);
+
+        let mut again = TrainableNTuple::new();
+        fit(&mut again, &positions, &config, None).unwrap();
+        assert_eq!(again.raw_weights(), network.raw_weights());
+    }
+
+    #[test]
+    fn fit_only_touches_the_selected_phases() {
+        let positions = game_positions(10);
+        let mut network = TrainableNTuple::new();
+        let config = SupervisedConfig {
+            epochs: 2,
+            phases: 25..=29,
+            ..SupervisedConfig::default()
+        };
+        fit(&mut network, &positions, &config, None).unwrap();
+
+        let weights = network.raw_weights();
+        assert!(weights[..25].iter().flatten().flatten().all(|&w| w == 0.0));
+        assert!(weights[25..].iter().flatten().flatten().any(|&w| w != 0.0));
+        assert_eq!(network.metadata().get(KEY_SUPERVISED_PHASES), Some("25-29"));
+    }
+
+    #[test]
+    fn fit_rejects_invalid_settings() {
+        let positions = game_positions(1);
+        let mut network = TrainableNTuple::new();
+        for config in [
+            SupervisedConfig {
+                batch_size: 0,
+                ..SupervisedConfig::default()
+            },
+            SupervisedConfig {
+                learning_rate: 0.0,
+                ..SupervisedConfig::default()
+            },
+            SupervisedConfig {
+                l2: -1.0,
+                ..SupervisedConfig::default()
+            },
+            SupervisedConfig {
+                phases: 0..=PHASE_COUNT,
+                ..SupervisedConfig::default()
+            },
+            SupervisedConfig {
+                validation_fraction: 1.0,
+                ..SupervisedConfig::default()
+            },
+        ] {
+            assert!(fit(&mut network, &positions, &config, None).is_err());
+        }
+        assert!(fit(&mut network, &[], &SupervisedConfig::default(), None).is_err());
+    }
+}
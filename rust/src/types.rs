@@ -25,6 +25,25 @@ pub struct GameState {
     pub flipped: Vec<u8>,
 }
 
+/// One analyzed move from the side-to-move perspective. `score` is the raw
+/// search score; the calibrated fields are `None` when the model has no
+/// calibration and exact when the position was solved.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MoveAnalysis {
+    pub row: u8,
+    pub col: u8,
+    pub score: f32,
+    pub disc_difference: Option<f32>,
+    pub win_probability: Option<f32>,
+}
+
+/// Discs that can no longer be flipped, per color.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StableDiscs {
+    pub black: Vec<Position>,
+    pub white: Vec<Position>,
+}
+
 /// Final result after game over.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct GameResult {
@@ -1,3 +1,4 @@
+use reversi::ai::level::LevelConfig;
 use reversi::ai::ntuple::NTupleEvaluator;
 use reversi::ai::search::Searcher;
 use reversi::board::Board;
@@ -21,13 +22,13 @@ impl SearchBackedSelector {
 }
 
 impl MoveSelector for SearchBackedSelector {
-    fn select_move(&self, board: &Board, is_black: bool, level: u8) -> Option<usize> {
+    fn select_move(&self, board: &Board, is_black: bool, level: &LevelConfig) -> Option<usize> {
         let legal = board.legal_moves(is_black);
         if legal == 0 {
             return None;
         }
 
-        let mut searcher = Searcher::new(&self.evaluator, level);
+        let mut searcher = Searcher::with_level_config(&self.evaluator, level);
         Some(searcher.search(board, is_black))
     }
 }
@@ -13,7 +13,7 @@ const BLACK_PLAYER_TURN_TEXT = getPlayerTurnText(PLAYER_BLACK)
 export const startLevel = async (page: Page, level: number): Promise<void> => {
   await page.goto(APP_PATH)
   await expect(page.getByRole('heading', { name: 'Start game' })).toBeVisible()
-  await page.getByRole('button', { name: `Level ${level}` }).click()
+  await page.getByRole('button', { name: `Level ${level}`, exact: true }).click()
   await page.getByRole('button', { name: `Start level ${level} as Black` }).click()
   await expect(page.getByRole('grid', { name: 'Reversi board' })).toBeVisible()
   await expect(page.getByText(BLACK_PLAYER_TURN_TEXT)).toBeVisible()
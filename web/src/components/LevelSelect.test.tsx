@@ -29,6 +29,7 @@ describe('LevelSelect', () => {
     expect(screen.getByRole('button', { name: /Play second/i })).toBeInTheDocument()
     expect(screen.getByRole('button', { name: /^Level 1$/ })).toBeInTheDocument()
     expect(screen.getByRole('button', { name: /^Level 6$/ })).toBeInTheDocument()
+    expect(screen.getByRole('button', { name: /^Level 10$/ })).toBeInTheDocument()
 
     await user.click(screen.getByRole('button', { name: /Play second/i }))
     expect(onPlayerChange).toHaveBeenCalledWith(PLAYER_WHITE)
@@ -13,7 +13,7 @@ interface LevelSelectProps {
   onStart: () => void
 }
 
-const LEVELS = [1, 2, 3, 4, 5, 6] as const
+const LEVELS = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10] as const
 const PLAYER_OPTIONS: Player[] = [1, 2]
 
 function LevelSelect({
@@ -57,6 +57,25 @@ describe('useGame', () => {
     expect(worker.terminated).toBe(true)
   })
 
+  it('asks the worker to stop pondering when the page is hidden', () => {
+    const worker = new MockWorker()
+    const { unmount } = renderHook(() =>
+      useGame({ createWorker: () => worker as unknown as Worker }),
+    )
+    const setVisibility = (state: DocumentVisibilityState): void => {
+      Object.defineProperty(document, 'visibilityState', { configurable: true, value: state })
+      document.dispatchEvent(new Event('visibilitychange'))
+    }
+
+    setVisibility('visible')
+    expect(worker.postedMessages).toEqual([])
+
+    setVisibility('hidden')
+    expect(worker.postedMessages).toEqual([{ type: 'stop_pondering' }])
+    setVisibility('visible')
+    unmount()
+  })
+
   it('starts game and syncs game_state into React state', async () => {
     const worker = new MockWorker()
     const { result } = renderHook(() =>
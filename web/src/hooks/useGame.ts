@@ -30,7 +30,7 @@ const DEFAULT_PLAYER = PLAYER_BLACK
 const createDefaultWorker = (): Worker => new Worker(workerUrl, { type: 'module' })
 
 const isValidLevel = (level: number): boolean =>
-  Number.isInteger(level) && level >= 1 && level <= 6
+  Number.isInteger(level) && level >= 1 && level <= 10
 
 const isValidCell = (value: number): boolean =>
   Number.isInteger(value) && value >= 0 && value <= 7
@@ -205,10 +205,25 @@ export const useGame = (options: UseGameOptions = {}): GameHook => {
     }
   }, [])
 
+  useEffect(() => {
+    // The AI ponders on the player's time; a hidden page should not burn CPU.
+    const handleVisibilityChange = (): void => {
+      if (document.visibilityState === 'hidden') {
+        const request: WorkerRequest = { type: 'stop_pondering' }
+        workerRef.current?.postMessage(request)
+      }
+    }
+
+    document.addEventListener('visibilitychange', handleVisibilityChange)
+    return () => {
+      document.removeEventListener('visibilitychange', handleVisibilityChange)
+    }
+  }, [])
+
   const startGame = useCallback(
     async (level: number, player: Player): Promise<void> => {
       if (!isValidLevel(level)) {
-        const levelError = new Error('level must be an integer between 1 and 6')
+        const levelError = new Error('level must be an integer between 1 and 10')
         setError(levelError.message)
         return Promise.reject(levelError)
       }
@@ -28,6 +28,15 @@ export const place_stone = (row: number, col: number): never => {
   throw new Error('test mock: place_stone is not implemented')
 }
 
+export const ponder = (budgetMs: number): never => {
+  void budgetMs
+  throw new Error('test mock: ponder is not implemented')
+}
+
+export const stop_pondering = (): never => {
+  throw new Error('test mock: stop_pondering is not implemented')
+}
+
 export const wasm_ready = (): boolean => false
 
 const init = async (): Promise<InitOutput> =>
@@ -8,6 +8,8 @@ const wasmMock = vi.hoisted(() => ({
   get_result: vi.fn(),
   init_game: vi.fn(),
   place_stone: vi.fn(),
+  ponder: vi.fn(),
+  stop_pondering: vi.fn(),
   wasm_ready: vi.fn(),
 }))
 
@@ -18,6 +20,8 @@ vi.mock('./pkg/reversi', () => ({
   get_result: wasmMock.get_result,
   init_game: wasmMock.init_game,
   place_stone: wasmMock.place_stone,
+  ponder: wasmMock.ponder,
+  stop_pondering: wasmMock.stop_pondering,
   wasm_ready: wasmMock.wasm_ready,
 }))
 
@@ -52,6 +56,7 @@ describe('wasm wrapper', () => {
     wasmMock.place_stone.mockReturnValue(validGameState())
     wasmMock.ai_move.mockReturnValue(validGameState())
     wasmMock.get_result.mockReturnValue(validGameResult())
+    wasmMock.ponder.mockReturnValue(true)
   })
 
   it('ensureWasmModuleLoaded caches successful initialization', async () => {
@@ -88,13 +93,13 @@ describe('wasm wrapper', () => {
     const wrapper = await loadWrapper()
 
     expect(() => wrapper.initGame(0, PLAYER_BLACK)).toThrow(
-      'level must be an integer between 1 and 6',
+      'level must be an integer between 1 and 10',
     )
-    expect(() => wrapper.initGame(7, PLAYER_BLACK)).toThrow(
-      'level must be an integer between 1 and 6',
+    expect(() => wrapper.initGame(11, PLAYER_BLACK)).toThrow(
+      'level must be an integer between 1 and 10',
     )
     expect(() => wrapper.initGame(1.5, PLAYER_BLACK)).toThrow(
-      'level must be an integer between 1 and 6',
+      'level must be an integer between 1 and 10',
     )
     expect(wasmMock.wasm_ready).not.toHaveBeenCalled()
     expect(wasmMock.init_game).not.toHaveBeenCalled()
@@ -117,12 +122,12 @@ describe('wasm wrapper', () => {
     const wrapper = await loadWrapper()
 
     const minLevelState = wrapper.initGame(1, PLAYER_BLACK)
-    const maxLevelState = wrapper.initGame(6, PLAYER_WHITE)
+    const maxLevelState = wrapper.initGame(10, PLAYER_WHITE)
 
     expect(minLevelState.board).toHaveLength(64)
     expect(maxLevelState.board).toHaveLength(64)
     expect(wasmMock.init_game).toHaveBeenNthCalledWith(1, 1, PLAYER_BLACK)
-    expect(wasmMock.init_game).toHaveBeenNthCalledWith(2, 6, PLAYER_WHITE)
+    expect(wasmMock.init_game).toHaveBeenNthCalledWith(2, 10, PLAYER_WHITE)
   })
 
   it('initGame throws when wasm module is not initialized', async () => {
@@ -272,4 +277,27 @@ describe('wasm wrapper', () => {
 
     expect(() => wrapper.getResult()).toThrow('get_result return value must be an object')
   })
+
+  it('ponder forwards the budget and validates it', async () => {
+    const wrapper = await loadWrapper()
+
+    expect(wrapper.ponder(50)).toBe(true)
+    expect(wasmMock.ponder).toHaveBeenCalledWith(50)
+
+    expect(() => wrapper.ponder(-1)).toThrow(
+      'ponder: budgetMs must be a non-negative integer',
+    )
+    wasmMock.ponder.mockReturnValueOnce('yes')
+    expect(() => wrapper.ponder(50)).toThrow('ponder return value must be a boolean')
+  })
+
+  it('ponder and stopPondering throw when wasm module is not initialized', async () => {
+    const wrapper = await loadWrapper()
+    wasmMock.wasm_ready.mockReturnValue(false)
+
+    expect(() => wrapper.ponder(50)).toThrow(wasmNotReadyMessage)
+    expect(() => wrapper.stopPondering()).toThrow(wasmNotReadyMessage)
+    expect(wasmMock.ponder).not.toHaveBeenCalled()
+    expect(wasmMock.stop_pondering).not.toHaveBeenCalled()
+  })
 })
@@ -4,6 +4,8 @@ import init, {
   get_result as wasmGetResult,
   init_game as wasmInitGame,
   place_stone as wasmPlaceStone,
+  ponder as wasmPonder,
+  stop_pondering as wasmStopPondering,
   wasm_ready as wasmReadyRaw,
   type InitInput,
   type InitOutput,
@@ -78,6 +80,23 @@ export const getResult = (): GameResult => {
   return asGameResult(wasmGetResult(), 'get_result')
 }
 
+/**
+ * Searches the AI's reply to the predicted player move for up to `budgetMs`
+ * and returns whether another call would make further progress.
+ */
+export const ponder = (budgetMs: number): boolean => {
+  if (!Number.isInteger(budgetMs) || budgetMs < 0) {
+    throw new Error('ponder: budgetMs must be a non-negative integer')
+  }
+  assertWasmReady()
+  return asBoolean(wasmPonder(budgetMs), 'ponder return value')
+}
+
+export const stopPondering = (): void => {
+  assertWasmReady()
+  wasmStopPondering()
+}
+
 const assertWasmReady = (): void => {
   if (!wasmReadyRaw()) {
     throw new Error(
@@ -158,8 +177,8 @@ const asNumberArray = (value: unknown, label: string): number[] => {
 }
 
 const assertValidLevel = (level: number): void => {
-  if (!Number.isInteger(level) || level < 1 || level > 6) {
-    throw new Error('level must be an integer between 1 and 6')
+  if (!Number.isInteger(level) || level < 1 || level > 10) {
+    throw new Error('level must be an integer between 1 and 10')
   }
 }
 
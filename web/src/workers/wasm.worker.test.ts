@@ -2,7 +2,7 @@
 import { existsSync, readFileSync } from 'node:fs'
 import { dirname, resolve } from 'node:path'
 import { fileURLToPath, pathToFileURL } from 'node:url'
-import { beforeEach, describe, expect, it, vi } from 'vitest'
+import { afterEach, beforeEach, describe, expect, it, vi } from 'vitest'
 import { PLAYER_BLACK, PLAYER_WHITE } from '../types/player'
 import type { GameResult, GameState, Position } from '../wasm'
 import {
@@ -21,6 +21,8 @@ const wasmMock = vi.hoisted(() => ({
   placeStone: vi.fn(),
   aiMove: vi.fn(),
   getResult: vi.fn(),
+  ponder: vi.fn(),
+  stopPondering: vi.fn(),
 }))
 
 vi.mock('../wasm', () => ({
@@ -30,6 +32,8 @@ vi.mock('../wasm', () => ({
   placeStone: wasmMock.placeStone,
   aiMove: wasmMock.aiMove,
   getResult: wasmMock.getResult,
+  ponder: wasmMock.ponder,
+  stopPondering: wasmMock.stopPondering,
 }))
 
 const makeGameState = (overrides: Partial<GameState> = {}): GameState => ({
@@ -137,6 +141,9 @@ const realWasmDependencies: WorkerDependencies = {
     getLoadedBindings().place_stone(row, col) as GameState,
   aiMove: (): GameState => getLoadedBindings().ai_move() as GameState,
   getResult: (): GameResult => getLoadedBindings().get_result() as GameResult,
+  // Pondering only saves time, so the deterministic run leaves it out.
+  ponder: (): boolean => false,
+  stopPondering: (): void => {},
 }
 
 const runDeterministicGameWithWorkerHandler = async (
@@ -226,6 +233,11 @@ describe('wasm worker handler', () => {
     wasmMock.placeStone.mockReturnValue(makeGameState({ current_player: 1 }))
     wasmMock.aiMove.mockReturnValue(makeGameState({ current_player: 1 }))
     wasmMock.getResult.mockReturnValue(makeResult())
+    wasmMock.ponder.mockReturnValue(false)
+  })
+
+  afterEach(() => {
+    vi.useRealTimers()
   })
 
   it('handles init_game and posts game_state with legal moves', async () => {
@@ -470,6 +482,60 @@ describe('wasm worker handler', () => {
     ])
   })
 
+  it('ponders on the player turn until the next request arrives', async () => {
+    vi.useFakeTimers()
+    const { scope, posted } = makeScope()
+    const handler = createWorkerMessageHandler(scope)
+    wasmMock.ponder.mockReturnValue(true)
+
+    await handler({ data: { type: 'init_game', payload: { level: 3, player: PLAYER_BLACK } } })
+    expect(wasmMock.ponder).not.toHaveBeenCalled()
+    vi.runOnlyPendingTimers()
+    vi.runOnlyPendingTimers()
+    expect(wasmMock.ponder).toHaveBeenCalledTimes(2)
+    expect(wasmMock.ponder).toHaveBeenCalledWith(50)
+
+    await handler({ data: { type: 'stop_pondering' } })
+    vi.runOnlyPendingTimers()
+
+    expect(wasmMock.ponder).toHaveBeenCalledTimes(2)
+    expect(wasmMock.stopPondering).toHaveBeenCalledTimes(1)
+    expect(posted).toHaveLength(1)
+  })
+
+  it('stops pondering once the search has nothing left to do', async () => {
+    vi.useFakeTimers()
+    const { scope } = makeScope()
+    const handler = createWorkerMessageHandler(scope)
+
+    await handler({ data: { type: 'place_stone', payload: { row: 2, col: 3 } } })
+    vi.runOnlyPendingTimers()
+
+    expect(wasmMock.ponder).toHaveBeenCalledTimes(1)
+    expect(vi.getTimerCount()).toBe(0)
+  })
+
+  it('does not ponder after the game is over', async () => {
+    vi.useFakeTimers()
+    const { scope } = makeScope()
+    const handler = createWorkerMessageHandler(scope)
+    wasmMock.placeStone.mockReturnValueOnce(makeGameState({ is_game_over: true }))
+
+    await handler({ data: { type: 'place_stone', payload: { row: 7, col: 7 } } })
+
+    expect(vi.getTimerCount()).toBe(0)
+  })
+
+  it('ignores stop_pondering before a game starts', async () => {
+    const { scope, posted } = makeScope()
+    const handler = createWorkerMessageHandler(scope)
+
+    await handler({ data: { type: 'stop_pondering' } })
+
+    expect(wasmMock.stopPondering).not.toHaveBeenCalled()
+    expect(posted).toEqual([])
+  })
+
   it('handles get_result and posts result payload', async () => {
     const { scope, posted } = makeScope()
     const handler = createWorkerMessageHandler(scope)
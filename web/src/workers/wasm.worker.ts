@@ -5,6 +5,8 @@ import {
   getResult,
   initGame,
   placeStone,
+  ponder,
+  stopPondering,
   type GameResult,
   type GameState,
   type Position,
@@ -22,8 +24,13 @@ type PlaceStoneRequest = {
   payload: { row: number; col: number }
 } & RequestWithId
 type GetResultRequest = RequestWithId & { type: 'get_result' }
+type StopPonderingRequest = RequestWithId & { type: 'stop_pondering' }
 
-export type WorkerRequest = InitGameRequest | PlaceStoneRequest | GetResultRequest
+export type WorkerRequest =
+  | InitGameRequest
+  | PlaceStoneRequest
+  | GetResultRequest
+  | StopPonderingRequest
 type IncomingWorkerRequest = WorkerRequest | { type: string; payload?: unknown; requestId?: unknown }
 
 export type WorkerResponse =
@@ -49,6 +56,8 @@ export interface WorkerDependencies {
   placeStone: typeof placeStone
   aiMove: typeof aiMove
   getResult: typeof getResult
+  ponder: typeof ponder
+  stopPondering: typeof stopPondering
 }
 
 const defaultDependencies: WorkerDependencies = {
@@ -58,9 +67,13 @@ const defaultDependencies: WorkerDependencies = {
   placeStone,
   aiMove,
   getResult,
+  ponder,
+  stopPondering,
 }
 
 const MAX_AI_STEPS = 64
+// Pondering runs in short slices so that the player's move is handled promptly.
+const PONDER_SLICE_MS = 50
 const INVALID_MESSAGE_SHAPE = 'Invalid worker message shape'
 
 const isIntegerInRange = (value: unknown, min: number, max: number): boolean =>
@@ -80,7 +93,7 @@ const isValidInitPayload = (payload: unknown): payload is InitGameRequest['paylo
   }
 
   return (
-    isIntegerInRange((payload as { level: unknown }).level, 1, 6)
+    isIntegerInRange((payload as { level: unknown }).level, 1, 10)
     && isValidPlayerValue((payload as { player: unknown }).player)
   )
 }
@@ -107,6 +120,29 @@ export const createWorkerMessageHandler = (
   dependencies: WorkerDependencies = defaultDependencies,
 ): ((event: WorkerMessageEvent) => Promise<void>) => {
   let playerColor: Player = PLAYER_BLACK
+  // Bumped by every request, which ends the running ponder loop.
+  let ponderGeneration = 0
+  let hasGame = false
+
+  const startPondering = (): void => {
+    const generation = ponderGeneration
+    const step = (): void => {
+      if (generation !== ponderGeneration) {
+        return
+      }
+      let hasMoreWork = false
+      try {
+        hasMoreWork = dependencies.ponder(PONDER_SLICE_MS)
+      } catch {
+        // Pondering is best effort; the next move is simply searched from scratch.
+        return
+      }
+      if (hasMoreWork) {
+        setTimeout(step, 0)
+      }
+    }
+    setTimeout(step, 0)
+  }
 
   const postResponse = (message: WorkerResponse, requestId?: string): void => {
     if (requestId === undefined) {
@@ -140,6 +176,7 @@ export const createWorkerMessageHandler = (
       return
     }
     const requestId = rawRequestId
+    ponderGeneration += 1
 
     try {
       switch (request.type) {
@@ -154,6 +191,7 @@ export const createWorkerMessageHandler = (
           playerColor = payload.player
           const aiPlayer = opponentOf(playerColor)
           let state = dependencies.initGame(payload.level, payload.player)
+          hasGame = true
           let aiStepCount = 0
 
           while (state.current_player === aiPlayer && !state.is_game_over) {
@@ -177,6 +215,7 @@ export const createWorkerMessageHandler = (
 
           const moves = dependencies.getLegalMoves()
           postResponse({ type: 'game_state', payload: { state, moves } }, requestId)
+          startPondering()
           return
         }
         case 'place_stone': {
@@ -224,6 +263,7 @@ export const createWorkerMessageHandler = (
 
           const moves = dependencies.getLegalMoves()
           postResponse({ type: 'game_state', payload: { state, moves } }, requestId)
+          startPondering()
           return
         }
         case 'get_result': {
@@ -232,6 +272,13 @@ export const createWorkerMessageHandler = (
           postResponse({ type: 'result', payload: result }, requestId)
           return
         }
+        case 'stop_pondering': {
+          // No reply: the page sends this when it is hidden and waits for nothing.
+          if (hasGame) {
+            dependencies.stopPondering()
+          }
+          return
+        }
         default: {
           postResponse({
             type: 'error',